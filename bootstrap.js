@@ -1,3 +1,23 @@
-import('./pkg').then((module) => {
-    module.run_app();
-});
+// Anything the Rust side can check is reported by the app itself; this only
+// covers what has to work before it can load at all.
+function showUnsupported(missing) {
+    document.body.innerHTML =
+        '<div style="font-family: sans-serif; max-width: 28rem; margin: 4rem auto; padding: 0 1.5rem;">' +
+        '<h1 style="font-size: 1.25rem;">This browser can\'t run Yewchat</h1>' +
+        '<p>Missing: ' + missing.join(', ') + '.</p>' +
+        '<p>Try an up-to-date Chrome, Firefox, Safari or Edge, outside of embedded app views.</p>' +
+        '</div>';
+}
+
+if (typeof WebAssembly !== 'object' || typeof WebAssembly.instantiate !== 'function') {
+    showUnsupported(['WebAssembly']);
+} else {
+    import(/* webpackChunkName: "app" */ './pkg').then((module) => {
+        module.run_app();
+    }, (error) => {
+        // Compiling the module fails in webviews that disable WebAssembly
+        // without removing the global.
+        console.error(error);
+        showUnsupported(['WebAssembly']);
+    });
+}
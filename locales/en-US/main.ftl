@@ -0,0 +1,651 @@
+not-found = 404 baby
+
+## Login
+
+login-username = Username
+login-submit = Go Chatting!
+
+## Chat
+
+users-title = Users
+room-members = #{ $room } — { $count }
+room-others = Everyone else
+dm-available = Not in this room · available for direct messages
+message-placeholder = Message
+send = Send
+new-messages = New messages
+muted-message = Message from muted user { $name }
+show-message = show message
+mute = Mute
+unmute = Unmute
+block = Block
+unblock = Unblock
+plaintext-enable = Enable plain text mode
+plaintext-disable = Disable plain text mode
+sound-mute = Mute sounds
+sound-unmute = Unmute sounds
+desktop-notifications-enable = Enable desktop notifications
+desktop-notifications-disable = Disable desktop notifications
+theme-system = Theme: follow system
+theme-light = Theme: light
+theme-dark = Theme: dark
+topic-prompt = Room topic
+
+## Empty room
+
+empty-title = It's quiet in here...
+empty-subtitle = Be the first to say something. A few ideas to get started:
+empty-invite-title = Invite people
+empty-invite-body = Share this link so others can join:
+empty-topic-title = Set a topic
+empty-topic-body = Let people know what this room is about.
+empty-welcome-title = Post a welcome message
+welcome-template-1 = Hi everyone, welcome to the room! 👋
+welcome-template-2 = Welcome! Introduce yourself and tell us what you're working on.
+welcome-template-3 = Hello! This room is for ... — feel free to jump in.
+
+## Preferences
+
+preferences = Settings
+prefs-notifications = Notifications
+prefs-notify-about = Notify me about
+level-all = All messages
+level-mentions = Mentions only
+level-none = Nothing
+prefs-quiet-hours = Quiet hours
+prefs-quiet-hours-to = to
+prefs-channel-override = Override for #{ $channel }
+prefs-use-default = Use default
+prefs-media = Media
+prefs-media-proxy = Media proxy URL template
+prefs-media-proxy-help = External images are fetched through this proxy so other hosts don't see your IP. {"{"}url{"}"} is replaced with the encoded original.
+prefs-load-originals = Load original media directly
+prefs-language = Language
+
+## Settings screen
+
+prefs-appearance = Appearance
+prefs-theme = Theme
+prefs-avatar-style = Generated avatars
+avatar-adventurer = Adventurers
+avatar-bottts = Robots
+avatar-identicon = Patterns
+avatar-initials = Initials
+prefs-server-url = Chat server
+prefs-server-url-help = Leave empty for the default server. Changing it reconnects right away.
+prefs-server-url-invalid = Enter a ws:// or wss:// address.
+
+## Sessions
+
+sessions-title = Active sessions
+sessions-empty = No other sessions.
+sessions-this-device = This device
+sessions-sign-out = Sign out
+sessions-revoked = This session was signed out from another device.
+
+## Scrolling
+
+new-messages-below = { $count ->
+    [one] 1 new message ↓
+   *[other] { $count } new messages ↓
+}
+
+## Moderation
+
+modlog-title = Moderation log
+modlog-open = Moderation log
+modlog-all = All
+modlog-empty = No moderation actions yet.
+modlog-time = Time
+modlog-action = Action
+modlog-actor = Moderator
+modlog-target = Target
+modlog-reason = Reason
+modaction-delete = Delete
+modaction-kick = Kick
+modaction-ban = Ban
+modaction-pin = Pin
+modaction-purge = Purge
+mod-kick = Kick from #{ $room }
+mod-ban = Ban from #{ $room }
+mod-purge = Delete all their messages
+mod-kick-confirm = Remove { $name } from #{ $room }? They can join again.
+mod-ban-confirm = Ban { $name } from #{ $room }? They will not be able to join again.
+mod-purge-confirm = Delete every message { $name } sent in #{ $room }? This cannot be undone.
+mod-kicked = A moderator removed you from #{ $room }.
+mod-banned = A moderator banned you from #{ $room }.
+history-loading = Loading older messages…
+history-start = This is the beginning of the conversation.
+
+## Message actions
+
+message-actions = Message actions
+message-deleted = This message was deleted.
+message-copy = Copy text
+message-copied = Copied
+message-copy-link = Copy link
+permalink-copied = Link copied
+permalink-missing = The linked message could not be found.
+message-remove-for-me = Remove for me
+message-delete-for-everyone = Delete for everyone
+renamed-author = { $old } (now { $new })
+
+## Attachments
+
+attach-file = Attach a file
+attachment-too-large = That file is too large to send.
+attachment-failed = The file could not be uploaded. Please try again.
+attachment-not-in-dm = Files cannot be sent in direct messages, as they would not be end-to-end encrypted.
+attachment-download = Download
+attachment-drop-here = Drop a file to share it
+attachment-confirm-title = Send this file?
+attachment-sending = Sending { $name }…
+cancel = Cancel
+close = Close
+
+## Invitations
+
+invite = Invite
+invite-title = Invite people to #{ $room }
+invite-search = Search for people
+invite-no-results = No one to invite yet. Try searching by name.
+invite-remove = Remove
+invite-submit = { $count ->
+    [0] Invite
+    [one] Invite 1 person
+   *[other] Invite { $count } people
+}
+invite-received = { $from } invited you to #{ $room }
+invite-accept = Accept
+invite-decline = Decline
+notification-center = Notifications
+notification-center-empty = You're all caught up.
+
+## Join requests
+
+join-requests-title = Requests to join #{ $room }
+join-requests-empty = No pending requests.
+join-requests-badge = { $count ->
+    [one] 1 request
+   *[other] { $count } requests
+}
+join-approve = Approve
+join-reject = Reject
+join-pending = Your request to join #{ $room } is waiting for a moderator.
+join-approved = Your request to join #{ $room } was approved.
+join-rejected = Your request to join #{ $room } was declined.
+dismiss = Dismiss
+
+## Link previews
+
+preview-show = Show preview
+preview-hide = Hide preview
+
+## GIFs
+
+gif-open = Search GIFs
+gif-search = Search Tenor
+gif-hint = Type to search for a GIF.
+gif-loading = Searching…
+gif-failed = GIF search is unavailable right now.
+gif-no-results = No GIFs found.
+gif-attribution = Powered by Tenor
+
+## Welcome card
+
+welcome-bot = Room bot
+welcome-card-title = Welcome to #{ $room }!
+welcome-card-rules = Rules
+welcome-card-pinned = Start here
+welcome-card-roles = Getting roles
+welcome-card-local = Only you can see this message.
+
+## Room rules
+rules-title = Rules of #{ $room }
+rules-intro = Please read and accept the rules before posting here.
+rules-accept = I accept
+rules-locked = Accept this room's rules to start posting.
+rules-read = Read rules
+
+## Media embeds
+media-image = Image
+
+## Activity digest
+digest-title = Daily digest
+digest-loading = Loading digest settings…
+digest-enable = Send me a daily digest of rooms I follow
+digest-email = Email
+digest-webhook = Webhook
+digest-follow-room = Include #{ $room }
+digest-save = Save digest settings
+digest-invalid-email = Enter a valid email address.
+digest-invalid-webhook = Webhooks must use an https:// URL.
+digest-pending = Waiting for confirmation. Follow the link sent to { $address }.
+digest-active = Digests are delivered to { $address }.
+
+## Notification routing
+prefs-keywords = Also notify me about these words
+prefs-keywords-placeholder = deploy, outage, lunch
+prefs-group-mentions = Notify me about @here and @everyone
+route-explainer = Why did or didn't this notify me?
+route-notified = Notified
+route-not-notified = Not notified
+route-own-message = you sent it
+route-blocked = the sender is blocked
+route-muted = the sender is muted
+route-quiet-hours = quiet hours were on
+route-room-silenced = this room is set to notify about nothing
+route-mentioned = you were mentioned
+route-group-mention = { $group } mentions everyone in the room
+route-group-mentions-off = { $group } was used, but group mentions are turned off
+route-keyword = it contains your keyword "{ $keyword }"
+route-all-messages = this room notifies about all messages
+route-not-mentioned = this room only notifies about mentions
+
+## Voice calls
+call-start = 📞 Voice call
+call-incoming = { $name } is calling you
+call-outgoing = Calling { $name }…
+call-connecting = Connecting…
+call-active = In a call with { $name }
+call-accept = Accept
+call-decline = Decline
+call-mute = Mute
+call-unmute = Unmute
+call-hang-up = Hang up
+call-declined = { $name } declined the call.
+call-busy = { $name } is in another call.
+call-missed = Missed call from { $name }.
+call-ended = The call with { $name } ended.
+call-failed = The call with { $name } could not be connected.
+
+## Undo send
+undo-send-pending = Sending "{ $message }"…
+undo-send = Undo
+delivery-sending = Sending…
+delivery-sent = Sent
+delivery-retry = Not sent. Retry
+delivery-discard = Discard
+
+## Video calls
+call-start-video = 🎥 Video call
+call-incoming-video = { $name } is video calling you
+call-accept-audio = Audio only
+call-accept-video = With video
+call-camera-on = Camera on
+call-camera-off = Camera off
+call-microphone = Microphone
+call-camera = Camera
+call-dock = Dock to the side
+call-undock = Float
+call-picture-in-picture = Picture in picture
+call-expand = Expand call
+
+## Typing indicator
+prefs-privacy = Privacy
+prefs-typing-signal = Let others see when I'm typing
+prefs-typing-signal-help = Typing slash commands is never shown.
+typing-signal-immediate = Right away
+typing-signal-delayed = After a few seconds of typing
+typing-signal-never = Never
+typing-one = { $name } is typing…
+typing-two = { $first } and { $second } are typing…
+typing-three = { $first }, { $second } and { $third } are typing…
+typing-many = { $first }, { $second } and { $count } others are typing…
+
+## Screen sharing
+call-share-screen = Share screen
+call-stop-sharing = Stop sharing
+
+## Encrypted direct messages
+dm-start = Message privately
+dm-encrypted-help = End-to-end encrypted: only the two of you can read it
+dm-no-key = No encryption key from this user yet
+dm-unreadable = the message could not be decrypted
+dm-other-key = it was sealed for an encryption key this device does not have; restore your key backup to read such messages
+dm-failed = A direct message from { $name } was lost: { $reason }
+dm-composing = Encrypted message to { $name }
+dm-waiting-for-key = Waiting for { $name }'s encryption key
+dm-back-to-room = Back to the room
+dm-label = 🔒 Direct message
+dm-to = 🔒 To { $name }
+
+## Reactions and room info
+room-info = Room info
+room-info-about = About
+room-info-stats = Stats
+room-info-no-topic = No topic set
+room-info-members = { $count } members
+room-stats-window = Counted over the messages loaded in this window.
+room-stats-empty = No reactions in the loaded messages yet.
+room-stats-top-reactions = Top reactions
+room-stats-top-messages = Most reacted messages
+room-stats-jump = Jump to message
+room-stats-reaction-count = { $count } reactions
+
+## Pinned conversations
+dm-pinned = Pinned
+dm-pin = Pin conversation
+dm-unpin = Unpin conversation
+
+## Accounts
+login-password = Password
+login-register = Create account
+login-need-account = New here? Create an account
+login-have-account = Already have an account? Sign in
+auth-password-too-short = Passwords need at least { $min } characters
+auth-invalid-credentials = Wrong username or password
+auth-username-taken = That username is taken
+auth-failed = Could not reach the server, try again
+
+## Unsupported browsers
+unsupported-title = This browser can't run Yewchat
+unsupported-intro = Some features the app relies on are missing:
+unsupported-advice = Try an up-to-date Chrome, Firefox, Safari or Edge, outside of private browsing and embedded app views.
+capability-websocket = WebSockets
+capability-websocket-help = Messages travel over a live connection to the server. Some proxies and embedded views block it.
+capability-indexeddb = IndexedDB
+capability-indexeddb-help = Used for storing data in the browser. Often turned off in private browsing.
+capability-local-storage = Local storage
+capability-local-storage-help = Keeps your session and settings. Blocked when cookies and site data are disabled.
+
+## Connection presets
+prefs-connection = Connection
+prefs-connection-help = Changes apply right away, without reconnecting.
+transport-default = Default
+transport-mobile-friendly = Mobile-friendly (saves battery, queues more while offline)
+transport-low-latency = Low latency (reconnects fast)
+
+## OAuth
+oauth-github = Continue with GitHub
+oauth-google = Continue with Google
+oauth-signing-in = Signing you in…
+oauth-back = Back to sign in
+auth-oauth-rejected = The sign-in was cancelled or has expired, please try again
+
+## Split view
+split-open = Open beside the room
+split-close = Close side pane
+
+## Signing out
+sign-out = Sign out
+
+## Mini chat
+mini-chat-open = Pop out a mini chat
+mini-chat-placeholder = Message · Enter to send
+
+## Focus mode
+focus-mode-enter = Focus mode (Ctrl+Shift+F)
+focus-mode-exit = Leave focus mode (Ctrl+Shift+F)
+
+## Username rules
+auth-username-length = Usernames are { $min } to { $max } characters long
+auth-username-charset = Use only letters, digits, "_", "-" and "."
+
+## Flood limit
+send-cooldown = Slow down · { $seconds }s
+
+## Printing
+print-title = Print conversation
+print-help = Leave a date empty to print from the start or up to now.
+print-from = From
+print-until = Until
+print-invalid-range = The end date is before the start date
+print-action = Print
+
+## Word filter
+prefs-word-filter = Filtered words
+prefs-word-filter-placeholder = Comma-separated, e.g. darn, heck
+prefs-word-filter-help = Hidden in other people's messages until you choose to show them
+word-filter-reveal = Show filtered words
+
+## Saved messages
+saved-title = Saved messages
+saved-composing = Note to self, on all your devices
+saved-label = 🔖 Saved
+
+## Export
+
+export-title = Export
+export-help = Downloads the messages loaded in this window, including decrypted direct messages.
+export-json = Download JSON
+export-text = Download text
+
+## Safety numbers
+
+verify-title = Safety number
+verify-help = Compare these numbers with { $name } in person or over a call, or scan each other's code. If they match, nobody is reading along.
+verify-yours = Your fingerprint
+verify-theirs = { $name }'s fingerprint
+verify-mark = Mark as verified
+verify-clear = Clear verification
+verify-verified = Verified
+verify-open = Verify
+verify-dismiss = Dismiss
+verify-key-changed = { $name }'s encryption key changed. They may have a new device, or someone may be intercepting your messages. Compare safety numbers again.
+verify-verified-key-changed = The key you verified for { $name } changed. Do not trust new direct messages from them until you verify it again.
+
+## Key backup
+
+key-backup-title = Encryption key
+key-backup-help = Your direct messages can only be read with the key on this device. Save it to a file sealed with a passphrase, to restore it on another device or after clearing this one.
+key-backup-passphrase = Passphrase
+key-backup-min-length = At least { $count } characters. It cannot be recovered if forgotten.
+key-backup-download = Download key backup
+key-backup-restore = Restore from file…
+key-backup-restored = Encryption key restored
+key-backup-malformed = This is not a Yewchat key backup.
+key-backup-other-account = This key backup belongs to another account.
+key-backup-wrong-passphrase = Wrong passphrase, or the backup was altered.
+key-backup-too-short = The passphrase is too short.
+key-backup-failed = The key backup could not be made.
+
+## Import
+
+import-title = Open an archive
+import-help = Shows a JSON export read-only, without contacting the server.
+import-open = Choose file…
+import-too-large = That file is too large to be an archive.
+import-unreadable = The file could not be read.
+import-malformed = This is not a Yewchat archive: { $reason }
+import-unsupported-version = This archive was made by a newer version (format { $version }).
+import-missing-sender = Message { $number } in the archive has no sender.
+import-bad-timestamp = Message { $number } in the archive has an invalid time.
+archive-title = Archive of #{ $room }
+archive-exported = Exported { $date } · { $count ->
+    [one] 1 message
+   *[other] { $count } messages
+}
+archive-empty = This archive has no messages.
+archive-read-only = Read-only archive. Nothing here is sent to the server.
+archive-close = Close archive
+
+## App updates
+
+update-available = A new version of Yewchat is ready.
+update-reload = Reload
+update-later = Later
+
+## Code blocks
+
+code-copy = Copy
+code-copied = Copied
+
+## Date dividers
+
+date-today = Today
+date-yesterday = Yesterday
+
+## Read receipts
+
+seen-by = Seen by { $names }
+
+## Member list
+
+members-filter = Filter people
+members-sort = Sort members
+members-sort-room = Room order
+members-sort-name = A–Z
+members-sort-online = Online first
+members-sort-active = Recently active
+members-no-match = Nobody matches.
+members-partial = Only members loaded so far are shown.
+
+## Accessibility
+
+messages-log = Messages
+
+## Custom status
+
+status-placeholder = What's your status?
+status-clear = Clear status
+status-meeting = In a meeting
+status-commuting = Commuting
+status-sick = Out sick
+status-vacation = On vacation
+
+## Presence
+
+presence-online = Active
+presence-away = Away
+
+## Message length
+
+composer-too-long = Too long to send; shorten the message first
+message-truncated = (cut short)
+
+## Emoji
+
+emoji-picker = Insert emoji
+
+## Room notifications
+
+channel-notifications = Notifications for this room
+channel-level-default = Default ({ $level })
+
+## Quote replies
+
+message-quote = Quote
+quoting-message = Replying to { $name }
+quoting-cancel = Cancel quote
+quote-jump = Go to the original message
+quote-unavailable = The original message is not loaded
+
+## Forwarding
+
+message-forward = Forward
+message-forwarded-from = Forwarded from { $name }
+forward-title = Forward to…
+
+## Polls
+
+poll-votes = { $count ->
+    [one] 1 vote
+   *[other] { $count } votes
+}
+poll-closed = Closed
+poll-close = Close poll
+poll-room-only = Polls can only be posted in the room.
+
+## Disappearing messages
+
+disappearing-title = Disappearing messages
+disappearing-off = Off
+disappearing-countdown = Time left before this message disappears
+duration-seconds = { $count } s
+duration-minutes = { $count } min
+duration-hours = { $count } h
+duration-days = { $count } d
+
+## Scheduled messages
+
+schedule-title = Send later
+schedule-help = The message goes out at the chosen time, as long as the app is open then.
+schedule-in-hour = In an hour
+schedule-tomorrow = Tomorrow at 9:00
+schedule-at = Send at
+schedule-in-past = That time has already passed.
+schedule-action = Schedule
+schedule-done = Scheduled for { $time }
+schedule-no-commands = Commands cannot be scheduled.
+scheduled-count = { $count ->
+    [one] 1 scheduled message
+   *[other] { $count } scheduled messages
+}
+scheduled-missed = Missed while closed
+scheduled-send-now = Send now
+scheduled-cancel = Cancel scheduled message
+
+## Translation
+
+message-translate = Translate
+translation-loading = Translating…
+translation-show-original = Translated · Show original
+translation-show = Show translation
+translation-failed = The message could not be translated.
+
+## Narrow screens
+
+sidebar-toggle = Show or hide people
+
+## Keyboard shortcuts
+
+shortcuts-title = Keyboard shortcuts
+shortcuts-mac = On macOS, use Cmd instead of Ctrl.
+shortcut-quick-switcher = Jump to a conversation
+shortcut-close = Close panels and dialogs
+shortcut-edit-last = Edit your last message, from an empty composer
+shortcut-previous-conversation = Previous conversation
+shortcut-next-conversation = Next conversation
+shortcut-focus-mode = Toggle focus mode
+shortcut-action-log = Toggle the action log
+shortcut-help = Show these shortcuts
+switcher-title = Jump to a conversation
+switcher-placeholder = Type a name…
+switcher-no-match = No conversations match.
+editing-message = Editing message. Press Enter to save or Esc to cancel.
+editing-cancel = Cancel editing
+message-edited = (edited)
+
+## Bots
+
+bot-badge = BOT
+
+## Action log
+
+action-log-title = Action log
+action-log-clear = Clear
+action-log-back = Step back
+action-log-forward = Step forward
+action-log-live = Back to live
+action-log-empty = Nothing recorded yet.
+
+## Slash commands
+
+command-unknown = There is no /{ $name } command. Type /help for the list.
+command-usage = Usage: { $usage }
+command-help-me = /me <action> — Describe what you are doing
+command-help-shrug = /shrug [message] — Append ¯\_(ツ)_/¯
+command-help-poll = /poll "question" <option> <option>… — Start a poll (quote options with spaces)
+command-help-clear = /clear — Empty the message view, for you only
+command-help-help = /help — List the commands
+
+## Keyboard reactions
+
+reactions-quick = Quick reactions
+reactions-keyboard-hint = ← → to choose, Enter to react, Esc to close
+
+## Pasting transcripts
+
+paste-transcript-title = This looks like a chat transcript. Quote it instead of sending it as your own lines?
+paste-as-quote = Paste as quote
+paste-as-text = Paste as text
+
+## Selecting messages
+select-message = Select message
+selection-count = { $count ->
+    [one] 1 message selected
+   *[other] { $count } messages selected
+}
+selection-copy = Copy selection
+selection-clear = Cancel
@@ -0,0 +1,651 @@
+not-found = 404: página no encontrada
+
+## Login
+
+login-username = Nombre de usuario
+login-submit = ¡A chatear!
+
+## Chat
+
+users-title = Usuarios
+room-members = #{ $room } — { $count }
+room-others = Todos los demás
+dm-available = No está en esta sala · disponible para mensajes directos
+message-placeholder = Mensaje
+send = Enviar
+new-messages = Mensajes nuevos
+muted-message = Mensaje de un usuario silenciado: { $name }
+show-message = mostrar mensaje
+mute = Silenciar
+unmute = Dejar de silenciar
+block = Bloquear
+unblock = Desbloquear
+plaintext-enable = Activar modo de texto sin formato
+plaintext-disable = Desactivar modo de texto sin formato
+sound-mute = Silenciar sonidos
+sound-unmute = Activar sonidos
+desktop-notifications-enable = Activar notificaciones de escritorio
+desktop-notifications-disable = Desactivar notificaciones de escritorio
+theme-system = Tema: según el sistema
+theme-light = Tema: claro
+theme-dark = Tema: oscuro
+topic-prompt = Tema de la sala
+
+## Empty room
+
+empty-title = Todo está muy tranquilo por aquí...
+empty-subtitle = Sé el primero en decir algo. Algunas ideas para empezar:
+empty-invite-title = Invitar a otras personas
+empty-invite-body = Comparte este enlace para que otros se unan:
+empty-topic-title = Definir un tema
+empty-topic-body = Cuéntales a todos de qué trata esta sala.
+empty-welcome-title = Publicar un mensaje de bienvenida
+welcome-template-1 = ¡Hola a todos, bienvenidos a la sala! 👋
+welcome-template-2 = ¡Bienvenido! Preséntate y cuéntanos en qué estás trabajando.
+welcome-template-3 = ¡Hola! Esta sala es para ... — no dudes en participar.
+
+## Preferences
+
+preferences = Ajustes
+prefs-notifications = Notificaciones
+prefs-notify-about = Notificarme sobre
+level-all = Todos los mensajes
+level-mentions = Solo menciones
+level-none = Nada
+prefs-quiet-hours = Horas de silencio
+prefs-quiet-hours-to = a
+prefs-channel-override = Ajuste para #{ $channel }
+prefs-use-default = Usar el predeterminado
+prefs-media = Multimedia
+prefs-media-proxy = Plantilla de URL del proxy multimedia
+prefs-media-proxy-help = Las imágenes externas se cargan a través de este proxy para que otros servidores no vean tu IP. {"{"}url{"}"} se sustituye por la URL original codificada.
+prefs-load-originals = Cargar el contenido multimedia original directamente
+prefs-language = Idioma
+
+## Settings screen
+
+prefs-appearance = Apariencia
+prefs-theme = Tema
+prefs-avatar-style = Avatares generados
+avatar-adventurer = Aventureros
+avatar-bottts = Robots
+avatar-identicon = Patrones
+avatar-initials = Iniciales
+prefs-server-url = Servidor de chat
+prefs-server-url-help = Déjalo vacío para usar el servidor predeterminado. Al cambiarlo se reconecta de inmediato.
+prefs-server-url-invalid = Introduce una dirección ws:// o wss://.
+
+## Sessions
+
+sessions-title = Sesiones activas
+sessions-empty = No hay otras sesiones.
+sessions-this-device = Este dispositivo
+sessions-sign-out = Cerrar sesión
+sessions-revoked = Esta sesión se cerró desde otro dispositivo.
+
+## Scrolling
+
+new-messages-below = { $count ->
+    [one] 1 mensaje nuevo ↓
+   *[other] { $count } mensajes nuevos ↓
+}
+
+## Moderation
+
+modlog-title = Registro de moderación
+modlog-open = Registro de moderación
+modlog-all = Todo
+modlog-empty = Todavía no hay acciones de moderación.
+modlog-time = Hora
+modlog-action = Acción
+modlog-actor = Moderador
+modlog-target = Objetivo
+modlog-reason = Motivo
+modaction-delete = Eliminar
+modaction-kick = Expulsar
+modaction-ban = Vetar
+modaction-pin = Fijar
+modaction-purge = Purgar
+mod-kick = Expulsar de #{ $room }
+mod-ban = Vetar en #{ $room }
+mod-purge = Eliminar todos sus mensajes
+mod-kick-confirm = ¿Quitar a { $name } de #{ $room }? Podrá volver a unirse.
+mod-ban-confirm = ¿Vetar a { $name } en #{ $room }? No podrá volver a unirse.
+mod-purge-confirm = ¿Eliminar todos los mensajes que { $name } envió en #{ $room }? No se puede deshacer.
+mod-kicked = Un moderador te quitó de #{ $room }.
+mod-banned = Un moderador te vetó en #{ $room }.
+history-loading = Cargando mensajes anteriores…
+history-start = Este es el comienzo de la conversación.
+
+## Message actions
+
+message-actions = Acciones del mensaje
+message-deleted = Este mensaje fue eliminado.
+message-copy = Copiar texto
+message-copied = Copiado
+message-copy-link = Copiar enlace
+permalink-copied = Enlace copiado
+permalink-missing = No se encontró el mensaje enlazado.
+message-remove-for-me = Eliminar para mí
+message-delete-for-everyone = Eliminar para todos
+renamed-author = { $old } (ahora { $new })
+
+## Adjuntos
+
+attach-file = Adjuntar un archivo
+attachment-too-large = El archivo es demasiado grande para enviarlo.
+attachment-failed = No se pudo subir el archivo. Inténtalo de nuevo.
+attachment-not-in-dm = No se pueden enviar archivos en mensajes directos, ya que no irían cifrados de extremo a extremo.
+attachment-download = Descargar
+attachment-drop-here = Suelta un archivo para compartirlo
+attachment-confirm-title = ¿Enviar este archivo?
+attachment-sending = Enviando { $name }…
+cancel = Cancelar
+close = Cerrar
+
+## Invitaciones
+
+invite = Invitar
+invite-title = Invitar personas a #{ $room }
+invite-search = Buscar personas
+invite-no-results = Nadie a quien invitar todavía. Prueba a buscar por nombre.
+invite-remove = Quitar
+invite-submit = { $count ->
+    [0] Invitar
+    [one] Invitar a 1 persona
+   *[other] Invitar a { $count } personas
+}
+invite-received = { $from } te invitó a #{ $room }
+invite-accept = Aceptar
+invite-decline = Rechazar
+notification-center = Notificaciones
+notification-center-empty = Estás al día.
+
+## Solicitudes de acceso
+
+join-requests-title = Solicitudes para unirse a #{ $room }
+join-requests-empty = No hay solicitudes pendientes.
+join-requests-badge = { $count ->
+    [one] 1 solicitud
+   *[other] { $count } solicitudes
+}
+join-approve = Aprobar
+join-reject = Rechazar
+join-pending = Tu solicitud para unirte a #{ $room } está esperando a un moderador.
+join-approved = Tu solicitud para unirte a #{ $room } fue aprobada.
+join-rejected = Tu solicitud para unirte a #{ $room } fue rechazada.
+dismiss = Descartar
+
+## Vistas previas de enlaces
+
+preview-show = Mostrar vista previa
+preview-hide = Ocultar vista previa
+
+## GIFs
+
+gif-open = Buscar GIFs
+gif-search = Buscar en Tenor
+gif-hint = Escribe para buscar un GIF.
+gif-loading = Buscando…
+gif-failed = La búsqueda de GIFs no está disponible ahora mismo.
+gif-no-results = No se encontraron GIFs.
+gif-attribution = Con la tecnología de Tenor
+
+## Tarjeta de bienvenida
+
+welcome-bot = Bot de la sala
+welcome-card-title = ¡Bienvenido a #{ $room }!
+welcome-card-rules = Normas
+welcome-card-pinned = Empieza aquí
+welcome-card-roles = Cómo conseguir roles
+welcome-card-local = Solo tú puedes ver este mensaje.
+
+## Room rules
+rules-title = Normas de #{ $room }
+rules-intro = Lee y acepta las normas antes de publicar aquí.
+rules-accept = Acepto
+rules-locked = Acepta las normas de esta sala para empezar a publicar.
+rules-read = Leer normas
+
+## Media embeds
+media-image = Imagen
+
+## Activity digest
+digest-title = Resumen diario
+digest-loading = Cargando la configuración del resumen…
+digest-enable = Enviarme un resumen diario de las salas que sigo
+digest-email = Correo electrónico
+digest-webhook = Webhook
+digest-follow-room = Incluir #{ $room }
+digest-save = Guardar configuración del resumen
+digest-invalid-email = Introduce un correo electrónico válido.
+digest-invalid-webhook = Los webhooks deben usar una URL https://.
+digest-pending = Pendiente de confirmación. Sigue el enlace enviado a { $address }.
+digest-active = Los resúmenes se envían a { $address }.
+
+## Notification routing
+prefs-keywords = Avisarme también de estas palabras
+prefs-keywords-placeholder = despliegue, caída, comida
+prefs-group-mentions = Avisarme de @here y @everyone
+route-explainer = ¿Por qué me avisó o no?
+route-notified = Avisado
+route-not-notified = Sin aviso
+route-own-message = lo enviaste tú
+route-blocked = el remitente está bloqueado
+route-muted = el remitente está silenciado
+route-quiet-hours = las horas de silencio estaban activas
+route-room-silenced = esta sala no avisa de nada
+route-mentioned = te mencionaron
+route-group-mention = { $group } menciona a toda la sala
+route-group-mentions-off = se usó { $group }, pero las menciones de grupo están desactivadas
+route-keyword = contiene tu palabra clave «{ $keyword }»
+route-all-messages = esta sala avisa de todos los mensajes
+route-not-mentioned = esta sala solo avisa de menciones
+
+## Voice calls
+call-start = 📞 Llamada de voz
+call-incoming = { $name } te está llamando
+call-outgoing = Llamando a { $name }…
+call-connecting = Conectando…
+call-active = En llamada con { $name }
+call-accept = Aceptar
+call-decline = Rechazar
+call-mute = Silenciar
+call-unmute = Activar micrófono
+call-hang-up = Colgar
+call-declined = { $name } rechazó la llamada.
+call-busy = { $name } está en otra llamada.
+call-missed = Llamada perdida de { $name }.
+call-ended = La llamada con { $name } terminó.
+call-failed = No se pudo conectar la llamada con { $name }.
+
+## Undo send
+undo-send-pending = Enviando «{ $message }»…
+undo-send = Deshacer
+delivery-sending = Enviando…
+delivery-sent = Enviado
+delivery-retry = No enviado. Reintentar
+delivery-discard = Descartar
+
+## Video calls
+call-start-video = 🎥 Videollamada
+call-incoming-video = { $name } te está videollamando
+call-accept-audio = Solo audio
+call-accept-video = Con vídeo
+call-camera-on = Encender cámara
+call-camera-off = Apagar cámara
+call-microphone = Micrófono
+call-camera = Cámara
+call-dock = Acoplar al lateral
+call-undock = Flotante
+call-picture-in-picture = Imagen en imagen
+call-expand = Ampliar llamada
+
+## Typing indicator
+prefs-privacy = Privacidad
+prefs-typing-signal = Mostrar a otros cuando escribo
+prefs-typing-signal-help = Los comandos con barra nunca se muestran.
+typing-signal-immediate = Al instante
+typing-signal-delayed = Tras unos segundos escribiendo
+typing-signal-never = Nunca
+typing-one = { $name } está escribiendo…
+typing-two = { $first } y { $second } están escribiendo…
+typing-three = { $first }, { $second } y { $third } están escribiendo…
+typing-many = { $first }, { $second } y { $count } más están escribiendo…
+
+## Screen sharing
+call-share-screen = Compartir pantalla
+call-stop-sharing = Dejar de compartir
+
+## Encrypted direct messages
+dm-start = Mensaje privado
+dm-encrypted-help = Cifrado de extremo a extremo: solo vosotros dos podéis leerlo
+dm-no-key = Este usuario aún no tiene clave de cifrado
+dm-unreadable = no se pudo descifrar el mensaje
+dm-other-key = se cifró para una clave que este dispositivo no tiene; restaura tu copia de seguridad de la clave para leer estos mensajes
+dm-failed = Se perdió un mensaje directo de { $name }: { $reason }
+dm-composing = Mensaje cifrado para { $name }
+dm-waiting-for-key = Esperando la clave de cifrado de { $name }
+dm-back-to-room = Volver a la sala
+dm-label = 🔒 Mensaje directo
+dm-to = 🔒 Para { $name }
+
+## Reactions and room info
+room-info = Información de la sala
+room-info-about = Acerca de
+room-info-stats = Estadísticas
+room-info-no-topic = Sin tema
+room-info-members = { $count } miembros
+room-stats-window = Contado sobre los mensajes cargados en esta ventana.
+room-stats-empty = Aún no hay reacciones en los mensajes cargados.
+room-stats-top-reactions = Reacciones principales
+room-stats-top-messages = Mensajes con más reacciones
+room-stats-jump = Ir al mensaje
+room-stats-reaction-count = { $count } reacciones
+
+## Pinned conversations
+dm-pinned = Fijadas
+dm-pin = Fijar conversación
+dm-unpin = Desfijar conversación
+
+## Accounts
+login-password = Contraseña
+login-register = Crear cuenta
+login-need-account = ¿Eres nuevo? Crea una cuenta
+login-have-account = ¿Ya tienes cuenta? Inicia sesión
+auth-password-too-short = La contraseña necesita al menos { $min } caracteres
+auth-invalid-credentials = Usuario o contraseña incorrectos
+auth-username-taken = Ese nombre de usuario ya existe
+auth-failed = No se pudo contactar con el servidor, inténtalo de nuevo
+
+## Unsupported browsers
+unsupported-title = Este navegador no puede ejecutar Yewchat
+unsupported-intro = Faltan algunas funciones de las que depende la aplicación:
+unsupported-advice = Prueba con una versión actual de Chrome, Firefox, Safari o Edge, fuera de la navegación privada y de vistas integradas en otras aplicaciones.
+capability-websocket = WebSockets
+capability-websocket-help = Los mensajes viajan por una conexión en vivo con el servidor. Algunos proxies y vistas integradas la bloquean.
+capability-indexeddb = IndexedDB
+capability-indexeddb-help = Se usa para guardar datos en el navegador. Suele estar desactivado en la navegación privada.
+capability-local-storage = Almacenamiento local
+capability-local-storage-help = Guarda tu sesión y tus ajustes. Se bloquea cuando las cookies y los datos de sitios están desactivados.
+
+## Connection presets
+prefs-connection = Conexión
+prefs-connection-help = Los cambios se aplican al momento, sin reconectar.
+transport-default = Predeterminada
+transport-mobile-friendly = Para móviles (ahorra batería, guarda más sin conexión)
+transport-low-latency = Baja latencia (reconecta rápido)
+
+## OAuth
+oauth-github = Continuar con GitHub
+oauth-google = Continuar con Google
+oauth-signing-in = Iniciando sesión…
+oauth-back = Volver al inicio de sesión
+auth-oauth-rejected = El inicio de sesión se canceló o caducó, inténtalo de nuevo
+
+## Split view
+split-open = Abrir junto a la sala
+split-close = Cerrar panel lateral
+
+## Signing out
+sign-out = Cerrar sesión
+
+## Mini chat
+mini-chat-open = Abrir un mini chat flotante
+mini-chat-placeholder = Mensaje · Intro para enviar
+
+## Focus mode
+focus-mode-enter = Modo concentración (Ctrl+Mayús+F)
+focus-mode-exit = Salir del modo concentración (Ctrl+Mayús+F)
+
+## Username rules
+auth-username-length = Los nombres de usuario tienen entre { $min } y { $max } caracteres
+auth-username-charset = Usa solo letras, números, "_", "-" y "."
+
+## Flood limit
+send-cooldown = Más despacio · { $seconds } s
+
+## Printing
+print-title = Imprimir conversación
+print-help = Deja una fecha vacía para imprimir desde el principio o hasta ahora.
+print-from = Desde
+print-until = Hasta
+print-invalid-range = La fecha final es anterior a la inicial
+print-action = Imprimir
+
+## Word filter
+prefs-word-filter = Palabras filtradas
+prefs-word-filter-placeholder = Separadas por comas, p. ej. rayos, caramba
+prefs-word-filter-help = Se ocultan en los mensajes de otras personas hasta que decidas mostrarlas
+word-filter-reveal = Mostrar palabras filtradas
+
+## Saved messages
+saved-title = Mensajes guardados
+saved-composing = Nota para ti, en todos tus dispositivos
+saved-label = 🔖 Guardado
+
+## Export
+
+export-title = Exportar
+export-help = Descarga los mensajes cargados en esta ventana, incluidos los mensajes directos descifrados.
+export-json = Descargar JSON
+export-text = Descargar texto
+
+## Safety numbers
+
+verify-title = Número de seguridad
+verify-help = Compara estos números con { $name } en persona o por una llamada, o escaneaos el código. Si coinciden, nadie está leyendo vuestros mensajes.
+verify-yours = Tu huella
+verify-theirs = Huella de { $name }
+verify-mark = Marcar como verificado
+verify-clear = Quitar verificación
+verify-verified = Verificado
+verify-open = Verificar
+verify-dismiss = Descartar
+verify-key-changed = La clave de cifrado de { $name } cambió. Puede que tenga un dispositivo nuevo, o que alguien esté interceptando vuestros mensajes. Comparad de nuevo los números de seguridad.
+verify-verified-key-changed = La clave que verificaste para { $name } cambió. No confíes en sus nuevos mensajes directos hasta verificarla de nuevo.
+
+## Key backup
+
+key-backup-title = Clave de cifrado
+key-backup-help = Tus mensajes directos solo se pueden leer con la clave de este dispositivo. Guárdala en un archivo protegido con una frase de contraseña para restaurarla en otro dispositivo o tras borrar este.
+key-backup-passphrase = Frase de contraseña
+key-backup-min-length = Al menos { $count } caracteres. No se puede recuperar si la olvidas.
+key-backup-download = Descargar copia de la clave
+key-backup-restore = Restaurar desde archivo…
+key-backup-restored = Clave de cifrado restaurada
+key-backup-malformed = No es una copia de seguridad de clave de Yewchat.
+key-backup-other-account = Esta copia de seguridad de clave pertenece a otra cuenta.
+key-backup-wrong-passphrase = Frase de contraseña incorrecta, o la copia fue alterada.
+key-backup-too-short = La frase de contraseña es demasiado corta.
+key-backup-failed = No se pudo crear la copia de seguridad de la clave.
+
+## Import
+
+import-title = Abrir un archivo
+import-help = Muestra una exportación JSON en modo de solo lectura, sin contactar con el servidor.
+import-open = Elegir archivo…
+import-too-large = Ese archivo es demasiado grande para ser un archivo de chat.
+import-unreadable = No se pudo leer el archivo.
+import-malformed = No es un archivo de Yewchat: { $reason }
+import-unsupported-version = Este archivo lo creó una versión más reciente (formato { $version }).
+import-missing-sender = El mensaje { $number } del archivo no tiene remitente.
+import-bad-timestamp = El mensaje { $number } del archivo tiene una hora no válida.
+archive-title = Archivo de #{ $room }
+archive-exported = Exportado { $date } · { $count ->
+    [one] 1 mensaje
+   *[other] { $count } mensajes
+}
+archive-empty = Este archivo no tiene mensajes.
+archive-read-only = Archivo de solo lectura. Nada de esto se envía al servidor.
+archive-close = Cerrar archivo
+
+## App updates
+
+update-available = Hay una nueva versión de Yewchat lista.
+update-reload = Recargar
+update-later = Más tarde
+
+## Code blocks
+
+code-copy = Copiar
+code-copied = Copiado
+
+## Date dividers
+
+date-today = Hoy
+date-yesterday = Ayer
+
+## Read receipts
+
+seen-by = Visto por { $names }
+
+## Member list
+
+members-filter = Filtrar personas
+members-sort = Ordenar miembros
+members-sort-room = Orden de la sala
+members-sort-name = A–Z
+members-sort-online = En línea primero
+members-sort-active = Activos recientemente
+members-no-match = Nadie coincide.
+members-partial = Solo se muestran los miembros cargados hasta ahora.
+
+## Accessibility
+
+messages-log = Mensajes
+
+## Custom status
+
+status-placeholder = ¿Cuál es tu estado?
+status-clear = Borrar estado
+status-meeting = En una reunión
+status-commuting = De camino
+status-sick = De baja
+status-vacation = De vacaciones
+
+## Presence
+
+presence-online = Activo
+presence-away = Ausente
+
+## Message length
+
+composer-too-long = Demasiado largo para enviarlo; acorta el mensaje primero
+message-truncated = (recortado)
+
+## Emoji
+
+emoji-picker = Insertar emoji
+
+## Room notifications
+
+channel-notifications = Notificaciones de esta sala
+channel-level-default = Predeterminado ({ $level })
+
+## Quote replies
+
+message-quote = Citar
+quoting-message = Respondiendo a { $name }
+quoting-cancel = Cancelar cita
+quote-jump = Ir al mensaje original
+quote-unavailable = El mensaje original no está cargado
+
+## Forwarding
+
+message-forward = Reenviar
+message-forwarded-from = Reenviado de { $name }
+forward-title = Reenviar a…
+
+## Polls
+
+poll-votes = { $count ->
+    [one] 1 voto
+   *[other] { $count } votos
+}
+poll-closed = Cerrada
+poll-close = Cerrar encuesta
+poll-room-only = Las encuestas solo se pueden publicar en la sala.
+
+## Disappearing messages
+
+disappearing-title = Mensajes temporales
+disappearing-off = Desactivado
+disappearing-countdown = Tiempo restante antes de que desaparezca este mensaje
+duration-seconds = { $count } s
+duration-minutes = { $count } min
+duration-hours = { $count } h
+duration-days = { $count } d
+
+## Scheduled messages
+
+schedule-title = Enviar más tarde
+schedule-help = El mensaje se envía a la hora elegida, siempre que la aplicación esté abierta en ese momento.
+schedule-in-hour = Dentro de una hora
+schedule-tomorrow = Mañana a las 9:00
+schedule-at = Enviar el
+schedule-in-past = Esa hora ya ha pasado.
+schedule-action = Programar
+schedule-done = Programado para el { $time }
+schedule-no-commands = Los comandos no se pueden programar.
+scheduled-count = { $count ->
+    [one] 1 mensaje programado
+   *[other] { $count } mensajes programados
+}
+scheduled-missed = Perdido mientras estaba cerrada
+scheduled-send-now = Enviar ahora
+scheduled-cancel = Cancelar mensaje programado
+
+## Translation
+
+message-translate = Traducir
+translation-loading = Traduciendo…
+translation-show-original = Traducido · Ver original
+translation-show = Ver traducción
+translation-failed = No se pudo traducir el mensaje.
+
+## Narrow screens
+
+sidebar-toggle = Mostrar u ocultar personas
+
+## Keyboard shortcuts
+
+shortcuts-title = Atajos de teclado
+shortcuts-mac = En macOS, usa Cmd en lugar de Ctrl.
+shortcut-quick-switcher = Ir a una conversación
+shortcut-close = Cerrar paneles y diálogos
+shortcut-edit-last = Editar tu último mensaje, con el compositor vacío
+shortcut-previous-conversation = Conversación anterior
+shortcut-next-conversation = Conversación siguiente
+shortcut-focus-mode = Activar o desactivar el modo concentración
+shortcut-action-log = Mostrar u ocultar el registro de acciones
+shortcut-help = Mostrar estos atajos
+switcher-title = Ir a una conversación
+switcher-placeholder = Escribe un nombre…
+switcher-no-match = Ninguna conversación coincide.
+editing-message = Editando mensaje. Pulsa Intro para guardar o Esc para cancelar.
+editing-cancel = Cancelar edición
+message-edited = (editado)
+
+## Bots
+
+bot-badge = BOT
+
+## Action log
+
+action-log-title = Registro de acciones
+action-log-clear = Borrar
+action-log-back = Paso atrás
+action-log-forward = Paso adelante
+action-log-live = Volver al presente
+action-log-empty = Aún no hay nada registrado.
+
+## Slash commands
+
+command-unknown = No existe el comando /{ $name }. Escribe /help para ver la lista.
+command-usage = Uso: { $usage }
+command-help-me = /me <acción> — Describe lo que estás haciendo
+command-help-shrug = /shrug [mensaje] — Añade ¯\_(ツ)_/¯
+command-help-poll = /poll "pregunta" <opción> <opción>… — Inicia una encuesta (entrecomilla las opciones con espacios)
+command-help-clear = /clear — Vacía la vista de mensajes, solo para ti
+command-help-help = /help — Lista los comandos
+
+## Keyboard reactions
+
+reactions-quick = Reacciones rápidas
+reactions-keyboard-hint = ← → para elegir, Intro para reaccionar, Esc para cerrar
+
+## Pasting transcripts
+
+paste-transcript-title = Parece una transcripción de chat. ¿Citarla en lugar de enviarla como tus propias líneas?
+paste-as-quote = Pegar como cita
+paste-as-text = Pegar como texto
+
+## Selecting messages
+select-message = Seleccionar mensaje
+selection-count = { $count ->
+    [one] 1 mensaje seleccionado
+   *[other] { $count } mensajes seleccionados
+}
+selection-copy = Copiar selección
+selection-clear = Cancelar
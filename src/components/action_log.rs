@@ -0,0 +1,79 @@
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::action_log::LoggedAction;
+use crate::services::i18n::t;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub entries: Vec<LoggedAction>,
+    pub can_step_back: bool,
+    pub can_step_forward: bool,
+    pub on_back: Callback<()>,
+    pub on_forward: Callback<()>,
+    /// Returns to the latest state after stepping through.
+    pub on_live: Callback<()>,
+    pub on_clear: Callback<()>,
+    pub on_close: Callback<()>,
+}
+
+fn format_time(millis: f64) -> String {
+    let date = js_sys::Date::new(&millis.into());
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        date.get_hours(),
+        date.get_minutes(),
+        date.get_seconds(),
+        date.get_milliseconds()
+    )
+}
+
+/// Debug builds only: the messages the chat handled, newest first, with what
+/// each changed. Stepping back and forth puts the state around them back on
+/// screen; actions stepped back past are dimmed.
+#[function_component(ActionLogPanel)]
+pub fn action_log_panel(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let rows = props
+        .entries
+        .iter()
+        .map(|entry| {
+            html! {
+                <tr class={classes!("border-t", "border-gray-300", "dark:border-gray-700", "align-top", (!entry.applied).then_some("opacity-40"))}>
+                    <td class="py-1 pr-3 whitespace-nowrap text-gray-500 dark:text-gray-400">{format_time(entry.at)}</td>
+                    <td class="py-1 font-mono text-xs break-all">
+                        {entry.action.clone()}
+                        <ul class="text-gray-500 dark:text-gray-400">
+                            { for entry.changes.iter().map(|change| html! {<li>{"· "}{change}</li>}) }
+                        </ul>
+                    </td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+    let button_class = "px-3 py-1 mr-3 rounded bg-gray-200 dark:bg-gray-700 text-sm disabled:opacity-50";
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("action-log-title")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-3xl max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t("action-log-title")}</div>
+                    <div>
+                        <button onclick={props.on_back.reform(|_| ())} disabled={!props.can_step_back} class={button_class}>{"◀ "}{t("action-log-back")}</button>
+                        <button onclick={props.on_forward.reform(|_| ())} disabled={!props.can_step_forward} class={button_class}>{t("action-log-forward")}{" ▶"}</button>
+                        <button onclick={props.on_live.reform(|_| ())} disabled={!props.can_step_forward} class={button_class}>{t("action-log-live")}</button>
+                        <button onclick={props.on_clear.reform(|_| ())} class={button_class}>{t("action-log-clear")}</button>
+                        <button onclick={props.on_close.reform(|_| ())} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                    </div>
+                </div>
+                {
+                    if props.entries.is_empty() {
+                        html! {<div class="text-sm text-gray-500 dark:text-gray-400">{t("action-log-empty")}</div>}
+                    } else {
+                        html! {<table class="w-full text-sm text-left"><tbody>{rows}</tbody></table>}
+                    }
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,101 @@
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::components::message_content::{self, RenderOptions};
+use crate::services::archive::Archive;
+use crate::services::i18n::{t, t_args};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub archive: Archive,
+    pub options: RenderOptions,
+    pub on_enlarge: Callback<String>,
+    pub on_close: Callback<()>,
+}
+
+fn format_time(millis: f64) -> String {
+    js_sys::Date::new(&millis.into())
+        .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+        .into()
+}
+
+/// A read-only look at an imported export, covering the chat until closed.
+#[function_component(ArchiveView)]
+pub fn archive_view(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let archive = &props.archive;
+    let messages = archive
+        .messages
+        .iter()
+        .map(|m| {
+            html! {
+                <div class="mb-4">
+                    <div class="text-sm">
+                        <span class="font-semibold">{m.from.clone()}</span>
+                        {
+                            match m.timestamp {
+                                Some(ts) => html! {<span class="ml-2 text-xs text-gray-500 dark:text-gray-400">{format_time(ts)}</span>},
+                                None => html! {},
+                            }
+                        }
+                        {
+                            if m.direct.is_some() {
+                                html! {<span class="ml-2 text-xs text-green-600 dark:text-green-400">{t("dm-label")}</span>}
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </div>
+                    <div class="text-gray-700 dark:text-gray-200 mt-1">
+                        {
+                            match &m.attachment {
+                                Some(attachment) => message_content::render_attachment(attachment, &props.options, props.on_enlarge.clone()),
+                                None => html! {},
+                            }
+                        }
+                        {
+                            if m.message.is_empty() {
+                                html! {}
+                            } else {
+                                message_content::render(&m.message, &props.options)
+                            }
+                        }
+                    </div>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div
+            ref={panel}
+            tabindex="-1"
+            role="dialog"
+            aria-modal="true"
+            aria-label={t_args("archive-title", &[("room", &archive.room)])}
+            class="fixed inset-0 z-20 flex flex-col bg-gray-100 dark:bg-gray-900 text-gray-900 dark:text-white outline-none"
+        >
+            <div class="flex items-center px-6 py-3 border-b border-gray-300 dark:border-gray-700">
+                <div class="flex-grow">
+                    <div class="text-xl">{t_args("archive-title", &[("room", &archive.room)])}</div>
+                    <div class="text-xs text-gray-500 dark:text-gray-400">
+                        {t_args("archive-exported", &[("date", &format_time(archive.exported_at)), ("count", &archive.messages.len().to_string())])}
+                    </div>
+                </div>
+                <button onclick={props.on_close.reform(|_| ())} title={t("archive-close")} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("archive-close")}>{"✕"}</button>
+            </div>
+            <div class="flex-grow overflow-y-auto px-6 py-4">
+                {
+                    if archive.messages.is_empty() {
+                        html! {<div class="text-sm text-gray-500 dark:text-gray-400">{t("archive-empty")}</div>}
+                    } else {
+                        messages
+                    }
+                }
+            </div>
+            <div class="px-6 py-2 text-xs text-center text-gray-500 dark:text-gray-400 border-t border-gray-300 dark:border-gray-700">
+                {t("archive-read-only")}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,65 @@
+use web_sys::{File, Url};
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::attachments;
+use crate::services::i18n::t;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub file: File,
+    pub on_confirm: Callback<()>,
+    pub on_cancel: Callback<()>,
+}
+
+/// Asks for confirmation before a file that was not explicitly picked (e.g.
+/// dropped onto the chat) is sent.
+#[function_component(AttachmentPreview)]
+pub fn attachment_preview(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let preview = use_state(|| None::<String>);
+    {
+        let preview = preview.clone();
+        use_effect_with_deps(
+            move |file: &File| {
+                let url = if attachments::is_image(&file.type_()) {
+                    Url::create_object_url_with_blob(file).ok()
+                } else {
+                    None
+                };
+                preview.set(url.clone());
+                move || {
+                    if let Some(url) = url {
+                        let _ = Url::revoke_object_url(&url);
+                    }
+                }
+            },
+            props.file.clone(),
+        );
+    }
+
+    let file = &props.file;
+    let size = attachments::format_size(file.size() as u64);
+    let on_confirm = props.on_confirm.reform(|_| ());
+    let on_cancel = props.on_cancel.reform(|_| ());
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-20">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("attachment-confirm-title")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md outline-none">
+                <div class="text-xl mb-4">{t("attachment-confirm-title")}</div>
+                {
+                    match &*preview {
+                        Some(src) => html! {<img src={src.clone()} alt={file.name()} class="max-h-64 mx-auto rounded mb-4"/>},
+                        None => html! {},
+                    }
+                }
+                <div class="text-sm break-all">{file.name()}</div>
+                <div class="text-xs text-gray-500 dark:text-gray-400 mb-6">{size}</div>
+                <div class="flex justify-end">
+                    <button onclick={on_cancel} class="px-4 py-2 mr-2 rounded bg-gray-200 dark:bg-gray-700">{t("cancel")}</button>
+                    <button onclick={on_confirm} class="px-4 py-2 rounded bg-green-600 text-white">{t("send")}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,326 @@
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlSelectElement, HtmlVideoElement, MediaStream};
+use yew::prelude::*;
+
+use crate::services::i18n::{t, t_args};
+use crate::services::webrtc::{self, CallState, Device, DeviceKind};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    Floating,
+    Docked,
+    /// Just the peer's video in a corner, for chatting alongside the call.
+    Mini,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub peer_name: String,
+    pub state: CallState,
+    pub muted: bool,
+    /// Whether the ringing peer offered video.
+    #[prop_or_default]
+    pub offered_video: bool,
+    #[prop_or_default]
+    pub camera_off: bool,
+    #[prop_or_default]
+    pub local_stream: Option<MediaStream>,
+    #[prop_or_default]
+    pub remote_stream: Option<MediaStream>,
+    /// Our own screen share, if running.
+    #[prop_or_default]
+    pub screen_stream: Option<MediaStream>,
+    #[prop_or_default]
+    pub remote_screen_stream: Option<MediaStream>,
+    #[prop_or_default]
+    pub can_share_screen: bool,
+    #[prop_or_default]
+    pub on_toggle_screen_share: Callback<()>,
+    /// Receives whether to answer with video.
+    pub on_accept: Callback<bool>,
+    pub on_decline: Callback<()>,
+    pub on_toggle_mute: Callback<()>,
+    #[prop_or_default]
+    pub on_toggle_camera: Callback<()>,
+    #[prop_or_default]
+    pub on_switch_device: Callback<(DeviceKind, String)>,
+    pub on_hang_up: Callback<()>,
+}
+
+fn has_video(stream: &Option<MediaStream>) -> bool {
+    stream
+        .as_ref()
+        .is_some_and(|s| s.get_video_tracks().length() > 0)
+}
+
+/// Points a `<video>` at `stream`, leaving it alone if it already plays it
+/// so that re-renders don't restart playback.
+fn attach(video: &NodeRef, stream: &Option<MediaStream>) {
+    if let Some(video) = video.cast::<HtmlVideoElement>() {
+        if video.src_object() != *stream {
+            video.set_src_object(stream.as_ref());
+        }
+    }
+}
+
+#[function_component(CallPanel)]
+pub fn call_panel(props: &Props) -> Html {
+    let layout = use_state(|| Layout::Floating);
+    let devices = use_state(Vec::<Device>::new);
+    let remote_video = use_node_ref();
+    let local_video = use_node_ref();
+    let screen_video = use_node_ref();
+
+    {
+        // Labels only become available once media access was granted, so
+        // look again after the call is set up.
+        let devices = devices.clone();
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move { devices.set(webrtc::devices().await) });
+                || ()
+            },
+            props.local_stream.is_some(),
+        );
+    }
+    // A screen share, ours or theirs, takes the large spot. Theirs wins if
+    // both are sharing.
+    let screen = props
+        .remote_screen_stream
+        .clone()
+        .or_else(|| props.screen_stream.clone());
+    {
+        let (remote_video, local_video, screen_video) =
+            (remote_video.clone(), local_video.clone(), screen_video.clone());
+        let (remote, local, screen) =
+            (props.remote_stream.clone(), props.local_stream.clone(), screen.clone());
+        use_effect(move || {
+            attach(&remote_video, &remote);
+            attach(&local_video, &local);
+            attach(&screen_video, &screen);
+            || ()
+        });
+    }
+
+    let name = props.peer_name.as_str();
+    let button = "px-3 py-1 rounded text-sm";
+    let hang_up = html! {
+        <button onclick={props.on_hang_up.reform(|_| ())} class={classes!(button, "bg-red-600", "text-white")}>
+            {t("call-hang-up")}
+        </button>
+    };
+    let in_call = matches!(props.state, CallState::Connecting | CallState::Active);
+    let show_video = in_call
+        && (screen.is_some() || has_video(&props.remote_stream) || has_video(&props.local_stream));
+
+    let (status, controls) = match props.state {
+        CallState::Incoming => {
+            let accept = |video: bool, label: String| html! {
+                <button onclick={props.on_accept.reform(move |_| video)} class={classes!(button, "ml-2", "bg-green-600", "text-white")}>
+                    {label}
+                </button>
+            };
+            let key = if props.offered_video { "call-incoming-video" } else { "call-incoming" };
+            (
+                t_args(key, &[("name", name)]),
+                html! {
+                    <>
+                    <button onclick={props.on_decline.reform(|_| ())} class={classes!(button, "bg-red-600", "text-white")}>
+                        {t("call-decline")}
+                    </button>
+                    {
+                        if props.offered_video {
+                            html! {<>{accept(false, t("call-accept-audio"))}{accept(true, t("call-accept-video"))}</>}
+                        } else {
+                            accept(false, t("call-accept"))
+                        }
+                    }
+                    </>
+                },
+            )
+        }
+        CallState::Outgoing => (t_args("call-outgoing", &[("name", name)]), hang_up),
+        CallState::Connecting | CallState::Active => {
+            let status = if props.state == CallState::Active {
+                t_args("call-active", &[("name", name)])
+            } else {
+                t("call-connecting")
+            };
+            let mute_label = if props.muted { t("call-unmute") } else { t("call-mute") };
+            let camera = if has_video(&props.local_stream) {
+                let label = if props.camera_off { t("call-camera-on") } else { t("call-camera-off") };
+                html! {
+                    <button onclick={props.on_toggle_camera.reform(|_| ())} class={classes!(button, "mr-2", "bg-gray-200", "dark:bg-gray-700")}>
+                        {label}
+                    </button>
+                }
+            } else {
+                html! {}
+            };
+            let share = if props.can_share_screen {
+                let label = if props.screen_stream.is_some() { t("call-stop-sharing") } else { t("call-share-screen") };
+                html! {
+                    <button onclick={props.on_toggle_screen_share.reform(|_| ())} class={classes!(button, "mr-2", "bg-gray-200", "dark:bg-gray-700")}>
+                        {label}
+                    </button>
+                }
+            } else {
+                html! {}
+            };
+            (
+                status,
+                html! {
+                    <>
+                    <button onclick={props.on_toggle_mute.reform(|_| ())} class={classes!(button, "mr-2", "bg-gray-200", "dark:bg-gray-700")}>
+                        {mute_label}
+                    </button>
+                    {camera}
+                    {share}
+                    {hang_up}
+                    </>
+                },
+            )
+        }
+    };
+
+    let device_select = |kind: DeviceKind| {
+        let options: Vec<&Device> = devices.iter().filter(|d| d.kind == kind).collect();
+        if options.len() < 2 || (kind == DeviceKind::Camera && !has_video(&props.local_stream)) {
+            return html! {};
+        }
+        let onchange = props.on_switch_device.reform(move |e: Event| {
+            (kind, e.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+        let label = match kind {
+            DeviceKind::Microphone => t("call-microphone"),
+            DeviceKind::Camera => t("call-camera"),
+        };
+        html! {
+            <label class="flex items-center text-xs text-gray-600 dark:text-gray-300 mb-2">
+                <span class="w-20">{label}</span>
+                <select {onchange} class="flex-grow bg-gray-200 dark:bg-gray-700 rounded px-2 py-1">
+                    {
+                        options.iter().enumerate().map(|(i, device)| {
+                            let label = if device.label.is_empty() { format!("#{}", i + 1) } else { device.label.clone() };
+                            html! {<option value={device.id.clone()}>{label}</option>}
+                        }).collect::<Html>()
+                    }
+                </select>
+            </label>
+        }
+    };
+
+    let set_layout = |target: Layout| {
+        let layout = layout.clone();
+        Callback::from(move |_| layout.set(target))
+    };
+    let picture_in_picture = {
+        let target = if screen.is_some() { screen_video.clone() } else { remote_video.clone() };
+        let layout = layout.clone();
+        Callback::from(move |_| match target.cast::<HtmlVideoElement>() {
+            Some(video) if webrtc::picture_in_picture_supported() => {
+                webrtc::request_picture_in_picture(&video)
+            }
+            _ => layout.set(Layout::Mini),
+        })
+    };
+    let layout_button = "text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white ml-2";
+
+    let videos = if show_video && screen.is_some() {
+        let thumbnail = "w-24 rounded border border-white object-cover bg-black";
+        html! {
+            <div class="relative bg-black rounded overflow-hidden mb-3">
+                <video ref={screen_video.clone()} autoplay=true muted=true playsinline=true class="w-full aspect-video object-contain"/>
+                {
+                    if *layout == Layout::Mini {
+                        html! {}
+                    } else {
+                        html! {
+                            <div class="absolute bottom-2 right-2 flex">
+                                <video ref={remote_video.clone()} autoplay=true muted=true playsinline=true class={classes!(thumbnail, "mr-2")}/>
+                                <video
+                                    ref={local_video.clone()}
+                                    autoplay=true
+                                    muted=true
+                                    playsinline=true
+                                    class={classes!(thumbnail, props.camera_off.then_some("invisible"))}
+                                />
+                            </div>
+                        }
+                    }
+                }
+            </div>
+        }
+    } else if show_video {
+        html! {
+            <div class="relative bg-black rounded overflow-hidden mb-3">
+                <video ref={remote_video.clone()} autoplay=true muted=true playsinline=true class="w-full aspect-video object-cover"/>
+                {
+                    if *layout == Layout::Mini {
+                        html! {}
+                    } else {
+                        html! {
+                            <video
+                                ref={local_video.clone()}
+                                autoplay=true
+                                muted=true
+                                playsinline=true
+                                class={classes!("absolute", "bottom-2", "right-2", "w-24", "rounded", "border", "border-white", "object-cover", props.camera_off.then_some("invisible"))}
+                            />
+                        }
+                    }
+                }
+            </div>
+        }
+    } else {
+        html! {}
+    };
+
+    if *layout == Layout::Mini && show_video {
+        return html! {
+            <div class="fixed bottom-24 right-6 z-20 w-48 shadow-lg cursor-pointer" title={t("call-expand")} onclick={set_layout(Layout::Floating)}>
+                {videos}
+            </div>
+        };
+    }
+
+    let container = match *layout {
+        Layout::Docked => "fixed top-0 right-0 h-full w-96 z-20 flex flex-col justify-center bg-white dark:bg-gray-800 text-gray-900 dark:text-white shadow-lg p-4",
+        _ => "fixed bottom-24 right-6 z-20 w-80 bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg shadow-lg p-4",
+    };
+
+    html! {
+        <div class={container}>
+            {
+                if show_video {
+                    html! {
+                        <div class="flex justify-end text-sm mb-2">
+                            <button onclick={picture_in_picture} title={t("call-picture-in-picture")} class={layout_button} aria-label={t("call-picture-in-picture")}>{"⧉"}</button>
+                            {
+                                if *layout == Layout::Docked {
+                                    html! {<button onclick={set_layout(Layout::Floating)} title={t("call-undock")} class={layout_button} aria-label={t("call-undock")}>{"⇱"}</button>}
+                                } else {
+                                    html! {<button onclick={set_layout(Layout::Docked)} title={t("call-dock")} class={layout_button} aria-label={t("call-dock")}>{"⇥"}</button>}
+                                }
+                            }
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            {videos}
+            <div class={classes!("text-sm", "mb-3", (props.state == CallState::Incoming).then_some("animate-pulse"))}>
+                {"📞 "}{status}
+            </div>
+            {
+                if in_call {
+                    html! {<>{device_select(DeviceKind::Microphone)}{device_select(DeviceKind::Camera)}</>}
+                } else {
+                    html! {}
+                }
+            }
+            <div class="flex justify-end">{controls}</div>
+        </div>
+    }
+}
@@ -1,20 +1,671 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::mem;
+use std::ops::Range;
+
+use gloo_events::EventListener;
+use gloo_timers::callback::{Interval, Timeout};
 use serde::{Deserialize, Serialize};
-use web_sys::HtmlInputElement;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{DragEvent, Element, File, HtmlElement, HtmlTextAreaElement, KeyboardEvent};
 use yew::prelude::*;
 use yew_agent::{Bridge, Bridged};
+use yew_router::prelude::*;
 
+use crate::components::attachment_preview::AttachmentPreview;
+use crate::components::call_panel::CallPanel;
+use crate::components::composer::{self, Composer};
+use crate::components::digest_settings::DigestSettings;
+use crate::components::gif_picker::GifPicker;
+use crate::components::invite_dialog::InviteDialog;
+use crate::components::join_requests::JoinRequests;
+use crate::components::message_content::{self, RenderOptions};
+use crate::components::moderation_log::ModerationLog;
+use crate::components::action_log::ActionLogPanel;
+use crate::components::archive_view::ArchiveView;
+use crate::components::notification_center::NotificationCenter;
+use crate::components::pinned_dms::{PinnedDm, PinnedDms};
+use crate::components::poll_card::PollCard;
+use crate::components::settings::SettingsScreen;
+use crate::components::key_backup::KeyBackup as KeyBackupSettings;
+use crate::components::print_dialog::{PrintDialog, PrintRange};
+use crate::components::quick_switcher::{Destination, QuickSwitcher};
+use crate::components::room_info::{RoomInfoPanel, TopMessage};
+use crate::components::rules_modal::RulesModal;
+use crate::components::schedule_dialog::ScheduleDialog;
+use crate::components::safety_number::SafetyNumberDialog;
+use crate::components::session_list::SessionList;
+use crate::components::shortcut_help::ShortcutHelp;
+use crate::components::welcome_card::WelcomeCard;
+use crate::services::action_log::{self, ActionLog, Snapshot};
+use crate::services::archive::{self, Archive, ArchiveError, ArchivedMessage, ExportFormat};
+use crate::services::attachments::{self, Attachment, AttachmentError};
+use crate::services::auth;
+use crate::services::avatar::{self, AvatarStyle};
+use crate::services::block_list::BlockList;
+use crate::services::clipboard;
+use crate::services::commands::{self, Outcome};
+use crate::services::digest::DigestPrefs;
+use crate::services::gif_search;
+use crate::services::i18n::{self, t, t_args};
+use crate::services::link_preview::{self, LinkPreview};
+use crate::services::document_pip;
+use crate::services::drafts::DraftStore;
+use crate::services::emoji;
+use crate::services::e2ee::{self, BackupError, CryptoError, KeyBackup, KeyPair, Passphrase, Sealed};
 use crate::services::event_bus::EventBus;
-use crate::{services::websocket::WebsocketService, User};
+use crate::services::moderation::{ModAction, ModActionKind, Role};
+use crate::services::notification_router::{Incoming, NotificationRouter, Routed};
+use crate::services::notifications;
+use crate::services::polls::{NewPoll, Poll, PollClose, PollVote};
+use crate::services::presence::{IdleWatcher, Presence};
+use crate::services::reactions::{self, Reactions, RoomStats};
+use crate::services::rate_limit::SendThrottle;
+use crate::services::rooms::{self, Invitation, JoinRequest, Membership, RoomInfo, RoomRules, WelcomeRule};
+use crate::services::sanitize;
+use crate::services::scheduler::Scheduler;
+use crate::services::session::{self, SessionEntry};
+use crate::services::settings::{NotificationLevel, Settings, DISAPPEARING_AFTER};
+use crate::services::theme;
+use crate::services::translate;
+use crate::services::shortcuts::{self, Action};
+use crate::services::typing::{self, TypingBroadcaster, TypingSignal};
+use crate::services::webrtc::{
+    self, Call, CallAnswer, CallCandidate, CallEnd, CallEvent, CallOffer, CallState, DeviceKind,
+    EndReason, Signal,
+};
+use crate::services::verification::{KeySeen, KnownKeys};
+use crate::services::word_filter;
+use crate::services::sound;
+use crate::{services::websocket::WebsocketService, Route, User};
 
+#[derive(Debug)]
 pub enum Msg {
     HandleMsg(String),
     SubmitMessage,
+    ToggleMute(String),
+    ToggleBlock(String),
+    RevealMessage(usize),
+    UseTemplate(&'static str),
+    SetTopic,
+    ToggleNotifications,
+    VisibilityChanged,
+    WindowFocused,
+    TogglePlaintext,
+    ToggleSound,
+    ToggleSettingsPanel,
+    UpdateSettings(Settings),
+    CycleTheme,
+    RevokeSession(String),
+    PaneScrolled,
+    JumpToLatest,
+    DraftChanged(String),
+    ToggleModerationLog,
+    /// Debug builds only; see [`ActionLog`].
+    ToggleActionLog,
+    ClearActionLog,
+    /// Puts back the state before the latest action still applied.
+    ActionLogBack,
+    /// Puts back the state after the next action stepped back past.
+    ActionLogForward,
+    /// Puts back the latest state after stepping through the log.
+    ActionLogLive,
+    ToggleMessageMenu(usize),
+    HideMessage(String),
+    DeleteMessage(String),
+    /// Presses a bot message's button, by message id and payload.
+    SendBotCommand(String, String),
+    /// Kicks, bans or purges a user, by user id, once confirmed.
+    Moderate(ModActionKind, String),
+    /// Toggles our reaction on a message, by message id and emoji.
+    React(String, String),
+    ToggleRoomInfo,
+    /// Scrolls to a message, by index.
+    JumpToMessage(usize),
+    AttachFile(File),
+    PreviewFile(File),
+    ConfirmPendingFile,
+    CancelPendingFile,
+    DragActive(bool),
+    UploadProgress(usize, f64),
+    AttachmentReady(usize, Result<Attachment, AttachmentError>),
+    EnlargeImage(Option<String>),
+    SidebarScrolled,
+    MentionQuery(Option<String>),
+    ToggleInviteDialog,
+    InviteSearch(String),
+    InviteUsers(Vec<String>),
+    ToggleNotificationCenter,
+    RespondToInvitation(String, bool),
+    DismissNotice(usize),
+    ToggleJoinRequests,
+    DecideJoinRequest(String, bool),
+    LinkPreviewLoaded(String, Option<LinkPreview>),
+    /// Translates a message, by id.
+    Translate(String),
+    Translated(String, Result<String, String>),
+    /// Switches a translated message between its translation and original.
+    ToggleOriginal(String),
+    TogglePreview(usize),
+    ToggleGifPicker,
+    SendGif(String),
+    ToggleRules,
+    AcceptRules,
+    SaveDigestPrefs(DigestPrefs),
+    /// Opens or closes the profile popover of a member, by user id.
+    ToggleProfile(String),
+    /// Overrides the room's notification level; `None` goes back to the
+    /// default from the settings.
+    SetChannelLevel(Option<NotificationLevel>),
+    /// Sets our status: an emoji, if any, and the text. An empty text clears it.
+    SetStatus(Option<String>, String),
+    /// We went away or came back.
+    PresenceChanged(Presence),
+    /// Calls a user by id, with or without video.
+    StartCall(String, bool),
+    Call(CallEvent),
+    AcceptCall(bool),
+    ToggleCallMute,
+    ToggleCallCamera,
+    ToggleScreenShare,
+    SwitchCallDevice(DeviceKind, String),
+    EndCall,
+    /// Points the composer at an encrypted direct message to a user, by id,
+    /// or back at the room.
+    SetDirectPeer(Option<String>),
+    /// Opens a direct message conversation in a pane beside the room, by user
+    /// id, or closes the pane.
+    SetSplitPeer(Option<String>),
+    SignOut,
+    ToggleFocusMode,
+    TogglePrintDialog,
+    /// Adds a message to the selection, or removes it; with `true`, selects
+    /// everything from the last one picked up to it.
+    SelectMessage(usize, bool),
+    ClearSelection,
+    CopySelection,
+    DismissCommandNotice,
+    /// Downloads the loaded messages.
+    ExportHistory(ExportFormat),
+    /// Reads an exported archive to show it read-only.
+    OpenArchive(File),
+    ArchiveLoaded(Result<Archive, ArchiveError>),
+    CloseArchive,
+    /// Downloads our encryption key, sealed under the passphrase.
+    BackupKey(Passphrase),
+    /// Reads a key backup to replace our key with the one in it.
+    RestoreKey(File, Passphrase),
+    KeyBackupLoaded(Result<String, BackupError>, Passphrase),
+    /// Shows the safety number of our conversation with a peer, or closes it.
+    ShowSafetyNumber(Option<String>),
+    /// Marks a peer's current key as verified.
+    VerifyKey(String),
+    UnverifyKey(String),
+    /// Hides the warning that a peer's key changed, without verifying it.
+    AcknowledgeKeyChange(String),
+    /// A message received keyboard focus, by index.
+    MessageFocused(usize),
+    /// A navigation key pressed on a focused message: the arrows move between
+    /// messages, `+` opens the quick reactions, which the left and right
+    /// arrows, Enter and Escape then drive.
+    MessageKey(usize, String),
+    /// Lays out the messages in range for printing and opens the print dialog.
+    PrintConversation(PrintRange),
+    PrintDone,
+    /// Shows the current conversation in a compact always-on-top window, or
+    /// in a floating panel where that is not supported.
+    PopOut,
+    /// The picture-in-picture window, or `None` if it could not be opened.
+    MiniWindowOpened(Option<web_sys::Window>),
+    CloseMiniChat,
+    MiniSend,
+    /// Re-checks the flood limit's cooldown, once a second while it lasts.
+    CooldownTick,
+    SubmitSplitMessage,
+    /// Pins or unpins the direct message conversation with a user, by id.
+    TogglePinnedDm(String),
+    MovePinnedDm(usize, usize),
+    DispatchMessage(usize),
+    UndoSend(usize),
+    FilterMembers(String),
+    SortMembers(MemberSort),
+    Shortcut(Action),
+    /// Opens or closes the member list drawer on narrow screens.
+    ToggleSidebar,
+    ViewportResized,
+    /// Opens a conversation picked in the quick switcher.
+    QuickSwitch(Option<String>),
+    CancelEdit,
+    ToggleScheduleDialog,
+    /// Schedules the composer text for the open conversation, at
+    /// milliseconds since the epoch.
+    ScheduleMessage(f64),
+    ToggleScheduled,
+    /// The scheduler's timer fired.
+    ScheduledDue,
+    CancelScheduled(u64),
+    /// Sends a scheduled message whose time passed while the app was closed.
+    SendScheduledNow(u64),
+    /// Sets how long our messages in the open conversation last, in
+    /// seconds; `None` keeps them.
+    SetDisappearing(Option<u32>),
+    ExpiryTick,
+    /// Votes in a poll by id, or takes our vote back with `None`.
+    VotePoll(String, Option<usize>),
+    ClosePoll(String),
+    /// Copies the text of the message at this index.
+    CopyMessage(usize),
+    /// Copies a link to the message at this index.
+    CopyPermalink(usize),
+    /// Scrolls to the message the address points at, loading older history
+    /// until it turns up.
+    OpenPermalink,
+    ClearHighlight,
+    /// Shows a short-lived confirmation at the bottom of the screen.
+    ShowToast(String),
+    HideToast,
+    /// Quotes the message at this index in our next one.
+    QuoteMessage(usize),
+    CancelQuote,
+    /// Opens the conversation picker to forward the message at this index.
+    ForwardMessage(usize),
+    /// Sends the message being forwarded to the picked conversation, or
+    /// closes the picker.
+    Forward(Option<Option<String>>),
+    /// A sent message was not echoed back in time, by client id.
+    DeliveryTimedOut(String),
+    RetrySend(String),
+    DiscardFailed(String),
+    /// Someone's typing signal ran out without being renewed, by user id.
+    TypingExpired(String),
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryRequest {
+    /// The oldest room message the client has, by id; the page holds the
+    /// ones sent before it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<String>,
+    /// Number of room messages the client already has, counted from the
+    /// newest, for servers that page by count.
+    offset: usize,
+    limit: usize,
+}
+
+/// The part of another room message a reply quotes, shown above the reply
+/// and linking back to it by id.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Quote {
+    id: String,
+    from: String,
+    excerpt: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 struct MessageData {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default, rename = "fromId")]
+    from_id: Option<String>,
     from: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    attachment: Option<Attachment>,
+    #[serde(default)]
+    reactions: Reactions,
+    /// Milliseconds since the epoch, from the server or else when the
+    /// message arrived. Unknown for history from older servers.
+    #[serde(default)]
+    timestamp: Option<f64>,
+    /// Sent by a bot or webhook rather than a person.
+    #[serde(default)]
+    bot: bool,
+    /// Buttons a bot attached to the message.
+    #[serde(default)]
+    actions: Vec<BotAction>,
+    /// Our own id for a message we sent, relayed back in the broadcast so it
+    /// can be matched with the local copy.
+    #[serde(default, rename = "clientId")]
+    client_id: Option<String>,
+    #[serde(default)]
+    edited: bool,
+    #[serde(default)]
+    quote: Option<Quote>,
+    /// Who originally wrote a forwarded message.
+    #[serde(default, rename = "forwardedFrom")]
+    forwarded_from: Option<String>,
+    #[serde(default)]
+    poll: Option<Poll>,
+    /// Seconds after `timestamp` at which the message disappears.
+    #[serde(default, rename = "expiresIn")]
+    expires_in: Option<u32>,
+    #[serde(skip)]
+    deleted: bool,
+    /// The sender's name at the time the message was sent, if they have since renamed.
+    #[serde(skip)]
+    renamed_from: Option<String>,
+    /// Set on the local-only onboarding message for a room.
+    #[serde(skip)]
+    welcome: Option<WelcomeRule>,
+    /// The other side of an encrypted direct message, by user id.
+    #[serde(skip)]
+    direct: Option<String>,
+    /// Set on our local copy of a sent message until the server has it.
+    #[serde(skip)]
+    delivery: Option<Delivery>,
+    /// The text arrived longer than [`MAX_MESSAGE_LEN`] and was cut.
+    #[serde(skip)]
+    truncated: bool,
+}
+
+/// How the sidebar orders the room's members.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MemberSort {
+    /// As the server lists them, paged in for large rooms.
+    RoomOrder,
+    Alphabetical,
+    OnlineFirst,
+    /// By their latest message among those loaded.
+    RecentlyActive,
+}
+
+impl MemberSort {
+    const ALL: [MemberSort; 4] = [
+        MemberSort::RoomOrder,
+        MemberSort::Alphabetical,
+        MemberSort::OnlineFirst,
+        MemberSort::RecentlyActive,
+    ];
+
+    fn label(&self) -> String {
+        t(match self {
+            MemberSort::RoomOrder => "members-sort-room",
+            MemberSort::Alphabetical => "members-sort-name",
+            MemberSort::OnlineFirst => "members-sort-online",
+            MemberSort::RecentlyActive => "members-sort-active",
+        })
+    }
+}
+
+/// Where our local copy of a sent message stands until the server has it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Delivery {
+    Sending,
+    Failed,
+    /// Given up on after failing. Kept so message indices stay put, but not
+    /// listed.
+    Discarded,
+}
+
+/// A room message as we send it; the server broadcasts it as [`MessageData`]
+/// with the same `client_id`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutgoingMessage<'a> {
+    client_id: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quote: Option<&'a Quote>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    forwarded_from: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_in: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct BotAction {
+    label: String,
+    /// Handed back to the bot as is when the button is pressed.
+    payload: String,
+}
+
+/// A bot message's button being pressed, routed by the server to the bot
+/// that sent the message.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BotCommand<'a> {
+    message_id: &'a str,
+    payload: &'a str,
+}
+
+impl From<&MessageData> for ArchivedMessage {
+    fn from(m: &MessageData) -> Self {
+        ArchivedMessage {
+            id: m.id.clone(),
+            from_id: m.from_id.clone(),
+            from: m.from.clone(),
+            message: m.message.clone(),
+            attachment: m.attachment.clone(),
+            timestamp: m.timestamp,
+            direct: m.direct.clone(),
+            bot: m.bot,
+        }
+    }
+}
+
+impl MessageData {
+    /// Legacy payloads without a sender id are keyed on the display name.
+    fn sender_id(&self) -> &str {
+        self.from_id.as_deref().unwrap_or(&self.from)
+    }
+
+    /// When a disappearing message is due, in milliseconds since the epoch.
+    fn expires_at(&self) -> Option<f64> {
+        Some(self.timestamp? + f64::from(self.expires_in?) * 1000.0)
+    }
+
+    /// Text used wherever a one-line summary is needed, e.g. notifications.
+    fn preview(&self) -> String {
+        if let Some(poll) = &self.poll {
+            return format!("📊 {}", poll.question);
+        }
+        match &self.attachment {
+            Some(attachment) if self.message.is_empty() => format!("📎 {}", attachment.name),
+            _ => self.message.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MemberPageRequest<'a> {
+    room: &'a str,
+    offset: usize,
+    limit: usize,
+}
+
+#[derive(Deserialize)]
+struct MemberPage {
+    room: String,
+    /// Current size of the room, so the full list can be laid out before
+    /// every page has arrived.
+    total: usize,
+    offset: usize,
+    members: Vec<UserEntry>,
+}
+
+#[derive(Serialize)]
+struct UserSearchRequest<'a> {
+    query: &'a str,
+    limit: usize,
+}
+
+#[derive(Deserialize)]
+struct UserSearchResults {
+    query: String,
+    results: Vec<UserEntry>,
+}
+
+#[derive(Serialize)]
+struct InviteRequest<'a> {
+    room: &'a str,
+    users: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct TypingUpdate<'a> {
+    room: &'a str,
+    typing: bool,
+}
+
+#[derive(Deserialize)]
+struct TypingEvent {
+    room: String,
+    id: String,
+    #[serde(default)]
+    name: String,
+    typing: bool,
+}
+
+/// A reaction being added or removed. The server fills in `user_id` when
+/// relaying it.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReactionUpdate {
+    id: String,
+    emoji: String,
+    added: bool,
+    #[serde(default)]
+    user_id: String,
+}
+
+/// New text for one of our room messages, relayed to everyone as is.
+#[derive(Serialize, Deserialize)]
+struct MessageEdit {
+    id: String,
+    message: String,
+}
+
+/// How far someone has read the room, by the id of the newest message they
+/// saw. We send it without `user_id`; the server fills it in when relaying.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadReceipt {
+    room: String,
+    message_id: String,
+    #[serde(default)]
+    user_id: String,
+}
+
+/// A short line shown under someone's name. We send it without `user_id`;
+/// the server fills it in when relaying. An empty text clears it.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserStatus {
+    #[serde(default)]
+    user_id: String,
+    #[serde(default)]
+    emoji: Option<String>,
+    text: String,
+}
+
+impl UserStatus {
+    fn display(&self) -> String {
+        match &self.emoji {
+            Some(emoji) => format!("{} {}", emoji, self.text),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Whether someone is at the keyboard. Sent without `user_id`, like
+/// [`UserStatus`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PresenceUpdate {
+    #[serde(default)]
+    user_id: String,
+    presence: Presence,
+}
+
+#[derive(Serialize)]
+struct KeyAnnouncement<'a> {
+    /// Who the key is for; `None` announces it to everyone.
+    peer: Option<&'a str>,
+    key: &'a str,
+}
+
+#[derive(Deserialize)]
+struct PeerKey {
+    id: String,
+    key: String,
+}
+
+/// As with call signaling, `peer` names the recipient on the way out and is
+/// rewritten by the server to the sender on the way in.
+#[derive(Serialize, Deserialize)]
+struct DirectMessage {
+    peer: String,
+    #[serde(default)]
+    name: String,
+    /// Left outside the seal so the server can drop the message once due.
+    #[serde(default, rename = "expiresIn", skip_serializing_if = "Option::is_none")]
+    expires_in: Option<u32>,
+    /// Our public key when sending; on the way in, the recipient key it was
+    /// sealed for, to tell a key we no longer have from tampering.
+    #[serde(default, rename = "sealedFor", skip_serializing_if = "Option::is_none")]
+    sealed_for: Option<String>,
+    #[serde(flatten)]
+    sealed: Sealed,
+}
+
+/// A saved message. Unlike direct messages these are not sealed, as each
+/// device has its own key and notes must reach all of ours.
+#[derive(Serialize, Deserialize)]
+struct SavedNote {
+    /// Picked by the sending client, so its other sessions can tell their
+    /// own copy apart.
+    id: String,
     message: String,
+    timestamp: f64,
+}
+
+#[derive(Serialize)]
+struct RulesAck<'a> {
+    room: &'a str,
+    version: u32,
+}
+
+#[derive(Serialize)]
+struct InviteResponse {
+    id: String,
+    accept: bool,
+}
+
+/// Sent by moderators to settle a join request, and by the server to tell
+/// the requester and the other moderators how it was settled.
+#[derive(Serialize, Deserialize)]
+struct JoinDecision {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    room: Option<String>,
+    approve: bool,
+}
+
+#[derive(Deserialize)]
+struct RoomEvent {
+    room: String,
+    id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModerationRequest<'a> {
+    room: &'a str,
+    user_id: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Rename {
+    #[serde(default)]
+    id: Option<String>,
+    from: String,
+    to: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -23,6 +674,59 @@ pub enum MsgTypes {
     Users,
     Register,
     Message,
+    Topic,
+    Session,
+    SessionList,
+    RevokeSession,
+    SessionRevoked,
+    Role,
+    ModAction,
+    /// Moderator requests, answered with a `ModAction` once carried out.
+    Kick,
+    Ban,
+    Purge,
+    History,
+    Delete,
+    Rename,
+    Attachment,
+    Members,
+    MemberPage,
+    UserSearch,
+    Invite,
+    InviteResponse,
+    JoinRequest,
+    JoinPending,
+    JoinDecision,
+    RoomInfo,
+    Join,
+    Leave,
+    RulesAck,
+    DigestPrefs,
+    CallOffer,
+    CallAnswer,
+    CallCandidate,
+    CallEnd,
+    Typing,
+    PublicKey,
+    DirectMessage,
+    Reaction,
+    /// The server refused our session token.
+    AuthFailed,
+    /// Notes to self, kept by the server and shared by all our sessions.
+    SavedMessage,
+    BotCommand,
+    Read,
+    Edit,
+    /// Someone's custom status, or ours to set. The server keeps it and
+    /// sends everyone's on joining.
+    Status,
+    Presence,
+    /// A poll to post, from us; from the server, the current state of one
+    /// after a vote or close. New polls arrive as a `Message`.
+    Poll,
+    PollVote,
+    /// Only honoured for the poll's creator.
+    PollClose,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -35,17 +739,672 @@ struct WebSocketMessage {
 
 #[derive(Clone)]
 struct UserProfile {
+    id: String,
     name: String,
     avatar: String,
 }
 
+#[derive(Deserialize)]
+struct UserEntry {
+    id: String,
+    name: String,
+    /// Provider picture of users who signed in with OAuth.
+    #[serde(default)]
+    avatar: Option<String>,
+}
+
+impl From<UserEntry> for UserProfile {
+    fn from(user: UserEntry) -> Self {
+        match user.avatar.as_deref().and_then(sanitize::safe_image_src) {
+            Some(avatar) => UserProfile {
+                id: user.id,
+                name: user.name,
+                avatar,
+            },
+            None => UserProfile::new(user.id, user.name),
+        }
+    }
+}
+
+impl UserProfile {
+    fn new(id: String, name: String) -> Self {
+        UserProfile {
+            avatar: avatar::url(&id),
+            id,
+            name,
+        }
+    }
+
+    /// Accepts `{"id", "name", "avatar"?}` entries as well as legacy bare names.
+    fn parse(entry: &str) -> Self {
+        match serde_json::from_str::<UserEntry>(entry) {
+            Ok(user) => user.into(),
+            Err(_) => UserProfile::new(entry.to_string(), entry.to_string()),
+        }
+    }
+}
+
+/// What the action log keeps of the chat around each message it handles,
+/// and puts back when stepping through: the messages and what is open or
+/// picked among them.
+#[derive(Clone)]
+struct ChatSnapshot {
+    messages: Vec<MessageData>,
+    direct_peer: Option<String>,
+    window_start: usize,
+    first_unread: Option<usize>,
+    selected: BTreeSet<usize>,
+    select_anchor: Option<usize>,
+    focused_message: Option<usize>,
+    editing: Option<usize>,
+    forwarding: Option<usize>,
+    open_menu: Option<usize>,
+    revealed: HashSet<usize>,
+    collapsed_previews: HashSet<usize>,
+    quoting: Option<Quote>,
+    show_settings: bool,
+    show_room_info: bool,
+    show_sidebar: bool,
+    show_quick_switcher: bool,
+    show_shortcuts: bool,
+    show_moderation_log: bool,
+    show_notification_center: bool,
+    show_join_requests: bool,
+    show_print_dialog: bool,
+    show_schedule_dialog: bool,
+}
+
+impl Snapshot for ChatSnapshot {
+    fn changes(&self, before: &Self) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.messages.len() != before.messages.len() {
+            changes.push(format!("messages: {} → {}", before.messages.len(), self.messages.len()));
+        }
+        // Matched by id rather than index, which history pages shift.
+        let key = |m: &MessageData| m.id.clone().or_else(|| m.client_id.clone());
+        let old: HashMap<String, &MessageData> = before
+            .messages
+            .iter()
+            .filter_map(|m| Some((key(m)?, m)))
+            .collect();
+        for (idx, message) in self.messages.iter().enumerate() {
+            match key(message).and_then(|key| old.get(&key)) {
+                Some(old) if *old != message => changes.push(format!("messages[{}]: {}", idx, action_log::describe(message))),
+                Some(_) => {}
+                None if idx >= before.messages.len() => {
+                    changes.push(format!("messages[{}]: {}", idx, action_log::describe(message)))
+                }
+                None => {}
+            }
+        }
+        let mut field = |name: &str, before: &dyn Debug, after: &dyn Debug| {
+            let (before, after) = (format!("{:?}", before), format!("{:?}", after));
+            if before != after {
+                changes.push(format!("{}: {} → {}", name, before, after));
+            }
+        };
+        field("direct_peer", &before.direct_peer, &self.direct_peer);
+        field("window_start", &before.window_start, &self.window_start);
+        field("first_unread", &before.first_unread, &self.first_unread);
+        field("selected", &before.selected, &self.selected);
+        field("select_anchor", &before.select_anchor, &self.select_anchor);
+        field("focused_message", &before.focused_message, &self.focused_message);
+        field("editing", &before.editing, &self.editing);
+        field("forwarding", &before.forwarding, &self.forwarding);
+        field("open_menu", &before.open_menu, &self.open_menu);
+        field("revealed", &before.revealed, &self.revealed);
+        field("collapsed_previews", &before.collapsed_previews, &self.collapsed_previews);
+        field("quoting", &before.quoting, &self.quoting);
+        field("show_settings", &before.show_settings, &self.show_settings);
+        field("show_room_info", &before.show_room_info, &self.show_room_info);
+        field("show_sidebar", &before.show_sidebar, &self.show_sidebar);
+        field("show_quick_switcher", &before.show_quick_switcher, &self.show_quick_switcher);
+        field("show_shortcuts", &before.show_shortcuts, &self.show_shortcuts);
+        field("show_moderation_log", &before.show_moderation_log, &self.show_moderation_log);
+        field("show_notification_center", &before.show_notification_center, &self.show_notification_center);
+        field("show_join_requests", &before.show_join_requests, &self.show_join_requests);
+        field("show_print_dialog", &before.show_print_dialog, &self.show_print_dialog);
+        field("show_schedule_dialog", &before.show_schedule_dialog, &self.show_schedule_dialog);
+        changes
+    }
+}
+
 pub struct Chat {
+    /// Everyone the server knows about, regardless of room.
     users: Vec<UserProfile>,
+    membership: Membership,
+    /// Profiles that arrived with member pages rather than the global list.
+    member_profiles: HashMap<String, UserProfile>,
+    sidebar: NodeRef,
+    member_list: NodeRef,
+    /// Rows of the member list currently rendered.
+    member_window: Range<usize>,
+    /// Narrows the member list by name; lowercased.
+    member_filter: String,
+    member_sort: MemberSort,
+    pending_member_pages: HashSet<usize>,
+    mention_query: Option<String>,
+    /// Server-side search results for mention queries with no local match.
+    user_search: HashMap<String, Vec<UserProfile>>,
+    pending_user_searches: HashSet<String>,
+    /// Search text in the invite dialog, or `None` while it is closed.
+    invite_query: Option<String>,
+    invitations: Vec<Invitation>,
+    show_notification_center: bool,
+    notices: Vec<String>,
+    /// Recent routing decisions about other people's messages, oldest first.
+    routing_log: VecDeque<Routed>,
+    /// Requests awaiting a moderator's decision, for rooms we moderate.
+    join_requests: Vec<JoinRequest>,
+    show_join_requests: bool,
+    /// Rooms we asked to join that have not decided yet.
+    pending_joins: HashSet<String>,
+    /// Rules of the current room that still have to be accepted before
+    /// posting.
+    pending_rules: Option<RoomRules>,
+    show_rules: bool,
+    /// Digest settings from the server, fetched when preferences open.
+    digest_prefs: Option<DigestPrefs>,
+    profile_popover: Option<String>,
+    /// Messages waiting out the undo grace period, in send order, with the
+    /// direct message recipient if any. Dropping an entry cancels its timer.
+    outbox: BTreeMap<usize, (Option<String>, String, Option<Quote>, Timeout)>,
+    next_outgoing: usize,
+    /// Fails sent room messages whose echo does not come, by client id.
+    /// Dropping an entry cancels its timer.
+    delivery_deadlines: HashMap<String, Timeout>,
+    typing_broadcaster: TypingBroadcaster,
+    /// Display names of people typing in the room, by user id. Dropping an
+    /// entry cancels its expiry timer.
+    typing: HashMap<String, (String, Timeout)>,
+    call: Option<Call>,
+    /// Generated for this login; the secret half never leaves the browser.
+    keys: KeyPair,
+    /// Public keys of other users, by user id.
+    peer_keys: HashMap<String, String>,
+    /// Keys we have seen and verified, across sessions.
+    known_keys: KnownKeys,
+    /// The peer whose safety number is on screen.
+    verifying: Option<String>,
+    /// Recipient of what the composer sends, if it is a direct message.
+    direct_peer: Option<String>,
     chat_input: NodeRef,
+    /// Conversation shown beside the room on wide screens, by user id.
+    split_peer: Option<String>,
+    split_input: NodeRef,
+    mini_chat: MiniChat,
+    mini_input: NodeRef,
+    send_throttle: SendThrottle,
+    /// Whole seconds until sending is allowed again, while flood limited.
+    cooldown: Option<u32>,
+    cooldown_tick: Option<Interval>,
+    /// Ticks once a second while any message is due to disappear, to count
+    /// down and remove it.
+    expiry_tick: Option<Interval>,
+    show_print_dialog: bool,
+    show_schedule_dialog: bool,
+    /// Whether the list of scheduled messages is expanded.
+    show_scheduled: bool,
+    /// Messages picked for copying, by index. Shift-clicking a message
+    /// starts the selection.
+    selected: BTreeSet<usize>,
+    select_anchor: Option<usize>,
+    /// Slash command output or error, shown above the composer to us only.
+    command_notice: Option<String>,
+    /// The message keyboard navigation is on, by index.
+    focused_message: Option<usize>,
+    /// Set when `focused_message` moved and needs DOM focus after rendering.
+    focus_pending: bool,
+    /// The highlighted emoji while the quick reactions of the focused message
+    /// are open, by position in `QUICK_REACTIONS`.
+    reaction_pick: Option<usize>,
+    /// Set while the print layout is rendered for the browser's print dialog.
+    print_range: Option<PrintRange>,
+    drafts: DraftStore,
+    scheduler: Scheduler,
     _producer: Box<dyn Bridge<EventBus>>,
     wss: WebsocketService,
+    username: String,
+    user_id: String,
+    /// The server predates user ids and keys everyone on their name, ours
+    /// included.
+    legacy_ids: bool,
     messages: Vec<MessageData>,
+    block_list: BlockList,
+    revealed: HashSet<usize>,
+    /// Unfurled links by URL; `None` while loading or if unfurling failed.
+    link_previews: HashMap<String, Option<LinkPreview>>,
+    /// Translations into our language by message id; `None` while loading.
+    translations: HashMap<String, Option<String>>,
+    /// Translated messages switched back to their original text, by id.
+    originals: HashSet<String>,
+    collapsed_previews: HashSet<usize>,
+    topic: Option<String>,
+    settings: Settings,
+    show_settings: bool,
+    /// An imported export being looked at, shown over the chat.
+    archive: Option<Archive>,
+    /// Why the last file picked for import could not be opened.
+    import_error: Option<String>,
+    key_backup_error: Option<String>,
+    session_id: String,
+    sessions: Vec<SessionEntry>,
+    role: Role,
+    /// The newest room message each user has seen, by user id and message id.
+    read_marks: HashMap<String, String>,
+    /// The message id in our last read receipt, so it is sent once.
+    read_sent: Option<String>,
+    /// Custom statuses by user id.
+    statuses: HashMap<String, UserStatus>,
+    /// Who is away, by user id. Everyone online and not in here is active.
+    away: HashSet<String>,
+    mod_actions: Vec<ModAction>,
+    show_moderation_log: bool,
+    action_log: ActionLog<ChatSnapshot>,
+    /// Input held back while stepping through the action log.
+    paused: Vec<Msg>,
+    /// Set while something that cannot wait is handled against the latest
+    /// state, with an earlier one from the action log on screen.
+    updating_latest: bool,
+    show_action_log: bool,
+    show_shortcuts: bool,
+    /// The member list drawer, on screens too narrow to show it alongside.
+    show_sidebar: bool,
+    /// Height of the visible viewport, which shrinks under an on-screen
+    /// keyboard where the layout viewport does not.
+    viewport_height: Option<f64>,
+    show_quick_switcher: bool,
+    /// Our message whose text the composer holds for editing, by index.
+    editing: Option<usize>,
+    /// A brief confirmation and the timer that hides it.
+    toast: Option<(String, Timeout)>,
+    /// The room message our next one replies to.
+    quoting: Option<Quote>,
+    /// The message whose conversation picker is open, by index.
+    forwarding: Option<usize>,
+    open_menu: Option<usize>,
+    show_room_info: bool,
+    /// Message to scroll to after the next render, by index.
+    jump_to: Option<usize>,
+    /// The id of a message a permalink asked for that is not loaded yet.
+    permalink: Option<String>,
+    /// The message just opened from a permalink, by id, and the timer that
+    /// ends its highlight.
+    highlighted: Option<(String, Timeout)>,
+    enlarged_image: Option<String>,
+    show_gif_picker: bool,
+    pending_file: Option<File>,
+    drag_active: bool,
+    /// Attachments being prepared or uploaded, by a local id, with their name
+    /// and progress.
+    uploads: BTreeMap<usize, (String, f64)>,
+    next_upload: usize,
+    notifications_enabled: bool,
+    plaintext: bool,
+    message_pane: NodeRef,
+    scroll_to_bottom: bool,
+    new_below: usize,
+    window_start: usize,
+    anchor_from_bottom: Option<i32>,
+    loading_history: bool,
+    history_exhausted: bool,
+    first_unread: Option<usize>,
+    /// Messages that notified while the tab was hidden, shown as a badge.
+    badge: usize,
+    unread_divider: NodeRef,
+    scroll_to_unread: bool,
+    _visibility_listener: Option<EventListener>,
+    _focus_listener: Option<EventListener>,
+    _hash_listener: Option<EventListener>,
+    _viewport_listener: Option<EventListener>,
+    _idle_watcher: IdleWatcher,
+    /// Page-wide keyboard shortcuts.
+    _shortcut_listener: Option<EventListener>,
+}
+
+/// Where the compact view of the current conversation is shown.
+enum MiniChat {
+    Closed,
+    /// Inside the page, for browsers without document picture-in-picture.
+    Floating,
+    Window {
+        window: web_sys::Window,
+        body: Element,
+        /// Closing the window and sending from it, as Yew's own handlers do
+        /// not reach into other documents.
+        _listeners: [EventListener; 2],
+    },
+}
+
+/// Whether a key press asks to send the mini chat's text: Enter in its
+/// textarea, leaving Shift+Enter for new lines.
+///
+/// Events from the picture-in-picture window belong to another realm, where
+/// `instanceof` checks against this window's classes fail, hence the
+/// unchecked casts here and on the textarea.
+fn mini_chat_submission(e: &KeyboardEvent) -> bool {
+    let in_textarea = e
+        .target()
+        .is_some_and(|target| target.unchecked_into::<Element>().tag_name() == "TEXTAREA");
+    if e.key() != "Enter" || e.shift_key() || !in_textarea {
+        return false;
+    }
+    e.prevent_default();
+    true
+}
+
+fn format_time(millis: f64) -> String {
+    js_sys::Date::new(&millis.into())
+        .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+        .into()
+}
+
+/// Plain-text rendering of messages, as copied or exported: the sender and
+/// time, then any file and the text.
+fn transcript<'a>(messages: impl Iterator<Item = &'a MessageData>) -> String {
+    messages
+        .filter(|m| !m.deleted)
+        .map(|m| {
+            let mut block = match m.timestamp {
+                Some(ts) => format!("{} — {}", m.from, format_time(ts)),
+                None => m.from.clone(),
+            };
+            if let Some(file) = &m.attachment {
+                block.push_str(&format!("\n{} ({})", file.name, file.url));
+            }
+            if !m.message.is_empty() {
+                block.push('\n');
+                block.push_str(&m.message);
+            }
+            block
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// The window's `visualViewport`, which web-sys does not bind yet.
+fn visual_viewport() -> Option<web_sys::EventTarget> {
+    let window = web_sys::window()?;
+    js_sys::Reflect::get(&window, &"visualViewport".into())
+        .ok()
+        .and_then(|viewport| viewport.dyn_into().ok())
+}
+
+fn visual_viewport_height() -> Option<f64> {
+    visual_viewport().and_then(|viewport| js_sys::Reflect::get(&viewport, &"height".into()).ok()?.as_f64())
+}
+
+/// Local midnight of the day `millis` falls on.
+fn local_day(millis: f64) -> f64 {
+    let date = js_sys::Date::new(&millis.into());
+    date.set_hours(0);
+    date.set_minutes(0);
+    date.set_seconds(0);
+    date.set_milliseconds(0);
+    date.get_time()
+}
+
+/// "Today", "Yesterday", or the full date, for a day from [`local_day`].
+fn day_label(day: f64) -> String {
+    let today = local_day(js_sys::Date::now());
+    if day == today {
+        return t("date-today");
+    }
+    // A millisecond before midnight is yesterday, whatever DST did.
+    if day == local_day(today - 1.0) {
+        return t("date-yesterday");
+    }
+    let options = js_sys::Object::new();
+    for (key, value) in [("weekday", "long"), ("year", "numeric"), ("month", "long"), ("day", "numeric")] {
+        let _ = js_sys::Reflect::set(&options, &key.into(), &value.into());
+    }
+    js_sys::Date::new(&day.into())
+        .to_locale_date_string("default", &options)
+        .into()
+}
+
+/// A span of time in its largest whole unit, e.g. "5 min".
+fn duration_label(seconds: u32) -> String {
+    let (key, count) = match seconds {
+        s if s >= 24 * 60 * 60 => ("duration-days", s / (24 * 60 * 60)),
+        s if s >= 60 * 60 => ("duration-hours", s / (60 * 60)),
+        s if s >= 60 => ("duration-minutes", s / 60),
+        s => ("duration-seconds", s),
+    };
+    t_args(key, &[("count", &count.to_string())])
+}
+
+fn cooldown_label(seconds: u32) -> String {
+    t_args("send-cooldown", &[("seconds", &seconds.to_string())])
+}
+
+fn message_element_id(idx: usize) -> String {
+    format!("message-{}", idx)
+}
+
+/// The message id a `#msg-<id>` fragment in the address points at.
+fn permalink_target() -> Option<String> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    hash.strip_prefix("#msg-").filter(|id| !id.is_empty()).map(String::from)
+}
+
+/// A link that opens the room scrolled to the message with `id`.
+fn permalink(id: &str) -> String {
+    let origin = web_sys::window()
+        .and_then(|w| w.location().origin().ok())
+        .unwrap_or_default();
+    let path = Route::Channel {
+        channel: CHANNEL.to_string(),
+    }
+    .to_path();
+    format!("{}{}#msg-{}", origin, path, id)
+}
+
+/// Parses a length given at build time; anything but digits fails the build.
+const fn parse_len(value: &str) -> usize {
+    let bytes = value.as_bytes();
+    let mut len = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(bytes[i].is_ascii_digit(), "YEWCHAT_MAX_MESSAGE_LEN must be a number");
+        len = len * 10 + (bytes[i] - b'0') as usize;
+        i += 1;
+    }
+    len
+}
+
+/// Cuts text longer than [`MAX_MESSAGE_LEN`] down to it, returning whether
+/// anything was cut.
+fn truncate_text(text: &mut String) -> bool {
+    match text.char_indices().nth(MAX_MESSAGE_LEN) {
+        Some((end, _)) => {
+            text.truncate(end);
+            text.push('…');
+            true
+        }
+        None => false,
+    }
+}
+
+fn within_limit(text: &str) -> bool {
+    text.chars().count() <= MAX_MESSAGE_LEN
+}
+
+/// What the server is sent on every (re)connect, so it knows who we are
+/// again and where to seal direct messages to us.
+fn handshake(username: &str, user_id: &str, token: &str, keys: &KeyPair) -> Vec<String> {
+    let session_info = serde_json::json!({
+        "id": session::session_id(),
+        "device": session::device_name(),
+    });
+    let announcement = KeyAnnouncement {
+        peer: None,
+        key: &keys.public_key(),
+    };
+    vec![
+        serde_json::to_string(&WebSocketMessage {
+            message_type: MsgTypes::Register,
+            data: Some(username.to_string()),
+            data_array: Some(vec![user_id.to_string(), token.to_string()]),
+        })
+        .unwrap(),
+        serde_json::to_string(&WebSocketMessage {
+            message_type: MsgTypes::Session,
+            data: Some(session_info.to_string()),
+            data_array: None,
+        })
+        .unwrap(),
+        serde_json::to_string(&WebSocketMessage {
+            message_type: MsgTypes::PublicKey,
+            data: Some(serde_json::to_string(&announcement).unwrap()),
+            data_array: None,
+        })
+        .unwrap(),
+    ]
+}
+
+/// Where something picked in the composer goes.
+#[derive(Debug, PartialEq)]
+enum Outgoing {
+    Room,
+    /// Sealed for the peer, as direct message text.
+    Direct(String),
+    Refused,
+}
+
+/// Only what can travel inside a sealed message, such as a GIF's URL, may
+/// be sent with a direct message open; files are uploaded in the clear, so
+/// they are refused there rather than posted to the room.
+fn outgoing(direct_peer: Option<&str>, sealable: bool) -> Outgoing {
+    match direct_peer {
+        None => Outgoing::Room,
+        Some(peer) if sealable => Outgoing::Direct(peer.to_string()),
+        Some(_) => Outgoing::Refused,
+    }
+}
+
+/// Whether `msg` comes from the network, a call, a timer or finished work
+/// rather than from input, so that it cannot wait while stepping through
+/// the action log: calls would stall and timers pile up.
+fn runs_in_background(msg: &Msg) -> bool {
+    matches!(
+        msg,
+        Msg::HandleMsg(_)
+            | Msg::Call(_)
+            | Msg::PresenceChanged(_)
+            | Msg::CooldownTick
+            | Msg::ExpiryTick
+            | Msg::ScheduledDue
+            | Msg::DispatchMessage(_)
+            | Msg::DeliveryTimedOut(_)
+            | Msg::TypingExpired(_)
+            | Msg::HideToast
+            | Msg::UploadProgress(..)
+            | Msg::AttachmentReady(..)
+            | Msg::LinkPreviewLoaded(..)
+            | Msg::Translated(..)
+    )
+}
+
+/// What the composer's draft is kept under: the direct message peer, or the
+/// room.
+fn draft_key(direct_peer: Option<&str>) -> &str {
+    direct_peer.unwrap_or(CHANNEL)
+}
+
+fn document_has_focus() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.has_focus().ok())
+        .unwrap_or(true)
+}
+
+/// Whether a drag carries files, as opposed to e.g. selected text.
+fn drags_files(event: &DragEvent) -> bool {
+    event
+        .data_transfer()
+        .is_some_and(|data| data.types().includes(&"Files".into(), 0))
+}
+
+fn dropped_file(event: &DragEvent) -> Option<File> {
+    event.data_transfer()?.files()?.get(0)
+}
+
+fn document_hidden() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .map(|d| d.hidden())
+        .unwrap_or(false)
 }
+
+pub const CHANNEL: &str = "general";
+
+/// Number of messages kept in the DOM while following the conversation.
+const RENDER_WINDOW: usize = 150;
+/// Messages added to the top of the window when scrolling back.
+const RENDER_PAGE: usize = 50;
+/// Distance from the top of the pane, in pixels, at which older messages are rendered.
+const RENDER_THRESHOLD: i32 = 300;
+/// Messages requested from the server per page of history.
+const HISTORY_PAGE: usize = 50;
+/// Members requested from the server per page in large rooms.
+const MEMBER_PAGE: usize = 100;
+/// Height of one entry in the user list, in pixels. Entries have a fixed
+/// height so that only the visible part of the member list is rendered.
+const MEMBER_ROW_HEIGHT: i32 = 72;
+/// Rows rendered beyond each edge of the visible part of the member list.
+const MEMBER_OVERSCAN: usize = 10;
+/// Names offered at once while typing an `@mention`.
+const MENTION_SUGGESTIONS: usize = 8;
+/// How long someone shows as typing after their last signal, in milliseconds.
+const TYPING_TIMEOUT: u32 = 6_000;
+/// How long a sent message can still be taken back, in milliseconds.
+const UNDO_SEND_DELAY: u32 = 5_000;
+/// How long a room message may go without the server echoing it before it
+/// counts as failed, in milliseconds.
+const DELIVERY_TIMEOUT: u32 = 10_000;
+/// Longest message, in characters. Set at build time with
+/// `YEWCHAT_MAX_MESSAGE_LEN` to match the server.
+const MAX_MESSAGE_LEN: usize = match option_env!("YEWCHAT_MAX_MESSAGE_LEN") {
+    Some(len) => parse_len(len),
+    None => 4_000,
+};
+/// Frames from the server larger than this, in bytes, are dropped unread.
+const MAX_FRAME_LEN: usize = 1 << 20;
+/// Longest custom status, in characters.
+const STATUS_LEN: usize = 80;
+/// Largest file read as a key backup; real ones are a few hundred bytes.
+const MAX_KEY_BACKUP_SIZE: f64 = 64.0 * 1024.0;
+/// How long a toast stays up, in milliseconds.
+const TOAST_DURATION: u32 = 2_000;
+/// How long a message opened from a permalink stays highlighted, in
+/// milliseconds.
+const HIGHLIGHT_DURATION: u32 = 2_000;
+/// How much of a quoted message travels with the reply, in characters.
+const QUOTE_EXCERPT: usize = 140;
+/// Statuses offered with one click, as an emoji and a message key.
+const STATUS_PRESETS: [(&str, &str); 4] = [
+    ("📅", "status-meeting"),
+    ("🚌", "status-commuting"),
+    ("🤒", "status-sick"),
+    ("🌴", "status-vacation"),
+];
+/// Routing decisions kept for the notification center's explainer.
+const ROUTING_LOG_LEN: usize = 20;
+/// Entries in each list of the room stats tab.
+const ROOM_STATS_LEN: usize = 5;
+/// Most recent messages kept in the mini chat.
+const MINI_CHAT_LEN: usize = 30;
+const MINI_CHAT_SIZE: (u32, u32) = (320, 480);
+/// Messages from one sender this close together, in milliseconds, are shown
+/// as one block.
+const GROUP_WINDOW: f64 = 5.0 * 60.0 * 1000.0;
+
+const WELCOME_TEMPLATES: [&str; 3] = [
+    "welcome-template-1",
+    "welcome-template-2",
+    "welcome-template-3",
+];
 impl Component for Chat {
     type Message = Msg;
     type Properties = ();
@@ -55,148 +1414,5588 @@ impl Component for Chat {
             .link()
             .context::<User>(Callback::noop())
             .expect("context to be set");
-        let wss = WebsocketService::new();
+        let settings = Settings::load();
+        let wss = WebsocketService::new(settings.server_url(), settings.transport.policy());
         let username = user.username.borrow().clone();
+        // The route guard only lets us here with a session.
+        let auth = auth::stored().unwrap_or_else(|| auth::AuthSession {
+            token: String::new(),
+            user_id: session::user_id(),
+            username: username.clone(),
+            avatar: None,
+        });
+        let user_id = auth.user_id.clone();
 
-        let message = WebSocketMessage {
-            message_type: MsgTypes::Register,
-            data: Some(username.to_string()),
-            data_array: None,
-        };
+        if notifications::is_enabled() {
+            notifications::request_permission();
+        }
 
-        if let Ok(_) = wss
-            .tx
-            .clone()
-            .try_send(serde_json::to_string(&message).unwrap())
-        {
-            log::debug!("message sent successfully");
+        let keys = KeyPair::load(&user_id);
+        wss.set_handshake(handshake(&username, &user_id, &auth.token, &keys));
+
+        let member_page = MemberPageRequest {
+            room: CHANNEL,
+            offset: 0,
+            limit: MEMBER_PAGE,
+        };
+        if let Err(e) = wss.tx.clone().try_send(
+            serde_json::to_string(&WebSocketMessage {
+                message_type: MsgTypes::MemberPage,
+                data: Some(serde_json::to_string(&member_page).unwrap()),
+                data_array: None,
+            })
+            .unwrap(),
+        ) {
+            log::debug!("error sending to channel: {:?}", e);
         }
+        ctx.link().send_message(Msg::OpenPermalink);
 
         Self {
             users: vec![],
+            membership: Membership::default(),
+            member_profiles: HashMap::new(),
+            sidebar: NodeRef::default(),
+            member_list: NodeRef::default(),
+            member_window: 0..MEMBER_PAGE,
+            member_filter: String::new(),
+            member_sort: MemberSort::RoomOrder,
+            pending_member_pages: HashSet::from([0]),
+            mention_query: None,
+            user_search: HashMap::new(),
+            pending_user_searches: HashSet::new(),
+            invite_query: None,
+            invitations: vec![],
+            show_notification_center: false,
+            notices: vec![],
+            routing_log: VecDeque::new(),
+            join_requests: vec![],
+            show_join_requests: false,
+            pending_joins: HashSet::new(),
+            pending_rules: None,
+            show_rules: false,
+            digest_prefs: None,
+            profile_popover: None,
+            outbox: BTreeMap::new(),
+            next_outgoing: 0,
+            delivery_deadlines: HashMap::new(),
+            typing_broadcaster: TypingBroadcaster::default(),
+            typing: HashMap::new(),
+            call: None,
+            keys,
+            peer_keys: HashMap::new(),
+            known_keys: KnownKeys::load(),
+            verifying: None,
+            direct_peer: auth::last_conversation(),
             messages: vec![],
             chat_input: NodeRef::default(),
+            split_peer: None,
+            split_input: NodeRef::default(),
+            mini_chat: MiniChat::Closed,
+            mini_input: NodeRef::default(),
+            send_throttle: SendThrottle::default(),
+            cooldown: None,
+            cooldown_tick: None,
+            expiry_tick: None,
+            show_print_dialog: false,
+            show_schedule_dialog: false,
+            show_scheduled: false,
+            selected: BTreeSet::new(),
+            select_anchor: None,
+            command_notice: None,
+            focused_message: None,
+            focus_pending: false,
+            reaction_pick: None,
+            print_range: None,
+            drafts: DraftStore::load(),
+            scheduler: Scheduler::load(ctx.link().callback(|_| Msg::ScheduledDue)),
             wss,
+            username,
+            user_id,
+            legacy_ids: false,
+            block_list: BlockList::load(),
+            revealed: HashSet::new(),
+            link_previews: HashMap::new(),
+            translations: HashMap::new(),
+            originals: HashSet::new(),
+            collapsed_previews: HashSet::new(),
+            topic: None,
+            settings,
+            show_settings: false,
+            archive: None,
+            import_error: None,
+            key_backup_error: None,
+            session_id: session::session_id(),
+            sessions: vec![],
+            role: Role::default(),
+            read_marks: HashMap::new(),
+            statuses: HashMap::new(),
+            away: HashSet::new(),
+            read_sent: None,
+            mod_actions: vec![],
+            show_moderation_log: false,
+            action_log: ActionLog::default(),
+            paused: Vec::new(),
+            updating_latest: false,
+            show_action_log: false,
+            show_shortcuts: false,
+            show_sidebar: false,
+            viewport_height: visual_viewport_height(),
+            show_quick_switcher: false,
+            editing: None,
+            toast: None,
+            quoting: None,
+            forwarding: None,
+            open_menu: None,
+            show_room_info: false,
+            jump_to: None,
+            permalink: None,
+            highlighted: None,
+            enlarged_image: None,
+            show_gif_picker: false,
+            pending_file: None,
+            drag_active: false,
+            uploads: BTreeMap::new(),
+            next_upload: 0,
+            notifications_enabled: notifications::is_enabled(),
+            plaintext: message_content::plaintext_mode(),
+            message_pane: NodeRef::default(),
+            scroll_to_bottom: false,
+            new_below: 0,
+            window_start: 0,
+            anchor_from_bottom: None,
+            loading_history: false,
+            history_exhausted: false,
+            first_unread: None,
+            badge: 0,
+            unread_divider: NodeRef::default(),
+            scroll_to_unread: false,
+            _visibility_listener: web_sys::window().and_then(|w| w.document()).map(|document| {
+                let link = ctx.link().clone();
+                EventListener::new(&document, "visibilitychange", move |_| {
+                    link.send_message(Msg::VisibilityChanged)
+                })
+            }),
+            _focus_listener: web_sys::window().map(|window| {
+                let link = ctx.link().clone();
+                EventListener::new(&window, "focus", move |_| link.send_message(Msg::WindowFocused))
+            }),
+            _hash_listener: web_sys::window().map(|window| {
+                let link = ctx.link().clone();
+                EventListener::new(&window, "hashchange", move |_| link.send_message(Msg::OpenPermalink))
+            }),
+            _idle_watcher: IdleWatcher::new(ctx.link().callback(Msg::PresenceChanged)),
+            _viewport_listener: visual_viewport().map(|viewport| {
+                let link = ctx.link().clone();
+                EventListener::new(&viewport, "resize", move |_| link.send_message(Msg::ViewportResized))
+            }),
+            _shortcut_listener: web_sys::window().and_then(|w| w.document()).map(|document| {
+                let link = ctx.link().clone();
+                EventListener::new(&document, "keydown", move |e| {
+                    let e = match e.dyn_ref::<KeyboardEvent>() {
+                        Some(e) => e,
+                        None => return,
+                    };
+                    // Keys the message list or composer already acted on are theirs.
+                    if e.default_prevented() {
+                        return;
+                    }
+                    if let Some(action) = shortcuts::action(e) {
+                        if action != Action::ClosePanels {
+                            e.prevent_default();
+                        }
+                        link.send_message(Msg::Shortcut(action));
+                    }
+                })
+            }),
             _producer: EventBus::bridge(ctx.link().callback(Msg::HandleMsg)),
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let devtool = matches!(
+            msg,
+            Msg::ToggleActionLog | Msg::ClearActionLog | Msg::ActionLogBack | Msg::ActionLogForward | Msg::ActionLogLive
+        );
+        if action_log::ENABLED && self.show_action_log && !devtool {
+            if self.action_log.stepping_through() && !self.updating_latest {
+                if runs_in_background(&msg) {
+                    return self.update_latest(ctx, msg);
+                }
+                // Input meant for the earlier state on screen waits, to be
+                // handled once back at the latest.
+                self.paused.push(msg);
+                return false;
+            }
+            self.action_log.record(&msg, self.snapshot());
+        }
         match msg {
             Msg::HandleMsg(s) => {
-                let msg: WebSocketMessage = serde_json::from_str(&s).unwrap();
+                if s.len() > MAX_FRAME_LEN {
+                    log::warn!("dropping a {} byte frame", s.len());
+                    return false;
+                }
+                let msg: WebSocketMessage = match serde_json::from_str(&s) {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        log::warn!("unreadable frame: {}", e);
+                        return false;
+                    }
+                };
                 match msg.message_type {
                     MsgTypes::Users => {
                         let users_from_message = msg.data_array.unwrap_or_default();
+                        self.legacy_ids = users_from_message
+                            .iter()
+                            .any(|u| serde_json::from_str::<UserEntry>(u).is_err());
                         self.users = users_from_message
                             .iter()
-                            .map(|u| UserProfile {
-                                name: u.into(),
-                                avatar: format!(
-                                    "https://avatars.dicebear.com/api/adventurer-neutral/{}.svg",
-                                    u
-                                )
-                                .into(),
-                            })
+                            .map(|u| UserProfile::parse(u))
                             .collect();
-                        return true;
+                        true
                     }
-                    MsgTypes::Message => {
-                        let message_data: MessageData =
-                            serde_json::from_str(&msg.data.unwrap()).unwrap();
-                        self.messages.push(message_data);
-                        return true;
+                    MsgTypes::Members => match msg.data {
+                        Some(room) => {
+                            self.membership.set(&room, msg.data_array.unwrap_or_default());
+                            room == CHANNEL
+                        }
+                        None => false,
+                    },
+                    MsgTypes::MemberPage => {
+                        match msg.data.and_then(|d| serde_json::from_str::<MemberPage>(&d).ok()) {
+                            Some(page) => {
+                                self.pending_member_pages.remove(&page.offset);
+                                let ids = page.members.iter().map(|m| m.id.clone()).collect();
+                                for member in page.members {
+                                    self.member_profiles.insert(member.id.clone(), member.into());
+                                }
+                                self.membership.add_page(&page.room, page.total, page.offset, ids);
+                                self.request_missing_members();
+                                page.room == CHANNEL
+                            }
+                            None => false,
+                        }
                     }
-                    _ => {
-                        return false;
+                    MsgTypes::UserSearch => {
+                        match msg.data.and_then(|d| serde_json::from_str::<UserSearchResults>(&d).ok()) {
+                            Some(search) => {
+                                self.pending_user_searches.remove(&search.query);
+                                let results = search
+                                    .results
+                                    .into_iter()
+                                    .map(UserProfile::from)
+                                    .collect();
+                                self.user_search.insert(search.query.clone(), results);
+                                self.mention_query.as_ref() == Some(&search.query)
+                            }
+                            None => false,
+                        }
                     }
-                }
-            }
-            Msg::SubmitMessage => {
-                let input = self.chat_input.cast::<HtmlInputElement>();
-                if let Some(input) = input {
-                    let message = WebSocketMessage {
-                        message_type: MsgTypes::Message,
-                        data: Some(input.value()),
-                        data_array: None,
-                    };
-                    if let Err(e) = self
-                        .wss
-                        .tx
-                        .clone()
-                        .try_send(serde_json::to_string(&message).unwrap())
-                    {
-                        log::debug!("error sending to channel: {:?}", e);
+                    MsgTypes::Invite => {
+                        match msg.data.and_then(|d| serde_json::from_str::<Invitation>(&d).ok()) {
+                            Some(invitation) => {
+                                notifications::notify_message(
+                                    &invitation.from,
+                                    &t_args("invite-received", &[("from", &invitation.from), ("room", &invitation.room)]),
+                                );
+                                self.invitations.retain(|i| i.id != invitation.id);
+                                self.invitations.push(invitation);
+                                true
+                            }
+                            None => false,
+                        }
                     }
-                    input.set_value("");
-                };
-                false
-            }
-        }
-    }
-
-    fn view(&self, ctx: &Context<Self>) -> Html {
-    let submit = ctx.link().callback(|_| Msg::SubmitMessage);
-
+                    MsgTypes::RoomInfo => {
+                        let info = match msg.data.and_then(|d| serde_json::from_str::<RoomInfo>(&d).ok()) {
+                            Some(info) if info.room == CHANNEL => info,
+                            _ => return false,
+                        };
+                        self.pending_rules = info
+                            .required_rules
+                            .filter(|rules| !rooms::rules_accepted(&info.room, rules));
+                        self.show_rules = self.pending_rules.is_some();
+                        // Only the first visit to a room gets the card.
+                        if let Some(rule) = info.welcome.filter(|_| rooms::mark_welcomed(&info.room)) {
+                            self.messages.push(MessageData {
+                                id: None,
+                                from_id: None,
+                                from: rule.bot.clone().unwrap_or_else(|| t("welcome-bot")),
+                                message: String::new(),
+                                attachment: None,
+                                reactions: Reactions::new(),
+                                timestamp: Some(js_sys::Date::now()),
+                                bot: false,
+                                actions: Vec::new(),
+                                client_id: None,
+                                edited: false,
+                                quote: None,
+                                forwarded_from: None,
+                                poll: None,
+                                expires_in: None,
+                                deleted: false,
+                                renamed_from: None,
+                                welcome: Some(rule),
+                                direct: None,
+                                delivery: None,
+                                truncated: false,
+                            });
+                            self.scroll_to_bottom = !self.scrolled_up();
+                        }
+                        true
+                    }
+                    MsgTypes::JoinRequest => {
+                        match msg.data.and_then(|d| serde_json::from_str::<JoinRequest>(&d).ok()) {
+                            Some(request) => {
+                                self.join_requests.retain(|r| r.id != request.id);
+                                self.join_requests.push(request);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::JoinPending => match msg.data {
+                        Some(room) => self.pending_joins.insert(room),
+                        None => false,
+                    },
+                    MsgTypes::JoinDecision => {
+                        match msg.data.and_then(|d| serde_json::from_str::<JoinDecision>(&d).ok()) {
+                            Some(decision) => {
+                                if let Some(id) = &decision.id {
+                                    self.join_requests.retain(|r| &r.id != id);
+                                }
+                                let ours = decision
+                                    .room
+                                    .as_ref()
+                                    .filter(|room| self.pending_joins.remove(*room));
+                                if let Some(room) = ours {
+                                    let key = if decision.approve { "join-approved" } else { "join-rejected" };
+                                    let notice = t_args(key, &[("room", room)]);
+                                    notifications::notify_message(room, &notice);
+                                    self.notices.push(notice);
+                                }
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::Join | MsgTypes::Leave => {
+                        match msg.data.and_then(|d| serde_json::from_str::<RoomEvent>(&d).ok()) {
+                            Some(event) => {
+                                if matches!(msg.message_type, MsgTypes::Join) {
+                                    self.membership.join(&event.room, &event.id);
+                                } else {
+                                    self.membership.leave(&event.room, &event.id);
+                                }
+                                event.room == CHANNEL
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::Message | MsgTypes::Attachment => {
+                        let mut message_data = match msg.data.and_then(|d| serde_json::from_str::<MessageData>(&d).ok()) {
+                            Some(message_data) => message_data,
+                            None => return false,
+                        };
+                        message_data.truncated = truncate_text(&mut message_data.message);
+                        message_data.timestamp.get_or_insert_with(js_sys::Date::now);
+                        // Our own broadcast takes the place of the local copy; a late
+                        // one still counts after the copy was marked failed.
+                        if let Some(idx) = message_data.client_id.as_deref().and_then(|id| self.local_copy(id)) {
+                            self.delivery_deadlines.remove(message_data.client_id.as_deref().unwrap());
+                            self.request_link_preview(ctx, &message_data.message);
+                            self.messages[idx] = message_data;
+                            return true;
+                        }
+                        // A reconnect or retried send can bring the same message twice.
+                        if self.holds(&message_data) {
+                            return false;
+                        }
+                        let sender = message_data.sender_id();
+                        let route = self.router().route(&Incoming {
+                            room: CHANNEL,
+                            sender_id: sender,
+                            message: &message_data.message,
+                        });
+                        if route.notify {
+                            notifications::notify_message(&message_data.from, &message_data.preview());
+                            if !self.settings.sound_muted && (!document_has_focus() || self.scrolled_up()) {
+                                sound::play_chime();
+                            }
+                            self.bump_badge();
+                        }
+                        if !self.is_me(sender) {
+                            if !self.block_list.is_blocked(sender)
+                                && !self.block_list.is_muted(sender)
+                                && self.first_unread.is_none()
+                                && document_hidden()
+                            {
+                                self.first_unread = Some(self.messages.len());
+                            }
+                            if self.routing_log.len() == ROUTING_LOG_LEN {
+                                self.routing_log.pop_front();
+                            }
+                            self.routing_log.push_back(Routed {
+                                from: message_data.from.clone(),
+                                preview: message_data.preview(),
+                                route,
+                            });
+                        }
+                        // Only follow new messages if the reader is already at the bottom.
+                        if self.is_me(sender) || !self.scrolled_up() {
+                            self.scroll_to_bottom = true;
+                        } else if !self.block_list.is_blocked(sender) {
+                            self.new_below += 1;
+                        }
+                        self.request_link_preview(ctx, &message_data.message);
+                        self.messages.push(message_data);
+                        if self.scroll_to_bottom {
+                            self.trim_window();
+                            self.mark_read();
+                        }
+                        self.seek_permalink(ctx);
+                        true
+                    }
+                    MsgTypes::Read => {
+                        match msg.data.and_then(|d| serde_json::from_str::<ReadReceipt>(&d).ok()) {
+                            Some(receipt) if receipt.room == CHANNEL && !self.is_me(&receipt.user_id) => {
+                                self.read_marks.insert(receipt.user_id, receipt.message_id);
+                                true
+                            }
+                            _ => false,
+                        }
+                    }
+                    MsgTypes::Status => match msg.data.and_then(|d| serde_json::from_str::<UserStatus>(&d).ok()) {
+                        Some(status) if status.text.trim().is_empty() && status.emoji.is_none() => {
+                            self.statuses.remove(&status.user_id).is_some()
+                        }
+                        Some(status) => {
+                            self.statuses.insert(status.user_id.clone(), status);
+                            true
+                        }
+                        None => false,
+                    },
+                    MsgTypes::Poll => {
+                        let poll = match msg.data.and_then(|d| serde_json::from_str::<Poll>(&d).ok()) {
+                            Some(poll) => poll,
+                            None => return false,
+                        };
+                        match self.messages.iter_mut().find_map(|m| m.poll.as_mut().filter(|p| p.id == poll.id)) {
+                            Some(current) => {
+                                *current = poll;
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::Presence => match msg.data.and_then(|d| serde_json::from_str::<PresenceUpdate>(&d).ok()) {
+                        Some(update) if update.presence == Presence::Away => self.away.insert(update.user_id),
+                        Some(update) => self.away.remove(&update.user_id),
+                        None => false,
+                    },
+                    MsgTypes::CallOffer => {
+                        let offer = match msg.data.and_then(|d| serde_json::from_str::<CallOffer>(&d).ok()) {
+                            Some(offer) => offer,
+                            None => return false,
+                        };
+                        if let Some(call) = self.call.as_ref().filter(|c| c.id == offer.call_id) {
+                            call.renegotiated(offer);
+                            return false;
+                        }
+                        let reason = if self.call.is_some() {
+                            Some(EndReason::Busy)
+                        } else if self.block_list.is_blocked(&offer.peer) {
+                            Some(EndReason::Declined)
+                        } else {
+                            None
+                        };
+                        if let Some(reason) = reason {
+                            self.send_signal(Signal::End(CallEnd {
+                                call_id: offer.call_id,
+                                peer: offer.peer,
+                                reason,
+                            }));
+                            return false;
+                        }
+                        let name = offer.name.clone();
+                        let key = if offer.video { "call-incoming-video" } else { "call-incoming" };
+                        match Call::incoming(offer, ctx.link().callback(Msg::Call)) {
+                            Ok(call) => {
+                                notifications::notify_message(&name, &t_args(key, &[("name", &name)]));
+                                if !self.settings.sound_muted {
+                                    sound::play_chime();
+                                }
+                                self.call = Some(call);
+                                true
+                            }
+                            Err(e) => {
+                                log::error!("failed to set up incoming call: {}", e);
+                                false
+                            }
+                        }
+                    }
+                    MsgTypes::CallAnswer => {
+                        let answer = msg.data.and_then(|d| serde_json::from_str::<CallAnswer>(&d).ok());
+                        match (answer, self.call.as_mut()) {
+                            (Some(answer), Some(call)) if call.id == answer.call_id => {
+                                call.answered(answer);
+                                true
+                            }
+                            _ => false,
+                        }
+                    }
+                    MsgTypes::CallCandidate => {
+                        let candidate = msg.data.and_then(|d| serde_json::from_str::<CallCandidate>(&d).ok());
+                        if let (Some(candidate), Some(call)) = (candidate, &self.call) {
+                            if call.id == candidate.call_id {
+                                call.add_candidate(candidate);
+                            }
+                        }
+                        false
+                    }
+                    MsgTypes::CallEnd => {
+                        let end = msg.data.and_then(|d| serde_json::from_str::<CallEnd>(&d).ok());
+                        match (end, self.call.take()) {
+                            (Some(end), Some(call)) if call.id == end.call_id => {
+                                let key = match end.reason {
+                                    EndReason::Declined => "call-declined",
+                                    EndReason::Busy => "call-busy",
+                                    EndReason::Failed => "call-failed",
+                                    EndReason::HangUp if call.state == CallState::Incoming => "call-missed",
+                                    EndReason::HangUp => "call-ended",
+                                };
+                                self.notices.push(t_args(key, &[("name", &call.peer_name)]));
+                                true
+                            }
+                            (_, call) => {
+                                self.call = call;
+                                false
+                            }
+                        }
+                    }
+                    MsgTypes::Typing => {
+                        let event = match msg.data.and_then(|d| serde_json::from_str::<TypingEvent>(&d).ok()) {
+                            Some(event) if event.room == CHANNEL && !self.is_me(&event.id) => event,
+                            _ => return false,
+                        };
+                        if !event.typing || self.block_list.is_blocked(&event.id) {
+                            return self.typing.remove(&event.id).is_some();
+                        }
+                        let link = ctx.link().clone();
+                        let id = event.id.clone();
+                        let expiry = Timeout::new(TYPING_TIMEOUT, move || {
+                            link.send_message(Msg::TypingExpired(id))
+                        });
+                        let name = if event.name.is_empty() { event.id.clone() } else { event.name };
+                        self.typing.insert(event.id, (name, expiry));
+                        true
+                    }
+                    MsgTypes::PublicKey => {
+                        let peer = match msg.data.and_then(|d| serde_json::from_str::<PeerKey>(&d).ok()) {
+                            Some(peer) if !self.is_me(&peer.id) && e2ee::parse_key(&peer.key).is_some() => peer,
+                            _ => return false,
+                        };
+                        let changed = match self.known_keys.see(&peer.id, &peer.key) {
+                            KeySeen::Changed { was_verified } => {
+                                let name = self.profile(&peer.id).map(|p| p.name.clone()).unwrap_or_else(|| peer.id.clone());
+                                let key = if was_verified { "verify-verified-key-changed" } else { "verify-key-changed" };
+                                self.notices.push(t_args(key, &[("name", &name)]));
+                                true
+                            }
+                            KeySeen::New | KeySeen::Same => false,
+                        };
+                        // Someone new has not seen our key yet, as it was
+                        // announced before they logged in.
+                        if self.peer_keys.insert(peer.id.clone(), peer.key).is_none() {
+                            self.announce_key(Some(&peer.id));
+                        }
+                        changed
+                            || self.profile_popover.as_ref() == Some(&peer.id)
+                            || self.direct_peer.as_ref() == Some(&peer.id)
+                            || self.verifying.as_ref() == Some(&peer.id)
+                    }
+                    MsgTypes::SavedMessage => {
+                        let notes: Vec<SavedNote> = msg
+                            .data
+                            .into_iter()
+                            .chain(msg.data_array.unwrap_or_default())
+                            .filter_map(|d| serde_json::from_str(&d).ok())
+                            .collect();
+                        let mut added = false;
+                        for note in notes {
+                            if self.messages.iter().any(|m| m.id.as_ref() == Some(&note.id)) {
+                                continue;
+                            }
+                            self.messages.push(MessageData {
+                                id: Some(note.id),
+                                from_id: Some(self.user_id.clone()),
+                                from: self.username.clone(),
+                                message: note.message,
+                                attachment: None,
+                                reactions: Reactions::new(),
+                                timestamp: Some(note.timestamp),
+                                bot: false,
+                                actions: Vec::new(),
+                                client_id: None,
+                                edited: false,
+                                quote: None,
+                                forwarded_from: None,
+                                poll: None,
+                                expires_in: None,
+                                deleted: false,
+                                renamed_from: None,
+                                welcome: None,
+                                direct: Some(self.user_id.clone()),
+                                delivery: None,
+                                truncated: false,
+                            });
+                            added = true;
+                        }
+                        added
+                    }
+                    MsgTypes::DirectMessage => {
+                        let dm = match msg.data.and_then(|d| serde_json::from_str::<DirectMessage>(&d).ok()) {
+                            Some(dm) if !self.block_list.is_blocked(&dm.peer) => dm,
+                            _ => return false,
+                        };
+                        let name = if dm.name.is_empty() { dm.peer.clone() } else { dm.name };
+                        let opened = match &dm.sealed_for {
+                            Some(key) if !self.keys.is_ours(key) => Err(CryptoError::OtherKey),
+                            _ => self
+                                .peer_keys
+                                .get(&dm.peer)
+                                .ok_or(CryptoError::BadKey)
+                                .and_then(|key| self.keys.open(key, &dm.peer, &self.user_id, &dm.sealed)),
+                        };
+                        let mut text = match opened {
+                            Ok(text) => text,
+                            Err(e) => {
+                                self.notices.push(t_args("dm-failed", &[("name", &name), ("reason", &e.message())]));
+                                return true;
+                            }
+                        };
+                        let truncated = truncate_text(&mut text);
+                        if !self.block_list.is_muted(&dm.peer) {
+                            notifications::notify_message(&name, &text);
+                            if !self.settings.sound_muted && (!document_has_focus() || self.scrolled_up()) {
+                                sound::play_chime();
+                            }
+                            self.bump_badge();
+                        }
+                        if self.scrolled_up() {
+                            self.new_below += 1;
+                        } else {
+                            self.scroll_to_bottom = true;
+                        }
+                        self.messages.push(MessageData {
+                            id: None,
+                            from_id: Some(dm.peer.clone()),
+                            from: name,
+                            message: text,
+                            attachment: None,
+                            reactions: Reactions::new(),
+                            timestamp: Some(js_sys::Date::now()),
+                            bot: false,
+                            actions: Vec::new(),
+                            client_id: None,
+                            edited: false,
+                            quote: None,
+                            forwarded_from: None,
+                            poll: None,
+                            expires_in: dm.expires_in,
+                            deleted: false,
+                            renamed_from: None,
+                            welcome: None,
+                            direct: Some(dm.peer),
+                            delivery: None,
+                            truncated,
+                        });
+                        if self.scroll_to_bottom {
+                            self.trim_window();
+                        }
+                        true
+                    }
+                    MsgTypes::DigestPrefs => {
+                        match msg.data.and_then(|d| serde_json::from_str::<DigestPrefs>(&d).ok()) {
+                            Some(prefs) => {
+                                self.digest_prefs = Some(prefs);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::SessionList => {
+                        self.sessions = msg
+                            .data_array
+                            .unwrap_or_default()
+                            .iter()
+                            .filter_map(|s| serde_json::from_str(s).ok())
+                            .collect();
+                        true
+                    }
+                    MsgTypes::SessionRevoked => {
+                        if msg.data.as_deref() == Some(self.session_id.as_str()) {
+                            self.sign_out(ctx);
+                            if let Some(window) = web_sys::window() {
+                                let _ = window.alert_with_message(&t("sessions-revoked"));
+                            }
+                        } else {
+                            self.sessions.retain(|s| Some(&s.id) != msg.data.as_ref());
+                        }
+                        true
+                    }
+                    MsgTypes::Role => {
+                        self.role = msg
+                            .data
+                            .and_then(|r| serde_json::from_value(serde_json::Value::String(r)).ok())
+                            .unwrap_or_default();
+                        true
+                    }
+                    MsgTypes::ModAction => {
+                        match msg.data.map(|d| serde_json::from_str::<ModAction>(&d)) {
+                            Some(Ok(action)) => {
+                                let changed = self.apply_mod_action(ctx, &action);
+                                self.mod_actions.push(action);
+                                changed || self.show_moderation_log
+                            }
+                            _ => false,
+                        }
+                    }
+                    MsgTypes::History => {
+                        let mut older: Vec<MessageData> = msg
+                            .data_array
+                            .unwrap_or_default()
+                            .iter()
+                            .filter_map(|m| serde_json::from_str(m).ok())
+                            .collect();
+                        for message in &mut older {
+                            message.truncated = truncate_text(&mut message.message);
+                        }
+                        for message in &older {
+                            self.request_link_preview(ctx, &message.message);
+                        }
+                        self.prepend_history(older);
+                        self.seek_permalink(ctx);
+                        true
+                    }
+                    MsgTypes::Rename => {
+                        match msg.data.and_then(|d| serde_json::from_str::<Rename>(&d).ok()) {
+                            Some(rename) => {
+                                self.apply_rename(ctx, rename.id.as_deref(), &rename.from, &rename.to);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::Edit => {
+                        let mut edit = match msg.data.and_then(|d| serde_json::from_str::<MessageEdit>(&d).ok()) {
+                            Some(edit) => edit,
+                            None => return false,
+                        };
+                        let truncated = truncate_text(&mut edit.message);
+                        match self.messages.iter_mut().find(|m| m.id.as_ref() == Some(&edit.id) && !m.deleted) {
+                            Some(message) => {
+                                message.message = edit.message;
+                                message.edited = true;
+                                message.truncated = truncated;
+                                self.translations.remove(&edit.id);
+                                self.originals.remove(&edit.id);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::Delete => {
+                        let id = msg.data;
+                        match self.messages.iter_mut().find(|m| m.id.is_some() && m.id == id) {
+                            Some(message) => {
+                                message.deleted = true;
+                                message.message.clear();
+                                message.attachment = None;
+                                message.reactions.clear();
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::AuthFailed => {
+                        self.sign_out(ctx);
+                        false
+                    }
+                    MsgTypes::Reaction => {
+                        let update = match msg.data.and_then(|d| serde_json::from_str::<ReactionUpdate>(&d).ok()) {
+                            Some(update) => update,
+                            None => return false,
+                        };
+                        match self.messages.iter_mut().find(|m| m.id.as_ref() == Some(&update.id)) {
+                            Some(message) => {
+                                reactions::apply(&mut message.reactions, &update.emoji, &update.user_id, update.added);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    MsgTypes::Topic => {
+                        self.topic = msg.data.filter(|t| !t.is_empty());
+                        true
+                    }
+                    _ => {
+                        false
+                    }
+                }
+            }
+            Msg::SubmitMessage if self.pending_rules.is_some() => {
+                self.show_rules = true;
+                true
+            }
+            Msg::SubmitMessage if self.editing.is_some() => {
+                let input = match self.chat_input.cast::<HtmlTextAreaElement>() {
+                    Some(input) if !input.value().trim().is_empty() && within_limit(&input.value()) => input,
+                    _ => return false,
+                };
+                let idx = self.editing.take().unwrap();
+                let text = emoji::expand_message(&input.value());
+                if let Some(message) = self.messages.get_mut(idx).filter(|m| m.message != text) {
+                    message.message = text.clone();
+                    message.edited = true;
+                    let edit = MessageEdit {
+                        id: message.id.clone().unwrap_or_default(),
+                        message: text,
+                    };
+                    self.translations.remove(&edit.id);
+                    self.originals.remove(&edit.id);
+                    self.send(&WebSocketMessage {
+                        message_type: MsgTypes::Edit,
+                        data: Some(serde_json::to_string(&edit).unwrap()),
+                        data_array: None,
+                    });
+                }
+                composer::reset(&input);
+                true
+            }
+            Msg::SubmitMessage => {
+                let input = match self.chat_input.cast::<HtmlTextAreaElement>() {
+                    Some(input) if !input.value().trim().is_empty() && within_limit(&input.value()) => input,
+                    _ => return false,
+                };
+                self.command_notice = None;
+                let text = input.value();
+                let outcome = commands::parse(&text, &self.username).unwrap_or(Ok(Outcome::Send(text)));
+                match outcome {
+                    Ok(Outcome::Send(text)) => {
+                        if !self.allow_send(ctx) {
+                            return true;
+                        }
+                        let quote = self.quoting.take();
+                        self.queue_outgoing(ctx, self.direct_peer.clone(), text, quote);
+                    }
+                    Ok(Outcome::Clear) => self.clear_messages(),
+                    Ok(Outcome::Show(text)) => self.command_notice = Some(text),
+                    Ok(Outcome::Poll(..)) if self.direct_peer.is_some() => {
+                        self.command_notice = Some(t("poll-room-only"));
+                        return true;
+                    }
+                    Ok(Outcome::Poll(question, options)) => {
+                        if !self.allow_send(ctx) {
+                            return true;
+                        }
+                        self.send(&WebSocketMessage {
+                            message_type: MsgTypes::Poll,
+                            data: Some(serde_json::to_string(&NewPoll { question: &question, options: &options }).unwrap()),
+                            data_array: None,
+                        });
+                    }
+                    // Keep the input so the command can be corrected.
+                    Err(e) => {
+                        self.command_notice = Some(e.message());
+                        return true;
+                    }
+                }
+                let typing = self.typing_broadcaster.stop();
+                self.send_typing(typing);
+                composer::reset(&input);
+                self.drafts.clear(draft_key(self.direct_peer.as_deref()));
+                self.mention_query = None;
+                true
+            }
+            Msg::DismissCommandNotice => {
+                self.command_notice = None;
+                true
+            }
+            Msg::ExportHistory(format) => {
+                self.export_history(format);
+                false
+            }
+            Msg::OpenArchive(file) => {
+                self.import_error = None;
+                if file.size() > archive::MAX_IMPORT_SIZE as f64 {
+                    self.import_error = Some(ArchiveError::TooLarge.message());
+                    return true;
+                }
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let result = match gloo_file::futures::read_as_text(&file.into()).await {
+                        Ok(text) => archive::parse(&text),
+                        Err(e) => {
+                            log::error!("failed to read archive: {:?}", e);
+                            Err(ArchiveError::Unreadable)
+                        }
+                    };
+                    link.send_message(Msg::ArchiveLoaded(result));
+                });
+                true
+            }
+            Msg::ArchiveLoaded(Ok(archive)) => {
+                self.show_settings = false;
+                self.archive = Some(archive);
+                true
+            }
+            Msg::ArchiveLoaded(Err(e)) => {
+                self.import_error = Some(e.message());
+                true
+            }
+            Msg::CloseArchive => {
+                self.archive = None;
+                true
+            }
+            Msg::ShowSafetyNumber(peer) => {
+                self.verifying = peer.filter(|peer| self.peer_keys.contains_key(peer));
+                true
+            }
+            Msg::VerifyKey(peer) => {
+                if let Some(key) = self.peer_keys.get(&peer) {
+                    self.known_keys.verify(&peer, key);
+                }
+                true
+            }
+            Msg::UnverifyKey(peer) => {
+                self.known_keys.unverify(&peer);
+                true
+            }
+            Msg::AcknowledgeKeyChange(peer) => {
+                self.known_keys.acknowledge_change(&peer);
+                true
+            }
+            Msg::BackupKey(passphrase) => {
+                self.key_backup_error = None;
+                match self.keys.backup(&self.user_id, &passphrase) {
+                    Ok(backup) => archive::download("yewchat-key-backup.json", ExportFormat::Json, &backup.to_json()),
+                    Err(e) => self.key_backup_error = Some(e.message()),
+                }
+                true
+            }
+            Msg::RestoreKey(file, passphrase) => {
+                self.key_backup_error = None;
+                if file.size() > MAX_KEY_BACKUP_SIZE {
+                    self.key_backup_error = Some(BackupError::Malformed.message());
+                    return true;
+                }
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let result = gloo_file::futures::read_as_text(&file.into()).await.map_err(|e| {
+                        log::error!("failed to read key backup: {:?}", e);
+                        BackupError::Malformed
+                    });
+                    link.send_message(Msg::KeyBackupLoaded(result, passphrase));
+                });
+                true
+            }
+            Msg::KeyBackupLoaded(text, passphrase) => {
+                let restored = text
+                    .and_then(|text| KeyBackup::parse(&text))
+                    .and_then(|backup| backup.restore(&self.user_id, &passphrase));
+                let keys = match restored {
+                    Ok(keys) => keys,
+                    Err(e) => {
+                        self.key_backup_error = Some(e.message());
+                        return true;
+                    }
+                };
+                keys.save(&self.user_id);
+                self.keys = keys;
+                let token = auth::stored().map(|auth| auth.token).unwrap_or_default();
+                self.wss
+                    .set_handshake(handshake(&self.username, &self.user_id, &token, &self.keys));
+                self.announce_key(None);
+                self.update(ctx, Msg::ShowToast(t("key-backup-restored")))
+            }
+            Msg::SetDirectPeer(peer) => {
+                self.profile_popover = None;
+                self.show_sidebar = false;
+                self.switch_conversation(peer);
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    let _ = input.focus();
+                }
+                true
+            }
+            Msg::SetSplitPeer(peer) => {
+                self.profile_popover = None;
+                if peer.is_some() && self.direct_peer == peer {
+                    self.switch_conversation(None);
+                }
+                self.split_peer = peer;
+                true
+            }
+            Msg::SelectMessage(idx, extend) => {
+                match self.select_anchor.filter(|_| extend) {
+                    Some(anchor) => self.selected.extend(anchor.min(idx)..=anchor.max(idx)),
+                    None => {
+                        if !self.selected.remove(&idx) {
+                            self.selected.insert(idx);
+                        }
+                    }
+                }
+                self.select_anchor = Some(idx);
+                true
+            }
+            Msg::ClearSelection => {
+                self.selected.clear();
+                self.select_anchor = None;
+                true
+            }
+            Msg::CopySelection => {
+                clipboard::write_text(&self.selection_transcript());
+                self.selected.clear();
+                self.select_anchor = None;
+                true
+            }
+            Msg::TogglePrintDialog => {
+                self.show_print_dialog = !self.show_print_dialog;
+                true
+            }
+            Msg::MessageFocused(idx) => {
+                if self.focused_message == Some(idx) {
+                    return false;
+                }
+                self.focused_message = Some(idx);
+                self.reaction_pick = None;
+                true
+            }
+            Msg::MessageKey(idx, key) => {
+                let count = reactions::QUICK_REACTIONS.len();
+                match (key.as_str(), self.reaction_pick) {
+                    ("ArrowUp" | "ArrowDown", _) => {
+                        let next = if key == "ArrowUp" {
+                            (self.window_start..idx).rev().find(|&i| self.navigable(i))
+                        } else {
+                            (idx + 1..self.messages.len()).find(|&i| self.navigable(i))
+                        };
+                        match next {
+                            Some(next) => {
+                                self.focused_message = Some(next);
+                                self.focus_pending = true;
+                                self.reaction_pick = None;
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    ("+", _) => {
+                        let reactable = self.messages.get(idx).is_some_and(|m| m.id.is_some() && !m.deleted);
+                        if reactable {
+                            self.focused_message = Some(idx);
+                            self.reaction_pick = Some(0);
+                        }
+                        reactable
+                    }
+                    ("ArrowLeft", Some(pick)) => {
+                        self.reaction_pick = Some((pick + count - 1) % count);
+                        true
+                    }
+                    ("ArrowRight", Some(pick)) => {
+                        self.reaction_pick = Some((pick + 1) % count);
+                        true
+                    }
+                    ("Enter", Some(pick)) => {
+                        self.reaction_pick = None;
+                        if let Some(id) = self.messages.get(idx).and_then(|m| m.id.clone()) {
+                            ctx.link()
+                                .send_message(Msg::React(id, reactions::QUICK_REACTIONS[pick].to_string()));
+                        }
+                        true
+                    }
+                    ("Escape", Some(_)) => {
+                        self.reaction_pick = None;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Msg::PrintConversation(range) => {
+                self.show_print_dialog = false;
+                self.print_range = Some(range);
+                true
+            }
+            Msg::PrintDone => {
+                self.print_range = None;
+                true
+            }
+            Msg::ToggleFocusMode => {
+                self.settings.focus_mode = !self.settings.focus_mode;
+                self.settings.save();
+                true
+            }
+            Msg::SignOut => {
+                self.sign_out(ctx);
+                false
+            }
+            Msg::PopOut => {
+                if !matches!(self.mini_chat, MiniChat::Closed) {
+                    return false;
+                }
+                if !document_pip::is_supported() {
+                    self.mini_chat = MiniChat::Floating;
+                    return true;
+                }
+                let link = ctx.link().clone();
+                spawn_local(async move {
+                    let (width, height) = MINI_CHAT_SIZE;
+                    match document_pip::open(width, height).await {
+                        Ok(window) => link.send_message(Msg::MiniWindowOpened(Some(window))),
+                        Err(e) => {
+                            log::error!("failed to open mini chat window: {:?}", e);
+                            link.send_message(Msg::MiniWindowOpened(None));
+                        }
+                    }
+                });
+                false
+            }
+            Msg::MiniWindowOpened(window) => {
+                let opened = window.and_then(|window| {
+                    let document = window.document()?;
+                    let body: Element = document.body()?.into();
+                    let closed = {
+                        let link = ctx.link().clone();
+                        EventListener::new(&window, "pagehide", move |_| link.send_message(Msg::CloseMiniChat))
+                    };
+                    let keydown = {
+                        let link = ctx.link().clone();
+                        EventListener::new(&document, "keydown", move |e| {
+                            if mini_chat_submission(e.unchecked_ref()) {
+                                link.send_message(Msg::MiniSend);
+                            }
+                        })
+                    };
+                    Some(MiniChat::Window {
+                        window,
+                        body,
+                        _listeners: [closed, keydown],
+                    })
+                });
+                self.mini_chat = opened.unwrap_or(MiniChat::Floating);
+                true
+            }
+            Msg::CloseMiniChat => {
+                if let MiniChat::Window { window, .. } = mem::replace(&mut self.mini_chat, MiniChat::Closed) {
+                    let _ = window.close();
+                }
+                true
+            }
+            Msg::MiniSend => {
+                let input = self.mini_input.get().map(|node| node.unchecked_into::<HtmlTextAreaElement>());
+                match input.filter(|i| !i.value().trim().is_empty()) {
+                    Some(input) if self.allow_send(ctx) => {
+                        self.queue_outgoing(ctx, self.direct_peer.clone(), input.value(), None);
+                        input.set_value("");
+                        true
+                    }
+                    _ => true,
+                }
+            }
+            Msg::CooldownTick => {
+                self.refresh_cooldown(ctx);
+                true
+            }
+            Msg::SubmitSplitMessage => {
+                let input = self.split_input.cast::<HtmlTextAreaElement>();
+                match (self.split_peer.clone(), input.filter(|i| !i.value().trim().is_empty() && within_limit(&i.value()))) {
+                    (Some(peer), Some(input)) if self.allow_send(ctx) => {
+                        self.queue_outgoing(ctx, Some(peer), input.value(), None);
+                        composer::reset(&input);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Msg::TogglePinnedDm(id) => {
+                self.profile_popover = None;
+                self.settings.toggle_pinned_dm(&id);
+                self.settings.save();
+                true
+            }
+            Msg::MovePinnedDm(from, to) => {
+                self.settings.move_pinned_dm(from, to);
+                self.settings.save();
+                true
+            }
+            Msg::DispatchMessage(id) => match self.outbox.remove(&id) {
+                Some((peer, text, quote, _)) => {
+                    self.dispatch(ctx, peer, text, quote, None);
+                    true
+                }
+                None => false,
+            },
+            Msg::Shortcut(action) => match action {
+                Action::QuickSwitcher => {
+                    self.show_quick_switcher = !self.show_quick_switcher;
+                    true
+                }
+                Action::ClosePanels => self.close_panels(),
+                Action::EditLast => self.edit_last(),
+                Action::PreviousConversation | Action::NextConversation => {
+                    let conversations = self.conversations();
+                    let current = conversations.iter().position(|c| *c == self.direct_peer).unwrap_or(0);
+                    let next = if action == Action::NextConversation {
+                        (current + 1) % conversations.len()
+                    } else {
+                        (current + conversations.len() - 1) % conversations.len()
+                    };
+                    self.update(ctx, Msg::SetDirectPeer(conversations[next].clone()))
+                }
+                Action::ToggleFocusMode => self.update(ctx, Msg::ToggleFocusMode),
+                Action::ToggleActionLog => self.update(ctx, Msg::ToggleActionLog),
+                Action::ShowHelp => {
+                    self.show_shortcuts = !self.show_shortcuts;
+                    true
+                }
+            },
+            Msg::QuickSwitch(peer) => {
+                self.show_quick_switcher = false;
+                self.update(ctx, Msg::SetDirectPeer(peer))
+            }
+            Msg::CancelEdit => {
+                self.editing = None;
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    composer::reset(&input);
+                }
+                true
+            }
+            Msg::ToggleScheduleDialog => {
+                let empty = self
+                    .chat_input
+                    .cast::<HtmlTextAreaElement>()
+                    .is_none_or(|input| input.value().trim().is_empty());
+                self.show_schedule_dialog = !self.show_schedule_dialog && !empty;
+                true
+            }
+            Msg::ScheduleMessage(send_at) => {
+                self.show_schedule_dialog = false;
+                let input = match self.chat_input.cast::<HtmlTextAreaElement>() {
+                    Some(input) if !input.value().trim().is_empty() && within_limit(&input.value()) => input,
+                    _ => return true,
+                };
+                // Commands run now or not at all; only text can wait.
+                let text = match commands::parse(&input.value(), &self.username) {
+                    None => input.value(),
+                    Some(Ok(Outcome::Send(text))) => text,
+                    Some(_) => {
+                        self.command_notice = Some(t("schedule-no-commands"));
+                        return true;
+                    }
+                };
+                self.scheduler.add(self.direct_peer.clone(), emoji::expand_message(&text), send_at);
+                composer::reset(&input);
+                self.drafts.clear(draft_key(self.direct_peer.as_deref()));
+                self.update(ctx, Msg::ShowToast(t_args("schedule-done", &[("time", &format_time(send_at))])))
+            }
+            Msg::ToggleScheduled => {
+                self.show_scheduled = !self.show_scheduled;
+                true
+            }
+            Msg::ScheduledDue => {
+                for message in self.scheduler.due(js_sys::Date::now()) {
+                    // The flood limit holds for these too; the rest wait in
+                    // the queue until it lifts.
+                    if !self.allow_send(ctx) {
+                        let wait = self.send_throttle.cooldown_ms(js_sys::Date::now()).unwrap_or_default();
+                        self.scheduler.retry_in(wait);
+                        break;
+                    }
+                    // One that can't go out, e.g. for want of the peer's key,
+                    // waits to be sent or cancelled by hand.
+                    if self.dispatch(ctx, message.peer, message.text, None, None) {
+                        self.scheduler.cancel(message.id);
+                    } else {
+                        self.scheduler.mark_missed(message.id);
+                    }
+                }
+                true
+            }
+            Msg::CancelScheduled(id) => self.scheduler.cancel(id).is_some(),
+            Msg::SendScheduledNow(id) => {
+                let message = match self.scheduler.pending().iter().find(|m| m.id == id) {
+                    Some(message) => message.clone(),
+                    None => return false,
+                };
+                if self.allow_send(ctx) && self.dispatch(ctx, message.peer, message.text, None, None) {
+                    self.scheduler.cancel(id);
+                }
+                true
+            }
+            Msg::SetDisappearing(seconds) => {
+                let conversation = self.direct_peer.clone().unwrap_or_else(|| CHANNEL.to_string());
+                match seconds {
+                    Some(seconds) => self.settings.disappearing.insert(conversation, seconds),
+                    None => self.settings.disappearing.remove(&conversation),
+                };
+                self.settings.save();
+                true
+            }
+            Msg::ExpiryTick => {
+                self.expire_messages();
+                self.refresh_expiry(ctx);
+                true
+            }
+            Msg::VotePoll(poll_id, option) => {
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::PollVote,
+                    data: Some(serde_json::to_string(&PollVote { poll_id: &poll_id, option }).unwrap()),
+                    data_array: None,
+                });
+                false
+            }
+            Msg::ClosePoll(poll_id) => {
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::PollClose,
+                    data: Some(serde_json::to_string(&PollClose { poll_id: &poll_id }).unwrap()),
+                    data_array: None,
+                });
+                false
+            }
+            Msg::CopyMessage(idx) => {
+                if let Some(message) = self.messages.get(idx) {
+                    let link = ctx.link().clone();
+                    clipboard::copy(&message.message, move || link.send_message(Msg::ShowToast(t("message-copied"))));
+                }
+                false
+            }
+            Msg::ShowToast(text) => {
+                let link = ctx.link().clone();
+                let timer = Timeout::new(TOAST_DURATION, move || link.send_message(Msg::HideToast));
+                self.toast = Some((text, timer));
+                true
+            }
+            Msg::HideToast => self.toast.take().is_some(),
+            Msg::CopyPermalink(idx) => {
+                self.open_menu = None;
+                if let Some(id) = self.messages.get(idx).and_then(|m| m.id.clone()) {
+                    let link = ctx.link().clone();
+                    clipboard::copy(&permalink(&id), move || link.send_message(Msg::ShowToast(t("permalink-copied"))));
+                }
+                true
+            }
+            Msg::OpenPermalink => {
+                self.permalink = permalink_target();
+                self.seek_permalink(ctx)
+            }
+            Msg::ClearHighlight => self.highlighted.take().is_some(),
+            Msg::QuoteMessage(idx) => {
+                self.open_menu = None;
+                let message = match self.messages.get(idx) {
+                    Some(message) if message.direct.is_none() && !message.deleted => message,
+                    _ => return false,
+                };
+                let id = match &message.id {
+                    Some(id) => id.clone(),
+                    None => return false,
+                };
+                let text = message.preview();
+                let mut excerpt: String = text.chars().take(QUOTE_EXCERPT).collect();
+                if excerpt.len() < text.len() {
+                    excerpt.push('…');
+                }
+                self.quoting = Some(Quote {
+                    id,
+                    from: message.from.clone(),
+                    excerpt,
+                });
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    let _ = input.focus();
+                }
+                true
+            }
+            Msg::CancelQuote => self.quoting.take().is_some(),
+            Msg::ForwardMessage(idx) => {
+                self.open_menu = None;
+                self.forwarding = Some(idx);
+                true
+            }
+            Msg::Forward(destination) => {
+                let (idx, peer) = match (self.forwarding.take(), destination) {
+                    (Some(idx), Some(peer)) => (idx, peer),
+                    _ => return true,
+                };
+                let forwardable = matches!(self.messages.get(idx), Some(m) if !m.deleted && m.poll.is_none());
+                if !forwardable || !self.allow_send(ctx) {
+                    return true;
+                }
+                let message = &self.messages[idx];
+                let mut text = message.message.clone();
+                if let Some(attachment) = &message.attachment {
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(&format!("📎 {} {}", attachment.name, attachment.url));
+                }
+                // Forwarding a forward keeps the original author.
+                let from = message.forwarded_from.clone().unwrap_or_else(|| message.from.clone());
+                self.dispatch(ctx, peer.clone(), text, None, Some(from));
+                self.update(ctx, Msg::SetDirectPeer(peer))
+            }
+            Msg::ToggleSidebar => {
+                self.show_sidebar = !self.show_sidebar;
+                true
+            }
+            Msg::ViewportResized => {
+                let height = visual_viewport_height();
+                let changed = height != self.viewport_height;
+                self.viewport_height = height;
+                if changed && !self.scrolled_up() {
+                    self.scroll_to_bottom = true;
+                }
+                changed
+            }
+            Msg::FilterMembers(filter) => {
+                self.member_filter = filter.trim().to_lowercase();
+                true
+            }
+            Msg::SortMembers(sort) => {
+                self.member_sort = sort;
+                true
+            }
+            Msg::DeliveryTimedOut(client_id) => {
+                self.delivery_deadlines.remove(&client_id);
+                match self.local_copy(&client_id) {
+                    Some(idx) if self.messages[idx].delivery == Some(Delivery::Sending) => {
+                        self.messages[idx].delivery = Some(Delivery::Failed);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Msg::RetrySend(client_id) => match self.local_copy(&client_id) {
+                Some(idx) => {
+                    self.transmit(ctx, idx);
+                    true
+                }
+                None => false,
+            },
+            Msg::DiscardFailed(client_id) => match self.local_copy(&client_id) {
+                Some(idx) => {
+                    self.messages[idx].delivery = Some(Delivery::Discarded);
+                    true
+                }
+                None => false,
+            },
+            Msg::UndoSend(id) => {
+                let text = match self.outbox.remove(&id) {
+                    Some((peer, text, quote, _)) => {
+                        self.switch_conversation(peer);
+                        self.quoting = quote;
+                        text
+                    }
+                    None => return false,
+                };
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    // Keep anything typed since, after the message coming back.
+                    let text = match input.value() {
+                        current if current.trim().is_empty() => text,
+                        current => format!("{}\n{}", text, current),
+                    };
+                    composer::set_text(&input, &text);
+                    self.drafts.set(draft_key(self.direct_peer.as_deref()), &text);
+                    let _ = input.focus();
+                }
+                true
+            }
+            Msg::ToggleProfile(id) => {
+                self.profile_popover = match self.profile_popover.take() {
+                    Some(open) if open == id => None,
+                    _ => Some(id),
+                };
+                true
+            }
+            Msg::SetStatus(emoji, text) => {
+                let text: String = text.trim().chars().take(STATUS_LEN).collect();
+                let status = UserStatus {
+                    user_id: String::new(),
+                    emoji: emoji.filter(|_| !text.is_empty()),
+                    text,
+                };
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::Status,
+                    data: Some(serde_json::to_string(&status).unwrap()),
+                    data_array: None,
+                });
+                false
+            }
+            Msg::SetChannelLevel(level) => {
+                match level {
+                    Some(level) => self.settings.channel_overrides.insert(CHANNEL.to_string(), level),
+                    None => self.settings.channel_overrides.remove(CHANNEL),
+                };
+                self.settings.save();
+                true
+            }
+            Msg::PresenceChanged(presence) => {
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::Presence,
+                    data: Some(serde_json::to_string(&PresenceUpdate {
+                        user_id: String::new(),
+                        presence,
+                    }).unwrap()),
+                    data_array: None,
+                });
+                let me = self.user_id.clone();
+                match presence {
+                    Presence::Away => self.away.insert(me),
+                    Presence::Online => self.away.remove(&me),
+                }
+            }
+            Msg::StartCall(id, video) => {
+                self.profile_popover = None;
+                if self.call.is_some() {
+                    return false;
+                }
+                let name = self
+                    .profile(&id)
+                    .map(|p| p.name.clone())
+                    .unwrap_or_else(|| id.clone());
+                match Call::start(&id, &name, &self.username, video, ctx.link().callback(Msg::Call)) {
+                    Ok(call) => self.call = Some(call),
+                    Err(e) => {
+                        log::error!("failed to start call: {}", e);
+                        self.notices.push(t_args("call-failed", &[("name", &name)]));
+                    }
+                }
+                true
+            }
+            Msg::Call(CallEvent::Signal(signal)) => {
+                self.send_signal(signal);
+                false
+            }
+            Msg::Call(CallEvent::Connected) => match self.call.as_mut() {
+                Some(call) => {
+                    call.state = CallState::Active;
+                    true
+                }
+                None => false,
+            },
+            Msg::Call(CallEvent::Media) => self.call.is_some(),
+            Msg::Call(CallEvent::Failed(e)) => {
+                log::error!("call failed: {}", e);
+                if let Some(call) = self.call.take() {
+                    call.end(EndReason::Failed);
+                    self.notices.push(t_args("call-failed", &[("name", &call.peer_name)]));
+                }
+                true
+            }
+            Msg::AcceptCall(video) => {
+                if let Some(call) = self.call.as_mut() {
+                    call.accept(video);
+                }
+                true
+            }
+            Msg::ToggleCallCamera => {
+                if let Some(call) = self.call.as_mut() {
+                    call.toggle_camera();
+                }
+                true
+            }
+            Msg::ToggleScreenShare => {
+                if let Some(call) = &self.call {
+                    if call.is_sharing_screen() {
+                        call.stop_screen_share();
+                    } else {
+                        call.share_screen();
+                    }
+                }
+                false
+            }
+            Msg::SwitchCallDevice(kind, device_id) => {
+                if let Some(call) = &self.call {
+                    call.switch_device(kind, device_id);
+                }
+                false
+            }
+            Msg::ToggleCallMute => {
+                if let Some(call) = self.call.as_mut() {
+                    call.toggle_mute();
+                }
+                true
+            }
+            Msg::EndCall => {
+                if let Some(call) = self.call.take() {
+                    let reason = if call.state == CallState::Incoming {
+                        EndReason::Declined
+                    } else {
+                        EndReason::HangUp
+                    };
+                    call.end(reason);
+                }
+                true
+            }
+            Msg::ToggleMute(id) => {
+                self.block_list.toggle_mute(&id);
+                true
+            }
+            Msg::ToggleBlock(id) => {
+                self.block_list.toggle_block(&id);
+                true
+            }
+            Msg::RevealMessage(idx) => {
+                self.revealed.insert(idx)
+            }
+            Msg::UseTemplate(template) => {
+                if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                    let text = t(template);
+                    composer::set_text(&input, &text);
+                    self.drafts.set(draft_key(self.direct_peer.as_deref()), &text);
+                    let _ = input.focus();
+                }
+                false
+            }
+            Msg::ToggleNotifications => {
+                self.notifications_enabled = !self.notifications_enabled;
+                notifications::set_enabled(self.notifications_enabled);
+                true
+            }
+            Msg::VisibilityChanged => {
+                if document_hidden() {
+                    // Everything up to now has been seen; start a fresh unread run.
+                    self.first_unread = None;
+                    false
+                } else {
+                    self.scroll_to_unread = self.first_unread.is_some();
+                    self.clear_badge();
+                    if let Some(first_unread) = self.first_unread {
+                        self.window_start = self.window_start.min(first_unread);
+                    }
+                    self.mark_read();
+                    self.scroll_to_unread
+                }
+            }
+            Msg::WindowFocused => {
+                self.mark_read();
+                self.clear_badge();
+                false
+            }
+            Msg::TogglePlaintext => {
+                self.plaintext = !self.plaintext;
+                message_content::set_plaintext_mode(self.plaintext);
+                true
+            }
+            Msg::ToggleSound => {
+                self.settings.sound_muted = !self.settings.sound_muted;
+                self.settings.save();
+                true
+            }
+            Msg::ToggleSettingsPanel => {
+                self.show_settings = !self.show_settings;
+                if self.show_settings {
+                    self.send(&WebSocketMessage {
+                        message_type: MsgTypes::SessionList,
+                        data: None,
+                        data_array: None,
+                    });
+                    self.send(&WebSocketMessage {
+                        message_type: MsgTypes::DigestPrefs,
+                        data: None,
+                        data_array: None,
+                    });
+                }
+                true
+            }
+            Msg::PaneScrolled => {
+                let pane = match self.message_pane.cast::<Element>() {
+                    Some(pane) => pane,
+                    None => return false,
+                };
+                if self.window_start > 0 && pane.scroll_top() < RENDER_THRESHOLD {
+                    self.window_start = self.window_start.saturating_sub(RENDER_PAGE);
+                    self.anchor_from_bottom = Some(pane.scroll_height() - pane.scroll_top());
+                    true
+                } else if pane.scroll_top() < RENDER_THRESHOLD
+                    && !self.loading_history
+                    && !self.history_exhausted
+                {
+                    self.request_history();
+                    true
+                } else if !self.scrolled_up() {
+                    self.mark_read();
+                    let trimmed = self.trim_window();
+                    self.scroll_to_bottom = trimmed;
+                    let cleared = mem::take(&mut self.new_below) > 0;
+                    trimmed || cleared
+                } else {
+                    false
+                }
+            }
+            Msg::ToggleMessageMenu(idx) => {
+                self.open_menu = if self.open_menu == Some(idx) { None } else { Some(idx) };
+                true
+            }
+            Msg::HideMessage(id) => {
+                self.open_menu = None;
+                self.settings.hidden_messages.insert(id);
+                self.settings.save();
+                true
+            }
+            Msg::DeleteMessage(id) => {
+                self.open_menu = None;
+                let allowed = self
+                    .messages
+                    .iter()
+                    .find(|m| m.id.as_ref() == Some(&id))
+                    .map(|m| self.can_delete(m))
+                    .unwrap_or(false);
+                if allowed {
+                    self.send(&WebSocketMessage {
+                        message_type: MsgTypes::Delete,
+                        data: Some(id),
+                        data_array: None,
+                    });
+                }
+                false
+            }
+            Msg::SendBotCommand(message_id, payload) => {
+                if !self.allow_send(ctx) {
+                    return true;
+                }
+                let command = BotCommand {
+                    message_id: &message_id,
+                    payload: &payload,
+                };
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::BotCommand,
+                    data: Some(serde_json::to_string(&command).unwrap()),
+                    data_array: None,
+                });
+                false
+            }
+            Msg::Moderate(kind, user_id) => {
+                self.open_menu = None;
+                self.profile_popover = None;
+                let message_type = match kind {
+                    ModActionKind::Kick => MsgTypes::Kick,
+                    ModActionKind::Ban => MsgTypes::Ban,
+                    ModActionKind::Purge => MsgTypes::Purge,
+                    _ => return true,
+                };
+                if !self.role.can_moderate() || self.is_me(&user_id) {
+                    return true;
+                }
+                let name = self.profile(&user_id).map(|p| p.name.clone()).unwrap_or_else(|| user_id.clone());
+                let key = match kind {
+                    ModActionKind::Kick => "mod-kick-confirm",
+                    ModActionKind::Ban => "mod-ban-confirm",
+                    _ => "mod-purge-confirm",
+                };
+                let question = t_args(key, &[("name", &name), ("room", CHANNEL)]);
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message(&question).ok())
+                    .unwrap_or(false);
+                if confirmed {
+                    let request = ModerationRequest {
+                        room: CHANNEL,
+                        user_id: &user_id,
+                    };
+                    self.send(&WebSocketMessage {
+                        message_type,
+                        data: Some(serde_json::to_string(&request).unwrap()),
+                        data_array: None,
+                    });
+                }
+                true
+            }
+            Msg::AttachFile(file) => {
+                if self.refuse_file() || !self.allow_send(ctx) {
+                    return true;
+                }
+                let id = self.next_upload;
+                self.next_upload += 1;
+                self.uploads.insert(id, (file.name(), 0.0));
+                let link = ctx.link().clone();
+                let progress = ctx.link().callback(move |fraction| Msg::UploadProgress(id, fraction));
+                spawn_local(async move {
+                    let result = attachments::prepare(file.into(), move |f| progress.emit(f)).await;
+                    link.send_message(Msg::AttachmentReady(id, result));
+                });
+                true
+            }
+            Msg::PreviewFile(file) => {
+                self.drag_active = false;
+                if self.refuse_file() {
+                    return true;
+                }
+                if self.pending_rules.is_some() {
+                    self.show_rules = true;
+                } else {
+                    self.pending_file = Some(file);
+                }
+                true
+            }
+            Msg::ConfirmPendingFile => {
+                if let Some(file) = self.pending_file.take() {
+                    ctx.link().send_message(Msg::AttachFile(file));
+                }
+                true
+            }
+            Msg::CancelPendingFile => {
+                self.pending_file = None;
+                true
+            }
+            Msg::DragActive(active) => {
+                // Dropping files is not offered in direct messages.
+                let active = active && outgoing(self.direct_peer.as_deref(), false) == Outgoing::Room;
+                let changed = self.drag_active != active;
+                self.drag_active = active;
+                changed
+            }
+            Msg::UploadProgress(id, fraction) => match self.uploads.get_mut(&id) {
+                Some((_, progress)) => {
+                    *progress = fraction;
+                    true
+                }
+                None => false,
+            },
+            Msg::AttachmentReady(id, Ok(attachment)) => {
+                self.uploads.remove(&id);
+                // Uploads are only started in the room, see `refuse_file`,
+                // and go there even if a direct message was opened since.
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::Attachment,
+                    data: Some(serde_json::to_string(&attachment).unwrap()),
+                    data_array: None,
+                });
+                true
+            }
+            Msg::AttachmentReady(id, Err(e)) => {
+                self.uploads.remove(&id);
+                if let AttachmentError::Failed(reason) = &e {
+                    log::error!("attachment failed: {}", reason);
+                }
+                if let Some(window) = web_sys::window() {
+                    let _ = window.alert_with_message(&e.message());
+                }
+                true
+            }
+            Msg::SidebarScrolled => {
+                let window = self.visible_members();
+                if window == self.member_window {
+                    return false;
+                }
+                self.member_window = window;
+                self.request_missing_members();
+                true
+            }
+            Msg::MentionQuery(query) => {
+                if let Some(query) = &query {
+                    if self.local_mention_matches(query).is_empty() {
+                        self.search_users(query);
+                    }
+                }
+                let changed = self.mention_query != query;
+                self.mention_query = query;
+                changed
+            }
+            Msg::ToggleInviteDialog => {
+                self.invite_query = match self.invite_query {
+                    Some(_) => None,
+                    None => Some(String::new()),
+                };
+                true
+            }
+            Msg::InviteSearch(query) => {
+                self.search_users(&query);
+                self.invite_query = Some(query);
+                true
+            }
+            Msg::InviteUsers(users) => {
+                self.invite_query = None;
+                if !users.is_empty() {
+                    let request = InviteRequest { room: CHANNEL, users };
+                    self.send(&WebSocketMessage {
+                        message_type: MsgTypes::Invite,
+                        data: Some(serde_json::to_string(&request).unwrap()),
+                        data_array: None,
+                    });
+                }
+                true
+            }
+            Msg::ToggleNotificationCenter => {
+                self.show_notification_center = !self.show_notification_center;
+                true
+            }
+            Msg::ToggleGifPicker => {
+                self.show_gif_picker = !self.show_gif_picker;
+                true
+            }
+            Msg::SendGif(url) => {
+                self.show_gif_picker = false;
+                if !self.allow_send(ctx) {
+                    return true;
+                }
+                // Like anything typed, so it is echoed, can be undone and
+                // follows the conversation's disappearing timer.
+                match outgoing(self.direct_peer.as_deref(), true) {
+                    Outgoing::Room => self.queue_outgoing(ctx, None, url, None),
+                    Outgoing::Direct(peer) => self.queue_outgoing(ctx, Some(peer), url, None),
+                    Outgoing::Refused => {}
+                }
+                true
+            }
+            Msg::LinkPreviewLoaded(url, preview) => {
+                self.link_previews.insert(url, preview);
+                true
+            }
+            Msg::Translate(id) => {
+                self.open_menu = None;
+                self.originals.remove(&id);
+                let text = match self.messages.iter().find(|m| m.id.as_ref() == Some(&id)) {
+                    Some(message) if message.direct.is_none() && !self.translations.contains_key(&id) => message.message.clone(),
+                    _ => return true,
+                };
+                self.translations.insert(id.clone(), None);
+                let (link, locale) = (ctx.link().clone(), self.settings.locale);
+                spawn_local(async move {
+                    let translated = translate::translate(&text, locale).await;
+                    link.send_message(Msg::Translated(id, translated));
+                });
+                true
+            }
+            Msg::Translated(id, Ok(text)) => {
+                // Dropped meanwhile, e.g. by an edit.
+                if self.translations.contains_key(&id) {
+                    self.translations.insert(id, Some(text));
+                }
+                true
+            }
+            Msg::Translated(id, Err(e)) => {
+                log::error!("translation failed: {}", e);
+                self.translations.remove(&id);
+                self.update(ctx, Msg::ShowToast(t("translation-failed")))
+            }
+            Msg::ToggleOriginal(id) => {
+                if !self.originals.remove(&id) {
+                    self.originals.insert(id);
+                }
+                true
+            }
+            Msg::TogglePreview(idx) => {
+                if !self.collapsed_previews.remove(&idx) {
+                    self.collapsed_previews.insert(idx);
+                }
+                true
+            }
+            Msg::DismissNotice(index) => {
+                if index < self.notices.len() {
+                    self.notices.remove(index);
+                }
+                true
+            }
+            Msg::ToggleJoinRequests => {
+                self.show_join_requests = !self.show_join_requests && self.role.can_moderate();
+                true
+            }
+            Msg::DecideJoinRequest(id, approve) => {
+                self.join_requests.retain(|r| r.id != id);
+                let decision = JoinDecision {
+                    id: Some(id),
+                    room: None,
+                    approve,
+                };
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::JoinDecision,
+                    data: Some(serde_json::to_string(&decision).unwrap()),
+                    data_array: None,
+                });
+                true
+            }
+            Msg::SaveDigestPrefs(prefs) => {
+                // The server echoes the stored prefs back, including whether
+                // the address still needs confirming.
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::DigestPrefs,
+                    data: Some(serde_json::to_string(&prefs).unwrap()),
+                    data_array: None,
+                });
+                false
+            }
+            Msg::ToggleRules => {
+                self.show_rules = !self.show_rules && self.pending_rules.is_some();
+                true
+            }
+            Msg::AcceptRules => {
+                if let Some(rules) = self.pending_rules.take() {
+                    rooms::accept_rules(CHANNEL, &rules);
+                    self.send(&WebSocketMessage {
+                        message_type: MsgTypes::RulesAck,
+                        data: Some(
+                            serde_json::to_string(&RulesAck {
+                                room: CHANNEL,
+                                version: rules.version,
+                            })
+                            .unwrap(),
+                        ),
+                        data_array: None,
+                    });
+                }
+                self.show_rules = false;
+                true
+            }
+            Msg::RespondToInvitation(id, accept) => {
+                self.invitations.retain(|i| i.id != id);
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::InviteResponse,
+                    data: Some(serde_json::to_string(&InviteResponse { id, accept }).unwrap()),
+                    data_array: None,
+                });
+                true
+            }
+            Msg::EnlargeImage(src) => {
+                self.enlarged_image = src;
+                true
+            }
+            Msg::React(id, emoji) => {
+                self.open_menu = None;
+                let added = match self.messages.iter().find(|m| m.id.as_ref() == Some(&id)) {
+                    Some(message) => !reactions::reacted(&message.reactions, &emoji, &self.user_id),
+                    None => return false,
+                };
+                let update = ReactionUpdate {
+                    id,
+                    emoji,
+                    added,
+                    user_id: String::new(),
+                };
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::Reaction,
+                    data: Some(serde_json::to_string(&update).unwrap()),
+                    data_array: None,
+                });
+                true
+            }
+            Msg::ToggleRoomInfo => {
+                self.show_room_info = !self.show_room_info;
+                true
+            }
+            Msg::JumpToMessage(idx) => {
+                self.show_room_info = false;
+                self.window_start = self.window_start.min(idx);
+                self.jump_to = Some(idx);
+                self.scroll_to_bottom = false;
+                true
+            }
+            Msg::ToggleModerationLog => {
+                self.show_moderation_log = !self.show_moderation_log && self.role.can_moderate();
+                true
+            }
+            Msg::ToggleActionLog => {
+                // Nothing is recorded while closed, so a gap would follow.
+                if self.show_action_log {
+                    self.update(ctx, Msg::ActionLogLive);
+                    self.action_log.clear();
+                }
+                self.show_action_log = !self.show_action_log && action_log::ENABLED;
+                true
+            }
+            Msg::ClearActionLog => {
+                self.update(ctx, Msg::ActionLogLive);
+                self.action_log.clear();
+                self.show_action_log
+            }
+            Msg::ActionLogBack | Msg::ActionLogForward | Msg::ActionLogLive => {
+                if !self.action_log.stepping_through() {
+                    self.action_log.settle(self.snapshot());
+                }
+                let state = match msg {
+                    Msg::ActionLogBack => self.action_log.step_back(),
+                    Msg::ActionLogForward => self.action_log.step_forward(),
+                    _ => self.action_log.live(),
+                };
+                if let Some(state) = state {
+                    self.restore(state);
+                }
+                if !self.action_log.stepping_through() {
+                    for msg in mem::take(&mut self.paused) {
+                        self.update(ctx, msg);
+                    }
+                }
+                true
+            }
+            Msg::DraftChanged(text) => {
+                self.drafts.set(draft_key(self.direct_peer.as_deref()), &text);
+                // Typing is announced to the room, which has no business
+                // knowing about a direct message.
+                let mode = if self.direct_peer.is_some() {
+                    TypingSignal::Never
+                } else {
+                    self.settings.typing_signal
+                };
+                let typing = self.typing_broadcaster.input(&text, js_sys::Date::now(), mode);
+                self.send_typing(typing);
+                false
+            }
+            Msg::TypingExpired(id) => self.typing.remove(&id).is_some(),
+            Msg::JumpToLatest => {
+                self.new_below = 0;
+                self.scroll_to_bottom = true;
+                self.trim_window();
+                true
+            }
+            Msg::RevokeSession(id) => {
+                self.send(&WebSocketMessage {
+                    message_type: MsgTypes::RevokeSession,
+                    data: Some(id.clone()),
+                    data_array: None,
+                });
+                if id == self.session_id {
+                    self.sign_out(ctx);
+                } else {
+                    self.sessions.retain(|s| s.id != id);
+                }
+                true
+            }
+            Msg::UpdateSettings(settings) => {
+                settings.save();
+                i18n::set_locale(settings.locale);
+                self.wss.set_policy(settings.transport.policy());
+                self.wss.set_url(settings.server_url());
+                if settings.theme != self.settings.theme {
+                    theme::apply(settings.theme);
+                }
+                if settings.avatar_style != self.settings.avatar_style {
+                    self.restyle_avatars(self.settings.avatar_style, settings.avatar_style);
+                }
+                // Translations were into the old language.
+                if settings.locale != self.settings.locale {
+                    self.translations.clear();
+                    self.originals.clear();
+                }
+                self.settings = settings;
+                true
+            }
+            Msg::CycleTheme => {
+                self.settings.theme = self.settings.theme.next();
+                self.settings.save();
+                theme::apply(self.settings.theme);
+                true
+            }
+            Msg::SetTopic => {
+                let topic = web_sys::window()
+                    .and_then(|w| w.prompt_with_message(&t("topic-prompt")).ok().flatten());
+                if let Some(topic) = topic {
+                    self.send(&WebSocketMessage {
+                        message_type: MsgTypes::Topic,
+                        data: Some(topic.trim().to_string()),
+                        data_array: None,
+                    });
+                }
+                false
+            }
+        }
+    }
+
+    /// Messages still in their undo period are sent rather than lost.
+    fn destroy(&mut self, ctx: &Context<Self>) {
+        if let MiniChat::Window { window, .. } = &self.mini_chat {
+            let _ = window.close();
+        }
+        for (peer, text, quote, _) in mem::take(&mut self.outbox).into_values() {
+            self.dispatch(ctx, peer, text, quote, None);
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        self.refresh_expiry(ctx);
+        if self.print_range.is_some() {
+            // Blocks until the dialog is dismissed.
+            if let Err(e) = web_sys::window().unwrap().print() {
+                log::error!("failed to print: {:?}", e);
+            }
+            ctx.link().send_message(Msg::PrintDone);
+        }
+        if first_render {
+            if let (Some(input), Some(draft)) = (
+                self.chat_input.cast::<HtmlTextAreaElement>(),
+                self.drafts.get(draft_key(self.direct_peer.as_deref())),
+            ) {
+                composer::set_text(&input, draft);
+            }
+        }
+        if self.focus_pending {
+            self.focus_pending = false;
+            let message = self.focused_message.and_then(|idx| {
+                web_sys::window()
+                    .and_then(|w| w.document())
+                    .and_then(|d| d.get_element_by_id(&message_element_id(idx)))
+            });
+            if let Some(message) = message.and_then(|m| m.dyn_into::<HtmlElement>().ok()) {
+                let _ = message.focus();
+            }
+        }
+        if let Some(idx) = self.jump_to.take() {
+            if let Some(message) = web_sys::window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.get_element_by_id(&message_element_id(idx)))
+            {
+                message.scroll_into_view_with_bool(true);
+            }
+        } else if self.scroll_to_unread {
+            if let Some(divider) = self.unread_divider.cast::<Element>() {
+                divider.scroll_into_view_with_bool(true);
+            }
+            self.scroll_to_unread = false;
+        } else if let Some(pane) = self.message_pane.cast::<Element>() {
+            if self.scroll_to_bottom {
+                pane.set_scroll_top(pane.scroll_height());
+            } else if let Some(from_bottom) = self.anchor_from_bottom {
+                // Keep the same message under the viewport after older ones were prepended.
+                pane.set_scroll_top(pane.scroll_height() - from_bottom);
+            }
+        }
+        self.scroll_to_bottom = false;
+        self.anchor_from_bottom = None;
+    }
+
+    // html! checks each component prop it is given with a bare field access.
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+    let submit = ctx.link().callback(|_| Msg::SubmitMessage);
+    let render_options = RenderOptions {
+        plaintext: self.plaintext,
+        media_proxy: self.settings.media_proxy().map(String::from),
+    };
+    let others: Vec<&UserProfile> = self
+        .users
+        .iter()
+        .filter(|u| !self.membership.is_member(CHANNEL, &u.id) && self.matches_member_filter(u))
+        .collect();
+    let join_request_count = self.join_requests.iter().filter(|r| r.room == CHANNEL).count();
+    // Messages from blocked users alone still leave the room looking empty.
+    let empty = !self.messages.iter().any(|m| self.listed(m));
+    let member_count = self
+        .membership
+        .listed(CHANNEL)
+        .map_or(self.users.len(), <[_]>::len);
+    let focus_mode = self.settings.focus_mode;
+    let selecting = !self.selected.is_empty();
+    // The one message reachable with Tab; the arrow keys reach the others.
+    let tab_stop = self
+        .focused_message
+        .filter(|&idx| idx >= self.window_start && self.navigable(idx))
+        .or_else(|| (self.window_start..self.messages.len()).rev().find(|&idx| self.navigable(idx)));
+    // The message rendered just before, for grouping; `None` after anything
+    // that breaks a group.
+    let mut previous: Option<usize> = None;
+    // Local midnight of the last dated message rendered, for day dividers.
+    let mut current_day: Option<f64> = None;
+    let seen = self.seen_by();
+
     html! {
-        <div class="flex w-screen h-screen bg-gray-900 text-white">
-            <div class="flex-none w-1/4 h-full bg-gray-800 overflow-y-auto">
-                <div class="text-xl p-3 border-b border-gray-700">{"Users"}</div>
+        <>
+        <div
+            class={classes!("flex", "w-screen", "h-screen", "bg-gray-100", "dark:bg-gray-900", "text-gray-900", "dark:text-white",
+                self.print_range.is_some().then_some("print:hidden"))}
+            style={self.viewport_height.map(|height| format!("height: {}px", height))}
+        >
+            {
+                if self.show_schedule_dialog {
+                    html!{
+                        <ScheduleDialog
+                            on_schedule={ctx.link().callback(Msg::ScheduleMessage)}
+                            on_close={ctx.link().callback(|_| Msg::ToggleScheduleDialog)}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {self.view_safety_number(ctx)}
+            {
+                if self.show_print_dialog {
+                    html!{
+                        <PrintDialog
+                            on_print={ctx.link().callback(Msg::PrintConversation)}
+                            on_close={ctx.link().callback(|_| Msg::TogglePrintDialog)}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.show_settings {
+                    html!{
+                        <SettingsScreen
+                            settings={self.settings.clone()}
+                            channel={CHANNEL}
+                            on_change={ctx.link().callback(Msg::UpdateSettings)}
+                            on_close={ctx.link().callback(|_| Msg::ToggleSettingsPanel)}
+                        >
+                            <div class="text-lg mt-6 mb-2">{t("sessions-title")}</div>
+                            <SessionList
+                                sessions={self.sessions.clone()}
+                                current={self.session_id.clone()}
+                                on_revoke={ctx.link().callback(Msg::RevokeSession)}
+                            />
+                            <div class="text-lg mt-6 mb-2">{t("digest-title")}</div>
+                            <DigestSettings
+                                prefs={self.digest_prefs.clone()}
+                                room={CHANNEL}
+                                on_save={ctx.link().callback(Msg::SaveDigestPrefs)}
+                            />
+                            <div class="text-lg mt-6 mb-2">{t("export-title")}</div>
+                            <div class="text-sm text-gray-500 dark:text-gray-400 mb-2">{t("export-help")}</div>
+                            <div class="flex">
+                                <button
+                                    onclick={ctx.link().callback(|_| Msg::ExportHistory(ExportFormat::Json))}
+                                    class="px-3 py-1 mr-2 rounded bg-gray-200 dark:bg-gray-700 text-sm"
+                                >
+                                    {t("export-json")}
+                                </button>
+                                <button
+                                    onclick={ctx.link().callback(|_| Msg::ExportHistory(ExportFormat::Text))}
+                                    class="px-3 py-1 rounded bg-gray-200 dark:bg-gray-700 text-sm"
+                                >
+                                    {t("export-text")}
+                                </button>
+                            </div>
+                            <div class="text-lg mt-6 mb-2">{t("key-backup-title")}</div>
+                            <KeyBackupSettings
+                                on_backup={ctx.link().callback(Msg::BackupKey)}
+                                on_restore={ctx.link().callback(|(file, passphrase)| Msg::RestoreKey(file, passphrase))}
+                                error={self.key_backup_error.clone()}
+                            />
+                            <div class="text-lg mt-6 mb-2">{t("import-title")}</div>
+                            <div class="text-sm text-gray-500 dark:text-gray-400 mb-2">{t("import-help")}</div>
+                            <label class="inline-block px-3 py-1 rounded bg-gray-200 dark:bg-gray-700 text-sm cursor-pointer">
+                                {t("import-open")}
+                                <input
+                                    type="file"
+                                    accept=".json,application/json"
+                                    class="hidden"
+                                    onchange={ctx.link().batch_callback(|e: Event| {
+                                        let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                                        let file = input.files().and_then(|files| files.get(0));
+                                        // Allow picking the same file again.
+                                        input.set_value("");
+                                        file.map(Msg::OpenArchive)
+                                    })}
+                                />
+                            </label>
+                            {
+                                match &self.import_error {
+                                    Some(error) => html!{<div class="mt-2 text-sm text-red-500 break-words">{error}</div>},
+                                    None => html!{},
+                                }
+                            }
+                        </SettingsScreen>
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.invite_query.is_some() {
+                    html!{
+                        <InviteDialog
+                            room={CHANNEL}
+                            candidates={self.invite_candidates()}
+                            on_search={ctx.link().callback(Msg::InviteSearch)}
+                            on_invite={ctx.link().callback(Msg::InviteUsers)}
+                            on_close={ctx.link().callback(|_| Msg::ToggleInviteDialog)}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.show_join_requests {
+                    html!{
+                        <JoinRequests
+                            room={CHANNEL}
+                            requests={self.join_requests.iter().filter(|r| r.room == CHANNEL).cloned().collect::<Vec<_>>()}
+                            on_decide={ctx.link().callback(|(id, approve)| Msg::DecideJoinRequest(id, approve))}
+                            on_close={ctx.link().callback(|_| Msg::ToggleJoinRequests)}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                match &self.call {
+                    Some(call) => html!{
+                        <CallPanel
+                            peer_name={call.peer_name.clone()}
+                            state={call.state}
+                            muted={call.muted}
+                            offered_video={call.offered_video}
+                            camera_off={call.camera_off}
+                            local_stream={call.local_stream()}
+                            remote_stream={call.remote_stream()}
+                            screen_stream={call.screen_stream()}
+                            remote_screen_stream={call.remote_screen_stream()}
+                            can_share_screen={webrtc::screen_share_supported()}
+                            on_toggle_screen_share={ctx.link().callback(|_| Msg::ToggleScreenShare)}
+                            on_accept={ctx.link().callback(Msg::AcceptCall)}
+                            on_decline={ctx.link().callback(|_| Msg::EndCall)}
+                            on_toggle_mute={ctx.link().callback(|_| Msg::ToggleCallMute)}
+                            on_toggle_camera={ctx.link().callback(|_| Msg::ToggleCallCamera)}
+                            on_switch_device={ctx.link().callback(|(kind, id)| Msg::SwitchCallDevice(kind, id))}
+                            on_hang_up={ctx.link().callback(|_| Msg::EndCall)}
+                        />
+                    },
+                    None => html!{},
+                }
+            }
+            {
+                match self.pending_rules.as_ref().filter(|_| self.show_rules) {
+                    Some(rules) => html!{
+                        <RulesModal
+                            room={CHANNEL}
+                            rules={rules.rules.clone()}
+                            on_accept={ctx.link().callback(|_| Msg::AcceptRules)}
+                            on_close={ctx.link().callback(|_| Msg::ToggleRules)}
+                        />
+                    },
+                    None => html!{},
+                }
+            }
+            {
+                if self.show_notification_center {
+                    html!{
+                        <NotificationCenter
+                            invitations={self.invitations.clone()}
+                            on_respond={ctx.link().callback(|(id, accept)| Msg::RespondToInvitation(id, accept))}
+                            notices={self.notices.clone()}
+                            routing={self.routing_log.iter().cloned().collect::<Vec<_>>()}
+                            on_dismiss={ctx.link().callback(Msg::DismissNotice)}
+                            on_close={ctx.link().callback(|_| Msg::ToggleNotificationCenter)}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.show_room_info {
+                    self.view_room_info(ctx)
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.show_action_log {
+                    html!{
+                        <ActionLogPanel
+                            entries={self.action_log.entries()}
+                            can_step_back={self.action_log.can_step_back()}
+                            can_step_forward={self.action_log.stepping_through()}
+                            on_back={ctx.link().callback(|_| Msg::ActionLogBack)}
+                            on_forward={ctx.link().callback(|_| Msg::ActionLogForward)}
+                            on_live={ctx.link().callback(|_| Msg::ActionLogLive)}
+                            on_clear={ctx.link().callback(|_| Msg::ClearActionLog)}
+                            on_close={ctx.link().callback(|_| Msg::ToggleActionLog)}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.show_shortcuts {
+                    html!{<ShortcutHelp on_close={ctx.link().callback(|_| Msg::Shortcut(Action::ShowHelp))}/>}
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.show_quick_switcher {
+                    html!{
+                        <QuickSwitcher
+                            destinations={self.destinations()}
+                            on_pick={ctx.link().callback(Msg::QuickSwitch)}
+                            on_close={ctx.link().callback(|_| Msg::Shortcut(Action::QuickSwitcher))}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                match &self.toast {
+                    Some((text, _)) => html!{
+                        <div role="status" class="fixed bottom-24 left-1/2 transform -translate-x-1/2 z-20 bg-gray-800 dark:bg-gray-200 text-white dark:text-gray-900 text-sm rounded-full px-4 py-2 shadow-lg">
+                            {text.clone()}
+                        </div>
+                    },
+                    None => html!{},
+                }
+            }
+            {
+                if self.forwarding.is_some() {
+                    html!{
+                        <QuickSwitcher
+                            title={t("forward-title")}
+                            destinations={self.destinations()}
+                            on_pick={ctx.link().callback(|peer| Msg::Forward(Some(peer)))}
+                            on_close={ctx.link().callback(|_| Msg::Forward(None))}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.show_moderation_log {
+                    html!{
+                        <ModerationLog
+                            actions={self.mod_actions.clone()}
+                            on_close={ctx.link().callback(|_| Msg::ToggleModerationLog)}
+                        />
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                match &self.archive {
+                    Some(archive) => html!{
+                        <ArchiveView
+                            archive={archive.clone()}
+                            options={render_options.clone()}
+                            on_enlarge={ctx.link().callback(|src| Msg::EnlargeImage(Some(src)))}
+                            on_close={ctx.link().callback(|_| Msg::CloseArchive)}
+                        />
+                    },
+                    None => html!{},
+                }
+            }
+            {
+                if let Some(src) = &self.enlarged_image {
+                    html!{
+                        <div
+                            onclick={ctx.link().callback(|_| Msg::EnlargeImage(None))}
+                            title={t("close")}
+                            class="fixed inset-0 z-30 flex items-center justify-center bg-black bg-opacity-75 p-8 cursor-zoom-out"
+                        >
+                            <img src={src.clone()} alt="" class="max-w-full max-h-full rounded shadow-lg"/>
+                        </div>
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            {
+                if self.show_sidebar {
+                    html!{
+                        <div
+                            onclick={ctx.link().callback(|_| Msg::ToggleSidebar)}
+                            class="fixed inset-0 z-20 bg-black bg-opacity-50 md:hidden"
+                        ></div>
+                    }
+                } else {
+                    html!{}
+                }
+            }
+            <div
+                ref={self.sidebar.clone()}
+                onscroll={ctx.link().callback(|_| Msg::SidebarScrolled)}
+                role="complementary"
+                aria-label={t("users-title")}
+                class={classes!("fixed", "inset-y-0", "left-0", "z-20", "w-72", "max-w-full", "transform", "transition-transform",
+                    "md:relative", "md:z-auto", "md:w-1/4", "md:translate-x-0",
+                    "flex-none", "h-full", "bg-white", "dark:bg-gray-800", "overflow-y-auto",
+                    if self.show_sidebar { "translate-x-0" } else { "-translate-x-full" },
+                    focus_mode.then_some("hidden"))}
+            >
+                <div class="flex items-center justify-between text-xl p-3 border-b border-gray-300 dark:border-gray-700">
+                    <span class="flex-grow">{t("users-title")}</span>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::TogglePlaintext)}
+                        title={ if self.plaintext { t("plaintext-disable") } else { t("plaintext-enable") } }
+                        class={classes!("text-sm", "mr-2", "hover:text-black", "dark:hover:text-white", if self.plaintext { "text-green-400" } else { "text-gray-600 dark:text-gray-300" })}
+                        aria-label={ if self.plaintext { t("plaintext-disable") } else { t("plaintext-enable") } }
+                    >
+                        {"Aa"}
+                    </button>
+                    {
+                        if self.role.can_moderate() {
+                            html!{
+                                <button
+                                    onclick={ctx.link().callback(|_| Msg::ToggleModerationLog)}
+                                    title={t("modlog-open")}
+                                    class="text-sm mr-2 text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white"
+                                    aria-label={t("modlog-open")}
+                                >
+                                    {"🛡"}
+                                </button>
+                            }
+                        } else {
+                            html!{}
+                        }
+                    }
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::ToggleNotificationCenter)}
+                        title={t("notification-center")}
+                        aria-label={t("notification-center")}
+                        class="relative text-sm mr-2 text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white"
+                    >
+                        {"📥"}
+                        {
+                            if self.invitations.is_empty() && self.notices.is_empty() {
+                                html!{}
+                            } else {
+                                html!{
+                                    <span class="absolute -top-1 -right-2 bg-red-500 text-white text-xs rounded-full px-1">{self.invitations.len() + self.notices.len()}</span>
+                                }
+                            }
+                        }
+                    </button>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::CycleTheme)}
+                        title={self.settings.theme.label()}
+                        aria-label={self.settings.theme.label()}
+                        class="text-sm mr-2 text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white"
+                    >
+                        {self.settings.theme.icon()}
+                    </button>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::ToggleSettingsPanel)}
+                        title={t("preferences")}
+                        class="text-sm mr-2 text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white"
+                        aria-label={t("preferences")}
+                    >
+                        {"⚙"}
+                    </button>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::ToggleSound)}
+                        title={ if self.settings.sound_muted { t("sound-unmute") } else { t("sound-mute") } }
+                        class="text-sm mr-2 text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white"
+                    >
+                        { if self.settings.sound_muted { "🔇" } else { "🔊" } }
+                    </button>
+                    {
+                        if notifications::is_supported() {
+                            let title = if self.notifications_enabled { t("desktop-notifications-disable") } else { t("desktop-notifications-enable") };
+                            html!{
+                                <button onclick={ctx.link().callback(|_| Msg::ToggleNotifications)} {title} class="text-sm mr-2 text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white">
+                                    { if self.notifications_enabled { "🔔" } else { "🔕" } }
+                                </button>
+                            }
+                        } else {
+                            html!{}
+                        }
+                    }
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::SignOut)}
+                        title={t("sign-out")}
+                        class="text-sm text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white"
+                        aria-label={t("sign-out")}
+                    >
+                        {"⎋"}
+                    </button>
+                </div>
+                { self.view_pinned_dms(ctx) }
+                <div class="flex items-center justify-between px-3 pt-3 text-xs uppercase text-gray-500 dark:text-gray-400">
+                    <span>{t_args("room-members", &[("room", CHANNEL), ("count", &member_count.to_string())])}</span>
+                    {
+                        if self.role.can_moderate() && join_request_count > 0 {
+                            html!{
+                                <button onclick={ctx.link().callback(|_| Msg::ToggleJoinRequests)} class="normal-case bg-red-500 text-white rounded-full px-2">
+                                    {t_args("join-requests-badge", &[("count", &join_request_count.to_string())])}
+                                </button>
+                            }
+                        } else {
+                            html!{}
+                        }
+                    }
+                    <button onclick={ctx.link().callback(|_| Msg::ToggleInviteDialog)} class="normal-case text-green-600 hover:underline">
+                        {t("invite")}
+                    </button>
+                </div>
+                <div class="flex items-center px-3 pt-2">
+                    <input
+                        type="search"
+                        placeholder={t("members-filter")}
+                        aria-label={t("members-filter")}
+                        oninput={ctx.link().callback(|e: InputEvent| {
+                            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                            Msg::FilterMembers(input.value())
+                        })}
+                        class="flex-grow min-w-0 bg-gray-100 dark:bg-gray-700 rounded px-2 py-1 text-sm"
+                    />
+                    <select
+                        aria-label={t("members-sort")}
+                        title={t("members-sort")}
+                        onchange={ctx.link().batch_callback(|e: Event| {
+                            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+                            select.value().parse::<usize>().ok().and_then(|i| MemberSort::ALL.get(i)).map(|&sort| Msg::SortMembers(sort))
+                        })}
+                        class="ml-2 bg-gray-100 dark:bg-gray-700 rounded px-1 py-1 text-sm"
+                    >
+                        {
+                            MemberSort::ALL.iter().enumerate().map(|(i, sort)| html!{
+                                <option value={i.to_string()} selected={self.member_sort == *sort}>{sort.label()}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+                { self.view_members(ctx) }
+                {
+                    if others.is_empty() {
+                        html!{}
+                    } else {
+                        html!{
+                            <>
+                            <div class="px-3 pt-3 text-xs uppercase text-gray-500 dark:text-gray-400">{t("room-others")}</div>
+                            { others.into_iter().map(|u| self.view_user(ctx, u, true)).collect::<Html>() }
+                            </>
+                        }
+                    }
+                }
+            </div>
+            {
+                match &self.pending_file {
+                    Some(file) => html!{
+                        <AttachmentPreview
+                            file={file.clone()}
+                            on_confirm={ctx.link().callback(|_| Msg::ConfirmPendingFile)}
+                            on_cancel={ctx.link().callback(|_| Msg::CancelPendingFile)}
+                        />
+                    },
+                    None => html!{},
+                }
+            }
+            <div
+                class={classes!("relative", "flex-grow", "flex", "flex-col", focus_mode.then_some("max-w-3xl mx-auto"))}
+                ondragenter={ctx.link().batch_callback(|e: DragEvent| drags_files(&e).then(|| Msg::DragActive(true)))}
+                ondragover={Callback::from(|e: DragEvent| if drags_files(&e) { e.prevent_default() })}
+            >
+                {
+                    if self.drag_active {
+                        html!{
+                            <div
+                                class="absolute inset-0 z-10 flex items-center justify-center bg-green-600 bg-opacity-20 border-4 border-dashed border-green-600 rounded-lg"
+                                ondragover={Callback::from(|e: DragEvent| e.prevent_default())}
+                                ondragleave={ctx.link().callback(|_| Msg::DragActive(false))}
+                                ondrop={ctx.link().callback(|e: DragEvent| {
+                                    e.prevent_default();
+                                    match dropped_file(&e) {
+                                        Some(file) => Msg::PreviewFile(file),
+                                        None => Msg::DragActive(false),
+                                    }
+                                })}
+                            >
+                                <div class="pointer-events-none text-xl text-green-700 dark:text-green-300">{t("attachment-drop-here")}</div>
+                            </div>
+                        }
+                    } else {
+                        html!{}
+                    }
+                }
+                {
+                    if self.pending_joins.contains(CHANNEL) {
+                        html!{
+                            <div class="px-6 py-2 text-sm bg-yellow-100 dark:bg-yellow-900 text-yellow-800 dark:text-yellow-200">
+                                {t_args("join-pending", &[("room", CHANNEL)])}
+                            </div>
+                        }
+                    } else {
+                        html!{}
+                    }
+                }
+                {
+                    if focus_mode {
+                        html!{
+                            <button
+                                onclick={ctx.link().callback(|_| Msg::ToggleFocusMode)}
+                                title={t("focus-mode-exit")}
+                                class="absolute top-2 right-4 z-10 text-sm text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white"
+                                aria-label={t("focus-mode-exit")}
+                            >
+                                {"⤡"}
+                            </button>
+                        }
+                    } else {
+                        html!{}
+                    }
+                }
+                <div class={classes!("flex", "items-center", "px-6", "py-3", "border-b", "border-gray-300", "dark:border-gray-700", "text-sm",
+                    "text-gray-600", "dark:text-gray-300", focus_mode.then_some("hidden"))}>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::ToggleSidebar)}
+                        title={t("sidebar-toggle")}
+                        aria-label={t("sidebar-toggle")}
+                        aria-expanded={self.show_sidebar.to_string()}
+                        class="md:hidden mr-3 text-lg hover:text-black dark:hover:text-white"
+                    >
+                        {"☰"}
+                    </button>
+                    <span class="flex-grow truncate">{ self.topic.clone().unwrap_or_default() }</span>
+                    { self.view_disappearing(ctx) }
+                    { self.view_channel_level(ctx) }
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::ToggleFocusMode)}
+                        title={t("focus-mode-enter")}
+                        class="ml-2 hover:text-black dark:hover:text-white"
+                        aria-label={t("focus-mode-enter")}
+                    >
+                        {"⤢"}
+                    </button>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::TogglePrintDialog)}
+                        title={t("print-title")}
+                        class="ml-2 hover:text-black dark:hover:text-white"
+                        aria-label={t("print-title")}
+                    >
+                        {"🖨"}
+                    </button>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::PopOut)}
+                        title={t("mini-chat-open")}
+                        class="ml-2 hover:text-black dark:hover:text-white"
+                        aria-label={t("mini-chat-open")}
+                    >
+                        {"⧉"}
+                    </button>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::ToggleRoomInfo)}
+                        title={t("room-info")}
+                        class="ml-2 hover:text-black dark:hover:text-white"
+                        aria-label={t("room-info")}
+                    >
+                        {"ⓘ"}
+                    </button>
+                </div>
+                <div class="relative flex-grow min-h-0 flex flex-col">
+                <div
+                    ref={self.message_pane.clone()}
+                    onscroll={ctx.link().callback(|_| Msg::PaneScrolled)}
+                    role="log"
+                    aria-live="polite"
+                    aria-relevant="additions"
+                    aria-label={t("messages-log")}
+                    class="flex-grow overflow-y-auto px-6 py-4"
+                >
+                    {
+                        if self.loading_history && self.window_start == 0 {
+                            html!{<div class="text-center text-xs text-gray-500 dark:text-gray-400 mb-4">{t("history-loading")}</div>}
+                        } else if self.history_exhausted && self.window_start == 0 && !empty {
+                            html!{<div class="text-center text-xs text-gray-500 dark:text-gray-400 mb-4">{t("history-start")}</div>}
+                        } else {
+                            html!{}
+                        }
+                    }
+                    { if empty { self.view_empty_state(ctx) } else { html!{} } }
+                    {
+                        self.messages.iter().enumerate().skip(self.window_start).filter(|(_, m)| self.listed(m)).map(|(idx, m)| {
+                            let avatar = self
+                                .users
+                                .iter()
+                                .find(|u| u.id == m.sender_id())
+                                .map(|u| u.avatar.clone())
+                                .unwrap_or_else(|| avatar::url(m.sender_id()));
+                            let author = match &m.renamed_from {
+                                Some(original) => t_args("renamed-author", &[("old", original), ("new", &m.from)]),
+                                None => m.from.clone(),
+                            };
+                            let direct = match &m.direct {
+                                Some(peer) if peer == &self.user_id => {
+                                    html!{<span class="ml-2 text-xs text-green-600 dark:text-green-400">{t("saved-label")}</span>}
+                                }
+                                Some(peer) if self.is_me(m.sender_id()) => {
+                                    let name = self.profile(peer).map(|p| p.name.as_str()).unwrap_or(peer);
+                                    html!{<span class="ml-2 text-xs text-green-600 dark:text-green-400">{t_args("dm-to", &[("name", name)])}</span>}
+                                }
+                                Some(_) => html!{<span class="ml-2 text-xs text-green-600 dark:text-green-400">{t("dm-label")}</span>},
+                                None => html!{},
+                            };
+                            let day = m.timestamp.map(local_day);
+                            let new_day = day.is_some() && day != current_day;
+                            if new_day {
+                                current_day = day;
+                            }
+                            let day_divider = match day.filter(|_| new_day) {
+                                Some(day) => html!{
+                                    <div class="flex items-center my-4 text-xs text-gray-500 dark:text-gray-400">
+                                        <div class="flex-grow border-t border-gray-300 dark:border-gray-700"></div>
+                                        <span class="mx-2">{day_label(day)}</span>
+                                        <div class="flex-grow border-t border-gray-300 dark:border-gray-700"></div>
+                                    </div>
+                                },
+                                None => html!{},
+                            };
+                            let continued = !new_day && previous.is_some_and(|prev| self.continues_group(prev, idx));
+                            previous = Some(idx);
+                            if self.block_list.is_muted(m.sender_id()) && !self.revealed.contains(&idx) {
+                                previous = None;
+                                let reveal = ctx.link().callback(move |_| Msg::RevealMessage(idx));
+                                return html!{
+                                    <>
+                                    {day_divider}
+                                    <div class="flex items-center mb-4 text-sm text-gray-500 dark:text-gray-400">
+                                        <span>{t_args("muted-message", &[("name", &m.from)])}</span>
+                                        <button onclick={reveal} class="ml-2 underline hover:text-black dark:hover:text-white">{t("show-message")}</button>
+                                    </div>
+                                    </>
+                                };
+                            }
+                            let picking = self.focused_message == Some(idx) && self.reaction_pick.is_some();
+                            let divider = if self.first_unread == Some(idx) {
+                                html!{
+                                    <div ref={self.unread_divider.clone()} class="flex items-center my-4 text-xs text-red-400">
+                                        <div class="flex-grow border-t border-red-400"></div>
+                                        <span class="mx-2 uppercase">{t("new-messages")}</span>
+                                        <div class="flex-grow border-t border-red-400"></div>
+                                    </div>
+                                }
+                            } else {
+                                html!{}
+                            };
+                            html!{
+                                <>
+                                {day_divider}
+                                {divider}
+                                <div
+                                    id={message_element_id(idx)}
+                                    tabindex={if tab_stop == Some(idx) { "0" } else { "-1" }}
+                                    aria-keyshortcuts="+"
+                                    onfocus={ctx.link().callback(move |_| Msg::MessageFocused(idx))}
+                                    onkeydown={ctx.link().batch_callback(move |e: KeyboardEvent| {
+                                        // Leave keys alone in the buttons and inputs inside the message.
+                                        if e.target() != e.current_target() {
+                                            return None;
+                                        }
+                                        let handled = match e.key().as_str() {
+                                            "ArrowUp" | "ArrowDown" | "+" => true,
+                                            "ArrowLeft" | "ArrowRight" | "Enter" | "Escape" => picking,
+                                            _ => false,
+                                        };
+                                        handled.then(|| {
+                                            e.prevent_default();
+                                            Msg::MessageKey(idx, e.key())
+                                        })
+                                    })}
+                                    onclick={ctx.link().batch_callback(move |e: MouseEvent| {
+                                        (e.shift_key() || selecting).then(|| {
+                                            e.prevent_default();
+                                            Msg::SelectMessage(idx, e.shift_key() && selecting)
+                                        })
+                                    })}
+                                    class={classes!("group", "flex", "items-start", "mb-4", "rounded-lg", "outline-none",
+                                        continued.then_some("-mt-3"),
+                                        "focus-visible:ring-2", "focus-visible:ring-green-500",
+                                        selecting.then_some("select-none cursor-pointer"),
+                                        self.selected.contains(&idx).then_some("bg-green-50 dark:bg-gray-800"),
+                                        "transition-colors", "duration-1000",
+                                        self.highlighted.as_ref().is_some_and(|(id, _)| m.id.as_ref() == Some(id))
+                                            .then_some("bg-yellow-100 dark:bg-yellow-900"))}
+                                >
+                                    {
+                                        if selecting {
+                                            html!{
+                                                <input
+                                                    type="checkbox"
+                                                    class="mt-3 mr-3"
+                                                    checked={self.selected.contains(&idx)}
+                                                    aria-label={t("select-message")}
+                                                />
+                                            }
+                                        } else {
+                                            html!{}
+                                        }
+                                    }
+                                    {
+                                        if continued {
+                                            html!{<div class="w-10 mr-4 flex-none"></div>}
+                                        } else {
+                                            html!{<img class="w-10 h-10 rounded-full mr-4" src={avatar} alt="avatar"/>}
+                                        }
+                                    }
+                                    <div class={classes!("px-4", "rounded-lg", if continued { "py-2" } else { "py-4" }, if m.bot {
+                                        "bg-indigo-50 dark:bg-indigo-900 border border-indigo-200 dark:border-indigo-700"
+                                    } else {
+                                        "bg-gray-200 dark:bg-gray-700"
+                                    })}>
+                                        {
+                                            if continued {
+                                                html!{}
+                                            } else {
+                                                html!{
+                                                    <div class="text-sm mb-1">
+                                                        {author}
+                                                        {
+                                                            if m.bot {
+                                                                html!{<span class="ml-2 px-1 rounded text-xs font-bold bg-indigo-600 text-white">{t("bot-badge")}</span>}
+                                                            } else {
+                                                                html!{}
+                                                            }
+                                                        }
+                                                        {direct}
+                                                    </div>
+                                                }
+                                            }
+                                        }
+                                        <div class="text-gray-700 dark:text-gray-200">
+                                            {
+                                                if m.deleted {
+                                                    html!{<p class="italic text-gray-500 dark:text-gray-400">{t("message-deleted")}</p>}
+                                                } else if let Some(rule) = &m.welcome {
+                                                    html!{<WelcomeCard room={CHANNEL} rule={rule.clone()}/>}
+                                                } else if let Some(poll) = &m.poll {
+                                                    let (vote_id, close_id) = (poll.id.clone(), poll.id.clone());
+                                                    html!{
+                                                        <PollCard
+                                                            poll={poll.clone()}
+                                                            me={self.user_id.clone()}
+                                                            on_vote={ctx.link().callback(move |option| Msg::VotePoll(vote_id.clone(), option))}
+                                                            on_close={ctx.link().callback(move |_| Msg::ClosePoll(close_id.clone()))}
+                                                        />
+                                                    }
+                                                } else {
+                                                    html!{
+                                                        <>
+                                                        {
+                                                            match &m.attachment {
+                                                                Some(attachment) => message_content::render_attachment(
+                                                                    attachment,
+                                                                    &render_options,
+                                                                    ctx.link().callback(|src| Msg::EnlargeImage(Some(src))),
+                                                                ),
+                                                                None => html!{},
+                                                            }
+                                                        }
+                                                        {
+                                                            match &m.forwarded_from {
+                                                                Some(from) => html!{<div class="text-xs italic text-gray-500 dark:text-gray-400">{"↪ "}{t_args("message-forwarded-from", &[("name", from)])}</div>},
+                                                                None => html!{},
+                                                            }
+                                                        }
+                                                        { self.view_quote(ctx, m) }
+                                                        { self.view_message_text(ctx, idx, m, &render_options) }
+                                                        {
+                                                            match m.expires_at() {
+                                                                Some(at) => {
+                                                                    let left = ((at - js_sys::Date::now()) / 1000.0).ceil().max(0.0) as u32;
+                                                                    html!{
+                                                                        <span class="mr-2 text-xs text-gray-500 dark:text-gray-400" title={t("disappearing-countdown")}>
+                                                                            {"⏳ "}{duration_label(left)}
+                                                                        </span>
+                                                                    }
+                                                                }
+                                                                None => html!{},
+                                                            }
+                                                        }
+                                                        {
+                                                            if m.edited {
+                                                                html!{<span class="text-xs text-gray-500 dark:text-gray-400">{t("message-edited")}</span>}
+                                                            } else {
+                                                                html!{}
+                                                            }
+                                                        }
+                                                        {
+                                                            if m.truncated {
+                                                                html!{<span class="text-xs text-gray-500 dark:text-gray-400">{t("message-truncated")}</span>}
+                                                            } else {
+                                                                html!{}
+                                                            }
+                                                        }
+                                                        { self.view_link_preview(ctx, idx, &m.message, &render_options) }
+                                                        { self.view_bot_actions(ctx, m) }
+                                                        { self.view_reactions(ctx, m) }
+                                                        { self.view_reaction_picker(ctx, idx, m) }
+                                                        { self.view_delivery(ctx, m, seen.get(&idx)) }
+                                                        </>
+                                                    }
+                                                }
+                                            }
+                                        </div>
+                                    </div>
+                                    {
+                                        if !m.deleted && m.poll.is_none() && !m.message.is_empty() {
+                                            html!{
+                                                <button
+                                                    onclick={ctx.link().callback(move |_| Msg::CopyMessage(idx))}
+                                                    title={t("message-copy")}
+                                                    class="invisible group-hover:visible ml-2 text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white px-1"
+                                                    aria-label={t("message-copy")}
+                                                >
+                                                    {"⧉"}
+                                                </button>
+                                            }
+                                        } else {
+                                            html!{}
+                                        }
+                                    }
+                                    { self.view_message_menu(ctx, idx, m) }
+                                </div>
+                                </>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+                {
+                    if self.new_below > 0 {
+                        html!{
+                            <button
+                                onclick={ctx.link().callback(|_| Msg::JumpToLatest)}
+                                class="absolute bottom-4 left-1/2 transform -translate-x-1/2 bg-green-600 text-white text-sm rounded-full px-4 py-2 shadow-lg"
+                            >
+                                {t_args("new-messages-below", &[("count", &self.new_below.to_string())])}
+                            </button>
+                        }
+                    } else {
+                        html!{}
+                    }
+                }
+                </div>
+                {self.view_typing()}
+                {self.view_selection_bar(ctx)}
+                {self.view_scheduled(ctx)}
+                {
+                    match &self.quoting {
+                        Some(quote) => html!{
+                            <div class="flex items-center justify-between px-6 py-2 text-sm bg-gray-50 dark:bg-gray-900 border-t border-gray-300 dark:border-gray-700">
+                                <div class="min-w-0 pl-2 border-l-4 border-green-500">
+                                    <div class="font-semibold">{t_args("quoting-message", &[("name", &quote.from)])}</div>
+                                    <div class="truncate text-gray-500 dark:text-gray-400">{quote.excerpt.clone()}</div>
+                                </div>
+                                <button
+                                    onclick={ctx.link().callback(|_| Msg::CancelQuote)}
+                                    title={t("quoting-cancel")}
+                                    class="ml-3 hover:text-black dark:hover:text-white"
+                                    aria-label={t("quoting-cancel")}
+                                >
+                                    {"✕"}
+                                </button>
+                            </div>
+                        },
+                        None => html!{},
+                    }
+                }
+                {
+                    if self.editing.is_some() {
+                        html!{
+                            <div class="flex items-center justify-between px-6 py-2 text-sm bg-yellow-50 dark:bg-gray-900 text-yellow-800 dark:text-yellow-300 border-t border-gray-300 dark:border-gray-700">
+                                <span>{t("editing-message")}</span>
+                                <button
+                                    onclick={ctx.link().callback(|_| Msg::CancelEdit)}
+                                    title={t("editing-cancel")}
+                                    class="ml-3 hover:text-black dark:hover:text-white"
+                                    aria-label={t("editing-cancel")}
+                                >
+                                    {"✕"}
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        html!{}
+                    }
+                }
+                {
+                    match &self.command_notice {
+                        Some(notice) => html!{
+                            <div class="flex items-start justify-between px-6 py-2 text-sm bg-white dark:bg-gray-800 border-t border-gray-300 dark:border-gray-700">
+                                <span class="whitespace-pre-line">{notice}</span>
+                                <button
+                                    onclick={ctx.link().callback(|_| Msg::DismissCommandNotice)}
+                                    class="ml-3 text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white"
+                                    aria-label={t("close")}
+                                >
+                                    {"✕"}
+                                </button>
+                            </div>
+                        },
+                        None => html!{},
+                    }
+                }
+                {
+                    self.outbox.iter().map(|(id, (_, text, _, _))| {
+                        let id = *id;
+                        html!{
+                            <div class="flex items-center justify-between px-6 py-2 text-sm bg-gray-800 dark:bg-gray-900 text-white">
+                                <span class="truncate">{t_args("undo-send-pending", &[("message", text)])}</span>
+                                <button
+                                    onclick={ctx.link().callback(move |_| Msg::UndoSend(id))}
+                                    class="ml-3 font-semibold text-green-400 hover:underline"
+                                >
+                                    {t("undo-send")}
+                                </button>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+                {
+                    self.uploads.values().map(|(name, progress)| {
+                        let percent = (progress * 100.0).round();
+                        html!{
+                            <div class="px-6 py-1 text-xs text-gray-600 dark:text-gray-300 bg-white dark:bg-gray-800 border-t border-gray-300 dark:border-gray-700">
+                                <div class="flex justify-between mb-1">
+                                    <span class="truncate">{t_args("attachment-sending", &[("name", name)])}</span>
+                                    <span class="ml-2">{format!("{}%", percent)}</span>
+                                </div>
+                                <div class="h-1 bg-gray-200 dark:bg-gray-700 rounded">
+                                    <div class="h-1 bg-green-600 rounded" style={format!("width: {}%", percent)}></div>
+                                </div>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+                {
+                    if self.pending_rules.is_some() {
+                        html!{
+                            <div class="w-full flex items-center justify-between px-6 py-4 bg-white dark:bg-gray-800 border-t border-gray-300 dark:border-gray-700 text-sm text-gray-600 dark:text-gray-300">
+                                <span>{t("rules-locked")}</span>
+                                <button
+                                    onclick={ctx.link().callback(|_| Msg::ToggleRules)}
+                                    class="ml-3 px-4 py-2 rounded bg-green-600 text-white"
+                                >
+                                    {t("rules-read")}
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        html!{
+                        <>
+                        { self.view_direct_banner(ctx) }
+                        <div class="relative w-full flex items-center justify-between bg-white dark:bg-gray-800 border-t border-gray-300 dark:border-gray-700">
+                            {
+                                if self.show_gif_picker {
+                                    html!{
+                                        <GifPicker
+                                            render_options={render_options.clone()}
+                                            on_select={ctx.link().callback(Msg::SendGif)}
+                                            on_close={ctx.link().callback(|_| Msg::ToggleGifPicker)}
+                                        />
+                                    }
+                                } else {
+                                    html!{}
+                                }
+                            }
+                            {
+                                if gif_search::is_enabled() {
+                                    html!{
+                                        <button
+                                            onclick={ctx.link().callback(|_| Msg::ToggleGifPicker)}
+                                            title={t("gif-open")}
+                                            class="ml-3 px-1 text-xs font-bold border border-current rounded text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white"
+                                        >
+                                            {"GIF"}
+                                        </button>
+                                    }
+                                } else {
+                                    html!{}
+                                }
+                            }
+                            <Composer
+                                input_ref={self.chat_input.clone()}
+                                on_submit={submit}
+                                on_input={ctx.link().callback(Msg::DraftChanged)}
+                                on_attach={self.direct_peer.is_none().then(|| ctx.link().callback(Msg::AttachFile))}
+                                on_paste_file={ctx.link().callback(Msg::PreviewFile)}
+                                mention_suggestions={self.mention_suggestions()}
+                                on_mention_query={ctx.link().callback(Msg::MentionQuery)}
+                                on_edit_last={ctx.link().callback(|_| Msg::Shortcut(Action::EditLast))}
+                                large={focus_mode}
+                                max_len={MAX_MESSAGE_LEN}
+                                cooldown={self.cooldown.map(cooldown_label)}
+                                on_schedule={ctx.link().callback(|_| Msg::ToggleScheduleDialog)}
+                            />
+                        </div>
+                        </>
+                        }
+                    }
+                }
+            </div>
+            { self.view_split_pane(ctx, &render_options) }
+            {
+                match &self.mini_chat {
+                    MiniChat::Closed => html!{},
+                    MiniChat::Floating => html!{
+                        <div class="fixed bottom-4 right-4 z-20 w-80 h-96 rounded-lg shadow-lg overflow-hidden border border-gray-300 dark:border-gray-700">
+                            { self.view_mini_chat(ctx, &render_options, true) }
+                        </div>
+                    },
+                    MiniChat::Window { body, .. } => create_portal(
+                        html!{<div class="h-screen">{ self.view_mini_chat(ctx, &render_options, false) }</div>},
+                        body.clone(),
+                    ),
+                }
+            }
+        </div>
+        { self.view_print_transcript() }
+        </>
+    }
+}
+}
+
+impl Chat {
+    fn snapshot(&self) -> ChatSnapshot {
+        ChatSnapshot {
+            messages: self.messages.clone(),
+            direct_peer: self.direct_peer.clone(),
+            window_start: self.window_start,
+            first_unread: self.first_unread,
+            selected: self.selected.clone(),
+            select_anchor: self.select_anchor,
+            focused_message: self.focused_message,
+            editing: self.editing,
+            forwarding: self.forwarding,
+            open_menu: self.open_menu,
+            revealed: self.revealed.clone(),
+            collapsed_previews: self.collapsed_previews.clone(),
+            quoting: self.quoting.clone(),
+            show_settings: self.show_settings,
+            show_room_info: self.show_room_info,
+            show_sidebar: self.show_sidebar,
+            show_quick_switcher: self.show_quick_switcher,
+            show_shortcuts: self.show_shortcuts,
+            show_moderation_log: self.show_moderation_log,
+            show_notification_center: self.show_notification_center,
+            show_join_requests: self.show_join_requests,
+            show_print_dialog: self.show_print_dialog,
+            show_schedule_dialog: self.show_schedule_dialog,
+        }
+    }
+
+    /// Puts back a state from the action log. Pickers keyed on an index
+    /// into messages that may no longer be there are closed.
+    fn restore(&mut self, state: ChatSnapshot) {
+        self.messages = state.messages;
+        self.direct_peer = state.direct_peer;
+        self.window_start = state.window_start;
+        self.first_unread = state.first_unread;
+        self.selected = state.selected;
+        self.select_anchor = state.select_anchor;
+        self.focused_message = state.focused_message;
+        self.editing = state.editing;
+        self.forwarding = state.forwarding;
+        self.open_menu = state.open_menu;
+        self.revealed = state.revealed;
+        self.collapsed_previews = state.collapsed_previews;
+        self.quoting = state.quoting;
+        self.show_settings = state.show_settings;
+        self.show_room_info = state.show_room_info;
+        self.show_sidebar = state.show_sidebar;
+        self.show_quick_switcher = state.show_quick_switcher;
+        self.show_shortcuts = state.show_shortcuts;
+        self.show_moderation_log = state.show_moderation_log;
+        self.show_notification_center = state.show_notification_center;
+        self.show_join_requests = state.show_join_requests;
+        self.show_print_dialog = state.show_print_dialog;
+        self.show_schedule_dialog = state.show_schedule_dialog;
+        self.reaction_pick = None;
+        self.jump_to = None;
+        self.highlighted = None;
+    }
+
+    /// Handles `msg` while stepping through the action log, against the
+    /// latest state rather than the earlier one on screen, which is put back
+    /// after. It shows up in the log past the point on screen.
+    fn update_latest(&mut self, ctx: &Context<Self>, msg: Msg) -> bool {
+        let on_screen = self.snapshot();
+        if let Some(latest) = self.action_log.latest() {
+            self.restore(latest);
+        }
+        self.updating_latest = true;
+        self.update(ctx, msg);
+        self.updating_latest = false;
+        self.action_log.settle(self.snapshot());
+        self.restore(on_screen);
+        true
+    }
+
+    /// Whether `user_id` is ours. Only a server without ids knows us by
+    /// name; otherwise someone else may well share it.
+    fn is_me(&self, user_id: &str) -> bool {
+        user_id == self.my_id()
+    }
+
+    /// What the server keys us on.
+    fn my_id(&self) -> &str {
+        if self.legacy_ids {
+            &self.username
+        } else {
+            &self.user_id
+        }
+    }
+
+    fn router(&self) -> NotificationRouter<'_> {
+        NotificationRouter {
+            settings: &self.settings,
+            block_list: &self.block_list,
+            user_id: self.my_id(),
+            username: &self.username,
+            in_quiet_hours: self.settings.in_quiet_hours(),
+        }
+    }
+
+    /// Updates display names after a rename while history still shows who
+    /// said what. Peers without an id are keyed on their name, so for them
+    /// the key itself moves too.
+    fn apply_rename(&mut self, ctx: &Context<Self>, id: Option<&str>, old: &str, new: &str) {
+        let matches = |sender_id: &str, name: &str| match id {
+            Some(id) => sender_id == id,
+            None => name == old,
+        };
+        for message in self.messages.iter_mut() {
+            if matches(message.sender_id(), &message.from) {
+                message.renamed_from.get_or_insert_with(|| old.to_string());
+                message.from = new.to_string();
+            }
+        }
+        for user in self.users.iter_mut() {
+            if matches(&user.id, &user.name) {
+                if id.is_none() {
+                    user.id = new.to_string();
+                    user.avatar = avatar::url(new);
+                }
+                user.name = new.to_string();
+            }
+        }
+        if let Some(profile) = id.and_then(|id| self.member_profiles.get_mut(id)) {
+            profile.name = new.to_string();
+        }
+        if id.is_none() {
+            self.block_list.rename(old, new);
+            self.membership.rename(old, new);
+        }
+        if matches(&self.user_id, &self.username) {
+            self.username = new.to_string();
+            if let Some((user, _)) = ctx.link().context::<User>(Callback::noop()) {
+                *user.username.borrow_mut() = new.to_string();
+            }
+        }
+    }
+
+    /// Asks the server for users matching `query`, unless that search has
+    /// already been made.
+    fn search_users(&mut self, query: &str) {
+        if query.is_empty()
+            || self.user_search.contains_key(query)
+            || !self.pending_user_searches.insert(query.to_string())
+        {
+            return;
+        }
+        let request = UserSearchRequest {
+            query,
+            limit: MENTION_SUGGESTIONS,
+        };
+        self.send(&WebSocketMessage {
+            message_type: MsgTypes::UserSearch,
+            data: Some(serde_json::to_string(&request).unwrap()),
+            data_array: None,
+        });
+    }
+
+    /// People matching the invite dialog's search who are not in the room
+    /// yet, from loaded users first and then server-side results.
+    fn invite_candidates(&self) -> Vec<(String, String)> {
+        let query = match &self.invite_query {
+            Some(query) if !query.is_empty() => query,
+            _ => return vec![],
+        };
+        let lowered = query.to_lowercase();
+        let mut seen = HashSet::new();
+        self.users
+            .iter()
+            .chain(self.member_profiles.values())
+            .filter(|u| u.name.to_lowercase().contains(&lowered))
+            .chain(self.user_search.get(query).into_iter().flatten())
+            .filter(|u| !self.is_me(&u.id) && !self.membership.is_member(CHANNEL, &u.id))
+            .filter(|u| seen.insert(u.id.clone()))
+            .map(|u| (u.id.clone(), u.name.clone()))
+            .collect()
+    }
+
+    /// Loaded users whose name starts with `query`, ignoring case.
+    fn local_mention_matches(&self, query: &str) -> Vec<&UserProfile> {
+        let query = query.to_lowercase();
+        let mut seen = HashSet::new();
+        self.users
+            .iter()
+            .chain(self.member_profiles.values())
+            .filter(|u| !self.is_me(&u.id) && u.name.to_lowercase().starts_with(&query))
+            .filter(|u| seen.insert(u.id.as_str()))
+            .take(MENTION_SUGGESTIONS)
+            .collect()
+    }
+
+    /// Names to offer for the mention being typed. Falls back to server-side
+    /// search results when nobody loaded locally matches.
+    fn mention_suggestions(&self) -> Vec<String> {
+        let query = match &self.mention_query {
+            Some(query) => query,
+            None => return vec![],
+        };
+        let local = self.local_mention_matches(query);
+        let matches = if local.is_empty() {
+            self.user_search.get(query).map(|r| r.iter().collect()).unwrap_or_default()
+        } else {
+            local
+        };
+        matches.into_iter().map(|u| u.name.clone()).collect()
+    }
+
+    fn profile(&self, id: &str) -> Option<&UserProfile> {
+        self.users
+            .iter()
+            .find(|u| u.id == id)
+            .or_else(|| self.member_profiles.get(id))
+    }
+
+    /// Renders the active room's members. Large rooms are loaded in pages, so
+    /// only the rows near the viewport are rendered, with spacers standing in
+    /// for the rest and placeholders for members that have not arrived yet.
+    fn view_members(&self, ctx: &Context<Self>) -> Html {
+        if !self.member_filter.is_empty() || self.member_sort != MemberSort::RoomOrder {
+            return self.view_sorted_members(ctx);
+        }
+        let listed = match self.membership.listed(CHANNEL) {
+            Some(listed) => listed,
+            None => return self.users.iter().map(|u| self.view_user(ctx, u, false)).collect(),
+        };
+        let start = self.member_window.start.min(listed.len());
+        let end = self.member_window.end.clamp(start, listed.len());
+        let spacer = |rows: usize| format!("height: {}px", rows as i32 * MEMBER_ROW_HEIGHT);
+
+        html! {
+            <div ref={self.member_list.clone()}>
+                <div style={spacer(start)}></div>
+                {
+                    listed[start..end].iter().map(|slot| match slot {
+                        Some(id) => match self.profile(id) {
+                            Some(profile) => self.view_user(ctx, profile, false),
+                            None => self.view_user(ctx, &UserProfile::new(id.clone(), id.clone()), false),
+                        },
+                        None => html! {
+                            <div class="px-3 py-1" style={spacer(1)}>
+                                <div class="h-full bg-gray-200 dark:bg-gray-700 rounded-lg animate-pulse"></div>
+                            </div>
+                        },
+                    }).collect::<Html>()
+                }
+                <div style={spacer(listed.len() - end)}></div>
+            </div>
+        }
+    }
+
+    /// The member list filtered and sorted. Works on the members loaded so
+    /// far, so it is not virtualized and says when some are missing.
+    fn view_sorted_members(&self, ctx: &Context<Self>) -> Html {
+        let (mut members, partial): (Vec<&UserProfile>, bool) = match self.membership.listed(CHANNEL) {
+            Some(listed) => (
+                listed.iter().flatten().filter_map(|id| self.profile(id)).collect(),
+                listed.iter().any(Option::is_none),
+            ),
+            None => (self.users.iter().collect(), false),
+        };
+        members.retain(|u| self.matches_member_filter(u));
+        let by_name = |a: &&UserProfile, b: &&UserProfile| a.name.to_lowercase().cmp(&b.name.to_lowercase());
+        match self.member_sort {
+            MemberSort::RoomOrder => {}
+            MemberSort::Alphabetical => members.sort_by(by_name),
+            MemberSort::OnlineFirst => {
+                let online = |u: &UserProfile| self.users.iter().any(|o| o.id == u.id);
+                members.sort_by(|a, b| online(b).cmp(&online(a)).then_with(|| by_name(a, b)));
+            }
+            MemberSort::RecentlyActive => {
+                let mut last_active: HashMap<&str, f64> = HashMap::new();
+                for m in &self.messages {
+                    if let Some(timestamp) = m.timestamp {
+                        let latest = last_active.entry(m.sender_id()).or_insert(timestamp);
+                        *latest = latest.max(timestamp);
+                    }
+                }
+                let active = |u: &UserProfile| last_active.get(u.id.as_str()).copied().unwrap_or(f64::MIN);
+                members.sort_by(|a, b| active(b).total_cmp(&active(a)).then_with(|| by_name(a, b)));
+            }
+        }
+        html! {
+            <>
+            {
+                if members.is_empty() {
+                    html! {<div class="px-3 py-2 text-sm text-gray-500 dark:text-gray-400">{t("members-no-match")}</div>}
+                } else {
+                    members.into_iter().map(|u| self.view_user(ctx, u, false)).collect::<Html>()
+                }
+            }
+            {
+                if partial {
+                    html! {<div class="px-3 py-2 text-xs text-gray-500 dark:text-gray-400">{t("members-partial")}</div>}
+                } else {
+                    html! {}
+                }
+            }
+            </>
+        }
+    }
+
+    /// Closes the topmost things first: an open overlay, then an edit in
+    /// progress. Returns whether anything closed.
+    fn close_panels(&mut self) -> bool {
+        let overlays = [
+            &mut self.show_quick_switcher,
+            &mut self.show_shortcuts,
+            &mut self.show_settings,
+            &mut self.show_print_dialog,
+            &mut self.show_schedule_dialog,
+            &mut self.show_join_requests,
+            &mut self.show_notification_center,
+            &mut self.show_room_info,
+            &mut self.show_action_log,
+            &mut self.show_moderation_log,
+            &mut self.show_sidebar,
+        ];
+        let mut closed = false;
+        for open in overlays {
+            closed |= mem::take(open);
+        }
+        closed |= self.invite_query.take().is_some();
+        closed |= self.archive.take().is_some();
+        closed |= self.enlarged_image.take().is_some();
+        closed |= self.profile_popover.take().is_some();
+        closed |= self.open_menu.take().is_some();
+        closed |= self.forwarding.take().is_some();
+        if !closed {
+            closed = self.quoting.take().is_some();
+        }
+        if !closed && self.editing.is_some() {
+            self.editing = None;
+            if let Some(input) = self.chat_input.cast::<HtmlTextAreaElement>() {
+                composer::reset(&input);
+            }
+            closed = true;
+        }
+        closed
+    }
+
+    /// Points the composer at `peer`'s conversation, or the room's, keeping
+    /// what was typed as the old one's draft and bringing back the new one's.
+    fn switch_conversation(&mut self, peer: Option<String>) {
+        auth::set_last_conversation(peer.as_deref());
+        if peer == self.direct_peer {
+            return;
+        }
+        let input = self.chat_input.cast::<HtmlTextAreaElement>();
+        if let Some(input) = &input {
+            self.drafts.set(draft_key(self.direct_peer.as_deref()), &input.value());
+        }
+        self.direct_peer = peer;
+        self.mention_query = None;
+        if let Some(input) = &input {
+            match self.drafts.get(draft_key(self.direct_peer.as_deref())) {
+                Some(draft) => composer::set_text(input, draft),
+                None => composer::reset(input),
+            }
+        }
+    }
+
+    /// Puts our latest room message in the composer for editing.
+    fn edit_last(&mut self) -> bool {
+        let idx = self.messages.iter().rposition(|m| {
+            self.is_me(m.sender_id())
+                && m.id.is_some()
+                && m.direct.is_none()
+                && m.attachment.is_none()
+                && m.poll.is_none()
+                && m.delivery.is_none()
+                && !m.deleted
+        });
+        let (idx, input) = match (idx, self.chat_input.cast::<HtmlTextAreaElement>()) {
+            (Some(idx), Some(input)) => (idx, input),
+            _ => return false,
+        };
+        self.switch_conversation(None);
+        self.editing = Some(idx);
+        composer::set_text(&input, &self.messages[idx].message);
+        let _ = input.focus();
+        true
+    }
+
+    /// The conversations Alt+↑/↓ steps through: the room, pinned direct
+    /// messages, then anyone else we have direct messages with.
+    fn conversations(&self) -> Vec<Option<String>> {
+        let mut conversations = vec![None];
+        let peers = self
+            .settings
+            .pinned_dms
+            .iter()
+            .cloned()
+            .chain(self.messages.iter().filter_map(|m| m.direct.clone()));
+        for peer in peers {
+            if !conversations.contains(&Some(peer.clone())) {
+                conversations.push(Some(peer));
+            }
+        }
+        conversations
+    }
+
+    /// Everything the quick switcher offers: the conversations, then online
+    /// people to start one with.
+    fn destinations(&self) -> Vec<Destination> {
+        let mut conversations = self.conversations();
+        for user in &self.users {
+            if !self.is_me(&user.id) && !conversations.contains(&Some(user.id.clone())) {
+                conversations.push(Some(user.id.clone()));
+            }
+        }
+        conversations
+            .into_iter()
+            .map(|peer| Destination {
+                label: match &peer {
+                    Some(peer) => self.conversation_title(peer),
+                    None => format!("#{}", CHANNEL),
+                },
+                peer,
+            })
+            .collect()
+    }
+
+    fn matches_member_filter(&self, user: &UserProfile) -> bool {
+        self.member_filter.is_empty() || user.name.to_lowercase().contains(&self.member_filter)
+    }
+
+    /// Rows of the member list that intersect the sidebar's viewport, plus
+    /// some overscan.
+    fn visible_members(&self) -> Range<usize> {
+        let (sidebar, list) = match (
+            self.sidebar.cast::<Element>(),
+            self.member_list.cast::<HtmlElement>(),
+        ) {
+            (Some(sidebar), Some(list)) => (sidebar, list),
+            _ => return self.member_window.clone(),
+        };
+        let top = (sidebar.scroll_top() - list.offset_top()).max(0);
+        let first = (top / MEMBER_ROW_HEIGHT) as usize;
+        let last = ((top + sidebar.client_height()) / MEMBER_ROW_HEIGHT) as usize + 1;
+        first.saturating_sub(MEMBER_OVERSCAN)..last + MEMBER_OVERSCAN
+    }
+
+    fn request_missing_members(&mut self) {
+        let index = match self.membership.first_missing(CHANNEL, self.member_window.clone()) {
+            Some(index) => index,
+            None => return,
+        };
+        let offset = index / MEMBER_PAGE * MEMBER_PAGE;
+        if self.pending_member_pages.insert(offset) {
+            let request = MemberPageRequest {
+                room: CHANNEL,
+                offset,
+                limit: MEMBER_PAGE,
+            };
+            self.send(&WebSocketMessage {
+                message_type: MsgTypes::MemberPage,
+                data: Some(serde_json::to_string(&request).unwrap()),
+                data_array: None,
+            });
+        }
+    }
+
+    fn view_user(&self, ctx: &Context<Self>, u: &UserProfile, outside_room: bool) -> Html {
+        let toggle_profile = {
+            let id = u.id.clone();
+            ctx.link().callback(move |_| Msg::ToggleProfile(id.clone()))
+        };
+        html! {
+            <div class="relative px-3 py-1" style={format!("height: {}px", MEMBER_ROW_HEIGHT)}>
+                <div class="flex items-center h-full bg-gray-200 dark:bg-gray-700 rounded-lg p-2">
+                    <div class="relative flex-none">
+                        <img class="w-12 h-12 rounded-full cursor-pointer" src={u.avatar.clone()} alt="" onclick={toggle_profile.clone()}/>
+                        {self.view_presence_dot(&u.id)}
+                    </div>
+                    <div class="ml-3 text-sm flex-grow min-w-0">
+                        <button
+                            onclick={toggle_profile}
+                            aria-haspopup="dialog"
+                            aria-expanded={(self.profile_popover.as_deref() == Some(u.id.as_str())).to_string()}
+                            class="text-left hover:underline focus-visible:underline"
+                        >
+                            {u.name.clone()}
+                        </button>
+                        {
+                            if outside_room {
+                                html!{<div class="text-xs text-gray-500 dark:text-gray-400">{t("dm-available")}</div>}
+                            } else if let Some(status) = self.statuses.get(&u.id) {
+                                html!{<div class="text-xs text-gray-500 dark:text-gray-400 truncate" title={status.display()}>{status.display()}</div>}
+                            } else {
+                                html!{}
+                            }
+                        }
+                    </div>
+                    {
+                        if !self.is_me(&u.id) {
+                            let mute = {
+                                let id = u.id.clone();
+                                ctx.link().callback(move |_| Msg::ToggleMute(id.clone()))
+                            };
+                            let block = {
+                                let id = u.id.clone();
+                                ctx.link().callback(move |_| Msg::ToggleBlock(id.clone()))
+                            };
+                            html!{
+                                <div class="flex flex-col text-xs text-gray-600 dark:text-gray-300">
+                                    <button onclick={mute} class="hover:text-black dark:hover:text-white">
+                                        { if self.block_list.is_muted(&u.id) { t("unmute") } else { t("mute") } }
+                                    </button>
+                                    <button onclick={block} class="hover:text-black dark:hover:text-white">
+                                        { if self.block_list.is_blocked(&u.id) { t("unblock") } else { t("block") } }
+                                    </button>
+                                </div>
+                            }
+                        } else {
+                            html!{}
+                        }
+                    }
+                </div>
+                {
+                    if self.profile_popover.as_deref() == Some(u.id.as_str()) {
+                        self.view_profile_popover(ctx, u)
+                    } else {
+                        html!{}
+                    }
+                }
+            </div>
+        }
+    }
+
+    fn view_profile_popover(&self, ctx: &Context<Self>, u: &UserProfile) -> Html {
+        let can_call = !self.is_me(&u.id) && self.call.is_none() && webrtc::is_supported();
+        let call = |video: bool| {
+            let id = u.id.clone();
+            ctx.link().callback(move |_| Msg::StartCall(id.clone(), video))
+        };
+        let message = {
+            let id = u.id.clone();
+            ctx.link().callback(move |_| Msg::SetDirectPeer(Some(id.clone())))
+        };
+        let pinned = self.settings.pinned_dms.contains(&u.id);
+        let pin = {
+            let id = u.id.clone();
+            ctx.link().callback(move |_| Msg::TogglePinnedDm(id.clone()))
+        };
+        html! {
+            <div class="absolute left-3 right-3 top-full -mt-1 z-10 bg-white dark:bg-gray-800 rounded shadow-lg p-3 text-sm">
+                <div class="flex items-center mb-2">
+                    <img class="w-8 h-8 rounded-full" src={u.avatar.clone()} alt="avatar"/>
+                    <div class="ml-2 min-w-0">
+                        <div class="font-semibold truncate">{u.name.clone()}</div>
+                        {
+                            match self.statuses.get(&u.id) {
+                                Some(status) => html! {<div class="text-xs text-gray-500 dark:text-gray-400 break-words">{status.display()}</div>},
+                                None => html! {},
+                            }
+                        }
+                    </div>
+                </div>
+                {
+                    if can_call {
+                        html! {
+                            <div class="flex mb-2">
+                                <button onclick={call(false)} class="flex-grow px-3 py-1 mr-2 rounded bg-green-600 text-white">{t("call-start")}</button>
+                                <button onclick={call(true)} class="flex-grow px-3 py-1 rounded bg-green-600 text-white">{t("call-start-video")}</button>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                {
+                    if self.is_me(&u.id) {
+                        self.view_status_picker(ctx)
+                    } else {
+                        html! {
+                            <>
+                            <button
+                                onclick={message}
+                                disabled={!self.peer_keys.contains_key(&u.id)}
+                                title={if self.peer_keys.contains_key(&u.id) { t("dm-encrypted-help") } else { t("dm-no-key") }}
+                                class="w-full px-3 py-1 rounded bg-gray-200 dark:bg-gray-700 disabled:opacity-50"
+                            >
+                                {"🔒 "}{t("dm-start")}
+                            </button>
+                            <button onclick={pin} class="w-full mt-2 px-3 py-1 rounded bg-gray-200 dark:bg-gray-700">
+                                { if pinned { t("dm-unpin") } else { t("dm-pin") } }
+                            </button>
+                            {self.view_mod_actions(ctx, &u.id, "w-full mt-2 px-3 py-1 rounded bg-gray-200 dark:bg-gray-700")}
+                            </>
+                        }
+                    }
+                }
+            </div>
+        }
+    }
+
+    fn bump_badge(&mut self) {
+        if document_hidden() || !document_has_focus() {
+            self.badge += 1;
+            notifications::set_badge(self.badge);
+        }
+    }
+
+    fn clear_badge(&mut self) {
+        if mem::take(&mut self.badge) > 0 {
+            notifications::set_badge(0);
+        }
+    }
+
+    /// The room's notification level, chosen from the header.
+    fn view_channel_level(&self, ctx: &Context<Self>) -> Html {
+        let current = self.settings.channel_overrides.get(CHANNEL).copied();
+        let onchange = ctx.link().callback(|e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            Msg::SetChannelLevel(NotificationLevel::ALL.iter().find(|l| format!("{:?}", l) == select.value()).copied())
+        });
+        let icon = match self.settings.level_for(CHANNEL) {
+            NotificationLevel::All => "🔔",
+            NotificationLevel::Mentions => "@",
+            NotificationLevel::None => "🔕",
+        };
+        html! {
+            <label class="ml-2 flex items-center" title={t("channel-notifications")}>
+                <span aria-hidden="true">{icon}</span>
+                <select
+                    {onchange}
+                    aria-label={t("channel-notifications")}
+                    class="ml-1 bg-transparent text-sm hover:text-black dark:hover:text-white"
+                >
+                    <option value="" selected={current.is_none()}>
+                        {t_args("channel-level-default", &[("level", &self.settings.notification_level.label())])}
+                    </option>
+                    {
+                        NotificationLevel::ALL.iter().map(|level| html! {
+                            <option value={format!("{:?}", level)} selected={current == Some(*level)}>{level.label()}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+            </label>
+        }
+    }
+
+    /// Messages waiting to be sent later, as a count that expands into a list
+    /// to cancel from.
+    fn view_scheduled(&self, ctx: &Context<Self>) -> Html {
+        let pending = self.scheduler.pending();
+        if pending.is_empty() {
+            return html! {};
+        }
+        let rows = pending.iter().map(|message| {
+            let id = message.id;
+            let conversation = match &message.peer {
+                Some(peer) => self.conversation_title(peer),
+                None => format!("#{}", CHANNEL),
+            };
+            html! {
+                <li class="flex items-center py-1">
+                    <div class="flex-grow min-w-0">
+                        <div class="text-xs text-gray-500 dark:text-gray-400">
+                            {conversation}{" · "}{format_time(message.send_at)}
+                            {
+                                if message.missed {
+                                    html! {<span class="ml-1 text-red-500">{t("scheduled-missed")}</span>}
+                                } else {
+                                    html! {}
+                                }
+                            }
+                        </div>
+                        <div class="truncate">{&message.text}</div>
+                    </div>
+                    {
+                        if message.missed {
+                            html! {
+                                <button onclick={ctx.link().callback(move |_| Msg::SendScheduledNow(id))} class="ml-3 text-green-600 hover:underline">
+                                    {t("scheduled-send-now")}
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    <button
+                        onclick={ctx.link().callback(move |_| Msg::CancelScheduled(id))}
+                        title={t("scheduled-cancel")}
+                        class="ml-3 hover:text-black dark:hover:text-white"
+                        aria-label={t("scheduled-cancel")}
+                    >
+                        {"✕"}
+                    </button>
+                </li>
+            }
+        });
+        html! {
+            <div class="px-6 py-2 text-sm bg-gray-50 dark:bg-gray-900 border-t border-gray-300 dark:border-gray-700">
+                <button onclick={ctx.link().callback(|_| Msg::ToggleScheduled)} aria-expanded={self.show_scheduled.to_string()} class="hover:underline">
+                    {"🕒 "}{t_args("scheduled-count", &[("count", &pending.len().to_string())])}
+                </button>
+                {
+                    if self.show_scheduled {
+                        html! {<ul class="mt-1 max-h-40 overflow-y-auto">{ for rows }</ul>}
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        }
+    }
+
+    /// The disappearing message timer of the open conversation.
+    fn view_disappearing(&self, ctx: &Context<Self>) -> Html {
+        // Notes to self are kept on purpose.
+        if self.direct_peer.as_deref() == Some(self.user_id.as_str()) {
+            return html! {};
+        }
+        let current = self
+            .settings
+            .disappearing
+            .get(self.direct_peer.as_deref().unwrap_or(CHANNEL))
+            .copied();
+        let onchange = ctx.link().callback(|e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            Msg::SetDisappearing(select.value().parse().ok())
+        });
+        html! {
+            <label class="ml-2 flex items-center" title={t("disappearing-title")}>
+                <span aria-hidden="true">{"⏳"}</span>
+                <select
+                    {onchange}
+                    aria-label={t("disappearing-title")}
+                    class="ml-1 bg-transparent text-sm hover:text-black dark:hover:text-white"
+                >
+                    <option value="" selected={current.is_none()}>{t("disappearing-off")}</option>
+                    {
+                        DISAPPEARING_AFTER.iter().map(|&seconds| html! {
+                            <option value={seconds.to_string()} selected={current == Some(seconds)}>{duration_label(seconds)}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+            </label>
+        }
+    }
+
+    /// A dot on the avatar of someone online: green while active, amber
+    /// while away.
+    fn view_presence_dot(&self, id: &str) -> Html {
+        if !self.users.iter().any(|u| u.id == id) {
+            return html! {};
+        }
+        let presence = if self.away.contains(id) { Presence::Away } else { Presence::Online };
+        html! {
+            <span
+                role="img"
+                title={presence.label()}
+                aria-label={presence.label()}
+                class={classes!("absolute", "bottom-0", "right-0", "w-3", "h-3", "rounded-full", "border-2", "border-gray-200", "dark:border-gray-700",
+                    if presence == Presence::Away { "bg-yellow-400" } else { "bg-green-500" })}
+            ></span>
+        }
+    }
+
+    /// Our own status: a free text field, presets and a way to clear it.
+    fn view_status_picker(&self, ctx: &Context<Self>) -> Html {
+        let current = self.statuses.get(&self.user_id);
+        let presets = STATUS_PRESETS.iter().map(|(emoji, key)| {
+            let onclick = ctx.link().callback(move |_| Msg::SetStatus(Some(emoji.to_string()), t(key)));
+            html! {
+                <button {onclick} class="px-2 py-1 mr-1 mb-1 rounded bg-gray-200 dark:bg-gray-700 text-xs">
+                    {emoji}{" "}{t(key)}
+                </button>
+            }
+        });
+        html! {
+            <>
+            <input
+                type="text"
+                maxlength={STATUS_LEN.to_string()}
+                placeholder={t("status-placeholder")}
+                aria-label={t("status-placeholder")}
+                value={current.map(|s| s.text.clone()).unwrap_or_default()}
+                onchange={ctx.link().callback(|e: Event| {
+                    let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                    Msg::SetStatus(None, input.value())
+                })}
+                class="w-full px-2 py-1 mb-2 rounded bg-gray-200 dark:bg-gray-700"
+            />
+            <div class="flex flex-wrap">{ for presets }</div>
+            {
+                if current.is_some() {
+                    html! {
+                        <button
+                            onclick={ctx.link().callback(|_| Msg::SetStatus(None, String::new()))}
+                            class="w-full mt-1 px-3 py-1 rounded bg-gray-200 dark:bg-gray-700"
+                        >
+                            {t("status-clear")}
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            </>
+        }
+    }
+
+    /// Swaps generated avatars over to `to`; pictures people set themselves
+    /// stay as they are.
+    fn restyle_avatars(&mut self, from: AvatarStyle, to: AvatarStyle) {
+        avatar::set_style(to);
+        for user in self.users.iter_mut().chain(self.member_profiles.values_mut()) {
+            if user.avatar == from.url(&user.id) {
+                user.avatar = to.url(&user.id);
+            }
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view_pinned_dms(&self, ctx: &Context<Self>) -> Html {
+        let pins = self
+            .settings
+            .pinned_dms
+            .iter()
+            .map(|id| PinnedDm {
+                id: id.clone(),
+                name: self.profile(id).map(|p| p.name.clone()).unwrap_or_else(|| id.clone()),
+                avatar: avatar::url(id),
+            })
+            .collect::<Vec<_>>();
+        let saved_open = self.direct_peer.as_ref() == Some(&self.user_id);
+        let open_saved = {
+            let id = self.user_id.clone();
+            ctx.link().callback(move |_| Msg::SetDirectPeer(Some(id.clone())))
+        };
+        html! {
+            <>
+            <div class="px-3 pt-2">
+                <button
+                    onclick={open_saved}
+                    class={classes!("flex", "items-center", "w-full", "rounded-lg", "p-2", "text-sm",
+                        if saved_open { "bg-green-100 dark:bg-green-900" } else { "hover:bg-gray-200 dark:hover:bg-gray-700" })}
+                >
+                    {"🔖 "}{t("saved-title")}
+                </button>
+            </div>
+            <PinnedDms
+                {pins}
+                active={self.direct_peer.clone()}
+                on_open={ctx.link().callback(|id| Msg::SetDirectPeer(Some(id)))}
+                on_unpin={ctx.link().callback(Msg::TogglePinnedDm)}
+                on_move={ctx.link().callback(|(from, to)| Msg::MovePinnedDm(from, to))}
+            />
+            </>
+        }
+    }
+
+    /// Counts a send against the flood limit. Returns whether it may go ahead;
+    /// either way the cooldown shown in the composer is brought up to date.
+    fn allow_send(&mut self, ctx: &Context<Self>) -> bool {
+        let allowed = self.send_throttle.try_send(js_sys::Date::now());
+        self.refresh_cooldown(ctx);
+        allowed
+    }
+
+    fn refresh_cooldown(&mut self, ctx: &Context<Self>) {
+        self.cooldown = self.send_throttle.cooldown_secs(js_sys::Date::now());
+        match self.cooldown {
+            Some(_) if self.cooldown_tick.is_none() => {
+                let link = ctx.link().clone();
+                self.cooldown_tick = Some(Interval::new(1_000, move || link.send_message(Msg::CooldownTick)));
+            }
+            Some(_) => {}
+            None => self.cooldown_tick = None,
+        }
+    }
+
+    /// Runs the expiry ticker for as long as any message is due to disappear.
+    fn refresh_expiry(&mut self, ctx: &Context<Self>) {
+        let pending = self.messages.iter().any(|m| !m.deleted && m.expires_at().is_some());
+        match (pending, &self.expiry_tick) {
+            (true, None) => {
+                let link = ctx.link().clone();
+                self.expiry_tick = Some(Interval::new(1_000, move || link.send_message(Msg::ExpiryTick)));
+            }
+            (false, Some(_)) => self.expiry_tick = None,
+            _ => {}
+        }
+    }
+
+    /// Wipes disappearing messages that are due. They keep their slot, so
+    /// indices held elsewhere stay valid, but are no longer listed.
+    fn expire_messages(&mut self) {
+        let now = js_sys::Date::now();
+        let mut forgotten = false;
+        for message in &mut self.messages {
+            if message.deleted || !message.expires_at().is_some_and(|at| at <= now) {
+                continue;
+            }
+            message.deleted = true;
+            message.message.clear();
+            message.attachment = None;
+            message.quote = None;
+            message.poll = None;
+            message.reactions = Reactions::new();
+            if let Some(id) = &message.id {
+                forgotten |= self.settings.hidden_messages.remove(id);
+            }
+        }
+        if forgotten {
+            self.settings.save();
+        }
+    }
+
+    /// Whether a file picked, pasted or dropped cannot be sent in the open
+    /// conversation, saying why if so.
+    fn refuse_file(&mut self) -> bool {
+        let refused = outgoing(self.direct_peer.as_deref(), false) == Outgoing::Refused;
+        if refused {
+            self.notices.push(t("attachment-not-in-dm"));
+        }
+        refused
+    }
+
+    /// Holds a message back for the undo-send delay before it goes out.
+    fn queue_outgoing(&mut self, ctx: &Context<Self>, peer: Option<String>, text: String, quote: Option<Quote>) {
+        let text = emoji::expand_message(&text);
+        let id = self.next_outgoing;
+        self.next_outgoing += 1;
+        let link = ctx.link().clone();
+        let timer = Timeout::new(UNDO_SEND_DELAY, move || link.send_message(Msg::DispatchMessage(id)));
+        self.outbox.insert(id, (peer, text, quote, timer));
+    }
+
+    fn view_direct_banner(&self, ctx: &Context<Self>) -> Html {
+        let peer = match &self.direct_peer {
+            Some(peer) => peer,
+            None => return html! {},
+        };
+        let name = self.profile(peer).map(|p| p.name.as_str()).unwrap_or(peer);
+        let status = if peer == &self.user_id {
+            format!("🔖 {}", t("saved-composing"))
+        } else if self.peer_keys.contains_key(peer) {
+            format!("🔒 {}", t_args("dm-composing", &[("name", name)]))
+        } else {
+            format!("🔒 {}", t_args("dm-waiting-for-key", &[("name", name)]))
+        };
+        let show_safety_number = ctx.link().callback({
+            let peer = peer.clone();
+            move |_| Msg::ShowSafetyNumber(Some(peer.clone()))
+        });
+        let verification = if peer == &self.user_id || !self.peer_keys.contains_key(peer) {
+            html! {}
+        } else if self.known_keys.is_verified(peer) {
+            html! {
+                <button onclick={show_safety_number.clone()} title={t("verify-title")} class="ml-3 px-2 rounded bg-green-200 dark:bg-green-900 hover:text-black dark:hover:text-white">
+                    {format!("✓ {}", t("verify-verified"))}
+                </button>
+            }
+        } else {
+            html! {
+                <button onclick={show_safety_number.clone()} class="ml-3 underline hover:text-black dark:hover:text-white">
+                    {t("verify-open")}
+                </button>
+            }
+        };
+        let key_changed = if self.known_keys.has_changed(peer) {
+            html! {
+                <div role="alert" class="flex items-center px-6 py-2 text-sm bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 border-t border-red-300 dark:border-red-700">
+                    <span class="flex-grow">{format!("⚠ {}", t_args("verify-key-changed", &[("name", name)]))}</span>
+                    <button onclick={show_safety_number} class="ml-3 font-semibold underline">{t("verify-open")}</button>
+                    <button
+                        onclick={ctx.link().callback({
+                            let peer = peer.clone();
+                            move |_| Msg::AcknowledgeKeyChange(peer.clone())
+                        })}
+                        class="ml-3 hover:text-black dark:hover:text-white"
+                    >
+                        {t("verify-dismiss")}
+                    </button>
+                </div>
+            }
+        } else {
+            html! {}
+        };
+        html! {
+            <>
+            {key_changed}
+            <div class="flex items-center justify-between px-6 py-2 text-sm bg-green-50 dark:bg-gray-900 text-green-800 dark:text-green-300 border-t border-gray-300 dark:border-gray-700">
+                <span class="flex-grow truncate">{status}</span>
+                {verification}
+                <button
+                    onclick={ctx.link().callback({
+                        let peer = peer.clone();
+                        move |_| Msg::SetSplitPeer(Some(peer.clone()))
+                    })}
+                    title={t("split-open")}
+                    class="hidden lg:inline ml-3 hover:text-black dark:hover:text-white"
+                    aria-label={t("split-open")}
+                >
+                    {"◫"}
+                </button>
+                <button
+                    onclick={ctx.link().callback(|_| Msg::SetDirectPeer(None))}
+                    title={t("dm-back-to-room")}
+                    class="ml-3 hover:text-black dark:hover:text-white"
+                    aria-label={t("dm-back-to-room")}
+                >
+                    {"✕"}
+                </button>
+            </div>
+            </>
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view_safety_number(&self, ctx: &Context<Self>) -> Html {
+        let (peer, key) = match self.verifying.as_ref().and_then(|peer| Some((peer, self.peer_keys.get(peer)?))) {
+            Some(verifying) => verifying,
+            None => return html! {},
+        };
+        let ours = self.keys.public_key();
+        let fingerprints = (
+            e2ee::fingerprint(&self.user_id, &ours),
+            e2ee::fingerprint(peer, key),
+            e2ee::safety_number((&self.user_id, &ours), (peer, key)),
+        );
+        let (our_fingerprint, their_fingerprint, safety_number) = match fingerprints {
+            (Some(ours), Some(theirs), Some(both)) => (ours, theirs, both),
+            _ => return html! {},
+        };
+        let name = self.profile(peer).map(|p| p.name.clone()).unwrap_or_else(|| peer.clone());
+        html! {
+            <SafetyNumberDialog
+                peer_name={name}
+                {our_fingerprint}
+                {their_fingerprint}
+                {safety_number}
+                verified={self.known_keys.is_verified(peer)}
+                changed={self.known_keys.has_changed(peer)}
+                on_verify={ctx.link().callback({
+                    let peer = peer.clone();
+                    move |_| Msg::VerifyKey(peer.clone())
+                })}
+                on_unverify={ctx.link().callback({
+                    let peer = peer.clone();
+                    move |_| Msg::UnverifyKey(peer.clone())
+                })}
+                on_close={ctx.link().callback(|_| Msg::ShowSafetyNumber(None))}
+            />
+        }
+    }
+
+    fn conversation_title(&self, peer: &str) -> String {
+        if peer == self.user_id {
+            return format!("🔖 {}", t("saved-title"));
+        }
+        format!("🔒 {}", self.profile(peer).map(|p| p.name.as_str()).unwrap_or(peer))
+    }
+
+    /// The split-view pane: one direct message conversation with its own
+    /// composer, next to the room. Only laid out on wide screens.
+    #[allow(clippy::unnecessary_operation)]
+    fn view_split_pane(&self, ctx: &Context<Self>, render_options: &RenderOptions) -> Html {
+        let peer = match &self.split_peer {
+            Some(peer) => peer,
+            None => return html! {},
+        };
+        let messages = self
+            .messages
+            .iter()
+            .filter(|m| m.direct.as_ref() == Some(peer) && !m.deleted)
+            .map(|m| {
+                html! {
+                    <div class="mb-3">
+                        <div class="text-xs text-gray-500 dark:text-gray-400">{m.from.clone()}</div>
+                        <div class="text-sm text-gray-700 dark:text-gray-200">{self.view_filtered(m, render_options)}</div>
+                    </div>
+                }
+            })
+            .collect::<Html>();
+        html! {
+            <div class="hidden lg:flex flex-col w-1/3 min-w-0 border-l border-gray-300 dark:border-gray-700">
+                <div class="flex items-center justify-between px-4 py-3 border-b border-gray-300 dark:border-gray-700 text-sm">
+                    <span class="truncate">{self.conversation_title(peer)}</span>
+                    <button
+                        onclick={ctx.link().callback(|_| Msg::SetSplitPeer(None))}
+                        title={t("split-close")}
+                        class="ml-3 text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white"
+                        aria-label={t("split-close")}
+                    >
+                        {"✕"}
+                    </button>
+                </div>
+                <div class="flex-grow overflow-y-auto px-4 py-3">{messages}</div>
+                <div class="bg-white dark:bg-gray-800 border-t border-gray-300 dark:border-gray-700">
+                    <Composer
+                        input_ref={self.split_input.clone()}
+                        on_submit={ctx.link().callback(|_| Msg::SubmitSplitMessage)}
+                        max_len={MAX_MESSAGE_LEN}
+                        cooldown={self.cooldown.map(cooldown_label)}
+                    />
+                </div>
+            </div>
+        }
+    }
+
+    /// The latest messages of the conversation the composer is pointed at,
+    /// with a composer of its own. `in_page` is false when rendered into the
+    /// picture-in-picture window, where sending is wired up by hand.
+    fn view_mini_chat(&self, ctx: &Context<Self>, render_options: &RenderOptions, in_page: bool) -> Html {
+        let title = match &self.direct_peer {
+            Some(peer) => self.conversation_title(peer),
+            None => format!("#{}", CHANNEL),
+        };
+        // Newest first, laid out bottom-up so the pane opens at the end.
+        let messages = self
+            .messages
+            .iter()
+            .rev()
+            .filter(|m| m.direct == self.direct_peer && !m.deleted && !self.block_list.is_blocked(m.sender_id()))
+            .take(MINI_CHAT_LEN)
+            .map(|m| {
+                html! {
+                    <div class="mb-2">
+                        <div class="text-xs text-gray-500 dark:text-gray-400">{m.from.clone()}</div>
+                        { self.view_filtered(m, render_options) }
+                    </div>
+                }
+            })
+            .collect::<Html>();
+        let onkeydown = in_page.then(|| {
+            ctx.link()
+                .batch_callback(|e: KeyboardEvent| mini_chat_submission(&e).then_some(Msg::MiniSend))
+        });
+        html! {
+            <div class="flex flex-col h-full bg-white dark:bg-gray-800 text-gray-900 dark:text-white text-sm">
+                <div class="flex items-center justify-between px-3 py-2 border-b border-gray-300 dark:border-gray-700">
+                    <span class="truncate font-semibold">{title}</span>
+                    {
+                        if in_page {
+                            html! {
+                                <button
+                                    onclick={ctx.link().callback(|_| Msg::CloseMiniChat)}
+                                    title={t("close")}
+                                    class="ml-2 text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white"
+                                    aria-label={t("close")}
+                                >
+                                    {"✕"}
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </div>
+                <div class="flex-grow overflow-y-auto flex flex-col-reverse px-3 py-2">{messages}</div>
+                <textarea
+                    ref={self.mini_input.clone()}
+                    {onkeydown}
+                    rows="2"
+                    placeholder={self.cooldown.map_or_else(|| t("mini-chat-placeholder"), cooldown_label)}
+                    class="m-2 p-2 rounded-lg border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 resize-none"
+                ></textarea>
+            </div>
+        }
+    }
+
+    fn view_selection_bar(&self, ctx: &Context<Self>) -> Html {
+        if self.selected.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="flex items-center justify-between px-6 py-2 text-sm bg-gray-800 dark:bg-gray-900 text-white">
+                <span>{t_args("selection-count", &[("count", &self.selected.len().to_string())])}</span>
+                <span>
+                    <button onclick={ctx.link().callback(|_| Msg::CopySelection)} class="ml-3 font-semibold text-green-400 hover:underline">
+                        {t("selection-copy")}
+                    </button>
+                    <button onclick={ctx.link().callback(|_| Msg::ClearSelection)} class="ml-3 hover:underline">
+                        {t("selection-clear")}
+                    </button>
+                </span>
+            </div>
+        }
+    }
+
+    /// The selected messages as plain text, one block per message headed by
+    /// its sender and time.
+    fn selection_transcript(&self) -> String {
+        transcript(self.selected.iter().filter_map(|&idx| self.messages.get(idx)))
+    }
+
+    /// Messages worth keeping in an export: the loaded history, without
+    /// deleted messages and local-only onboarding.
+    fn exportable(&self) -> impl Iterator<Item = &MessageData> {
+        self.messages
+            .iter()
+            .filter(|m| !m.deleted && m.welcome.is_none() && m.delivery.is_none() && m.expires_in.is_none())
+    }
+
+    fn export_history(&self, format: ExportFormat) {
+        let contents = match format {
+            ExportFormat::Json => {
+                let archive = Archive {
+                    version: archive::VERSION,
+                    room: CHANNEL.to_string(),
+                    exported_at: js_sys::Date::now(),
+                    messages: self.exportable().map(ArchivedMessage::from).collect(),
+                };
+                serde_json::to_string_pretty(&archive).unwrap()
+            }
+            ExportFormat::Text => transcript(self.exportable()),
+        };
+        archive::download(&archive::file_name(CHANNEL, format), format, &contents);
+    }
+
+    /// Black-on-white copy of the room for printing, without media: images
+    /// and files are listed as links.
+    fn view_print_transcript(&self) -> Html {
+        let range = match self.print_range {
+            Some(range) => range,
+            None => return html! {},
+        };
+        let rows = self
+            .messages
+            .iter()
+            .filter(|m| !m.deleted && m.welcome.is_none() && !self.block_list.is_blocked(m.sender_id()))
+            .filter(|m| range.contains(m.timestamp))
+            .map(|m| {
+                let time = m.timestamp.map(format_time).unwrap_or_default();
+                let direct = if m.direct.is_some() { t("dm-label") } else { String::new() };
+                let body = match sanitize::media_kind(&m.message).and(sanitize::safe_url(&m.message)) {
+                    Some(url) => html! {<a href={url.clone()} class="underline break-all">{url}</a>},
+                    None => html! {<p class="whitespace-pre-wrap break-words">{m.message.clone()}</p>},
+                };
+                html! {
+                    <div class="mb-3" style="break-inside: avoid">
+                        <div class="text-xs text-gray-600">
+                            {time}{" · "}<span class="font-semibold text-black">{m.from.clone()}</span>{" "}{direct}
+                        </div>
+                        {
+                            match &m.attachment {
+                                Some(file) => html! {
+                                    <a href={file.url.clone()} class="underline break-all">{file.name.clone()}{" — "}{file.url.clone()}</a>
+                                },
+                                None => html! {},
+                            }
+                        }
+                        {body}
+                    </div>
+                }
+            })
+            .collect::<Html>();
+        html! {
+            <div class="hidden print:block bg-white text-black p-8">
+                <h1 class="text-xl mb-4">{format!("#{}", CHANNEL)}</h1>
+                {rows}
+            </div>
+        }
+    }
+
+    /// Others' text with filtered words masked, if the word filter has any.
+    fn filtered_text(&self, message: &MessageData) -> Option<String> {
+        if self.is_me(message.sender_id()) {
+            return None;
+        }
+        word_filter::mask(&message.message, &self.settings.filtered_words)
+    }
+
+    /// A message's text for the compact views, masked where filtered.
+    fn view_filtered(&self, message: &MessageData, render_options: &RenderOptions) -> Html {
+        match self.filtered_text(message) {
+            Some(masked) => html! {<p class="whitespace-pre-wrap break-words">{masked}</p>},
+            None => message_content::render(&message.message, render_options),
+        }
+    }
+
+    /// The message a reply quotes, linking back to it while it is loaded.
+    fn view_quote(&self, ctx: &Context<Self>, message: &MessageData) -> Html {
+        let quote = match &message.quote {
+            Some(quote) => quote,
+            None => return html! {},
+        };
+        let original = self
+            .messages
+            .iter()
+            .position(|m| m.id.as_ref() == Some(&quote.id) && !m.deleted);
+        let onclick = original.map(|idx| ctx.link().callback(move |_| Msg::JumpToMessage(idx)));
+        let title = if original.is_some() { t("quote-jump") } else { t("quote-unavailable") };
+        html! {
+            <button
+                {onclick}
+                disabled={original.is_none()}
+                {title}
+                class="block w-full max-w-md text-left my-1 pl-2 py-1 text-sm border-l-4 border-green-500 bg-gray-100 dark:bg-gray-700 rounded-r enabled:hover:bg-gray-200 dark:enabled:hover:bg-gray-600"
+            >
+                <span class="block font-semibold">{quote.from.clone()}</span>
+                <span class="block text-gray-600 dark:text-gray-300 break-words">{quote.excerpt.clone()}</span>
+            </button>
+        }
+    }
+
+    fn view_message_text(&self, ctx: &Context<Self>, idx: usize, message: &MessageData, render_options: &RenderOptions) -> Html {
+        if message.message.is_empty() {
+            return html! {};
+        }
+        match self.filtered_text(message).filter(|_| !self.revealed.contains(&idx)) {
+            Some(masked) => html! {
+                <p class="whitespace-pre-wrap break-words">
+                    {masked}
+                    <button
+                        onclick={ctx.link().callback(move |_| Msg::RevealMessage(idx))}
+                        class="ml-2 text-sm underline text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white"
+                    >
+                        {t("word-filter-reveal")}
+                    </button>
+                </p>
+            },
+            None => {
+                let translation = message
+                    .id
+                    .as_ref()
+                    .filter(|id| !self.originals.contains(*id))
+                    .and_then(|id| self.translations.get(id))
+                    .and_then(Option::as_ref);
+                html! {
+                    <>
+                    { message_content::render(translation.unwrap_or(&message.message), render_options) }
+                    { self.view_translation_toggle(ctx, message) }
+                    </>
+                }
+            }
+        }
+    }
+
+    /// Says a message is shown translated, with a switch back to the
+    /// original, or that its translation is on the way.
+    fn view_translation_toggle(&self, ctx: &Context<Self>, message: &MessageData) -> Html {
+        let id = match &message.id {
+            Some(id) => id,
+            None => return html! {},
+        };
+        let label = match self.translations.get(id) {
+            None => return html! {},
+            Some(None) => {
+                return html! {<span class="text-xs italic text-gray-500 dark:text-gray-400">{t("translation-loading")}</span>};
+            }
+            Some(Some(_)) if self.originals.contains(id) => t("translation-show"),
+            Some(Some(_)) => t("translation-show-original"),
+        };
+        let toggle = {
+            let id = id.clone();
+            ctx.link().callback(move |_| Msg::ToggleOriginal(id.clone()))
+        };
+        html! {
+            <button onclick={toggle} class="text-xs text-gray-500 dark:text-gray-400 underline hover:text-black dark:hover:text-white">
+                {label}
+            </button>
+        }
+    }
+
+    /// Reflects a kick, ban or purge the server carried out. Returns whether
+    /// the view changed.
+    fn apply_mod_action(&mut self, ctx: &Context<Self>, action: &ModAction) -> bool {
+        match action.action {
+            ModActionKind::Kick | ModActionKind::Ban if self.is_me(&action.target) => {
+                let key = if action.action == ModActionKind::Ban { "mod-banned" } else { "mod-kicked" };
+                self.sign_out(ctx);
+                if let Some(window) = web_sys::window() {
+                    let _ = window.alert_with_message(&t_args(key, &[("room", CHANNEL)]));
+                }
+                false
+            }
+            ModActionKind::Kick | ModActionKind::Ban => {
+                self.membership.leave(CHANNEL, &action.target);
+                self.users.retain(|u| u.id != action.target);
+                if self.profile_popover.as_ref() == Some(&action.target) {
+                    self.profile_popover = None;
+                }
+                true
+            }
+            ModActionKind::Purge => {
+                let mut changed = false;
+                for message in self
+                    .messages
+                    .iter_mut()
+                    .filter(|m| !m.deleted && m.sender_id() == action.target)
                 {
-                    self.users.clone().iter().map(|u| {
-                        html!{
-                            <div class="flex items-center m-3 bg-gray-700 rounded-lg p-2">
-                                <img class="w-12 h-12 rounded-full" src={u.avatar.clone()} alt="avatar"/>
-                                <div class="ml-3 text-sm">{u.name.clone()}</div>
-                            </div>
+                    message.deleted = true;
+                    message.message.clear();
+                    message.attachment = None;
+                    message.reactions.clear();
+                    changed = true;
+                }
+                changed
+            }
+            ModActionKind::Delete | ModActionKind::Pin => false,
+        }
+    }
+
+    /// Kick, ban and purge buttons for a moderator looking at someone else.
+    fn view_mod_actions(&self, ctx: &Context<Self>, user_id: &str, class: &'static str) -> Html {
+        if !self.role.can_moderate() || self.is_me(user_id) {
+            return html! {};
+        }
+        [
+            (ModActionKind::Kick, "mod-kick"),
+            (ModActionKind::Ban, "mod-ban"),
+            (ModActionKind::Purge, "mod-purge"),
+        ]
+        .into_iter()
+        .map(|(kind, key)| {
+            let id = user_id.to_string();
+            let onclick = ctx.link().callback(move |_| Msg::Moderate(kind, id.clone()));
+            html! {
+                <button {onclick} class={classes!(class, "text-red-500")}>{t_args(key, &[("room", CHANNEL)])}</button>
+            }
+        })
+        .collect()
+    }
+
+    /// Whether the message at `idx` is shown as part of the block of the one
+    /// rendered before it: same sender and conversation, close in time, and
+    /// with no unread divider between them.
+    fn continues_group(&self, prev: usize, idx: usize) -> bool {
+        let (a, b) = match (self.messages.get(prev), self.messages.get(idx)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return false,
+        };
+        let close = match (a.timestamp, b.timestamp) {
+            (Some(at), Some(bt)) => (0.0..=GROUP_WINDOW).contains(&(bt - at)),
+            _ => false,
+        };
+        close
+            && a.sender_id() == b.sender_id()
+            && a.direct == b.direct
+            && a.renamed_from == b.renamed_from
+            && a.welcome.is_none()
+            && b.welcome.is_none()
+            && self.first_unread != Some(idx)
+    }
+
+    /// Whether a message is shown in the list at all.
+    fn listed(&self, message: &MessageData) -> bool {
+        !self.block_list.is_blocked(message.sender_id())
+            && message.delivery != Some(Delivery::Discarded)
+            && (!message.deleted || message.expires_in.is_none())
+            && !message.id.as_ref().is_some_and(|id| self.settings.hidden_messages.contains(id))
+    }
+
+    /// Whether keyboard navigation stops at a message; muted ones are skipped
+    /// until revealed.
+    fn navigable(&self, idx: usize) -> bool {
+        self.messages.get(idx).is_some_and(|m| {
+            self.listed(m) && (!self.block_list.is_muted(m.sender_id()) || self.revealed.contains(&idx))
+        })
+    }
+
+    fn view_bot_actions(&self, ctx: &Context<Self>, message: &MessageData) -> Html {
+        let id = match &message.id {
+            Some(id) if message.bot && !message.actions.is_empty() => id,
+            _ => return html! {},
+        };
+        html! {
+            <div class="flex flex-wrap mt-2">
+                {
+                    message.actions.iter().map(|action| {
+                        let (id, payload) = (id.clone(), action.payload.clone());
+                        let onclick = ctx.link().callback(move |_| Msg::SendBotCommand(id.clone(), payload.clone()));
+                        html! {
+                            <button {onclick} class="mr-2 mb-1 px-3 py-1 rounded border border-indigo-400 text-sm text-indigo-700 dark:text-indigo-200 hover:bg-indigo-100 dark:hover:bg-indigo-800">
+                                {action.label.clone()}
+                            </button>
                         }
                     }).collect::<Html>()
                 }
             </div>
-            <div class="flex-grow flex flex-col">
-                <div class="flex-grow overflow-y-auto px-6 py-4">
+        }
+    }
+
+    /// The quick reactions opened with `+` on the focused message.
+    fn view_reaction_picker(&self, ctx: &Context<Self>, idx: usize, message: &MessageData) -> Html {
+        let (pick, id) = match (self.reaction_pick, &message.id) {
+            (Some(pick), Some(id)) if self.focused_message == Some(idx) => (pick, id),
+            _ => return html! {},
+        };
+        html! {
+            <div class="mt-2">
+                <div role="listbox" aria-label={t("reactions-quick")} class="flex">
                     {
-                        self.messages.iter().map(|m| {
-                            let user = self.users.iter().find(|u| u.name == m.from).unwrap();
-                            html!{
-                                <div class="flex items-start mb-4">
-                                    <img class="w-10 h-10 rounded-full mr-4" src={user.avatar.clone()} alt="avatar"/>
-                                    <div class="bg-gray-700 p-4 rounded-lg">
-                                        <div class="text-sm">{m.from.clone()}</div>
-                                        <div class="text-gray-200 mt-1">
-                                            {
-                                                if m.message.ends_with(".gif") {
-                                                    html!{<img src={m.message.clone()} alt="gif" class="max-w-xs"/>}
-                                                } else {
-                                                    html!{<p>{m.message.clone()}</p>}
-                                                }
-                                            }
-                                        </div>
-                                    </div>
-                                </div>
+                        reactions::QUICK_REACTIONS.iter().enumerate().map(|(i, &emoji)| {
+                            let id = id.clone();
+                            let react = ctx.link().callback(move |_| Msg::React(id.clone(), emoji.to_string()));
+                            html! {
+                                <button
+                                    onclick={react}
+                                    tabindex="-1"
+                                    role="option"
+                                    aria-selected={if i == pick { "true" } else { "false" }}
+                                    class={classes!("mr-1", "px-1", "rounded",
+                                        (i == pick).then_some("ring-2 ring-green-500 bg-white dark:bg-gray-800"))}
+                                >
+                                    {emoji}
+                                </button>
                             }
                         }).collect::<Html>()
                     }
                 </div>
-                <div class="w-full h-14 flex items-center justify-between bg-gray-800 border-t border-gray-700">
-                    <div class="flex items-center w-full">
-                        <input ref={self.chat_input.clone()} type="text" placeholder="Message" class="py-2 pl-4 pr-10 mx-3 bg-gray-700 rounded-full outline-none focus:ring-2 focus:ring-green-500 focus:border-transparent text-white" name="message" required=true />
-                        <button onclick={submit} class="p-3 shadow-sm bg-green-600 w-10 h-10 rounded-full flex justify-center items-center text-white">
-                            <svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg" class="fill-current w-6 h-6">
-                                <path d="M0 0h24v24H0z" fill="none"></path>
-                                <path d="M2.01 21L23 12 2.01 3 2 10l15 2-15 2z"></path>
-                            </svg>
+                <div class="mt-1 text-xs text-gray-500 dark:text-gray-400">{t("reactions-keyboard-hint")}</div>
+            </div>
+        }
+    }
+
+    fn can_delete(&self, message: &MessageData) -> bool {
+        !message.deleted && (self.is_me(message.sender_id()) || self.role.can_moderate())
+    }
+
+    fn view_message_menu(&self, ctx: &Context<Self>, idx: usize, message: &MessageData) -> Html {
+        let id = match &message.id {
+            Some(id) => id.clone(),
+            None => return html! {},
+        };
+        let toggle = ctx.link().callback(move |_| Msg::ToggleMessageMenu(idx));
+        let item_class = "block w-full text-left px-3 py-1 text-sm hover:bg-gray-200 dark:hover:bg-gray-700";
+
+        html! {
+            <div class="relative ml-2">
+                <button onclick={toggle} title={t("message-actions")} class="invisible group-hover:visible text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white px-1" aria-label={t("message-actions")}>
+                    {"⋯"}
+                </button>
+                {
+                    if self.open_menu == Some(idx) {
+                        let hide = {
+                            let id = id.clone();
+                            ctx.link().callback(move |_| Msg::HideMessage(id.clone()))
+                        };
+                        html! {
+                            <div class="absolute left-0 z-10 mt-1 w-48 bg-white dark:bg-gray-800 rounded shadow-lg py-1">
+                                <div class="flex justify-between px-2 pb-1 border-b border-gray-300 dark:border-gray-700">
+                                    {
+                                        reactions::QUICK_REACTIONS.iter().map(|&emoji| {
+                                            let id = id.clone();
+                                            let react = ctx.link().callback(move |_| Msg::React(id.clone(), emoji.to_string()));
+                                            html! {<button onclick={react} class="transform hover:scale-125">{emoji}</button>}
+                                        }).collect::<Html>()
+                                    }
+                                </div>
+                                {
+                                    if message.direct.is_none() && !message.deleted {
+                                        html! {
+                                            <button onclick={ctx.link().callback(move |_| Msg::QuoteMessage(idx))} class={item_class}>{t("message-quote")}</button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                {
+                                    if !message.deleted && message.poll.is_none() {
+                                        html! {
+                                            <button onclick={ctx.link().callback(move |_| Msg::ForwardMessage(idx))} class={item_class}>{t("message-forward")}</button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                {
+                                    // Decrypted direct messages never go to a third party.
+                                    if translate::is_enabled()
+                                        && message.direct.is_none()
+                                        && !message.deleted
+                                        && !message.message.is_empty()
+                                        && !self.translations.contains_key(&id)
+                                    {
+                                        let id = id.clone();
+                                        html! {
+                                            <button onclick={ctx.link().callback(move |_| Msg::Translate(id.clone()))} class={item_class}>{t("message-translate")}</button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                {
+                                    if message.direct.is_none() && !message.deleted {
+                                        html! {
+                                            <button onclick={ctx.link().callback(move |_| Msg::CopyPermalink(idx))} class={item_class}>{t("message-copy-link")}</button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                <button onclick={hide} class={item_class}>{t("message-remove-for-me")}</button>
+                                {
+                                    if self.can_delete(message) {
+                                        let delete = ctx.link().callback(move |_| Msg::DeleteMessage(id.clone()));
+                                        html! {
+                                            <button onclick={delete} class={classes!(item_class, "text-red-500")}>{t("message-delete-for-everyone")}</button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                {self.view_mod_actions(ctx, message.sender_id(), item_class)}
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        }
+    }
+
+    fn view_reactions(&self, ctx: &Context<Self>, message: &MessageData) -> Html {
+        let id = match &message.id {
+            Some(id) if !message.reactions.is_empty() => id,
+            _ => return html! {},
+        };
+        html! {
+            <div class="flex flex-wrap mt-2">
+                {
+                    message.reactions.iter().map(|(emoji, users)| {
+                        let mine = users.iter().any(|u| self.is_me(u));
+                        let names = users
+                            .iter()
+                            .map(|u| self.profile(u).map(|p| p.name.as_str()).unwrap_or(u))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let label = format!("{} {}", emoji, users.len());
+                        let react = {
+                            let (id, emoji) = (id.clone(), emoji.clone());
+                            ctx.link().callback(move |_| Msg::React(id.clone(), emoji.clone()))
+                        };
+                        html! {
+                            <button
+                                onclick={react}
+                                title={names}
+                                class={classes!("mr-1", "mb-1", "px-2", "rounded-full", "text-sm", "border",
+                                    if mine { "border-green-600 bg-green-100 dark:bg-green-900" } else { "border-gray-300 dark:border-gray-600" })}
+                            >
+                                {label}
+                            </button>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view_room_info(&self, ctx: &Context<Self>) -> Html {
+        let stats = RoomStats::collect(self.messages.iter().map(|m| &m.reactions), ROOM_STATS_LEN);
+        let top_messages = stats
+            .top_messages
+            .iter()
+            .map(|&(index, reactions)| TopMessage {
+                index,
+                from: self.messages[index].from.clone(),
+                preview: self.messages[index].preview(),
+                reactions,
+            })
+            .collect::<Vec<_>>();
+        html! {
+            <RoomInfoPanel
+                room={CHANNEL}
+                topic={self.topic.clone()}
+                member_count={self.membership.listed(CHANNEL).map(<[_]>::len)}
+                top_reactions={stats.top_reactions}
+                {top_messages}
+                on_jump={ctx.link().callback(Msg::JumpToMessage)}
+                on_close={ctx.link().callback(|_| Msg::ToggleRoomInfo)}
+            />
+        }
+    }
+
+    /// Starts unfurling the first link in `message` unless it is already
+    /// known. Media links are shown inline instead.
+    fn request_link_preview(&mut self, ctx: &Context<Self>, message: &str) {
+        if !link_preview::is_enabled() {
+            return;
+        }
+        let url = match link_preview::first_url(message) {
+            Some(url) if sanitize::media_kind(&url).is_none() && !self.link_previews.contains_key(&url) => url,
+            _ => return,
+        };
+        self.link_previews.insert(url.clone(), None);
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            let preview = link_preview::fetch(&url).await;
+            link.send_message(Msg::LinkPreviewLoaded(url, preview));
+        });
+    }
+
+    fn view_link_preview(&self, ctx: &Context<Self>, idx: usize, message: &str, options: &RenderOptions) -> Html {
+        if options.plaintext {
+            return html! {};
+        }
+        let preview = link_preview::first_url(message)
+            .and_then(|url| self.link_previews.get(&url))
+            .and_then(Option::as_ref)
+            .filter(|preview| !preview.is_empty());
+        match preview {
+            Some(preview) => message_content::render_link_preview(
+                preview,
+                options,
+                self.collapsed_previews.contains(&idx),
+                ctx.link().callback(move |_| Msg::TogglePreview(idx)),
+            ),
+            None => html! {},
+        }
+    }
+
+    /// Opens the message a permalink asked for once it is loaded, asking for
+    /// older history meanwhile. Gives up when the history runs out.
+    fn seek_permalink(&mut self, ctx: &Context<Self>) -> bool {
+        let id = match &self.permalink {
+            Some(id) => id.clone(),
+            None => return false,
+        };
+        if let Some(idx) = self.messages.iter().position(|m| m.id.as_ref() == Some(&id)) {
+            self.permalink = None;
+            if self.direct_peer.is_some() {
+                self.update(ctx, Msg::SetDirectPeer(None));
+            }
+            let link = ctx.link().clone();
+            let timer = Timeout::new(HIGHLIGHT_DURATION, move || link.send_message(Msg::ClearHighlight));
+            self.highlighted = Some((id, timer));
+            self.update(ctx, Msg::JumpToMessage(idx))
+        } else if self.history_exhausted {
+            self.permalink = None;
+            self.notices.push(t("permalink-missing"));
+            true
+        } else {
+            if !self.loading_history {
+                self.request_history();
+            }
+            false
+        }
+    }
+
+    fn request_history(&mut self) {
+        // Direct messages, notes, the welcome and copies still on their way
+        // are not part of the room's history on the server.
+        let room = self
+            .messages
+            .iter()
+            .filter(|m| m.direct.is_none() && m.delivery.is_none() && m.welcome.is_none());
+        let request = HistoryRequest {
+            before: room.clone().find_map(|m| m.id.clone()),
+            offset: room.count(),
+            limit: HISTORY_PAGE,
+        };
+        self.send(&WebSocketMessage {
+            message_type: MsgTypes::History,
+            data: Some(serde_json::to_string(&request).unwrap()),
+            data_array: None,
+        });
+        self.loading_history = true;
+    }
+
+    /// Inserts a page of older messages above the current ones, shifting every
+    /// index-based piece of state and keeping the viewport where it was.
+    fn prepend_history(&mut self, mut older: Vec<MessageData>) {
+        self.loading_history = false;
+        self.history_exhausted = older.len() < HISTORY_PAGE;
+        older.retain(|m| !self.holds(m));
+        let count = older.len();
+        if count == 0 {
+            return;
+        }
+
+        self.messages.splice(0..0, older);
+        self.revealed = mem::take(&mut self.revealed)
+            .into_iter()
+            .map(|idx| idx + count)
+            .collect();
+        self.collapsed_previews = mem::take(&mut self.collapsed_previews)
+            .into_iter()
+            .map(|idx| idx + count)
+            .collect();
+        self.selected = mem::take(&mut self.selected)
+            .into_iter()
+            .map(|idx| idx + count)
+            .collect();
+        self.select_anchor = self.select_anchor.map(|idx| idx + count);
+        self.focused_message = self.focused_message.map(|idx| idx + count);
+        self.first_unread = self.first_unread.map(|idx| idx + count);
+        self.editing = self.editing.map(|idx| idx + count);
+        self.forwarding = self.forwarding.map(|idx| idx + count);
+        self.open_menu = self.open_menu.map(|idx| idx + count);
+        self.jump_to = self.jump_to.map(|idx| idx + count);
+        if self.window_start > 0 {
+            self.window_start += count;
+        }
+        if let Some(pane) = self.message_pane.cast::<Element>() {
+            self.anchor_from_bottom = Some(pane.scroll_height() - pane.scroll_top());
+        }
+    }
+
+    /// Empties the message view for `/clear`. History already seen is not
+    /// fetched again until the page is reloaded.
+    fn clear_messages(&mut self) {
+        self.messages.clear();
+        self.editing = None;
+        self.revealed.clear();
+        self.collapsed_previews.clear();
+        self.selected.clear();
+        self.select_anchor = None;
+        self.focused_message = None;
+        self.reaction_pick = None;
+        self.open_menu = None;
+        self.forwarding = None;
+        self.jump_to = None;
+        self.permalink = None;
+        self.highlighted = None;
+        self.first_unread = None;
+        self.new_below = 0;
+        self.window_start = 0;
+        self.history_exhausted = true;
+    }
+
+    /// Shrinks the render window back to the most recent messages. Only valid
+    /// while the pane is scrolled to the bottom.
+    fn trim_window(&mut self) -> bool {
+        let start = self.messages.len().saturating_sub(RENDER_WINDOW);
+        if start > self.window_start {
+            self.window_start = start;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Leaves the chat for the login screen, forgetting everything tied to
+    /// this user. Messages still in their undo-send delay go out first.
+    fn sign_out(&mut self, ctx: &Context<Self>) {
+        for (peer, text, quote, _) in mem::take(&mut self.outbox).into_values() {
+            self.dispatch(ctx, peer, text, quote, None);
+        }
+        self.wss.close();
+        self.drafts.clear_all();
+        self.scheduler.clear_all();
+        session::clear_session_id();
+        auth::clear();
+        if let Some((user, _)) = ctx.link().context::<User>(Callback::noop()) {
+            user.username.borrow_mut().clear();
+        }
+        if let Some(history) = ctx.link().history() {
+            history.push(Route::Login);
+        }
+    }
+
+    /// Tells the room how far we have read, if the newest message is in view
+    /// of someone actually looking at the page.
+    fn mark_read(&mut self) {
+        if document_hidden() || !document_has_focus() || self.scrolled_up() {
+            return;
+        }
+        let newest = self
+            .messages
+            .iter()
+            .rev()
+            .filter(|m| m.direct.is_none() && m.welcome.is_none())
+            .find_map(|m| m.id.clone());
+        let message_id = match newest {
+            Some(id) if self.read_sent.as_ref() != Some(&id) => id,
+            _ => return,
+        };
+        self.read_sent = Some(message_id.clone());
+        let receipt = ReadReceipt {
+            room: CHANNEL.to_string(),
+            message_id,
+            user_id: String::new(),
+        };
+        self.send(&WebSocketMessage {
+            message_type: MsgTypes::Read,
+            data: Some(serde_json::to_string(&receipt).unwrap()),
+            data_array: None,
+        });
+    }
+
+    /// Who has seen our room messages, shown on the newest one each reader
+    /// got to, by message index.
+    fn seen_by(&self) -> HashMap<usize, Vec<&str>> {
+        let mut seen: HashMap<usize, Vec<&str>> = HashMap::new();
+        for (user, message_id) in &self.read_marks {
+            let read = match self.messages.iter().position(|m| m.id.as_ref() == Some(message_id)) {
+                Some(read) => read,
+                None => continue,
+            };
+            let ours = (0..=read).rev().find(|&idx| {
+                let m = &self.messages[idx];
+                self.is_me(m.sender_id()) && m.direct.is_none() && !m.deleted
+            });
+            if let Some(ours) = ours {
+                seen.entry(ours).or_default().push(user);
+            }
+        }
+        seen
+    }
+
+    /// The status glyph under our own messages: sending, failed with a retry,
+    /// who saw it, or else a tick for having reached the server.
+    fn view_delivery(&self, ctx: &Context<Self>, message: &MessageData, readers: Option<&Vec<&str>>) -> Html {
+        let client_id = message.client_id.clone().unwrap_or_default();
+        let readers = match (message.delivery, readers) {
+            (Some(Delivery::Sending), _) => {
+                return html! {
+                    <div class="flex justify-end mt-1 text-xs text-gray-500 dark:text-gray-400" title={t("delivery-sending")}>{"🕓"}</div>
+                };
+            }
+            (Some(_), _) => {
+                let discard = client_id.clone();
+                return html! {
+                    <div class="flex justify-end items-center mt-1 text-xs text-red-500">
+                        <button
+                            onclick={ctx.link().callback(move |_| Msg::RetrySend(client_id.clone()))}
+                            class="hover:underline"
+                        >
+                            {"⚠ "}{t("delivery-retry")}
+                        </button>
+                        <button
+                            onclick={ctx.link().callback(move |_| Msg::DiscardFailed(discard.clone()))}
+                            title={t("delivery-discard")}
+                            class="ml-2 text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white"
+                            aria-label={t("delivery-discard")}
+                        >
+                            {"✕"}
                         </button>
                     </div>
-                </div>
+                };
+            }
+            (None, Some(readers)) => readers,
+            (None, None) if self.is_me(message.sender_id()) => {
+                return html! {
+                    <div class="flex justify-end mt-1 text-xs text-gray-500 dark:text-gray-400" title={t("delivery-sent")}>{"✓"}</div>
+                };
+            }
+            (None, None) => return html! {},
+        };
+        let names = readers
+            .iter()
+            .map(|&id| self.profile(id).map(|p| p.name.as_str()).unwrap_or(id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        html! {
+            <div class="flex justify-end items-center mt-1" title={t_args("seen-by", &[("names", &names)])}>
+                <span class="mr-1 text-xs text-green-600 dark:text-green-400">{"✓✓"}</span>
+                {
+                    readers.iter().map(|&id| {
+                        let avatar = self
+                            .users
+                            .iter()
+                            .find(|u| u.id == id)
+                            .map(|u| u.avatar.clone())
+                            .unwrap_or_else(|| avatar::url(id));
+                        html! {<img class="w-4 h-4 rounded-full -ml-1 ring-1 ring-white dark:ring-gray-700" src={avatar} alt=""/>}
+                    }).collect::<Html>()
+                }
             </div>
-        </div>
+        }
+    }
+
+    fn scrolled_up(&self) -> bool {
+        self.message_pane
+            .cast::<Element>()
+            .map(|pane| pane.scroll_top() + pane.client_height() < pane.scroll_height() - 20)
+            .unwrap_or(false)
+    }
+
+    fn send_typing(&self, typing: Option<bool>) {
+        if let Some(typing) = typing {
+            self.send(&WebSocketMessage {
+                message_type: MsgTypes::Typing,
+                data: Some(serde_json::to_string(&TypingUpdate { room: CHANNEL, typing }).unwrap()),
+                data_array: None,
+            });
+        }
+    }
+
+    fn view_typing(&self) -> Html {
+        let mut names: Vec<&str> = self.typing.values().map(|(name, _)| name.as_str()).collect();
+        names.sort_unstable();
+        let text = match typing::summary(&names) {
+            Some(text) => text,
+            None => return html! {},
+        };
+        html! {
+            <div class="px-6 py-1 text-xs italic text-gray-500 dark:text-gray-400">{text}</div>
+        }
+    }
+
+    /// Sends a message that left the outbox, showing our copy of it right
+    /// away rather than once the server echoes it. Returns whether it went
+    /// out; a direct message stays unsent while we lack the peer's key.
+    fn dispatch(
+        &mut self,
+        ctx: &Context<Self>,
+        peer: Option<String>,
+        mut text: String,
+        mut quote: Option<Quote>,
+        mut forwarded_from: Option<String>,
+    ) -> bool {
+        // Direct messages are sealed as text alone, so a quote or forward
+        // travels in it as a plain line.
+        if peer.is_some() {
+            if let Some(quote) = quote.take() {
+                text = format!("> {}: {}\n{}", quote.from, quote.excerpt, text);
+            }
+            if let Some(from) = forwarded_from.take() {
+                text = format!("↪ {}\n{}", t_args("message-forwarded-from", &[("name", &from)]), text);
+                truncate_text(&mut text);
+            }
+        }
+        if let Some(peer) = &peer {
+            if peer == &self.user_id {
+                self.save_note(text);
+                return true;
+            }
+            // Never fall back to plaintext: without a key the message stays unsent.
+            if !self.peer_keys.contains_key(peer) {
+                self.notices.push(CryptoError::BadKey.message());
+                return false;
+            }
+        }
+        let expires_in = self
+            .settings
+            .disappearing
+            .get(peer.as_deref().unwrap_or(CHANNEL))
+            .copied();
+        self.messages.push(MessageData {
+            id: None,
+            from_id: Some(self.user_id.clone()),
+            from: self.username.clone(),
+            message: text,
+            attachment: None,
+            reactions: Reactions::new(),
+            timestamp: Some(js_sys::Date::now()),
+            bot: false,
+            actions: Vec::new(),
+            client_id: Some(session::uuid()),
+            edited: false,
+            quote,
+            forwarded_from,
+            poll: None,
+            expires_in,
+            deleted: false,
+            renamed_from: None,
+            welcome: None,
+            direct: peer,
+            delivery: None,
+            truncated: false,
+        });
+        self.scroll_to_bottom = true;
+        self.trim_window();
+        self.transmit(ctx, self.messages.len() - 1);
+        true
+    }
+
+    /// Sends, or sends again, our local copy at `idx`. Room messages stay
+    /// sending until their broadcast comes back; the server only relays
+    /// direct messages to the recipient, so those are done once sent.
+    fn transmit(&mut self, ctx: &Context<Self>, idx: usize) {
+        let message = &self.messages[idx];
+        let client_id = message.client_id.clone().unwrap_or_default();
+        let sent = match message.direct.clone() {
+            None => self.send(&WebSocketMessage {
+                message_type: MsgTypes::Message,
+                data: Some(
+                    serde_json::to_string(&OutgoingMessage {
+                        client_id: &client_id,
+                        message: &message.message,
+                        quote: message.quote.as_ref(),
+                        forwarded_from: message.forwarded_from.as_deref(),
+                        expires_in: message.expires_in,
+                    })
+                    .unwrap(),
+                ),
+                data_array: None,
+            }),
+            Some(peer) => {
+                let sealed = self
+                    .peer_keys
+                    .get(&peer)
+                    .ok_or(CryptoError::BadKey)
+                    .and_then(|key| self.keys.seal(key, &self.user_id, &peer, &message.message));
+                match sealed {
+                    Ok(sealed) => self.send(&WebSocketMessage {
+                        message_type: MsgTypes::DirectMessage,
+                        data: Some(
+                            serde_json::to_string(&DirectMessage {
+                                peer,
+                                name: self.username.clone(),
+                                expires_in: message.expires_in,
+                                sealed_for: Some(self.keys.public_key()),
+                                sealed,
+                            })
+                            .unwrap(),
+                        ),
+                        data_array: None,
+                    }),
+                    Err(e) => {
+                        self.notices.push(e.message());
+                        false
+                    }
+                }
+            }
+        };
+        let delivery = match (sent, self.messages[idx].direct.is_some()) {
+            (false, _) => Some(Delivery::Failed),
+            (true, true) => None,
+            (true, false) => {
+                let link = ctx.link().clone();
+                let expired = client_id.clone();
+                let deadline = Timeout::new(DELIVERY_TIMEOUT, move || link.send_message(Msg::DeliveryTimedOut(expired)));
+                self.delivery_deadlines.insert(client_id, deadline);
+                Some(Delivery::Sending)
+            }
+        };
+        self.messages[idx].delivery = delivery;
+    }
+
+    /// Whether we already have a message, by server or client id.
+    fn holds(&self, message: &MessageData) -> bool {
+        self.messages.iter().any(|m| {
+            (message.id.is_some() && m.id == message.id)
+                || (message.client_id.is_some() && m.client_id == message.client_id)
+        })
     }
-}
 
+    /// Our not yet delivered copy of a message, by client id.
+    fn local_copy(&self, client_id: &str) -> Option<usize> {
+        self.messages
+            .iter()
+            .rposition(|m| m.delivery.is_some() && m.client_id.as_deref() == Some(client_id))
+    }
+
+    fn save_note(&mut self, text: String) {
+        let note = SavedNote {
+            id: session::random_id(),
+            message: text,
+            timestamp: js_sys::Date::now(),
+        };
+        self.send(&WebSocketMessage {
+            message_type: MsgTypes::SavedMessage,
+            data: Some(serde_json::to_string(&note).unwrap()),
+            data_array: None,
+        });
+        self.messages.push(MessageData {
+            id: Some(note.id),
+            from_id: Some(self.user_id.clone()),
+            from: self.username.clone(),
+            message: note.message,
+            attachment: None,
+            reactions: Reactions::new(),
+            timestamp: Some(note.timestamp),
+            bot: false,
+            actions: Vec::new(),
+            client_id: None,
+            edited: false,
+            quote: None,
+            forwarded_from: None,
+            poll: None,
+            expires_in: None,
+            deleted: false,
+            renamed_from: None,
+            welcome: None,
+            direct: Some(self.user_id.clone()),
+            delivery: None,
+            truncated: false,
+        });
+        self.scroll_to_bottom = true;
+    }
+
+    fn announce_key(&self, peer: Option<&str>) {
+        let announcement = KeyAnnouncement {
+            peer,
+            key: &self.keys.public_key(),
+        };
+        self.send(&WebSocketMessage {
+            message_type: MsgTypes::PublicKey,
+            data: Some(serde_json::to_string(&announcement).unwrap()),
+            data_array: None,
+        });
+    }
+
+    fn send_signal(&self, signal: Signal) {
+        let (message_type, data) = match signal {
+            Signal::Offer(offer) => (MsgTypes::CallOffer, serde_json::to_string(&offer)),
+            Signal::Answer(answer) => (MsgTypes::CallAnswer, serde_json::to_string(&answer)),
+            Signal::Candidate(candidate) => (MsgTypes::CallCandidate, serde_json::to_string(&candidate)),
+            Signal::End(end) => (MsgTypes::CallEnd, serde_json::to_string(&end)),
+        };
+        self.send(&WebSocketMessage {
+            message_type,
+            data: Some(data.unwrap()),
+            data_array: None,
+        });
+    }
+
+    /// Queues a message on the socket, returning whether it was taken.
+    fn send(&self, message: &WebSocketMessage) -> bool {
+        match self
+            .wss
+            .tx
+            .clone()
+            .try_send(serde_json::to_string(message).unwrap())
+        {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("error sending to channel: {:?}", e);
+                false
+            }
+        }
+    }
 
+    fn view_empty_state(&self, ctx: &Context<Self>) -> Html {
+        let invite_link = web_sys::window()
+            .and_then(|w| w.location().origin().ok())
+            .unwrap_or_default();
+        let set_topic = ctx.link().callback(|_| Msg::SetTopic);
 
+        html! {
+            <div class="flex flex-col items-center justify-center h-full text-center text-gray-600 dark:text-gray-300">
+                <div class="text-2xl mb-2">{t("empty-title")}</div>
+                <div class="text-sm text-gray-500 dark:text-gray-400 mb-6">{t("empty-subtitle")}</div>
+                <div class="w-full max-w-md space-y-3">
+                    <div class="bg-white dark:bg-gray-800 rounded-lg p-3 text-left">
+                        <div class="text-sm font-bold mb-1">{t("empty-invite-title")}</div>
+                        <div class="text-xs text-gray-500 dark:text-gray-400">{t("empty-invite-body")}</div>
+                        <div class="text-xs text-green-400 break-all mt-1">{invite_link}</div>
+                    </div>
+                    <button onclick={set_topic} class="w-full bg-white dark:bg-gray-800 rounded-lg p-3 text-left hover:bg-gray-200 dark:hover:bg-gray-700">
+                        <div class="text-sm font-bold">{t("empty-topic-title")}</div>
+                        <div class="text-xs text-gray-500 dark:text-gray-400">{t("empty-topic-body")}</div>
+                    </button>
+                    <div class="bg-white dark:bg-gray-800 rounded-lg p-3 text-left">
+                        <div class="text-sm font-bold mb-2">{t("empty-welcome-title")}</div>
+                        {
+                            WELCOME_TEMPLATES.iter().map(|id| {
+                                let template: &'static str = id;
+                                let onclick = ctx.link().callback(move |_| Msg::UseTemplate(template));
+                                html!{
+                                    <button {onclick} class="block w-full text-left text-xs text-gray-600 dark:text-gray-300 bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 rounded px-2 py-1 mb-1">{t(template)}</button>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    
-}
\ No newline at end of file
+    #[test]
+    fn nothing_picked_in_a_direct_message_reaches_the_room() {
+        for sealable in [true, false] {
+            assert_ne!(outgoing(Some("bob-id"), sealable), Outgoing::Room);
+        }
+        assert_eq!(outgoing(Some("bob-id"), true), Outgoing::Direct("bob-id".to_string()));
+        assert_eq!(outgoing(Some("bob-id"), false), Outgoing::Refused);
+        assert_eq!(outgoing(None, true), Outgoing::Room);
+        assert_eq!(outgoing(None, false), Outgoing::Room);
+    }
+}
@@ -0,0 +1,665 @@
+use gloo_timers::callback::Timeout;
+use wasm_bindgen::JsCast;
+use web_sys::{DataTransfer, File, HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+use crate::components::message_content::{self, RenderOptions};
+use crate::services::emoji;
+use crate::services::i18n::t;
+use crate::services::transcript;
+
+/// Maximum height of the composer in pixels before it starts scrolling.
+const MAX_HEIGHT: i32 = 160;
+const EMOJI_SUGGESTIONS: usize = 8;
+/// Share of the length limit from which the counter shows, and from which it
+/// turns red.
+const COUNTER_FROM: f64 = 0.8;
+const COUNTER_WARN_FROM: f64 = 0.95;
+/// How long the send button is held to schedule instead, in milliseconds.
+const LONG_PRESS: u32 = 500;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub input_ref: NodeRef,
+    pub on_submit: Callback<()>,
+    #[prop_or_default]
+    pub on_input: Callback<String>,
+    /// Offers attaching a file when set.
+    #[prop_or_default]
+    pub on_attach: Option<Callback<File>>,
+    /// Receives images pasted from the clipboard, which should be confirmed
+    /// before sending.
+    #[prop_or_default]
+    pub on_paste_file: Callback<File>,
+    /// Names offered for the `@mention` being typed, best match first.
+    #[prop_or_default]
+    pub mention_suggestions: Vec<String>,
+    /// Receives the text after `@` while a mention is being typed, and `None`
+    /// once it is finished or abandoned.
+    #[prop_or_default]
+    pub on_mention_query: Callback<Option<String>>,
+    /// Up arrow in the empty input, to edit the last message sent.
+    #[prop_or_default]
+    pub on_edit_last: Callback<()>,
+    /// Starts taller with bigger text, for focus mode.
+    #[prop_or_default]
+    pub large: bool,
+    /// Shown instead of the send button while sending is flood limited.
+    #[prop_or_default]
+    pub cooldown: Option<String>,
+    /// Longest text that can be sent, in characters; longer text can still
+    /// be typed but not sent.
+    #[prop_or_default]
+    pub max_len: Option<usize>,
+    /// Asks when to send instead of sending now, offered by holding the send
+    /// button or with a button of its own.
+    #[prop_or_default]
+    pub on_schedule: Option<Callback<()>>,
+}
+
+fn fit_to_content(textarea: &HtmlTextAreaElement) {
+    let style = textarea.style();
+    let _ = style.set_property("height", "auto");
+    let height = textarea.scroll_height().min(MAX_HEIGHT);
+    let _ = style.set_property("height", &format!("{}px", height));
+}
+
+/// Clears the composer and shrinks it back to a single line.
+pub fn reset(textarea: &HtmlTextAreaElement) {
+    textarea.set_value("");
+    let _ = textarea.style().remove_property("height");
+}
+
+/// Replaces the composer text, e.g. when restoring a draft.
+pub fn set_text(textarea: &HtmlTextAreaElement, text: &str) {
+    textarea.set_value(text);
+    fit_to_content(textarea);
+}
+
+/// The word starting with `sigil` being typed at the caret, as the UTF-16
+/// offset of the sigil (matching the DOM's selection offsets) and the text
+/// typed after it.
+fn token_at_caret(textarea: &HtmlTextAreaElement, sigil: char) -> Option<(usize, String)> {
+    let caret = textarea.selection_start().ok()?? as usize;
+    let units: Vec<u16> = textarea.value().encode_utf16().collect();
+    let before = String::from_utf16_lossy(&units[..caret.min(units.len())]);
+    let at = before.rfind(sigil)?;
+    let query = &before[at + 1..];
+    let starts_word = before[..at].chars().next_back().is_none_or(char::is_whitespace);
+    if !starts_word || query.chars().any(char::is_whitespace) {
+        return None;
+    }
+    Some((before[..at].encode_utf16().count(), query.to_string()))
+}
+
+/// The `@mention` being typed at the caret.
+fn mention_at_caret(textarea: &HtmlTextAreaElement) -> Option<(usize, String)> {
+    token_at_caret(textarea, '@')
+}
+
+/// The `:shortcode` being typed at the caret. It takes two characters to
+/// count, so a lone `:` or a smiley like `:)` stays quiet.
+fn shortcode_at_caret(textarea: &HtmlTextAreaElement) -> Option<(usize, String)> {
+    token_at_caret(textarea, ':').filter(|(_, query)| {
+        query.chars().count() >= 2
+            && query.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-'))
+    })
+}
+
+/// Replaces the mention being typed with `@name ` and returns the new text.
+fn complete_mention(textarea: &HtmlTextAreaElement, name: &str) -> Option<String> {
+    complete_token(textarea, '@', &format!("@{} ", name))
+}
+
+/// Replaces the word starting with `sigil` at the caret with `replacement`
+/// and returns the new text.
+fn complete_token(textarea: &HtmlTextAreaElement, sigil: char, replacement: &str) -> Option<String> {
+    let (at, query) = token_at_caret(textarea, sigil)?;
+    let units: Vec<u16> = textarea.value().encode_utf16().collect();
+    let end = at + 1 + query.encode_utf16().count();
+
+    let mut text = String::from_utf16_lossy(&units[..at]);
+    text.push_str(replacement);
+    let caret = text.encode_utf16().count() as u32;
+    text.push_str(&String::from_utf16_lossy(&units[end..]));
+
+    set_text(textarea, &text);
+    let _ = textarea.set_selection_range(caret, caret);
+    Some(text)
+}
+
+/// Replaces the selection with `text`, leaving the caret after it, and
+/// returns the new text.
+fn insert_text(textarea: &HtmlTextAreaElement, text: &str) -> String {
+    let units: Vec<u16> = textarea.value().encode_utf16().collect();
+    let end = (textarea.selection_end().ok().flatten().unwrap_or(units.len() as u32) as usize).min(units.len());
+    let start = (textarea.selection_start().ok().flatten().unwrap_or(end as u32) as usize).min(end);
+
+    let mut value = String::from_utf16_lossy(&units[..start]);
+    value.push_str(text);
+    let caret = value.encode_utf16().count() as u32;
+    value.push_str(&String::from_utf16_lossy(&units[end..]));
+
+    set_text(textarea, &value);
+    let _ = textarea.set_selection_range(caret, caret);
+    value
+}
+
+/// A pasted transcript awaiting the choice between quoting it and pasting it
+/// as is.
+#[derive(Clone, PartialEq)]
+struct TranscriptPaste {
+    raw: String,
+    quote: String,
+}
+
+#[function_component(Composer)]
+pub fn composer(props: &Props) -> Html {
+    let selected = use_state(|| 0usize);
+    let transcript_paste = use_state(|| None::<TranscriptPaste>);
+    let length = use_state(|| 0usize);
+    let show_emoji = use_state(|| false);
+    // The `:shortcode` being typed, and the highlighted suggestion for it.
+    let emoji_query = use_state(|| None::<String>);
+    let emoji_selected = use_state(|| 0usize);
+    let emoji_suggestions = emoji_query
+        .as_deref()
+        .map(|query| emoji::search(query, EMOJI_SUGGESTIONS))
+        .unwrap_or_default();
+    // The text also changes from outside, e.g. drafts, edits and sending, so
+    // the count is taken again after every render, and a shortcode that is
+    // gone stops being completed.
+    {
+        let input_ref = props.input_ref.clone();
+        let length = length.clone();
+        let emoji_query = emoji_query.clone();
+        use_effect(move || {
+            if let Some(textarea) = input_ref.cast::<HtmlTextAreaElement>() {
+                let count = textarea.value().chars().count();
+                if *length != count {
+                    length.set(count);
+                }
+                if emoji_query.is_some() && shortcode_at_caret(&textarea).is_none() {
+                    emoji_query.set(None);
+                }
+            }
+            || ()
+        });
+    }
+    let too_long = props.max_len.is_some_and(|max| *length > max);
+    {
+        let selected = selected.clone();
+        use_effect_with_deps(
+            move |_| {
+                selected.set(0);
+                || ()
+            },
+            props.mention_suggestions.clone(),
+        );
+    }
+
+    let oninput = {
+        let on_input = props.on_input.clone();
+        let on_mention_query = props.on_mention_query.clone();
+        let length = length.clone();
+        let (emoji_query, emoji_selected) = (emoji_query.clone(), emoji_selected.clone());
+        Callback::from(move |e: InputEvent| {
+            let textarea: HtmlTextAreaElement = e.target_unchecked_into();
+            fit_to_content(&textarea);
+            length.set(textarea.value().chars().count());
+            on_input.emit(textarea.value());
+            on_mention_query.emit(mention_at_caret(&textarea).map(|(_, query)| query));
+            emoji_query.set(shortcode_at_caret(&textarea).map(|(_, query)| query));
+            emoji_selected.set(0);
+        })
+    };
+
+    let choose_emoji = {
+        let input_ref = props.input_ref.clone();
+        let on_input = props.on_input.clone();
+        let emoji_query = emoji_query.clone();
+        Callback::from(move |emoji: &'static str| {
+            if let Some(textarea) = input_ref.cast::<HtmlTextAreaElement>() {
+                if let Some(text) = complete_token(&textarea, ':', emoji) {
+                    on_input.emit(text);
+                }
+            }
+            emoji_query.set(None);
+        })
+    };
+
+    let choose = {
+        let input_ref = props.input_ref.clone();
+        let on_input = props.on_input.clone();
+        let on_mention_query = props.on_mention_query.clone();
+        Callback::from(move |name: String| {
+            if let Some(textarea) = input_ref.cast::<HtmlTextAreaElement>() {
+                if let Some(text) = complete_mention(&textarea, &name) {
+                    on_input.emit(text);
+                }
+            }
+            on_mention_query.emit(None);
+        })
+    };
+
+    // Enter sends; Shift+Enter inserts a newline. While mention or emoji
+    // suggestions are open, the arrow keys, Tab and Enter drive the list
+    // instead.
+    let onkeydown = {
+        let on_submit = props.on_submit.clone();
+        let on_edit_last = props.on_edit_last.clone();
+        let on_mention_query = props.on_mention_query.clone();
+        let suggestions = props.mention_suggestions.clone();
+        let selected = selected.clone();
+        let choose = choose.clone();
+        let transcript_paste = transcript_paste.clone();
+        let show_emoji = show_emoji.clone();
+        let emoji_suggestions = emoji_suggestions.clone();
+        let (emoji_query, emoji_selected) = (emoji_query.clone(), emoji_selected.clone());
+        let choose_emoji = choose_emoji.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.is_composing() {
+                return;
+            }
+            if *show_emoji && e.key() == "Escape" {
+                e.prevent_default();
+                show_emoji.set(false);
+                return;
+            }
+            if transcript_paste.is_some() && e.key() == "Escape" {
+                e.prevent_default();
+                transcript_paste.set(None);
+                return;
+            }
+            if !emoji_suggestions.is_empty() {
+                let count = emoji_suggestions.len();
+                let handled = match e.key().as_str() {
+                    "ArrowDown" => {
+                        emoji_selected.set((*emoji_selected + 1) % count);
+                        true
+                    }
+                    "ArrowUp" => {
+                        emoji_selected.set((*emoji_selected + count - 1) % count);
+                        true
+                    }
+                    "Enter" | "Tab" if !e.shift_key() => {
+                        choose_emoji.emit(emoji_suggestions[(*emoji_selected).min(count - 1)].1);
+                        true
+                    }
+                    "Escape" => {
+                        emoji_query.set(None);
+                        true
+                    }
+                    _ => false,
+                };
+                if handled {
+                    e.prevent_default();
+                    return;
+                }
+            }
+            if !suggestions.is_empty() {
+                let handled = match e.key().as_str() {
+                    "ArrowDown" => {
+                        selected.set((*selected + 1) % suggestions.len());
+                        true
+                    }
+                    "ArrowUp" => {
+                        selected.set((*selected + suggestions.len() - 1) % suggestions.len());
+                        true
+                    }
+                    "Enter" | "Tab" if !e.shift_key() => {
+                        choose.emit(suggestions[(*selected).min(suggestions.len() - 1)].clone());
+                        true
+                    }
+                    "Escape" => {
+                        on_mention_query.emit(None);
+                        true
+                    }
+                    _ => false,
+                };
+                if handled {
+                    e.prevent_default();
+                    return;
+                }
+            }
+            if e.key() == "Enter" && !e.shift_key() {
+                e.prevent_default();
+                if !too_long {
+                    on_submit.emit(());
+                }
+            }
+            let plain = !(e.alt_key() || e.ctrl_key() || e.meta_key() || e.shift_key());
+            if e.key() == "ArrowUp" && plain {
+                let textarea: HtmlTextAreaElement = e.target_unchecked_into();
+                if textarea.value().is_empty() {
+                    e.prevent_default();
+                    on_edit_last.emit(());
+                }
+            }
+        })
+    };
+
+    // Screenshots and copied images arrive as files, and chat transcripts are
+    // offered as a quote; other text pastes as usual. `ClipboardEvent` is
+    // still behind web-sys' unstable APIs, so its `clipboardData` is read
+    // reflectively.
+    let onpaste = {
+        let on_paste_file = props.on_paste_file.clone();
+        let transcript_paste = transcript_paste.clone();
+        Callback::from(move |e: Event| {
+            let data = match js_sys::Reflect::get(&e, &"clipboardData".into())
+                .ok()
+                .and_then(|data| data.dyn_into::<DataTransfer>().ok())
+            {
+                Some(data) => data,
+                None => return,
+            };
+            let file = data
+                .files()
+                .and_then(|files| files.get(0))
+                .filter(|file| file.type_().starts_with("image/"));
+            if let Some(file) = file {
+                e.prevent_default();
+                on_paste_file.emit(file);
+                return;
+            }
+            let text = data.get_data("text/plain").unwrap_or_default();
+            if transcript::looks_like_transcript(&text) {
+                e.prevent_default();
+                transcript_paste.set(Some(TranscriptPaste {
+                    quote: transcript::to_quote(&text),
+                    raw: text,
+                }));
+            }
+        })
+    };
+
+    let paste_choice = |quoted: bool| {
+        let input_ref = props.input_ref.clone();
+        let on_input = props.on_input.clone();
+        let transcript_paste = transcript_paste.clone();
+        Callback::from(move |e: MouseEvent| {
+            // Keep focus and the selection in the textarea.
+            e.prevent_default();
+            if let (Some(textarea), Some(paste)) = (input_ref.cast::<HtmlTextAreaElement>(), &*transcript_paste) {
+                let text = if quoted { &paste.quote } else { &paste.raw };
+                on_input.emit(insert_text(&textarea, text));
+            }
+            transcript_paste.set(None);
+        })
+    };
+
+    let toggle_emoji = {
+        let show_emoji = show_emoji.clone();
+        Callback::from(move |_| show_emoji.set(!*show_emoji))
+    };
+    let emoji_grid = if *show_emoji {
+        let mut offered: Vec<&str> = Vec::new();
+        let buttons = emoji::SHORTCODES
+            .iter()
+            // Aliases such as `+1` and `thumbsup` show once.
+            .filter(|(_, emoji)| {
+                let new = !offered.contains(emoji);
+                offered.push(emoji);
+                new
+            })
+            .map(|&(code, emoji)| {
+                let onmousedown = {
+                    let input_ref = props.input_ref.clone();
+                    let on_input = props.on_input.clone();
+                    let show_emoji = show_emoji.clone();
+                    Callback::from(move |e: MouseEvent| {
+                        // Keep focus and the selection in the textarea.
+                        e.prevent_default();
+                        if let Some(textarea) = input_ref.cast::<HtmlTextAreaElement>() {
+                            on_input.emit(insert_text(&textarea, emoji));
+                        }
+                        show_emoji.set(false);
+                    })
+                };
+                let code = format!(":{}:", code);
+                html! {
+                    <button {onmousedown} title={code} class="w-8 h-8 text-lg rounded hover:bg-gray-200 dark:hover:bg-gray-700">
+                        {emoji}
+                    </button>
+                }
+            })
+            .collect::<Html>();
+        html! {
+            <div class="absolute bottom-full left-0 mb-2 w-72 max-h-56 overflow-y-auto bg-white dark:bg-gray-800 rounded shadow-lg p-2 grid grid-cols-8 gap-1 z-10">
+                {buttons}
+            </div>
+        }
+    } else {
+        html! {}
+    };
+
+    let emoji_rows = emoji_suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, &(code, emoji))| {
+            let onmousedown = {
+                let choose_emoji = choose_emoji.clone();
+                Callback::from(move |e: MouseEvent| {
+                    // Keep focus in the textarea.
+                    e.prevent_default();
+                    choose_emoji.emit(emoji);
+                })
+            };
+            html! {
+                <li {onmousedown} class={classes!("px-3", "py-1", "cursor-pointer", "hover:bg-gray-200", "dark:hover:bg-gray-700",
+                    (i == *emoji_selected).then_some("bg-gray-200 dark:bg-gray-700"))}>
+                    {emoji}{" "}{format!(":{}:", code)}
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    let suggestions = props
+        .mention_suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let onmousedown = {
+                let choose = choose.clone();
+                let name = name.clone();
+                Callback::from(move |e: MouseEvent| {
+                    // Keep focus in the textarea.
+                    e.prevent_default();
+                    choose.emit(name.clone());
+                })
+            };
+            html! {
+                <li {onmousedown} class={classes!("px-3", "py-1", "cursor-pointer", "hover:bg-gray-200", "dark:hover:bg-gray-700",
+                    (i == *selected).then_some("bg-gray-200 dark:bg-gray-700"))}>
+                    {format!("@{}", name)}
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    // A long press schedules; the click ending it must not send as well.
+    let long_press = use_mut_ref(|| (None::<Timeout>, false));
+    let onclick = {
+        let on_submit = props.on_submit.clone();
+        let long_press = long_press.clone();
+        Callback::from(move |_| {
+            if !std::mem::take(&mut long_press.borrow_mut().1) {
+                on_submit.emit(());
+            }
+        })
+    };
+    let onpointerdown = props.on_schedule.clone().map(|on_schedule| {
+        let long_press = long_press.clone();
+        Callback::from(move |_: PointerEvent| {
+            let (on_schedule, pressed) = (on_schedule.clone(), long_press.clone());
+            *long_press.borrow_mut() = (None, false);
+            long_press.borrow_mut().0 = Some(Timeout::new(LONG_PRESS, move || {
+                pressed.borrow_mut().1 = true;
+                on_schedule.emit(());
+            }));
+        })
+    });
+    let onpointerup = {
+        let long_press = long_press.clone();
+        Callback::from(move |_: PointerEvent| long_press.borrow_mut().0 = None)
+    };
+    let onpointerleave = onpointerup.clone();
+
+    let counter = match props.max_len {
+        Some(max) if *length as f64 >= max as f64 * COUNTER_FROM => {
+            let warn = *length as f64 >= max as f64 * COUNTER_WARN_FROM;
+            html! {
+                <span
+                    aria-live="polite"
+                    title={too_long.then(|| t("composer-too-long"))}
+                    class={classes!("mr-3", "mb-3", "flex-none", "text-xs", "tabular-nums",
+                        if warn { "text-red-500 font-semibold" } else { "text-gray-500 dark:text-gray-400" })}
+                >
+                    {format!("{}/{}", *length, max)}
+                </span>
+            }
+        }
+        _ => html! {},
+    };
+
+    let file_input = use_node_ref();
+    let pick_file = {
+        let file_input = file_input.clone();
+        Callback::from(move |_| {
+            if let Some(input) = file_input.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+    let onchange = {
+        let on_attach = props.on_attach.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let (Some(file), Some(on_attach)) = (input.files().and_then(|files| files.get(0)), &on_attach) {
+                on_attach.emit(file);
+            }
+            // Allow picking the same file again.
+            input.set_value("");
+        })
+    };
+
+    html! {
+        <div class="flex items-end w-full py-2">
+            <input ref={file_input} type="file" class="hidden" {onchange}/>
+            {
+                if props.on_attach.is_some() {
+                    html! {
+                        <button onclick={pick_file} title={t("attach-file")} class="p-2 ml-3 mb-1 flex-none text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white" aria-label={t("attach-file")}>
+                            {"📎"}
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            <button onclick={toggle_emoji} title={t("emoji-picker")} aria-label={t("emoji-picker")} aria-expanded={show_emoji.to_string()} class="p-2 mb-1 flex-none text-gray-600 dark:text-gray-300 hover:text-black dark:hover:text-white">
+                {"😀"}
+            </button>
+            <div class="relative flex-grow mx-3">
+                {emoji_grid}
+                {
+                    match &*transcript_paste {
+                        Some(paste) => html! {
+                            <div class="absolute bottom-full left-0 right-0 mb-2 bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded shadow-lg p-3 text-sm z-10">
+                                <div class="mb-2">{t("paste-transcript-title")}</div>
+                                <div class="max-h-40 overflow-y-auto mb-3 p-2 rounded bg-gray-100 dark:bg-gray-700">
+                                    {message_content::render(&paste.quote, &RenderOptions::default())}
+                                </div>
+                                <div class="flex justify-end">
+                                    <button onmousedown={paste_choice(false)} class="px-3 py-1 mr-2 rounded bg-gray-200 dark:bg-gray-700">{t("paste-as-text")}</button>
+                                    <button onmousedown={paste_choice(true)} class="px-3 py-1 rounded bg-green-600 text-white">{t("paste-as-quote")}</button>
+                                </div>
+                            </div>
+                        },
+                        None => html! {},
+                    }
+                }
+                {
+                    if emoji_suggestions.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <ul class="absolute bottom-full left-0 mb-2 w-64 bg-white dark:bg-gray-800 rounded shadow-lg py-1 text-sm z-10">
+                                {emoji_rows}
+                            </ul>
+                        }
+                    }
+                }
+                {
+                    if props.mention_suggestions.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <ul class="absolute bottom-full left-0 mb-2 w-64 bg-white dark:bg-gray-800 rounded shadow-lg py-1 text-sm z-10">
+                                {suggestions}
+                            </ul>
+                        }
+                    }
+                }
+                <textarea
+                    ref={props.input_ref.clone()}
+                    {oninput}
+                    {onkeydown}
+                    {onpaste}
+                    rows={if props.large { "3" } else { "1" }}
+                    placeholder={t("message-placeholder")}
+                    class={classes!("block", "w-full", "resize-none", "px-4", "bg-gray-200", "dark:bg-gray-700", "rounded-2xl",
+                        "outline-none", "focus:ring-2", "focus:ring-green-500", "focus:border-transparent", "text-gray-900", "dark:text-white",
+                        if props.large { "text-lg py-3" } else { "py-2" })}
+                    name="message"
+                    required=true
+                    aria-invalid={too_long.then_some("true")}
+                />
+            </div>
+            {counter}
+            {
+                match &props.cooldown {
+                    Some(cooldown) => html! {
+                        <span class="mr-3 mb-3 flex-none text-xs text-gray-500 dark:text-gray-400">{cooldown}</span>
+                    },
+                    None => html! {
+                        <>
+                        {
+                            match &props.on_schedule {
+                                Some(on_schedule) => html! {
+                                    <button
+                                        onclick={on_schedule.reform(|_| ())}
+                                        disabled={too_long}
+                                        title={t("schedule-title")}
+                                        class="mr-2 mb-2 flex-none text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white disabled:opacity-50"
+                                        aria-label={t("schedule-title")}
+                                    >
+                                        {"🕒"}
+                                    </button>
+                                },
+                                None => html! {},
+                            }
+                        }
+                        <button
+                            {onclick}
+                            {onpointerdown}
+                            {onpointerup}
+                            {onpointerleave}
+                            disabled={too_long}
+                            title={if too_long { t("composer-too-long") } else { t("send") }}
+                            class="p-3 mr-3 shadow-sm bg-green-600 w-10 h-10 flex-none rounded-full flex justify-center items-center text-white disabled:opacity-50 select-none"
+                            aria-label={t("send")}
+                        >
+                            <svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg" class="fill-current w-6 h-6">
+                                <path d="M0 0h24v24H0z" fill="none"></path>
+                                <path d="M2.01 21L23 12 2.01 3 2 10l15 2-15 2z"></path>
+                            </svg>
+                        </button>
+                        </>
+                    },
+                }
+            }
+        </div>
+    }
+}
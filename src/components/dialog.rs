@@ -0,0 +1,63 @@
+use gloo_events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+use yew::prelude::*;
+
+const FOCUSABLE: &str = "button, [href], input, select, textarea, [tabindex]:not([tabindex='-1'])";
+
+/// Focus handling for a modal dialog. Attach the returned ref to the dialog
+/// panel, which needs `tabindex="-1"`: it is focused on open, Tab cycles
+/// within it, and focus goes back to where it was on close.
+pub fn use_dialog_focus() -> NodeRef {
+    let panel = use_node_ref();
+    {
+        let panel = panel.clone();
+        use_effect_with_deps(
+            move |_| {
+                let previous = web_sys::window()
+                    .and_then(|w| w.document())
+                    .and_then(|d| d.active_element())
+                    .and_then(|e| e.dyn_into::<HtmlElement>().ok());
+                let trap = panel.cast::<HtmlElement>().map(|element| {
+                    let _ = element.focus();
+                    let panel = element.clone();
+                    EventListener::new(&element, "keydown", move |e| {
+                        let e = match e.dyn_ref::<KeyboardEvent>() {
+                            Some(e) if e.key() == "Tab" => e,
+                            _ => return,
+                        };
+                        let focusable = match panel.query_selector_all(FOCUSABLE) {
+                            Ok(list) => list,
+                            Err(_) => return,
+                        };
+                        let edge = |index: u32| focusable.get(index).and_then(|n| n.dyn_into::<HtmlElement>().ok());
+                        let (first, last) = match (edge(0), edge(focusable.length().saturating_sub(1))) {
+                            (Some(first), Some(last)) => (first, last),
+                            _ => return,
+                        };
+                        let active = web_sys::window()
+                            .and_then(|w| w.document())
+                            .and_then(|d| d.active_element());
+                        let wrap_to = if e.shift_key() && active.as_ref() == Some(&*first) {
+                            last
+                        } else if !e.shift_key() && active.as_ref() == Some(&*last) {
+                            first
+                        } else {
+                            return;
+                        };
+                        e.prevent_default();
+                        let _ = wrap_to.focus();
+                    })
+                });
+                move || {
+                    drop(trap);
+                    if let Some(previous) = previous {
+                        let _ = previous.focus();
+                    }
+                }
+            },
+            (),
+        );
+    }
+    panel
+}
@@ -0,0 +1,162 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::services::digest::{DigestChannel, DigestPrefs};
+use crate::services::i18n::{t, t_args};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// What the server has on record, or `None` until it has answered.
+    pub prefs: Option<DigestPrefs>,
+    pub room: String,
+    pub on_save: Callback<DigestPrefs>,
+}
+
+#[function_component(DigestSettings)]
+pub fn digest_settings(props: &Props) -> Html {
+    let draft = use_state(|| props.prefs.clone().unwrap_or_default());
+    let error = use_state(|| None::<String>);
+
+    {
+        let draft = draft.clone();
+        let error = error.clone();
+        use_effect_with_deps(
+            move |prefs| {
+                draft.set(prefs.clone().unwrap_or_default());
+                error.set(None);
+                || ()
+            },
+            props.prefs.clone(),
+        );
+    }
+
+    let saved = match &props.prefs {
+        Some(prefs) => prefs,
+        None => {
+            return html! {
+                <div class="text-sm text-gray-500 dark:text-gray-400">{t("digest-loading")}</div>
+            }
+        }
+    };
+
+    let edit = |apply: fn(&mut DigestPrefs, String)| {
+        let draft = draft.clone();
+        move |value: String| {
+            let mut prefs = (*draft).clone();
+            apply(&mut prefs, value);
+            draft.set(prefs);
+        }
+    };
+
+    let toggle_enabled = {
+        let edit = edit(|prefs, _| prefs.enabled = !prefs.enabled);
+        Callback::from(move |_| edit(String::new()))
+    };
+    let on_channel = {
+        let edit = edit(|prefs, value| {
+            if let Some(channel) = DigestChannel::ALL.iter().find(|c| format!("{:?}", c) == value) {
+                prefs.channel = *channel;
+            }
+        });
+        Callback::from(move |e: Event| edit(e.target_unchecked_into::<HtmlSelectElement>().value()))
+    };
+    let on_address = {
+        let edit = edit(|prefs, value| prefs.address = value);
+        Callback::from(move |e: InputEvent| edit(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+    let toggle_room = {
+        let draft = draft.clone();
+        let room = props.room.clone();
+        Callback::from(move |_| {
+            let mut prefs = (*draft).clone();
+            if prefs.follows(&room) {
+                prefs.rooms.retain(|r| r != &room);
+            } else {
+                prefs.rooms.push(room.clone());
+            }
+            draft.set(prefs);
+        })
+    };
+    let on_save = {
+        let draft = draft.clone();
+        let error = error.clone();
+        let on_save = props.on_save.clone();
+        Callback::from(move |_| {
+            let mut prefs = (*draft).clone();
+            prefs.address = prefs.address.trim().to_string();
+            match prefs.validate() {
+                Ok(()) => on_save.emit(prefs),
+                Err(message) => error.set(Some(message)),
+            }
+        })
+    };
+
+    let status = if !saved.enabled {
+        html! {}
+    } else if saved.confirmed {
+        html! {
+            <div class="text-xs text-green-600 mb-2">{t_args("digest-active", &[("address", &saved.address)])}</div>
+        }
+    } else {
+        html! {
+            <div class="text-xs text-yellow-600 dark:text-yellow-400 mb-2">{t_args("digest-pending", &[("address", &saved.address)])}</div>
+        }
+    };
+    let placeholder = match draft.channel {
+        DigestChannel::Email => "you@example.com",
+        DigestChannel::Webhook => "https://hooks.example.com/digest",
+    };
+
+    html! {
+        <>
+        {status}
+        <label class="flex items-center text-sm text-gray-600 dark:text-gray-300 mb-2">
+            <input type="checkbox" class="mr-2" checked={draft.enabled} onclick={toggle_enabled} />
+            {t("digest-enable")}
+        </label>
+        {
+            if draft.enabled {
+                html! {
+                    <>
+                    <div class="flex mb-2">
+                        <select onchange={on_channel} class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm mr-2">
+                            {
+                                DigestChannel::ALL.iter().map(|channel| html! {
+                                    <option value={format!("{:?}", channel)} selected={draft.channel == *channel}>{channel.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                        <input
+                            type="text"
+                            class="flex-grow bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm"
+                            {placeholder}
+                            value={draft.address.clone()}
+                            oninput={on_address}
+                        />
+                    </div>
+                    <label class="flex items-center text-sm text-gray-600 dark:text-gray-300 mb-2">
+                        <input type="checkbox" class="mr-2" checked={draft.follows(&props.room)} onclick={toggle_room} />
+                        {t_args("digest-follow-room", &[("room", &props.room)])}
+                    </label>
+                    </>
+                }
+            } else {
+                html! {}
+            }
+        }
+        {
+            match &*error {
+                Some(message) => html! {<div class="text-xs text-red-500 mb-2">{message}</div>},
+                None => html! {},
+            }
+        }
+        <button
+            onclick={on_save}
+            disabled={*draft == *saved}
+            class="px-3 py-1 rounded text-sm bg-green-600 text-white disabled:opacity-50"
+        >
+            {t("digest-save")}
+        </button>
+        </>
+    }
+}
@@ -0,0 +1,111 @@
+use gloo_timers::callback::Timeout;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::message_content::{media_src, RenderOptions};
+use crate::services::gif_search::{self, Gif};
+use crate::services::i18n::t;
+
+/// Wait this long after the last keystroke before searching, in milliseconds.
+const SEARCH_DELAY: u32 = 300;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub render_options: RenderOptions,
+    /// Receives the URL of the chosen GIF.
+    pub on_select: Callback<String>,
+    pub on_close: Callback<()>,
+}
+
+#[derive(Clone, PartialEq)]
+enum Results {
+    Idle,
+    Loading,
+    Loaded(Vec<Gif>),
+    Failed,
+}
+
+#[function_component(GifPicker)]
+pub fn gif_picker(props: &Props) -> Html {
+    let results = use_state(|| Results::Idle);
+    let pending = use_mut_ref(|| None::<Timeout>);
+    // Counts searches so that a slow response can't overwrite a newer one.
+    let generation = use_mut_ref(|| 0u32);
+
+    let oninput = {
+        let results = results.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let query = input.value().trim().to_string();
+            *generation.borrow_mut() += 1;
+            if query.is_empty() {
+                pending.borrow_mut().take();
+                results.set(Results::Idle);
+                return;
+            }
+            let results = results.clone();
+            let generation = generation.clone();
+            let current = *generation.borrow();
+            *pending.borrow_mut() = Some(Timeout::new(SEARCH_DELAY, move || {
+                results.set(Results::Loading);
+                spawn_local(async move {
+                    let found = gif_search::search(&query).await;
+                    if *generation.borrow() != current {
+                        return;
+                    }
+                    match found {
+                        Ok(gifs) => results.set(Results::Loaded(gifs)),
+                        Err(e) => {
+                            log::debug!("GIF search failed: {}", e);
+                            results.set(Results::Failed);
+                        }
+                    }
+                });
+            }));
+        })
+    };
+
+    let body = match &*results {
+        Results::Idle => html! {<div class="text-sm text-gray-500 dark:text-gray-400 p-2">{t("gif-hint")}</div>},
+        Results::Loading => html! {<div class="text-sm text-gray-500 dark:text-gray-400 p-2">{t("gif-loading")}</div>},
+        Results::Failed => html! {<div class="text-sm text-red-500 p-2">{t("gif-failed")}</div>},
+        Results::Loaded(gifs) if gifs.is_empty() => {
+            html! {<div class="text-sm text-gray-500 dark:text-gray-400 p-2">{t("gif-no-results")}</div>}
+        }
+        Results::Loaded(gifs) => gifs
+            .iter()
+            .map(|gif| {
+                let onclick = {
+                    let url = gif.url.clone();
+                    props.on_select.reform(move |_| url.clone())
+                };
+                html! {
+                    <button {onclick} title={gif.description.clone()} class="rounded overflow-hidden hover:ring-2 hover:ring-green-500">
+                        <img src={media_src(&gif.preview, &props.render_options)} alt={gif.description.clone()} class="w-full h-24 object-cover"/>
+                    </button>
+                }
+            })
+            .collect::<Html>(),
+    };
+    let grid = matches!(&*results, Results::Loaded(gifs) if !gifs.is_empty());
+    let on_close = props.on_close.reform(|_| ());
+
+    html! {
+        <div class="absolute bottom-full left-3 mb-2 w-96 max-w-full bg-white dark:bg-gray-800 rounded-lg shadow-lg p-3 z-10">
+            <div class="flex items-center mb-2">
+                <input
+                    type="search"
+                    {oninput}
+                    placeholder={t("gif-search")}
+                    class="flex-grow px-3 py-1 rounded bg-gray-200 dark:bg-gray-700 outline-none focus:ring-2 focus:ring-green-500"
+                />
+                <button onclick={on_close} class="ml-2 text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+            </div>
+            <div class={classes!("max-h-72", "overflow-y-auto", grid.then_some("grid grid-cols-3 gap-2"))}>
+                {body}
+            </div>
+            <div class="text-right text-xs text-gray-400 mt-2">{t("gif-attribution")}</div>
+        </div>
+    }
+}
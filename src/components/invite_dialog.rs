@@ -0,0 +1,109 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::{t, t_args};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub room: String,
+    /// `(id, name)` pairs matching the current search, excluding members.
+    pub candidates: Vec<(String, String)>,
+    pub on_search: Callback<String>,
+    pub on_invite: Callback<Vec<String>>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(InviteDialog)]
+pub fn invite_dialog(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    // Kept in pick order so the chips don't jump around.
+    let selected = use_state(Vec::<(String, String)>::new);
+
+    let oninput = props.on_search.reform(|e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        input.value().trim().to_string()
+    });
+
+    let toggle = |candidate: (String, String)| {
+        let selected = selected.clone();
+        Callback::from(move |()| {
+            let mut next = (*selected).clone();
+            match next.iter().position(|(id, _)| *id == candidate.0) {
+                Some(index) => {
+                    next.remove(index);
+                }
+                None => next.push(candidate.clone()),
+            }
+            selected.set(next);
+        })
+    };
+
+    let chips = selected
+        .iter()
+        .map(|candidate| {
+            html! {
+                <button onclick={toggle(candidate.clone()).reform(|_| ())} title={t("invite-remove")} class="text-xs bg-green-600 text-white rounded-full px-2 py-1 mr-1 mb-1">
+                    {format!("{} ✕", candidate.1)}
+                </button>
+            }
+        })
+        .collect::<Html>();
+
+    let results = props
+        .candidates
+        .iter()
+        .map(|candidate| {
+            let checked = selected.iter().any(|(id, _)| *id == candidate.0);
+            html! {
+                <label class="flex items-center px-2 py-1 rounded cursor-pointer hover:bg-gray-200 dark:hover:bg-gray-700 text-sm">
+                    <input type="checkbox" class="mr-2" {checked} onchange={toggle(candidate.clone()).reform(|_| ())}/>
+                    {candidate.1.clone()}
+                </label>
+            }
+        })
+        .collect::<Html>();
+
+    let on_invite = {
+        let selected = selected.clone();
+        let on_invite = props.on_invite.clone();
+        Callback::from(move |_| on_invite.emit(selected.iter().map(|(id, _)| id.clone()).collect()))
+    };
+    let on_close = props.on_close.reform(|_| ());
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t_args("invite-title", &[("room", &props.room)])} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t_args("invite-title", &[("room", &props.room)])}</div>
+                    <button onclick={on_close} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                <input
+                    type="search"
+                    {oninput}
+                    placeholder={t("invite-search")}
+                    class="w-full mb-3 px-3 py-2 rounded bg-gray-200 dark:bg-gray-700 outline-none focus:ring-2 focus:ring-green-500"
+                />
+                <div class="flex flex-wrap mb-2">{chips}</div>
+                <div class="max-h-64 overflow-y-auto mb-4">
+                    {
+                        if props.candidates.is_empty() {
+                            html! {<div class="text-sm text-gray-500 dark:text-gray-400 px-2">{t("invite-no-results")}</div>}
+                        } else {
+                            results
+                        }
+                    }
+                </div>
+                <div class="flex justify-end">
+                    <button
+                        onclick={on_invite}
+                        disabled={selected.is_empty()}
+                        class="px-4 py-2 rounded bg-green-600 text-white disabled:opacity-50"
+                    >
+                        {t_args("invite-submit", &[("count", &selected.len().to_string())])}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
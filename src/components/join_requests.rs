@@ -0,0 +1,57 @@
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::{t, t_args};
+use crate::services::rooms::JoinRequest;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub room: String,
+    pub requests: Vec<JoinRequest>,
+    /// Receives the request id and whether it was approved.
+    pub on_decide: Callback<(String, bool)>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(JoinRequests)]
+pub fn join_requests(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let rows = props
+        .requests
+        .iter()
+        .map(|request| {
+            let decide = |approve: bool| {
+                let id = request.id.clone();
+                props.on_decide.reform(move |_| (id.clone(), approve))
+            };
+            html! {
+                <div class="flex items-center justify-between bg-gray-200 dark:bg-gray-700 rounded px-3 py-2 mb-2 text-sm">
+                    <span>{request.name.clone()}</span>
+                    <div>
+                        <button onclick={decide(false)} class="px-3 py-1 mr-2 rounded text-xs text-red-500 hover:bg-gray-300 dark:hover:bg-gray-600">{t("join-reject")}</button>
+                        <button onclick={decide(true)} class="px-3 py-1 rounded text-xs bg-green-600 text-white">{t("join-approve")}</button>
+                    </div>
+                </div>
+            }
+        })
+        .collect::<Html>();
+    let on_close = props.on_close.reform(|_| ());
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t_args("join-requests-title", &[("room", &props.room)])} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t_args("join-requests-title", &[("room", &props.room)])}</div>
+                    <button onclick={on_close} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                {
+                    if props.requests.is_empty() {
+                        html! {<div class="text-sm text-gray-500 dark:text-gray-400">{t("join-requests-empty")}</div>}
+                    } else {
+                        rows
+                    }
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,81 @@
+use web_sys::{File, HtmlInputElement};
+use yew::prelude::*;
+
+use crate::services::e2ee::{Passphrase, MIN_PASSPHRASE_LEN};
+use crate::services::i18n::{t, t_args};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// Receives the passphrase to seal a backup of our key under.
+    pub on_backup: Callback<Passphrase>,
+    /// Receives a backup file and the passphrase to open it with.
+    pub on_restore: Callback<(File, Passphrase)>,
+    pub error: Option<String>,
+}
+
+/// Saves our encryption key to a passphrase-sealed file, or puts one back,
+/// so direct messages stay readable on a new device.
+#[function_component(KeyBackup)]
+pub fn key_backup(props: &Props) -> Html {
+    let passphrase = use_state(String::new);
+
+    let oninput = {
+        let passphrase = passphrase.clone();
+        Callback::from(move |e: InputEvent| passphrase.set(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+    let long_enough = passphrase.chars().count() >= MIN_PASSPHRASE_LEN;
+    let on_backup = {
+        let passphrase = passphrase.clone();
+        props.on_backup.reform(move |_| Passphrase((*passphrase).clone()))
+    };
+    let on_restore = {
+        let passphrase = passphrase.clone();
+        let on_restore = props.on_restore.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let file = input.files().and_then(|files| files.get(0));
+            // Allow picking the same file again.
+            input.set_value("");
+            if let Some(file) = file {
+                on_restore.emit((file, Passphrase((*passphrase).clone())));
+            }
+        })
+    };
+
+    html! {
+        <>
+            <div class="text-sm text-gray-500 dark:text-gray-400 mb-2">{t("key-backup-help")}</div>
+            <input
+                type="password"
+                autocomplete="new-password"
+                {oninput}
+                value={(*passphrase).clone()}
+                placeholder={t("key-backup-passphrase")}
+                aria-label={t("key-backup-passphrase")}
+                class="block w-full mb-1 p-2 rounded border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-sm"
+            />
+            <div class="text-xs text-gray-500 dark:text-gray-400 mb-2">
+                {t_args("key-backup-min-length", &[("count", &MIN_PASSPHRASE_LEN.to_string())])}
+            </div>
+            <div class="flex">
+                <button
+                    onclick={on_backup}
+                    disabled={!long_enough}
+                    class="px-3 py-1 mr-2 rounded bg-gray-200 dark:bg-gray-700 text-sm disabled:opacity-50"
+                >
+                    {t("key-backup-download")}
+                </button>
+                <label class={classes!("inline-block", "px-3", "py-1", "rounded", "bg-gray-200", "dark:bg-gray-700", "text-sm", if long_enough { "cursor-pointer" } else { "opacity-50 pointer-events-none" })}>
+                    {t("key-backup-restore")}
+                    <input type="file" accept=".json,application/json" class="hidden" disabled={!long_enough} onchange={on_restore}/>
+                </label>
+            </div>
+            {
+                match &props.error {
+                    Some(error) => html!{<div class="mt-2 text-sm text-red-500 break-words">{error}</div>},
+                    None => html!{},
+                }
+            }
+        </>
+    }
+}
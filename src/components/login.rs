@@ -1,39 +1,146 @@
+use wasm_bindgen_futures::spawn_local;
 use web_sys::HtmlInputElement;
 use yew::functional::*;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::services::auth::{self, AuthError, AuthSession, OAuthProvider};
+use crate::services::i18n::t;
 use crate::Route;
 use crate::User;
 
 #[function_component(Login)]
 pub fn login() -> Html {
-    let username = use_state(|| String::new());
+    let username = use_state(String::new);
+    let password = use_state(String::new);
+    let registering = use_state(|| false);
+    let pending = use_state(|| false);
+    let error = use_state(|| None::<String>);
+    // The name the server last refused as taken, flagged until it is changed.
+    let taken = use_state(|| None::<String>);
     let user = use_context::<User>().expect("No context found.");
+    let history = use_history();
 
-    let oninput = {
-        let current_username = username.clone();
-
+    let input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
         Callback::from(move |e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
-            current_username.set(input.value());
+            state.set(input.value());
+        })
+    };
+
+    let onsubmit = {
+        let (username, password, registering) = (username.clone(), password.clone(), registering.clone());
+        let (pending, error, taken) = (pending.clone(), error.clone(), taken.clone());
+        Callback::from(move |e: FocusEvent| {
+            e.prevent_default();
+            let (name, secret) = ((*username).trim().to_string(), (*password).clone());
+            let registering = *registering;
+            let (pending, error, taken) = (pending.clone(), error.clone(), taken.clone());
+            let (user, history) = (user.clone(), history.clone());
+            pending.set(true);
+            spawn_local(async move {
+                let result = if registering {
+                    auth::register(&name, &secret).await
+                } else {
+                    auth::login(&name, &secret).await
+                };
+                pending.set(false);
+                match result {
+                    Ok(AuthSession { username, .. }) => {
+                        *user.username.borrow_mut() = username;
+                        if let Some(history) = &history {
+                            history.push(Route::Chat);
+                        }
+                    }
+                    Err(AuthError::UsernameTaken) => taken.set(Some(name)),
+                    Err(e) => {
+                        if let AuthError::Failed(reason) = &e {
+                            log::error!("authentication failed: {}", reason);
+                        }
+                        error.set(Some(e.message()));
+                    }
+                }
+            });
         })
     };
 
-    let onclick = {
-        let username = username.clone();
-        let user = user.clone();
-        Callback::from(move |_| *user.username.borrow_mut() = (*username).clone())
+    let toggle_mode = {
+        let (registering, error) = (registering.clone(), error.clone());
+        Callback::from(move |_| {
+            registering.set(!*registering);
+            error.set(None);
+        })
+    };
+
+    let providers = OAuthProvider::configured().into_iter().map(|provider| {
+        let onclick = Callback::from(move |_| {
+            let window = web_sys::window().unwrap();
+            match provider.authorize_url() {
+                Some(url) => {
+                    if let Err(e) = window.location().set_href(&url) {
+                        log::error!("failed to start oauth redirect: {:?}", e);
+                    }
+                }
+                None => log::error!("no oauth url for {:?}", provider),
+            }
+        });
+        html! {
+            <button {onclick} class="w-72 rounded-lg border border-gray-300 dark:border-gray-600 text-gray-800 dark:text-white p-3 mb-2 hover:bg-gray-100 dark:hover:bg-gray-700">
+                {provider.label()}
+            </button>
+        }
+    });
+
+    let field = "rounded-lg p-4 mb-2 border text-gray-800 border-gray-200 bg-white w-72";
+    let name = username.trim();
+    let name_error = if taken.as_deref() == Some(name) {
+        Some(AuthError::UsernameTaken.message())
+    } else if *registering && !name.is_empty() {
+        auth::validate_username(name).err().map(|e| e.message())
+    } else {
+        None
     };
+    let disabled = *pending || name.is_empty() || password.is_empty() || name_error.is_some();
 
     html! {
-       <div class="bg-gray-800 flex w-screen">
+       <div class="bg-white dark:bg-gray-800 flex w-screen">
             <div class="container mx-auto flex flex-col justify-center items-center">
-                <form class="m-4 flex">
-                    <input {oninput} class="rounded-l-lg p-4 border-t mr-0 border-b border-l text-gray-800 border-gray-200 bg-white" placeholder="Username" />
-                    <Link<Route> to={Route::Chat}> <button {onclick} disabled={username.len()<1} class="px-8 rounded-r-lg bg-violet-600	  text-white font-bold p-4 uppercase border-violet-600 border-t border-b border-r" >{"Go Chatting!"}</button></Link<Route>>
+                { for providers }
+                <form {onsubmit} class="m-4 flex flex-col items-center">
+                    <input
+                        oninput={input(&username)}
+                        class={classes!(field, name_error.is_some().then_some("border-red-500"))}
+                        autocomplete="username"
+                        placeholder={t("login-username")}
+                    />
+                    {
+                        match name_error {
+                            Some(message) => html! {<div class="text-sm text-red-500 mb-2 w-72">{message}</div>},
+                            None => html! {},
+                        }
+                    }
+                    <input
+                        oninput={input(&password)}
+                        type="password"
+                        class={field}
+                        autocomplete={if *registering { "new-password" } else { "current-password" }}
+                        placeholder={t("login-password")}
+                    />
+                    {
+                        match &*error {
+                            Some(message) => html! {<div class="text-sm text-red-500 mb-2 w-72">{message}</div>},
+                            None => html! {},
+                        }
+                    }
+                    <button type="submit" {disabled} class="w-72 rounded-lg bg-violet-600 text-white font-bold p-4 uppercase disabled:opacity-50">
+                        { if *registering { t("login-register") } else { t("login-submit") } }
+                    </button>
                 </form>
+                <button onclick={toggle_mode} class="text-sm text-violet-600 hover:underline">
+                    { if *registering { t("login-have-account") } else { t("login-need-account") } }
+                </button>
             </div>
         </div>
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,396 @@
+use gloo_storage::{LocalStorage, Storage};
+use yew::prelude::*;
+
+use crate::services::attachments::{self, Attachment};
+use crate::services::clipboard;
+use crate::services::emoji;
+use crate::services::highlight;
+use crate::services::i18n::t;
+use crate::services::link_preview::LinkPreview;
+use crate::services::sanitize::{self, MediaKind};
+
+const PLAINTEXT_KEY: &str = "yewchat.plaintext_mode";
+
+pub fn plaintext_mode() -> bool {
+    LocalStorage::get(PLAINTEXT_KEY).unwrap_or(false)
+}
+
+pub fn set_plaintext_mode(enabled: bool) {
+    if let Err(e) = LocalStorage::set(PLAINTEXT_KEY, enabled) {
+        log::error!("failed to persist plaintext mode: {:?}", e);
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RenderOptions {
+    pub plaintext: bool,
+    pub media_proxy: Option<String>,
+}
+
+/// Rewrites an external media URL through the configured proxy, if any.
+pub fn media_src(url: &str, options: &RenderOptions) -> String {
+    match &options.media_proxy {
+        Some(template) => {
+            let encoded: String = js_sys::encode_uri_component(url).into();
+            template.replace("{url}", &encoded)
+        }
+        None => url.to_string(),
+    }
+}
+
+pub fn render(message: &str, options: &RenderOptions) -> Html {
+    if options.plaintext {
+        // Text nodes are escaped by yew, so this path never produces markup.
+        return html! {<p class="whitespace-pre-wrap break-words">{message}</p>};
+    }
+
+    let media = sanitize::media_kind(message).zip(sanitize::safe_url(message));
+    match media {
+        Some((MediaKind::Image, url)) => html! {
+            <img src={media_src(&url, options)} alt={t("media-image")} class="max-w-xs max-h-64 rounded"/>
+        },
+        Some((MediaKind::Video, url)) => html! {
+            <video
+                src={media_src(&url, options)}
+                controls=true
+                loop=true
+                muted=true
+                preload="metadata"
+                class="max-w-xs max-h-64 rounded"
+            />
+        },
+        None => render_fenced(message),
+    }
+}
+
+/// A piece of a message: prose, or a fenced code block with its language.
+enum Segment<'a> {
+    Text(&'a str),
+    Code(Option<&'a str>, &'a str),
+}
+
+/// Splits out ```` ``` ```` fenced blocks. An unclosed fence is left as text.
+fn split_fences(message: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = message;
+    while let Some(open) = rest.find("```") {
+        let body = &rest[open + 3..];
+        let close = match body.find("```") {
+            Some(close) => close,
+            None => break,
+        };
+        let (lang, code) = match body[..close].split_once('\n') {
+            Some((first, code)) if !first.trim().contains(char::is_whitespace) => {
+                (Some(first.trim()).filter(|l| !l.is_empty()), code)
+            }
+            _ => (None, &body[..close]),
+        };
+        let before = rest[..open].trim_end_matches('\n');
+        if !before.is_empty() {
+            segments.push(Segment::Text(before));
+        }
+        segments.push(Segment::Code(lang, code.trim_end_matches('\n')));
+        rest = body[close + 3..].trim_start_matches('\n');
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+fn render_fenced(message: &str) -> Html {
+    if !message.contains("```") {
+        return render_blocks(message);
+    }
+    split_fences(message)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => render_blocks(text),
+            Segment::Code(lang, code) => html! {
+                <CodeBlock lang={lang.map(String::from)} code={code.to_string()}/>
+            },
+        })
+        .collect()
+}
+
+#[derive(Properties, PartialEq)]
+struct CodeBlockProps {
+    lang: Option<String>,
+    code: String,
+}
+
+#[function_component(CodeBlock)]
+fn code_block(props: &CodeBlockProps) -> Html {
+    let copied = use_state(|| false);
+    let copy = {
+        let (copied, code) = (copied.clone(), props.code.clone());
+        Callback::from(move |_| {
+            clipboard::write_text(&code);
+            copied.set(true);
+        })
+    };
+    let reset = {
+        let copied = copied.clone();
+        Callback::from(move |_| copied.set(false))
+    };
+    let markup = highlight::highlight(&props.code, props.lang.as_deref());
+    html! {
+        <div class="relative group my-1" onmouseleave={reset}>
+            <pre class="hljs overflow-x-auto rounded bg-gray-50 dark:bg-gray-900 p-3 text-sm">
+                <code class={props.lang.as_ref().map(|lang| format!("language-{}", lang))}>{sanitize::to_html(&markup)}</code>
+            </pre>
+            <button
+                onclick={copy}
+                class="absolute top-1 right-1 invisible group-hover:visible px-2 py-0.5 rounded text-xs bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600"
+            >
+                { if *copied { t("code-copied") } else { t("code-copy") } }
+            </button>
+        </div>
+    }
+}
+
+/// Lines starting with `>` become quote blocks; the rest is plain paragraphs.
+fn render_blocks(message: &str) -> Html {
+    let quoted = |line: &str| line.starts_with('>');
+    if !message.lines().any(quoted) {
+        return html! {<p class="whitespace-pre-wrap break-words">{sanitize::to_html(&format_inline(message))}</p>};
+    }
+    let mut blocks: Vec<(bool, Vec<&str>)> = Vec::new();
+    for line in message.lines() {
+        let quote = quoted(line);
+        let line = if quote {
+            line[1..].strip_prefix(' ').unwrap_or(&line[1..])
+        } else {
+            line
+        };
+        match blocks.last_mut() {
+            Some((q, lines)) if *q == quote => lines.push(line),
+            _ => blocks.push((quote, vec![line])),
+        }
+    }
+    blocks
+        .into_iter()
+        .map(|(quote, lines)| {
+            let body = sanitize::to_html(&format_inline(&lines.join("\n")));
+            if quote {
+                html! {
+                    <blockquote class="my-1 pl-3 border-l-4 border-gray-400 dark:border-gray-500 text-gray-700 dark:text-gray-300 whitespace-pre-wrap break-words">
+                        {body}
+                    </blockquote>
+                }
+            } else {
+                html! {<p class="whitespace-pre-wrap break-words">{body}</p>}
+            }
+        })
+        .collect()
+}
+
+/// Renders images inline at a bounded size, where clicking asks the parent to
+/// show the full-size version. Any other file becomes a download card.
+pub fn render_attachment(
+    attachment: &Attachment,
+    options: &RenderOptions,
+    on_enlarge: Callback<String>,
+) -> Html {
+    let src = match sanitize::safe_image_src(&attachment.url) {
+        Some(url) if attachments::is_image(&attachment.mime) => {
+            if url.starts_with("data:") {
+                url
+            } else {
+                media_src(&url, options)
+            }
+        }
+        _ => return render_file_card(attachment),
+    };
+    let onclick = {
+        let src = src.clone();
+        on_enlarge.reform(move |_| src.clone())
+    };
+
+    html! {
+        <img
+            {src}
+            {onclick}
+            alt={attachment.name.clone()}
+            title={attachment.name.clone()}
+            class="max-w-xs max-h-64 rounded cursor-zoom-in"
+        />
+    }
+}
+
+fn render_file_card(attachment: &Attachment) -> Html {
+    let details = html! {
+        <>
+        <span class="text-2xl mr-3">{attachments::icon(attachment)}</span>
+        <span class="flex flex-col min-w-0">
+            <span class="text-sm truncate">{&attachment.name}</span>
+            <span class="text-xs text-gray-500 dark:text-gray-400">
+                {format!("{} · {}", attachments::format_size(attachment.size), attachment.mime)}
+            </span>
+        </span>
+        </>
+    };
+    let class = "flex items-center max-w-xs p-3 rounded bg-white dark:bg-gray-800";
+
+    match sanitize::safe_download_href(&attachment.url) {
+        Some(href) => html! {
+            <a {href} download={attachment.name.clone()} rel="noopener noreferrer" title={t("attachment-download")} class={classes!(class, "hover:bg-gray-100", "dark:hover:bg-gray-600")}>
+                {details}
+            </a>
+        },
+        None => html! {<div {class}>{details}</div>},
+    }
+}
+
+/// Renders an unfurled link as a card that can be collapsed to a single line.
+pub fn render_link_preview(
+    preview: &LinkPreview,
+    options: &RenderOptions,
+    collapsed: bool,
+    on_toggle: Callback<()>,
+) -> Html {
+    let onclick = on_toggle.reform(|_| ());
+    let toggle = html! {
+        <button {onclick} class="text-xs text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white">
+            { if collapsed { t("preview-show") } else { t("preview-hide") } }
+        </button>
+    };
+    if collapsed {
+        return html! {<div class="mt-2">{toggle}</div>};
+    }
+
+    let href = sanitize::safe_link(&preview.url);
+    let image = preview
+        .image
+        .as_deref()
+        .and_then(sanitize::safe_url)
+        .map(|src| html! {<img src={media_src(&src, options)} alt="" class="w-16 h-16 object-cover rounded mr-3 flex-none"/>});
+
+    html! {
+        <div class="mt-2 max-w-md">
+            <div class="flex border-l-4 border-green-600 bg-white dark:bg-gray-800 rounded p-2">
+                {for image}
+                <div class="min-w-0">
+                    <a href={href} target="_blank" rel="noopener noreferrer nofollow" class="block text-sm font-bold truncate hover:underline">
+                        {preview.title.clone().unwrap_or_else(|| preview.url.clone())}
+                    </a>
+                    {
+                        match &preview.description {
+                            Some(description) => html! {
+                                <div class="text-xs text-gray-600 dark:text-gray-300 line-clamp-2">{description}</div>
+                            },
+                            None => html! {},
+                        }
+                    }
+                </div>
+            </div>
+            {toggle}
+        </div>
+    }
+}
+
+/// Converts `**bold**`, `*italic*`, `` `code` `` and bare links into markup.
+/// The output still goes through the sanitizer before reaching the DOM.
+pub(crate) fn format_inline(message: &str) -> String {
+    let mut out = String::new();
+    for (i, segment) in message.split('`').enumerate() {
+        // Odd segments sit between a pair of backticks.
+        if i % 2 == 1 && message.matches('`').count() > i {
+            out.push_str(&format!("<code>{}</code>", sanitize::escape(segment)));
+            continue;
+        }
+        if i > 0 && i % 2 == 1 {
+            out.push('`');
+        }
+        out.push_str(&format_emphasis(segment));
+    }
+    out
+}
+
+/// A stretch of an inline segment: escaped text that may still hold emphasis
+/// markers, or finished markup such as a link, which emphasis never looks into.
+enum Piece {
+    Text(String),
+    Markup(String),
+}
+
+fn format_emphasis(text: &str) -> String {
+    let mut pieces = Vec::new();
+    for (i, word) in text.split(' ').enumerate() {
+        if i > 0 {
+            pieces.push(Piece::Text(" ".to_string()));
+        }
+        pieces.push(match sanitize::safe_link(word) {
+            Some(url) if word.contains("://") => {
+                Piece::Markup(format!("<a href=\"{0}\">{0}</a>", sanitize::escape(&url)))
+            }
+            _ => Piece::Text(sanitize::escape(&emoji::expand(word))),
+        });
+    }
+
+    wrap_pairs(wrap_pairs(pieces, "**", "strong"), "*", "em")
+        .into_iter()
+        .map(|piece| match piece {
+            Piece::Text(text) | Piece::Markup(text) => text,
+        })
+        .collect()
+}
+
+fn wrap_pairs(pieces: Vec<Piece>, marker: &str, tag: &str) -> Vec<Piece> {
+    // What lies between one marker and the next, markup included.
+    let mut parts: Vec<Vec<Piece>> = vec![Vec::new()];
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => {
+                for (i, chunk) in text.split(marker).enumerate() {
+                    if i > 0 {
+                        parts.push(Vec::new());
+                    }
+                    if !chunk.is_empty() {
+                        parts.last_mut().unwrap().push(Piece::Text(chunk.to_string()));
+                    }
+                }
+            }
+            markup => parts.last_mut().unwrap().push(markup),
+        }
+    }
+
+    let mut parts = parts.into_iter().peekable();
+    let mut out = parts.next().unwrap_or_default();
+    while let Some(part) = parts.next() {
+        // A pair needs a closing marker and something to wrap.
+        if parts.peek().is_some() && !part.is_empty() {
+            out.push(Piece::Markup(format!("<{}>", tag)));
+            out.extend(part);
+            out.push(Piece::Markup(format!("</{}>", tag)));
+            out.extend(parts.next().unwrap_or_default());
+        } else {
+            out.push(Piece::Text(marker.to_string()));
+            out.extend(part);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emphasis_leaves_links_alone() {
+        assert_eq!(
+            format_inline("see https://example.com/*a*/b"),
+            "see <a href=\"https://example.com/*a*/b\">https://example.com/*a*/b</a>"
+        );
+        assert_eq!(
+            format_inline("*look* https://example.com/**a** **now**"),
+            "<em>look</em> <a href=\"https://example.com/**a**\">https://example.com/**a**</a> <strong>now</strong>"
+        );
+        // Emphasis may still wrap a whole link.
+        assert_eq!(
+            format_inline("*see https://example.com/ too*"),
+            "<em>see <a href=\"https://example.com/\">https://example.com/</a> too</em>"
+        );
+        assert_eq!(format_inline("2 * 3 * 4 and **"), "2 <em> 3 </em> 4 and **");
+    }
+}
@@ -1,2 +1,30 @@
 pub mod chat;
 pub mod login;
+pub mod message_content;
+pub mod settings;
+pub mod session_list;
+pub mod moderation_log;
+pub mod composer;
+pub mod attachment_preview;
+pub mod invite_dialog;
+pub mod notification_center;
+pub mod join_requests;
+pub mod gif_picker;
+pub mod welcome_card;
+pub mod rules_modal;
+pub mod digest_settings;
+pub mod call_panel;
+pub mod room_info;
+pub mod pinned_dms;
+pub mod unsupported_browser;
+pub mod oauth_callback;
+pub mod print_dialog;
+pub mod action_log;
+pub mod archive_view;
+pub mod shortcut_help;
+pub mod quick_switcher;
+pub mod poll_card;
+pub mod schedule_dialog;
+pub mod dialog;
+pub mod key_backup;
+pub mod safety_number;
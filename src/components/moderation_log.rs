@@ -0,0 +1,92 @@
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::t;
+use crate::services::moderation::{ModAction, ModActionKind};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub actions: Vec<ModAction>,
+    pub on_close: Callback<()>,
+}
+
+fn format_time(millis: f64) -> String {
+    js_sys::Date::new(&millis.into())
+        .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+        .into()
+}
+
+#[function_component(ModerationLog)]
+pub fn moderation_log(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let filter = use_state(|| None::<ModActionKind>);
+
+    let filter_button = |kind: Option<ModActionKind>| {
+        let onclick = {
+            let filter = filter.clone();
+            Callback::from(move |_| filter.set(kind))
+        };
+        let label = kind.map(|k| k.label()).unwrap_or_else(|| t("modlog-all"));
+        html! {
+            <button {onclick} class={classes!("px-2", "py-1", "rounded", "mr-2", "text-xs",
+                if *filter == kind { "bg-green-600 text-white" } else { "bg-gray-200 dark:bg-gray-700" })}>
+                {label}
+            </button>
+        }
+    };
+
+    let rows = props
+        .actions
+        .iter()
+        .rev()
+        .filter(|a| filter.is_none_or(|kind| a.action == kind))
+        .map(|a| {
+            html! {
+                <tr class="border-t border-gray-300 dark:border-gray-700">
+                    <td class="py-1 pr-3 whitespace-nowrap">{format_time(a.timestamp)}</td>
+                    <td class="py-1 pr-3">{a.action.label()}</td>
+                    <td class="py-1 pr-3">{a.actor.clone()}</td>
+                    <td class="py-1 pr-3">{a.target.clone()}</td>
+                    <td class="py-1 text-gray-500 dark:text-gray-400">{a.reason.clone().unwrap_or_default()}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let on_close = props.on_close.reform(|_| ());
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("modlog-title")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-3xl max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t("modlog-title")}</div>
+                    <button onclick={on_close} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                <div class="flex mb-4">
+                    {filter_button(None)}
+                    { for ModActionKind::ALL.iter().map(|k| filter_button(Some(*k))) }
+                </div>
+                {
+                    if props.actions.is_empty() {
+                        html! {<div class="text-sm text-gray-500 dark:text-gray-400">{t("modlog-empty")}</div>}
+                    } else {
+                        html! {
+                            <table class="w-full text-sm text-left">
+                                <thead>
+                                    <tr class="text-gray-500 dark:text-gray-400">
+                                        <th class="pr-3">{t("modlog-time")}</th>
+                                        <th class="pr-3">{t("modlog-action")}</th>
+                                        <th class="pr-3">{t("modlog-actor")}</th>
+                                        <th class="pr-3">{t("modlog-target")}</th>
+                                        <th>{t("modlog-reason")}</th>
+                                    </tr>
+                                </thead>
+                                <tbody>{rows}</tbody>
+                            </table>
+                        }
+                    }
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,120 @@
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::{t, t_args};
+use crate::services::notification_router::Routed;
+use crate::services::rooms::Invitation;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub invitations: Vec<Invitation>,
+    /// Receives the invitation id and whether it was accepted.
+    pub on_respond: Callback<(String, bool)>,
+    /// Informational entries, newest last.
+    #[prop_or_default]
+    pub notices: Vec<String>,
+    /// Receives the index of a dismissed notice.
+    #[prop_or_default]
+    pub on_dismiss: Callback<usize>,
+    /// Recent routing decisions, oldest first, for the "why" explainer.
+    #[prop_or_default]
+    pub routing: Vec<Routed>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(NotificationCenter)]
+pub fn notification_center(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let entries = props
+        .invitations
+        .iter()
+        .map(|invitation| {
+            let respond = |accept: bool| {
+                let id = invitation.id.clone();
+                props.on_respond.reform(move |_| (id.clone(), accept))
+            };
+            html! {
+                <div class="bg-gray-200 dark:bg-gray-700 rounded px-3 py-2 mb-2 text-sm">
+                    <div class="mb-2">
+                        {t_args("invite-received", &[("from", &invitation.from), ("room", &invitation.room)])}
+                    </div>
+                    <div class="flex justify-end">
+                        <button onclick={respond(false)} class="px-3 py-1 mr-2 rounded text-xs hover:bg-gray-300 dark:hover:bg-gray-600">{t("invite-decline")}</button>
+                        <button onclick={respond(true)} class="px-3 py-1 rounded text-xs bg-green-600 text-white">{t("invite-accept")}</button>
+                    </div>
+                </div>
+            }
+        })
+        .collect::<Html>();
+    let notices = props
+        .notices
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(index, notice)| {
+            let on_dismiss = props.on_dismiss.reform(move |_| index);
+            html! {
+                <div class="flex items-start justify-between bg-gray-200 dark:bg-gray-700 rounded px-3 py-2 mb-2 text-sm">
+                    <span>{notice.clone()}</span>
+                    <button onclick={on_dismiss} title={t("dismiss")} class="ml-2 text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("dismiss")}>{"✕"}</button>
+                </div>
+            }
+        })
+        .collect::<Html>();
+    let routing = props
+        .routing
+        .iter()
+        .rev()
+        .map(|routed| {
+            let (icon, outcome) = if routed.route.notify {
+                ("🔔", t("route-notified"))
+            } else {
+                ("🔕", t("route-not-notified"))
+            };
+            html! {
+                <div class="text-xs mb-2">
+                    <div class="truncate">
+                        <span class="mr-1">{icon}</span>
+                        <span class="font-semibold">{routed.from.clone()}</span>
+                        {": "}
+                        <span class="text-gray-600 dark:text-gray-300">{routed.preview.clone()}</span>
+                    </div>
+                    <div class="text-gray-500 dark:text-gray-400 ml-5">
+                        {format!("{} · {}", outcome, routed.route.reason.explain())}
+                    </div>
+                </div>
+            }
+        })
+        .collect::<Html>();
+    let on_close = props.on_close.reform(|_| ());
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("notification-center")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t("notification-center")}</div>
+                    <button onclick={on_close} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                {
+                    if props.invitations.is_empty() && props.notices.is_empty() {
+                        html! {<div class="text-sm text-gray-500 dark:text-gray-400">{t("notification-center-empty")}</div>}
+                    } else {
+                        html! {<>{entries}{notices}</>}
+                    }
+                }
+                {
+                    if props.routing.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <details class="mt-4">
+                                <summary class="text-sm cursor-pointer text-gray-600 dark:text-gray-300 mb-2">{t("route-explainer")}</summary>
+                                {routing}
+                            </details>
+                        }
+                    }
+                }
+            </div>
+        </div>
+    }
+}
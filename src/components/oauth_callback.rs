@@ -0,0 +1,73 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::functional::*;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::services::auth::{self, AuthError, AuthSession};
+use crate::services::i18n::t;
+use crate::Route;
+use crate::User;
+
+/// Why signing in failed, with a way back to the login screen.
+#[allow(clippy::unnecessary_operation)]
+fn failed(message: &str) -> Html {
+    html! {
+        <>
+        <div class="text-red-500 mb-4">{message}</div>
+        <Link<Route> to={Route::Login} classes="text-violet-600 hover:underline">
+            {t("oauth-back")}
+        </Link<Route>>
+        </>
+    }
+}
+
+/// Where OAuth providers redirect back to. Trades the code in the query
+/// string for a session and carries on to the chat.
+#[function_component(OAuthCallback)]
+pub fn oauth_callback() -> Html {
+    let error = use_state(|| None::<String>);
+    let user = use_context::<User>().expect("No context found.");
+    let history = use_history();
+
+    {
+        let error = error.clone();
+        use_effect_with_deps(
+            move |_| {
+                let query = web_sys::window()
+                    .and_then(|w| w.location().search().ok())
+                    .unwrap_or_default();
+                spawn_local(async move {
+                    match auth::complete_oauth(&query).await {
+                        Ok(AuthSession { username, .. }) => {
+                            *user.username.borrow_mut() = username;
+                            if let Some(history) = &history {
+                                history.replace(Route::Chat);
+                            }
+                        }
+                        Err(e) => {
+                            if let AuthError::Failed(reason) = &e {
+                                log::error!("oauth sign-in failed: {}", reason);
+                            }
+                            error.set(Some(e.message()));
+                        }
+                    }
+                });
+                || ()
+            },
+            (),
+        );
+    }
+
+    html! {
+        <div class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white flex w-screen">
+            <div class="container mx-auto flex flex-col justify-center items-center">
+                {
+                    match &*error {
+                        Some(message) => failed(message),
+                        None => html! {<div class="text-gray-500 dark:text-gray-400">{t("oauth-signing-in")}</div>},
+                    }
+                }
+            </div>
+        </div>
+    }
+}
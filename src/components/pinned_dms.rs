@@ -0,0 +1,109 @@
+use web_sys::DragEvent;
+use yew::prelude::*;
+
+use crate::services::i18n::t;
+
+#[derive(Clone, PartialEq)]
+pub struct PinnedDm {
+    pub id: String,
+    pub name: String,
+    pub avatar: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub pins: Vec<PinnedDm>,
+    /// The conversation the composer is pointed at, by user id.
+    #[prop_or_default]
+    pub active: Option<String>,
+    pub on_open: Callback<String>,
+    pub on_unpin: Callback<String>,
+    /// Receives the dragged pin's position and where it was dropped.
+    pub on_move: Callback<(usize, usize)>,
+}
+
+/// Pinned direct message conversations, listed above the members so they
+/// stay put whatever the member list shows.
+#[function_component(PinnedDms)]
+pub fn pinned_dms(props: &Props) -> Html {
+    let dragged = use_state(|| None::<usize>);
+
+    if props.pins.is_empty() {
+        return html! {};
+    }
+
+    let rows = props.pins.iter().enumerate().map(|(index, pin)| {
+        let ondragstart = {
+            let dragged = dragged.clone();
+            let id = pin.id.clone();
+            Callback::from(move |e: DragEvent| {
+                // Firefox only starts a drag that carries data.
+                if let Some(data) = e.data_transfer() {
+                    let _ = data.set_data("text/plain", &id);
+                }
+                dragged.set(Some(index));
+            })
+        };
+        let ondragend = {
+            let dragged = dragged.clone();
+            Callback::from(move |_| dragged.set(None))
+        };
+        let ondrop = {
+            let dragged = dragged.clone();
+            let on_move = props.on_move.clone();
+            Callback::from(move |e: DragEvent| {
+                e.prevent_default();
+                if let Some(from) = *dragged {
+                    if from != index {
+                        on_move.emit((from, index));
+                    }
+                }
+                dragged.set(None);
+            })
+        };
+        let open = {
+            let id = pin.id.clone();
+            props.on_open.reform(move |_| id.clone())
+        };
+        let unpin = {
+            let id = pin.id.clone();
+            props.on_unpin.reform(move |_| id.clone())
+        };
+        let active = props.active.as_ref() == Some(&pin.id);
+        html! {
+            <div
+                draggable="true"
+                {ondragstart}
+                {ondragend}
+                ondragover={Callback::from(|e: DragEvent| e.prevent_default())}
+                {ondrop}
+                class={classes!("group", "flex", "items-center", "px-3", "py-1", "cursor-move",
+                    (*dragged == Some(index)).then_some("opacity-50"))}
+            >
+                <button
+                    onclick={open}
+                    class={classes!("flex", "items-center", "flex-grow", "min-w-0", "rounded-lg", "p-2", "text-sm",
+                        if active { "bg-green-100 dark:bg-green-900" } else { "hover:bg-gray-200 dark:hover:bg-gray-700" })}
+                >
+                    <img class="w-8 h-8 rounded-full" src={pin.avatar.clone()} alt="avatar"/>
+                    <span class="ml-3 truncate">{"🔒 "}{pin.name.clone()}</span>
+                </button>
+                <button
+                    onclick={unpin}
+                    title={t("dm-unpin")}
+                    class="invisible group-hover:visible ml-1 text-xs text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white"
+                    aria-label={t("dm-unpin")}
+                >
+                    {"✕"}
+                </button>
+            </div>
+        }
+    });
+
+    html! {
+        <div class="py-2 border-b border-gray-300 dark:border-gray-700">
+            <div class="px-3 text-xs uppercase text-gray-500 dark:text-gray-400">{t("dm-pinned")}</div>
+            { for rows }
+        </div>
+    }
+}
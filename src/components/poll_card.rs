@@ -0,0 +1,73 @@
+use yew::prelude::*;
+
+use crate::services::i18n::{t, t_args};
+use crate::services::polls::Poll;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub poll: Poll,
+    /// Our user id, to show our vote and whether we may close the poll.
+    pub me: String,
+    /// Receives the option picked, or `None` when we take our vote back.
+    pub on_vote: Callback<Option<usize>>,
+    pub on_close: Callback<()>,
+}
+
+/// A poll in the message list, with a bar per option that fills with its
+/// share of the votes.
+#[function_component(PollCard)]
+pub fn poll_card(props: &Props) -> Html {
+    let poll = &props.poll;
+    let mine = poll.choice(&props.me);
+    let total = poll.total();
+
+    let options = poll.options.iter().enumerate().map(|(index, option)| {
+        let count = poll.count(index);
+        let share = (count * 100).checked_div(total).unwrap_or(0);
+        let chosen = mine == Some(index);
+        // Picking our own choice again takes the vote back.
+        let onclick = props.on_vote.reform(move |_| (!chosen).then_some(index));
+        html! {
+            <li>
+                <button
+                    {onclick}
+                    disabled={poll.closed}
+                    aria-pressed={chosen.to_string()}
+                    class={classes!("relative", "w-full", "text-left", "rounded", "overflow-hidden", "border", "px-3", "py-1", "text-sm",
+                        if chosen { "border-green-500" } else { "border-gray-300 dark:border-gray-600" },
+                        (!poll.closed).then_some("hover:border-green-400"))}
+                >
+                    <span class="absolute inset-y-0 left-0 bg-green-100 dark:bg-green-900" style={format!("width: {}%", share)}></span>
+                    <span class="relative flex justify-between">
+                        <span class="truncate">{ if chosen { "✓ " } else { "" } }{option}</span>
+                        <span class="ml-3 text-gray-500 dark:text-gray-400">{count}</span>
+                    </span>
+                </button>
+            </li>
+        }
+    });
+
+    html! {
+        <div class="max-w-md" role="group" aria-label={poll.question.clone()}>
+            <div class="font-bold">{"📊 "}{&poll.question}</div>
+            <ul class="mt-2 space-y-1">{ for options }</ul>
+            <div class="flex items-center justify-between mt-2 text-xs text-gray-500 dark:text-gray-400">
+                <span>
+                    {t_args("poll-votes", &[("count", &total.to_string())])}
+                    { if poll.closed { format!(" · {}", t("poll-closed")) } else { String::new() } }
+                </span>
+                {
+                    if !poll.closed && poll.creator_id == props.me {
+                        html! {
+                            <button onclick={props.on_close.reform(|_| ())} class="hover:text-black dark:hover:text-white">
+                                {t("poll-close")}
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+            </div>
+        </div>
+    }
+}
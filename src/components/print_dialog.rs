@@ -0,0 +1,89 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::t;
+
+/// Days to print, as milliseconds since the epoch. Open ends include
+/// everything on that side.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PrintRange {
+    /// Start of the first day.
+    pub from: Option<f64>,
+    /// End of the last day, exclusive.
+    pub until: Option<f64>,
+}
+
+impl PrintRange {
+    /// Messages without a timestamp are only printed when the whole
+    /// conversation is.
+    pub fn contains(&self, timestamp: Option<f64>) -> bool {
+        match timestamp {
+            Some(ts) => self.from.is_none_or(|from| ts >= from) && self.until.is_none_or(|until| ts < until),
+            None => self.from.is_none() && self.until.is_none(),
+        }
+    }
+}
+
+/// Local midnight starting the `YYYY-MM-DD` day of a date input, plus
+/// `days_after` days.
+fn local_day_start(value: &str, days_after: i32) -> Option<f64> {
+    let mut parts = value.splitn(3, '-').map(str::parse::<i32>);
+    let (year, month, day) = (parts.next()?.ok()?, parts.next()?.ok()?, parts.next()?.ok()?);
+    let date = js_sys::Date::new_with_year_month_day(year as u32, month - 1, day + days_after);
+    Some(date.get_time())
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub on_print: Callback<PrintRange>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(PrintDialog)]
+pub fn print_dialog(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let from = use_state(String::new);
+    let until = use_state(String::new);
+
+    let input = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: Event| state.set(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+    let range = PrintRange {
+        from: local_day_start(&from, 0),
+        until: local_day_start(&until, 1),
+    };
+    let invalid = matches!((range.from, range.until), (Some(from), Some(until)) if from >= until);
+    let onclick = props.on_print.reform(move |_| range);
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("print-title")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t("print-title")}</div>
+                    <button onclick={props.on_close.reform(|_| ())} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                <div class="text-sm text-gray-600 dark:text-gray-300 mb-4">{t("print-help")}</div>
+                <label class="block text-sm mb-2">
+                    {t("print-from")}
+                    <input type="date" onchange={input(&from)} class="block w-full mt-1 p-2 rounded border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700"/>
+                </label>
+                <label class="block text-sm mb-4">
+                    {t("print-until")}
+                    <input type="date" onchange={input(&until)} class="block w-full mt-1 p-2 rounded border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700"/>
+                </label>
+                {
+                    if invalid {
+                        html! {<div class="text-sm text-red-500 mb-2">{t("print-invalid-range")}</div>}
+                    } else {
+                        html! {}
+                    }
+                }
+                <button {onclick} disabled={invalid} class="px-4 py-2 rounded bg-green-600 text-white disabled:opacity-50">
+                    {t("print-action")}
+                </button>
+            </div>
+        </div>
+    }
+}
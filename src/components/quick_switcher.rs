@@ -0,0 +1,142 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::t;
+
+/// A conversation the switcher can open: `None` for the room, else the
+/// direct message peer's id.
+#[derive(Clone, PartialEq)]
+pub struct Destination {
+    pub peer: Option<String>,
+    pub label: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub destinations: Vec<Destination>,
+    /// Heading for the dialog when it picks a conversation for something
+    /// other than opening it.
+    #[prop_or_default]
+    pub title: Option<String>,
+    pub on_pick: Callback<Option<String>>,
+    pub on_close: Callback<()>,
+}
+
+/// Jumps to a conversation by typing part of its name, opened with Ctrl+K.
+#[function_component(QuickSwitcher)]
+pub fn quick_switcher(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let query = use_state(String::new);
+    let selected = use_state(|| 0usize);
+    let input_ref = use_node_ref();
+    {
+        let input_ref = input_ref.clone();
+        use_effect_with_deps(
+            move |_| {
+                if let Some(input) = input_ref.cast::<HtmlInputElement>() {
+                    let _ = input.focus();
+                }
+                || ()
+            },
+            (),
+        );
+    }
+
+    let title = props.title.clone().unwrap_or_else(|| t("switcher-title"));
+    let lowered = query.to_lowercase();
+    let matches: Vec<&Destination> = props
+        .destinations
+        .iter()
+        .filter(|d| d.label.to_lowercase().contains(&lowered))
+        .collect();
+    let current = (*selected).min(matches.len().saturating_sub(1));
+
+    let oninput = {
+        let (query, selected) = (query.clone(), selected.clone());
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+            selected.set(0);
+        })
+    };
+
+    let onkeydown = {
+        let selected = selected.clone();
+        let on_pick = props.on_pick.clone();
+        let peers: Vec<Option<String>> = matches.iter().map(|d| d.peer.clone()).collect();
+        Callback::from(move |e: KeyboardEvent| match e.key().as_str() {
+            "ArrowDown" if !peers.is_empty() => {
+                e.prevent_default();
+                selected.set((current + 1) % peers.len());
+            }
+            "ArrowUp" if !peers.is_empty() => {
+                e.prevent_default();
+                selected.set((current + peers.len() - 1) % peers.len());
+            }
+            "Enter" => {
+                e.prevent_default();
+                if let Some(peer) = peers.get(current) {
+                    on_pick.emit(peer.clone());
+                }
+            }
+            _ => {}
+        })
+    };
+
+    let rows = matches
+        .iter()
+        .enumerate()
+        .map(|(index, destination)| {
+            let onclick = {
+                let peer = destination.peer.clone();
+                props.on_pick.reform(move |_| peer.clone())
+            };
+            html! {
+                <li>
+                    <button {onclick} class={classes!("w-full", "text-left", "px-3", "py-2", "rounded", "truncate",
+                        if index == current { "bg-green-600 text-white" } else { "hover:bg-gray-200 dark:hover:bg-gray-700" })}>
+                        {destination.label.clone()}
+                    </button>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-start justify-center pt-24 z-10" onclick={props.on_close.reform(|_| ())}>
+            <div
+                ref={panel}
+                tabindex="-1"
+                role="dialog"
+                aria-modal="true"
+                aria-label={title.clone()}
+                onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}
+                class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-4 w-full max-w-md outline-none"
+            >
+                {
+                    match &props.title {
+                        Some(title) => html! {<h2 class="font-semibold mb-3">{title.clone()}</h2>},
+                        None => html! {},
+                    }
+                }
+                <input
+                    ref={input_ref}
+                    {oninput}
+                    {onkeydown}
+                    value={(*query).clone()}
+                    placeholder={t("switcher-placeholder")}
+                    aria-label={title}
+                    class="w-full bg-gray-100 dark:bg-gray-700 rounded px-3 py-2 mb-3 outline-none"
+                />
+                {
+                    if matches.is_empty() {
+                        html! {<div class="px-3 py-2 text-sm text-gray-500 dark:text-gray-400">{t("switcher-no-match")}</div>}
+                    } else {
+                        html! {<ul class="max-h-80 overflow-y-auto">{rows}</ul>}
+                    }
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,122 @@
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::{t, t_args};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tab {
+    About,
+    Stats,
+}
+
+/// A most-reacted message as listed in the stats tab.
+#[derive(Clone, PartialEq)]
+pub struct TopMessage {
+    /// Index into the chat's messages, for jumping to it.
+    pub index: usize,
+    pub from: String,
+    pub preview: String,
+    pub reactions: usize,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub room: String,
+    #[prop_or_default]
+    pub topic: Option<String>,
+    #[prop_or_default]
+    pub member_count: Option<usize>,
+    pub top_reactions: Vec<(String, usize)>,
+    pub top_messages: Vec<TopMessage>,
+    pub on_jump: Callback<usize>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(RoomInfoPanel)]
+pub fn room_info_panel(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let tab = use_state(|| Tab::About);
+
+    let tab_button = |target: Tab, label: String| {
+        let onclick = {
+            let tab = tab.clone();
+            Callback::from(move |_| tab.set(target))
+        };
+        html! {
+            <button {onclick} class={classes!("px-3", "py-1", "rounded", "mr-2", "text-sm",
+                if *tab == target { "bg-green-600 text-white" } else { "bg-gray-200 dark:bg-gray-700" })}>
+                {label}
+            </button>
+        }
+    };
+
+    let body = match *tab {
+        Tab::About => html! {
+            <>
+            <div class="mb-2">
+                { props.topic.clone().unwrap_or_else(|| t("room-info-no-topic")) }
+            </div>
+            {
+                match props.member_count {
+                    Some(count) => html! {
+                        <div class="text-sm text-gray-600 dark:text-gray-300">
+                            {t_args("room-info-members", &[("count", &count.to_string())])}
+                        </div>
+                    },
+                    None => html! {},
+                }
+            }
+            </>
+        },
+        Tab::Stats if props.top_reactions.is_empty() => html! {
+            <div class="text-sm text-gray-500 dark:text-gray-400">{t("room-stats-empty")}</div>
+        },
+        Tab::Stats => html! {
+            <>
+            <div class="text-xs text-gray-500 dark:text-gray-400 mb-3">{t("room-stats-window")}</div>
+            <div class="font-semibold mb-2">{t("room-stats-top-reactions")}</div>
+            <div class="flex flex-wrap mb-4">
+                {
+                    props.top_reactions.iter().map(|(emoji, count)| html! {
+                        <span class="mr-2 mb-2 px-2 py-1 rounded-full bg-gray-200 dark:bg-gray-700 text-sm">
+                            {emoji.clone()}{" "}{count}
+                        </span>
+                    }).collect::<Html>()
+                }
+            </div>
+            <div class="font-semibold mb-2">{t("room-stats-top-messages")}</div>
+            {
+                props.top_messages.iter().map(|m| {
+                    let index = m.index;
+                    let onclick = props.on_jump.reform(move |_| index);
+                    html! {
+                        <button {onclick} title={t("room-stats-jump")} class="block w-full text-left mb-2 p-2 rounded hover:bg-gray-200 dark:hover:bg-gray-700">
+                            <div class="flex justify-between text-xs text-gray-500 dark:text-gray-400">
+                                <span>{m.from.clone()}</span>
+                                <span>{t_args("room-stats-reaction-count", &[("count", &m.reactions.to_string())])}</span>
+                            </div>
+                            <div class="text-sm truncate">{m.preview.clone()}</div>
+                        </button>
+                    }
+                }).collect::<Html>()
+            }
+            </>
+        },
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={format!("#{}", props.room)} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{format!("#{}", props.room)}</div>
+                    <button onclick={props.on_close.reform(|_| ())} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                <div class="flex mb-4">
+                    {tab_button(Tab::About, t("room-info-about"))}
+                    {tab_button(Tab::Stats, t("room-info-stats"))}
+                </div>
+                {body}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,38 @@
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::{t, t_args};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub room: String,
+    pub rules: Vec<String>,
+    pub on_accept: Callback<()>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(RulesModal)]
+pub fn rules_modal(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let on_accept = props.on_accept.reform(|_| ());
+    let on_close = props.on_close.reform(|_| ());
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-20">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t_args("rules-title", &[("room", &props.room)])} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-2">
+                    <div class="text-xl">{t_args("rules-title", &[("room", &props.room)])}</div>
+                    <button onclick={on_close.clone()} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                <div class="text-sm text-gray-500 dark:text-gray-400 mb-4">{t("rules-intro")}</div>
+                <ol class="list-decimal list-inside text-sm space-y-2 mb-6">
+                    { for props.rules.iter().map(|rule| html! {<li>{rule}</li>}) }
+                </ol>
+                <div class="flex justify-end">
+                    <button onclick={on_close} class="px-4 py-2 mr-2 rounded bg-gray-200 dark:bg-gray-700">{t("cancel")}</button>
+                    <button onclick={on_accept} class="px-4 py-2 rounded bg-green-600 text-white">{t("rules-accept")}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
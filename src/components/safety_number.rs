@@ -0,0 +1,108 @@
+use qrcode::{Color, QrCode};
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::{t, t_args};
+
+/// `data` as an SVG QR code, one unit per module plus the quiet zone.
+fn qr_code(data: &str) -> Html {
+    let code = match QrCode::new(data) {
+        Ok(code) => code,
+        Err(e) => {
+            log::error!("failed to encode safety number: {:?}", e);
+            return html! {};
+        }
+    };
+    let width = code.width();
+    let path: String = code
+        .to_colors()
+        .iter()
+        .enumerate()
+        .filter(|(_, color)| **color == Color::Dark)
+        .map(|(i, _)| format!("M{} {}h1v1h-1z", i % width + 4, i / width + 4))
+        .collect();
+    let size = width + 8;
+    html! {
+        <svg viewBox={format!("0 0 {} {}", size, size)} class="w-48 h-48 mx-auto bg-white" shape-rendering="crispEdges" aria-hidden="true">
+            <path d={path} fill="black"/>
+        </svg>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub peer_name: String,
+    pub our_fingerprint: String,
+    pub their_fingerprint: String,
+    /// Both fingerprints in an order both sides agree on, as encoded in the
+    /// QR code.
+    pub safety_number: String,
+    pub verified: bool,
+    /// Their key changed since we last saw it.
+    pub changed: bool,
+    pub on_verify: Callback<()>,
+    pub on_unverify: Callback<()>,
+    pub on_close: Callback<()>,
+}
+
+/// Both parties' key fingerprints, to compare in person or over another
+/// channel before trusting a conversation.
+#[function_component(SafetyNumberDialog)]
+pub fn safety_number_dialog(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let name = props.peer_name.as_str();
+    let fingerprint = |label: String, digits: &str| {
+        html! {
+            <div class="mb-3">
+                <div class="text-sm text-gray-500 dark:text-gray-400">{label}</div>
+                <div class="font-mono tracking-wider select-all">{digits}</div>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("verify-title")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t("verify-title")}</div>
+                    <button onclick={props.on_close.reform(|_| ())} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                {
+                    if props.changed {
+                        html! {
+                            <div role="alert" class="mb-4 p-3 rounded bg-red-100 dark:bg-red-900 text-red-800 dark:text-red-200 text-sm">
+                                {t_args("verify-key-changed", &[("name", name)])}
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div class="text-sm text-gray-600 dark:text-gray-300 mb-4">{t_args("verify-help", &[("name", name)])}</div>
+                {qr_code(&props.safety_number)}
+                <div class="mt-4">
+                    {fingerprint(t("verify-yours"), &props.our_fingerprint)}
+                    {fingerprint(t_args("verify-theirs", &[("name", name)]), &props.their_fingerprint)}
+                </div>
+                {
+                    if props.verified {
+                        html! {
+                            <div class="flex items-center justify-between">
+                                <span class="text-green-700 dark:text-green-400">{format!("✓ {}", t("verify-verified"))}</span>
+                                <button onclick={props.on_unverify.reform(|_| ())} class="px-4 py-2 rounded bg-gray-200 dark:bg-gray-700">
+                                    {t("verify-clear")}
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        html! {
+                            <button onclick={props.on_verify.reform(|_| ())} class="px-4 py-2 rounded bg-green-600 text-white">
+                                {t("verify-mark")}
+                            </button>
+                        }
+                    }
+                }
+            </div>
+        </div>
+    }
+}
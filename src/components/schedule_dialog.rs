@@ -0,0 +1,91 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::t;
+
+/// Local time of a `YYYY-MM-DDTHH:MM` datetime input, as milliseconds since
+/// the epoch.
+fn local_time(value: &str) -> Option<f64> {
+    let (date, time) = value.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<i32>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let (hours, minutes) = time.split_once(':')?;
+    let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.get(..2)?.parse().ok()?);
+    let date = js_sys::Date::new_with_year_month_day_hr_min(year as u32, month - 1, day, hours, minutes);
+    Some(date.get_time())
+}
+
+/// The next 9:00 after today, a common pick for "later".
+fn tomorrow_morning() -> f64 {
+    let now = js_sys::Date::new_0();
+    js_sys::Date::new_with_year_month_day_hr_min(now.get_full_year(), now.get_month() as i32, now.get_date() as i32 + 1, 9, 0)
+        .get_time()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    /// Receives the chosen time, in milliseconds since the epoch.
+    pub on_schedule: Callback<f64>,
+    pub on_close: Callback<()>,
+}
+
+/// Picks when the message in the composer should go out.
+#[function_component(ScheduleDialog)]
+pub fn schedule_dialog(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let value = use_state(String::new);
+
+    let oninput = {
+        let value = value.clone();
+        Callback::from(move |e: InputEvent| value.set(e.target_unchecked_into::<HtmlInputElement>().value()))
+    };
+    let chosen = local_time(&value);
+    let past = chosen.is_some_and(|at| at <= js_sys::Date::now());
+    let preset = |label: String, at: f64| {
+        let onclick = props.on_schedule.reform(move |_| at);
+        html! {
+            <button {onclick} class="px-3 py-1 mr-2 mb-2 rounded bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600 text-sm">
+                {label}
+            </button>
+        }
+    };
+    let onclick = {
+        let on_schedule = props.on_schedule.clone();
+        Callback::from(move |_| {
+            if let Some(at) = chosen {
+                on_schedule.emit(at);
+            }
+        })
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("schedule-title")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t("schedule-title")}</div>
+                    <button onclick={props.on_close.reform(|_| ())} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                <div class="text-sm text-gray-600 dark:text-gray-300 mb-4">{t("schedule-help")}</div>
+                <div class="mb-2">
+                    {preset(t("schedule-in-hour"), js_sys::Date::now() + 60.0 * 60.0 * 1000.0)}
+                    {preset(t("schedule-tomorrow"), tomorrow_morning())}
+                </div>
+                <label class="block text-sm mb-4">
+                    {t("schedule-at")}
+                    <input type="datetime-local" {oninput} value={(*value).clone()} class="block w-full mt-1 p-2 rounded border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700"/>
+                </label>
+                {
+                    if past {
+                        html! {<div class="text-sm text-red-500 mb-2">{t("schedule-in-past")}</div>}
+                    } else {
+                        html! {}
+                    }
+                }
+                <button {onclick} disabled={chosen.is_none() || past} class="px-4 py-2 rounded bg-green-600 text-white disabled:opacity-50">
+                    {t("schedule-action")}
+                </button>
+            </div>
+        </div>
+    }
+}
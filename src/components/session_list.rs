@@ -0,0 +1,63 @@
+use yew::prelude::*;
+
+use crate::services::i18n::t;
+use crate::services::session::SessionEntry;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub sessions: Vec<SessionEntry>,
+    pub current: String,
+    pub on_revoke: Callback<String>,
+}
+
+fn format_time(millis: f64) -> String {
+    js_sys::Date::new(&millis.into())
+        .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+        .into()
+}
+
+#[function_component(SessionList)]
+pub fn session_list(props: &Props) -> Html {
+    if props.sessions.is_empty() {
+        return html! {
+            <div class="text-sm text-gray-500 dark:text-gray-400">{t("sessions-empty")}</div>
+        };
+    }
+
+    props
+        .sessions
+        .iter()
+        .map(|session| {
+            let current = session.id == props.current;
+            let on_revoke = {
+                let id = session.id.clone();
+                props.on_revoke.reform(move |_| id.clone())
+            };
+            html! {
+                <div class="flex items-center justify-between bg-gray-200 dark:bg-gray-700 rounded px-3 py-2 mb-2 text-sm">
+                    <div>
+                        <div>
+                            {session.device.clone()}
+                            {
+                                if current {
+                                    html! {<span class="ml-2 text-xs text-green-600">{t("sessions-this-device")}</span>}
+                                } else {
+                                    html! {}
+                                }
+                            }
+                        </div>
+                        <div class="text-xs text-gray-500 dark:text-gray-400">
+                            {
+                                match &session.address_hint {
+                                    Some(hint) => format!("{} · {}", format_time(session.last_active), hint),
+                                    None => format_time(session.last_active),
+                                }
+                            }
+                        </div>
+                    </div>
+                    <button onclick={on_revoke} class="text-xs text-red-500 hover:underline">{t("sessions-sign-out")}</button>
+                </div>
+            }
+        })
+        .collect::<Html>()
+}
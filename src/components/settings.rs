@@ -0,0 +1,431 @@
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::avatar::AvatarStyle;
+use crate::services::i18n::{t, t_args, Locale};
+use crate::services::settings::{
+    format_minutes, parse_minutes, NotificationLevel, QuietHours, Settings,
+};
+use crate::services::theme::Theme;
+use crate::services::transport::TransportPreset;
+use crate::services::typing::TypingSignal;
+use crate::services::websocket::{self, DEFAULT_URL};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub settings: Settings,
+    pub channel: String,
+    pub on_change: Callback<Settings>,
+    pub on_close: Callback<()>,
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// Everything the user can set, in one place. Changes are handed up as a
+/// whole new [`Settings`], which the chat saves and applies.
+#[function_component(SettingsScreen)]
+pub fn settings_screen(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let settings = &props.settings;
+    // An address that was typed but is not a websocket URL; not saved.
+    let bad_server_url = use_state(|| None::<String>);
+
+    let level_buttons = NotificationLevel::ALL
+        .iter()
+        .map(|level| {
+            let level = *level;
+            let onclick = {
+                let settings = settings.clone();
+                let on_change = props.on_change.clone();
+                Callback::from(move |_| {
+                    let mut settings = settings.clone();
+                    settings.notification_level = level;
+                    on_change.emit(settings);
+                })
+            };
+            let selected = settings.notification_level == level;
+            html! {
+                <button {onclick} class={classes!("px-3", "py-1", "rounded", "mr-2", "text-sm",
+                    if selected { "bg-green-600 text-white" } else { "bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600" })}>
+                    {level.label()}
+                </button>
+            }
+        })
+        .collect::<Html>();
+
+    let toggle_quiet_hours = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_| {
+            let mut settings = settings.clone();
+            settings.quiet_hours = match settings.quiet_hours {
+                Some(_) => None,
+                None => Some(QuietHours::default()),
+            };
+            on_change.emit(settings);
+        })
+    };
+
+    let quiet_hours_input = |start: bool| {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let (Some(minutes), Some(mut quiet)) =
+                (parse_minutes(&input.value()), settings.quiet_hours)
+            {
+                if start {
+                    quiet.start = minutes;
+                } else {
+                    quiet.end = minutes;
+                }
+                let mut settings = settings.clone();
+                settings.quiet_hours = Some(quiet);
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    let on_override = {
+        let settings = settings.clone();
+        let channel = props.channel.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            let level = NotificationLevel::ALL
+                .iter()
+                .find(|l| format!("{:?}", l) == select.value())
+                .copied();
+            match level {
+                Some(level) => settings.channel_overrides.insert(channel.clone(), level),
+                None => settings.channel_overrides.remove(&channel),
+            };
+            on_change.emit(settings);
+        })
+    };
+    let channel_override = settings.channel_overrides.get(&props.channel).copied();
+
+    let on_keywords = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.keywords = input
+                .value()
+                .split(',')
+                .map(|k| k.trim().to_string())
+                .filter(|k| !k.is_empty())
+                .collect();
+            on_change.emit(settings);
+        })
+    };
+
+    let on_filtered_words = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.filtered_words = input
+                .value()
+                .split(',')
+                .map(|w| w.trim().to_string())
+                .filter(|w| !w.is_empty())
+                .collect();
+            on_change.emit(settings);
+        })
+    };
+
+    let toggle_group_mentions = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_| {
+            let mut settings = settings.clone();
+            settings.notify_group_mentions = !settings.notify_group_mentions;
+            on_change.emit(settings);
+        })
+    };
+
+    let on_proxy = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            let value = input.value().trim().to_string();
+            settings.media_proxy = (!value.is_empty()).then_some(value);
+            on_change.emit(settings);
+        })
+    };
+
+    let toggle_originals = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_| {
+            let mut settings = settings.clone();
+            settings.load_original_media = !settings.load_original_media;
+            on_change.emit(settings);
+        })
+    };
+
+    let on_typing_signal = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let signal = TypingSignal::ALL
+                .iter()
+                .find(|s| format!("{:?}", s) == select.value());
+            if let Some(signal) = signal {
+                let mut settings = settings.clone();
+                settings.typing_signal = *signal;
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    let on_transport = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let preset = TransportPreset::ALL
+                .iter()
+                .find(|p| format!("{:?}", p) == select.value());
+            if let Some(preset) = preset {
+                let mut settings = settings.clone();
+                settings.transport = *preset;
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    let on_locale = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(locale) = Locale::ALL.iter().find(|l| l.id() == select.value()) {
+                let mut settings = settings.clone();
+                settings.locale = *locale;
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    let on_theme = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(theme) = Theme::ALL.iter().find(|t| format!("{:?}", t) == select.value()) {
+                let mut settings = settings.clone();
+                settings.theme = *theme;
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    let on_avatar_style = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(style) = AvatarStyle::ALL.iter().find(|s| format!("{:?}", s) == select.value()) {
+                let mut settings = settings.clone();
+                settings.avatar_style = *style;
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    let toggle_sound = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_| {
+            let mut settings = settings.clone();
+            settings.sound_muted = !settings.sound_muted;
+            on_change.emit(settings);
+        })
+    };
+
+    let on_server_url = {
+        let settings = settings.clone();
+        let on_change = props.on_change.clone();
+        let bad_server_url = bad_server_url.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value().trim().to_string();
+            if !value.is_empty() && !websocket::valid_url(&value) {
+                bad_server_url.set(Some(value));
+                return;
+            }
+            bad_server_url.set(None);
+            let mut settings = settings.clone();
+            settings.server_url = (!value.is_empty() && value != DEFAULT_URL).then_some(value);
+            on_change.emit(settings);
+        })
+    };
+
+    let on_close = props.on_close.reform(|_| ());
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("preferences")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t("preferences")}</div>
+                    <button onclick={on_close} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+
+                <div class="text-lg mb-2">{t("prefs-language")}</div>
+                <select onchange={on_locale} class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm mb-6">
+                    {
+                        Locale::ALL.iter().map(|locale| html! {
+                            <option value={locale.id()} selected={settings.locale == *locale}>{locale.name()}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+
+                <div class="text-lg mb-2">{t("prefs-appearance")}</div>
+                <div class="flex flex-wrap items-center text-sm mb-6">
+                    <select onchange={on_theme} aria-label={t("prefs-theme")} class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 mr-2 mb-1">
+                        {
+                            Theme::ALL.iter().map(|theme| html! {
+                                <option value={format!("{:?}", theme)} selected={settings.theme == *theme}>{theme.label()}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                    <label class="flex items-center text-gray-600 dark:text-gray-300 mb-1">
+                        <span class="mr-2">{t("prefs-avatar-style")}</span>
+                        <select onchange={on_avatar_style} class="bg-gray-200 dark:bg-gray-700 text-gray-900 dark:text-white rounded px-2 py-1">
+                            {
+                                AvatarStyle::ALL.iter().map(|style| html! {
+                                    <option value={format!("{:?}", style)} selected={settings.avatar_style == *style}>{style.label()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </label>
+                </div>
+
+                <div class="text-lg mb-2">{t("prefs-notifications")}</div>
+                <div class="text-sm text-gray-600 dark:text-gray-300 mb-2">{t("prefs-notify-about")}</div>
+                <div class="flex mb-4">{level_buttons}</div>
+
+                <label class="flex items-center text-sm text-gray-600 dark:text-gray-300 mb-2">
+                    <input type="checkbox" class="mr-2" checked={settings.sound_muted} onclick={toggle_sound} />
+                    {t("sound-mute")}
+                </label>
+
+                <label class="flex items-center text-sm text-gray-600 dark:text-gray-300 mb-2">
+                    <input type="checkbox" class="mr-2" checked={settings.quiet_hours.is_some()} onclick={toggle_quiet_hours} />
+                    {t("prefs-quiet-hours")}
+                </label>
+                {
+                    if let Some(quiet) = settings.quiet_hours {
+                        html! {
+                            <div class="flex items-center mb-6 text-sm">
+                                <input type="time" class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-1" value={format_minutes(quiet.start)} onchange={quiet_hours_input(true)} />
+                                <span class="mx-2">{t("prefs-quiet-hours-to")}</span>
+                                <input type="time" class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-1" value={format_minutes(quiet.end)} onchange={quiet_hours_input(false)} />
+                            </div>
+                        }
+                    } else {
+                        html! {<div class="mb-6"></div>}
+                    }
+                }
+
+                <div class="text-sm text-gray-600 dark:text-gray-300 mb-2">{t_args("prefs-channel-override", &[("channel", &props.channel)])}</div>
+                <select onchange={on_override} class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm">
+                    <option value="" selected={channel_override.is_none()}>{t("prefs-use-default")}</option>
+                    {
+                        NotificationLevel::ALL.iter().map(|level| html! {
+                            <option value={format!("{:?}", level)} selected={channel_override == Some(*level)}>{level.label()}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+
+                <div class="text-sm text-gray-600 dark:text-gray-300 mt-4 mb-1">{t("prefs-keywords")}</div>
+                <input
+                    type="text"
+                    class="w-full bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm mb-2"
+                    placeholder={t("prefs-keywords-placeholder")}
+                    value={settings.keywords.join(", ")}
+                    onchange={on_keywords}
+                />
+                <label class="flex items-center text-sm text-gray-600 dark:text-gray-300">
+                    <input type="checkbox" class="mr-2" checked={settings.notify_group_mentions} onclick={toggle_group_mentions} />
+                    {t("prefs-group-mentions")}
+                </label>
+
+                <div class="text-lg mt-6 mb-2">{t("prefs-media")}</div>
+                <div class="text-sm text-gray-600 dark:text-gray-300 mb-1">{t("prefs-media-proxy")}</div>
+                <input
+                    type="text"
+                    class="w-full bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm mb-1"
+                    placeholder="https://proxy.example.com/?url={url}"
+                    value={settings.media_proxy.clone().unwrap_or_default()}
+                    onchange={on_proxy}
+                />
+                <div class="text-xs text-gray-500 dark:text-gray-400 mb-2">
+                    {t("prefs-media-proxy-help")}
+                </div>
+                <label class="flex items-center text-sm text-gray-600 dark:text-gray-300">
+                    <input type="checkbox" class="mr-2" checked={settings.load_original_media} onclick={toggle_originals} />
+                    {t("prefs-load-originals")}
+                </label>
+
+                <div class="text-sm text-gray-600 dark:text-gray-300 mt-4 mb-1">{t("prefs-word-filter")}</div>
+                <input
+                    type="text"
+                    class="w-full bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm mb-1"
+                    placeholder={t("prefs-word-filter-placeholder")}
+                    value={settings.filtered_words.join(", ")}
+                    onchange={on_filtered_words}
+                />
+                <div class="text-xs text-gray-500 dark:text-gray-400">{t("prefs-word-filter-help")}</div>
+
+                <div class="text-lg mt-6 mb-2">{t("prefs-privacy")}</div>
+                <div class="text-sm text-gray-600 dark:text-gray-300 mb-1">{t("prefs-typing-signal")}</div>
+                <select onchange={on_typing_signal} class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm">
+                    {
+                        TypingSignal::ALL.iter().map(|signal| html! {
+                            <option value={format!("{:?}", signal)} selected={settings.typing_signal == *signal}>{signal.label()}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <div class="text-xs text-gray-500 dark:text-gray-400 mt-1">{t("prefs-typing-signal-help")}</div>
+
+                <div class="text-lg mt-6 mb-2">{t("prefs-connection")}</div>
+                <select onchange={on_transport} class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-1 text-sm">
+                    {
+                        TransportPreset::ALL.iter().map(|preset| html! {
+                            <option value={format!("{:?}", preset)} selected={settings.transport == *preset}>{preset.label()}</option>
+                        }).collect::<Html>()
+                    }
+                </select>
+                <div class="text-xs text-gray-500 dark:text-gray-400 mt-1">{t("prefs-connection-help")}</div>
+                <div class="text-sm text-gray-600 dark:text-gray-300 mt-4 mb-1">{t("prefs-server-url")}</div>
+                <input
+                    type="url"
+                    class={classes!("w-full", "bg-gray-200", "dark:bg-gray-700", "rounded", "px-2", "py-1", "text-sm", "mb-1",
+                        bad_server_url.is_some().then_some("border border-red-500"))}
+                    placeholder={DEFAULT_URL}
+                    value={(*bad_server_url).clone().or_else(|| settings.server_url.clone()).unwrap_or_default()}
+                    onchange={on_server_url}
+                />
+                {
+                    if bad_server_url.is_some() {
+                        html! {<div class="text-xs text-red-500">{t("prefs-server-url-invalid")}</div>}
+                    } else {
+                        html! {<div class="text-xs text-gray-500 dark:text-gray-400">{t("prefs-server-url-help")}</div>}
+                    }
+                }
+
+                { for props.children.iter() }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,43 @@
+use yew::prelude::*;
+
+use crate::components::dialog::use_dialog_focus;
+use crate::services::i18n::t;
+use crate::services::shortcuts::Action;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub on_close: Callback<()>,
+}
+
+/// The keyboard shortcuts, opened with `?`.
+#[function_component(ShortcutHelp)]
+pub fn shortcut_help(props: &Props) -> Html {
+    let panel = use_dialog_focus();
+    let rows = Action::ALL
+        .iter()
+        .filter(|action| action.available())
+        .map(|action| {
+            html! {
+                <tr class="border-t border-gray-300 dark:border-gray-700">
+                    <td class="py-2 pr-4 whitespace-nowrap">
+                        <kbd class="px-2 py-1 rounded bg-gray-200 dark:bg-gray-700 font-mono text-xs">{action.keys()}</kbd>
+                    </td>
+                    <td class="py-2">{action.label()}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-10">
+            <div ref={panel} tabindex="-1" role="dialog" aria-modal="true" aria-label={t("shortcuts-title")} class="bg-white dark:bg-gray-800 text-gray-900 dark:text-white rounded-lg p-6 w-full max-w-md max-h-screen overflow-y-auto outline-none">
+                <div class="flex justify-between items-center mb-4">
+                    <div class="text-xl">{t("shortcuts-title")}</div>
+                    <button onclick={props.on_close.reform(|_| ())} class="text-gray-500 dark:text-gray-400 hover:text-black dark:hover:text-white" aria-label={t("close")}>{"✕"}</button>
+                </div>
+                <table class="w-full text-sm text-left"><tbody>{rows}</tbody></table>
+                <div class="mt-4 text-xs text-gray-500 dark:text-gray-400">{t("shortcuts-mac")}</div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,35 @@
+use yew::prelude::*;
+
+use crate::services::capabilities::Capability;
+use crate::services::i18n::t;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub missing: Vec<Capability>,
+}
+
+/// Shown instead of the app when the browser lacks something it needs, so
+/// older browsers and locked-down webviews get an explanation rather than a
+/// blank page.
+#[function_component(UnsupportedBrowser)]
+pub fn unsupported_browser(props: &Props) -> Html {
+    html! {
+        <div class="flex w-screen h-screen items-center justify-center bg-white dark:bg-gray-800 text-gray-900 dark:text-white p-6">
+            <div class="max-w-md">
+                <div class="text-xl mb-2">{t("unsupported-title")}</div>
+                <div class="text-sm text-gray-600 dark:text-gray-300 mb-4">{t("unsupported-intro")}</div>
+                <ul class="mb-4">
+                    {
+                        props.missing.iter().map(|capability| html! {
+                            <li class="mb-2">
+                                <div class="font-semibold">{capability.label()}</div>
+                                <div class="text-sm text-gray-600 dark:text-gray-300">{capability.help()}</div>
+                            </li>
+                        }).collect::<Html>()
+                    }
+                </ul>
+                <div class="text-sm text-gray-600 dark:text-gray-300">{t("unsupported-advice")}</div>
+            </div>
+        </div>
+    }
+}
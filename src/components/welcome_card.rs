@@ -0,0 +1,50 @@
+use yew::prelude::*;
+
+use crate::services::i18n::{t, t_args};
+use crate::services::rooms::WelcomeRule;
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub room: String,
+    pub rule: WelcomeRule,
+}
+
+fn section(title: String, items: &[String]) -> Html {
+    if items.is_empty() {
+        return html! {};
+    }
+    html! {
+        <div class="mt-3">
+            <div class="text-xs uppercase text-gray-500 dark:text-gray-400 mb-1">{title}</div>
+            <ol class="list-decimal list-inside text-sm space-y-1">
+                { for items.iter().map(|item| html! {<li>{item}</li>}) }
+            </ol>
+        </div>
+    }
+}
+
+/// Onboarding card shown as a local-only message the first time someone
+/// joins a room with a welcome rule.
+#[function_component(WelcomeCard)]
+pub fn welcome_card(props: &Props) -> Html {
+    let rule = &props.rule;
+    html! {
+        <div class="max-w-md">
+            <div class="font-bold">{t_args("welcome-card-title", &[("room", &props.room)])}</div>
+            {section(t("welcome-card-rules"), &rule.rules)}
+            {section(t("welcome-card-pinned"), &rule.pinned)}
+            {
+                match &rule.roles {
+                    Some(roles) => html! {
+                        <div class="mt-3">
+                            <div class="text-xs uppercase text-gray-500 dark:text-gray-400 mb-1">{t("welcome-card-roles")}</div>
+                            <p class="text-sm whitespace-pre-wrap">{roles}</p>
+                        </div>
+                    },
+                    None => html! {},
+                }
+            }
+            <div class="mt-3 text-xs text-gray-500 dark:text-gray-400">{t("welcome-card-local")}</div>
+        </div>
+    }
+}
@@ -11,8 +11,16 @@ use yew::functional::*;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use components::chat::Chat;
+use components::chat::{self, Chat};
 use components::login::Login;
+use components::oauth_callback::OAuthCallback;
+use components::unsupported_browser::{self, UnsupportedBrowser};
+use services::avatar;
+use services::capabilities;
+use services::i18n::{self, t, Locale};
+use services::pwa;
+use services::settings::Settings;
+use services::theme;
 
 // When the `wee_alloc` feature is enabled, this uses `wee_alloc` as the global
 // allocator.
@@ -22,12 +30,18 @@ use components::login::Login;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-#[derive(Debug, Clone, Copy, PartialEq, Routable)]
+#[derive(Debug, Clone, PartialEq, Routable)]
 pub enum Route {
     #[at("/")]
     Login,
     #[at("/chat")]
     Chat,
+    /// A room, where permalinks point with `#msg-<id>`.
+    #[at("/chat/:channel")]
+    Channel { channel: String },
+    // Must match `auth::OAUTH_CALLBACK_PATH`.
+    #[at("/oauth/callback")]
+    OAuthCallback,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -42,34 +56,93 @@ pub struct UserInner {
 
 #[function_component(Main)]
 fn main() -> Html {
+    // A session kept from before a reload signs straight back in.
     let ctx = use_state(|| {
+        let username = services::auth::stored()
+            .map(|session| session.username)
+            .unwrap_or_else(|| "initial".into());
         Rc::new(UserInner {
-            username: RefCell::new("initial".into()),
+            username: RefCell::new(username),
         })
     });
 
+    // A newer version of the app, installed and waiting to take over.
+    let update = use_state(|| None::<web_sys::ServiceWorker>);
+    {
+        let update = update.clone();
+        use_effect_with_deps(
+            move |_| {
+                pwa::register(Callback::from(move |worker| update.set(Some(worker))));
+                || ()
+            },
+            (),
+        );
+    }
+    let update_prompt = match &*update {
+        Some(worker) => {
+            let reload = {
+                let worker = worker.clone();
+                Callback::from(move |_| pwa::apply_update(&worker))
+            };
+            let dismiss = {
+                let update = update.clone();
+                Callback::from(move |_| update.set(None))
+            };
+            html! {
+                <div class="fixed bottom-4 right-4 z-40 flex items-center bg-gray-800 text-white text-sm rounded-lg shadow-lg px-4 py-3 print:hidden">
+                    <span>{t("update-available")}</span>
+                    <button onclick={reload} class="ml-4 font-semibold text-green-400 hover:underline">{t("update-reload")}</button>
+                    <button onclick={dismiss} title={t("update-later")} class="ml-3 text-gray-400 hover:text-white" aria-label={t("update-later")}>{"✕"}</button>
+                </div>
+            }
+        }
+        None => html! {},
+    };
+
     html! {
         <ContextProvider<User> context={(*ctx).clone()}>
             <BrowserRouter>
-                <div class="flex w-screen h-screen">
+                <div class="flex w-screen h-screen print:block print:h-auto">
                     <Switch<Route> render={Switch::render(switch)}/>
                 </div>
             </BrowserRouter>
+            {update_prompt}
         </ContextProvider<User>>
     }
 }
 
+// Components without props are checked with a `let` of their `()` props.
+#[allow(clippy::let_unit_value, clippy::unnecessary_operation)]
 fn switch(selected_route: &Route) -> Html {
     match selected_route {
+        Route::Login if services::auth::stored().is_some() => html! {<Redirect<Route> to={Route::Chat}/>},
         Route::Login => html! {<Login />},
+        Route::Chat | Route::Channel { .. } if services::auth::stored().is_none() => {
+            html! {<Redirect<Route> to={Route::Login}/>}
+        }
         Route::Chat => html! {<Chat/>},
-        Route::NotFound => html! {<h1>{"404 baby"}</h1>},
+        Route::Channel { channel } if channel == chat::CHANNEL => html! {<Chat/>},
+        Route::Channel { .. } => html! {<h1>{t("not-found")}</h1>},
+        Route::OAuthCallback => html! {<OAuthCallback/>},
+        Route::NotFound => html! {<h1>{t("not-found")}</h1>},
     }
 }
 
 #[wasm_bindgen]
 pub fn run_app() -> Result<(), JsValue> {
     wasm_logger::init(wasm_logger::Config::default());
+    // Checked first, as loading settings needs local storage.
+    let missing = capabilities::missing();
+    if !missing.is_empty() {
+        log::error!("unsupported browser, missing {:?}", missing);
+        i18n::set_locale(Locale::detect());
+        yew::start_app_with_props::<UnsupportedBrowser>(unsupported_browser::Props { missing });
+        return Ok(());
+    }
+    let settings = Settings::load();
+    i18n::set_locale(settings.locale);
+    theme::apply(settings.theme);
+    avatar::set_style(settings.avatar_style);
     yew::start_app::<Main>();
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,208 @@
+//! A development aid: the latest messages the chat component handled, each
+//! with the state before and after it, to see what led up to the state on
+//! screen and to step back and forth through it. Only recorded while the
+//! panel is open, and never in release builds.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Write};
+use std::rc::Rc;
+
+/// Off in release builds.
+pub const ENABLED: bool = cfg!(debug_assertions);
+/// Every entry holds two snapshots of the whole message list, so this stays
+/// modest.
+const CAPACITY: usize = 100;
+/// Longer descriptions, e.g. whole websocket payloads, are cut here.
+const MAX_LEN: usize = 300;
+
+/// State the log can take snapshots of and put back.
+pub trait Snapshot: Clone {
+    /// What differs from `before`, one line per change.
+    fn changes(&self, before: &Self) -> Vec<String>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoggedAction {
+    /// Milliseconds since the epoch.
+    pub at: f64,
+    pub action: String,
+    pub changes: Vec<String>,
+    /// Whether the state on screen includes this action, i.e. we have not
+    /// stepped back past it.
+    pub applied: bool,
+}
+
+struct Entry<S> {
+    at: f64,
+    action: String,
+    before: Rc<S>,
+    /// Taken when the next action comes in, so whatever rendering changed
+    /// counts too. `None` until then.
+    after: Option<Rc<S>>,
+}
+
+pub struct ActionLog<S> {
+    entries: VecDeque<Entry<S>>,
+    /// While stepping through, how many entries the state on screen
+    /// includes. `None` when it is live.
+    position: Option<usize>,
+}
+
+impl<S> Default for ActionLog<S> {
+    fn default() -> Self {
+        ActionLog {
+            entries: VecDeque::new(),
+            position: None,
+        }
+    }
+}
+
+fn truncate(mut text: String) -> String {
+    if let Some((end, _)) = text.char_indices().nth(MAX_LEN) {
+        text.truncate(end);
+        text.push('…');
+    }
+    text
+}
+
+/// Takes up to `MAX_LEN` characters, then fails, which stops formatting.
+#[derive(Default)]
+struct Bounded {
+    text: String,
+    chars: usize,
+}
+
+impl Write for Bounded {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.chars == MAX_LEN {
+                return Err(fmt::Error);
+            }
+            self.text.push(c);
+            self.chars += 1;
+        }
+        Ok(())
+    }
+}
+
+/// `value`'s debug output, cut short while it is written, so a huge value
+/// is never formatted whole.
+pub fn describe(value: &dyn Debug) -> String {
+    let mut out = Bounded::default();
+    if write!(out, "{:?}", value).is_err() {
+        out.text.push('…');
+    }
+    out.text
+}
+
+impl<S: Snapshot> ActionLog<S> {
+    /// Logs `action` as about to be handled in `state`, which is also the
+    /// outcome of the one before. While stepping through, `state` must be the
+    /// latest one, not the one on screen.
+    pub fn record(&mut self, action: &impl Debug, state: S) {
+        if !ENABLED {
+            return;
+        }
+        let state = Rc::new(state);
+        self.settle_with(&state);
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+            self.position = self.position.map(|p| p.saturating_sub(1));
+        }
+        self.entries.push_back(Entry {
+            at: js_sys::Date::now(),
+            action: describe(action),
+            before: state,
+            after: None,
+        });
+    }
+
+    /// Takes `state` as the outcome of the latest action, before showing or
+    /// stepping through the log, or after handling one while stepping
+    /// through. Like for `record`, it must be the latest state.
+    pub fn settle(&mut self, state: S) {
+        self.settle_with(&Rc::new(state));
+    }
+
+    fn settle_with(&mut self, state: &Rc<S>) {
+        if let Some(last) = self.entries.back_mut().filter(|e| e.after.is_none()) {
+            last.after = Some(state.clone());
+        }
+    }
+
+    /// Newest first.
+    pub fn entries(&self) -> Vec<LoggedAction> {
+        let applied = self.position.unwrap_or(self.entries.len());
+        self.entries
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, entry)| LoggedAction {
+                at: entry.at,
+                action: entry.action.clone(),
+                changes: match &entry.after {
+                    Some(after) => after.changes(&entry.before).into_iter().map(truncate).collect(),
+                    None => Vec::new(),
+                },
+                applied: index < applied,
+            })
+            .collect()
+    }
+
+    pub fn can_step_back(&self) -> bool {
+        self.position.unwrap_or(self.entries.len()) > 0
+    }
+
+    /// Whether an earlier state is on screen; stepping forward is possible
+    /// until back at the latest.
+    pub fn stepping_through(&self) -> bool {
+        self.position.is_some()
+    }
+
+    /// The state before the latest action still applied, to put back.
+    pub fn step_back(&mut self) -> Option<S> {
+        let position = self.position.unwrap_or(self.entries.len()).checked_sub(1)?;
+        self.position = Some(position);
+        Some(S::clone(&self.entries[position].before))
+    }
+
+    /// The state after the next action we stepped back past, to put back.
+    pub fn step_forward(&mut self) -> Option<S> {
+        let position = self.position?;
+        let entry = self.entries.get(position)?;
+        let state = S::clone(entry.after.as_ref()?);
+        self.position = Some(position + 1).filter(|&p| p < self.entries.len());
+        Some(state)
+    }
+
+    /// The latest state, to put back when done stepping through.
+    pub fn live(&mut self) -> Option<S> {
+        self.position.take()?;
+        self.latest()
+    }
+
+    /// The latest state, while an earlier one is on screen, to handle what
+    /// cannot wait for the end of stepping through.
+    pub fn latest(&self) -> Option<S> {
+        self.entries.back()?.after.as_deref().cloned()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.position = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_stops_at_the_limit() {
+        let long = "x".repeat(10 * MAX_LEN);
+        let text = describe(&long);
+        assert_eq!(text.chars().count(), MAX_LEN + 1);
+        assert!(text.ends_with('…'));
+        assert_eq!(describe(&Some("short")), "Some(\"short\")");
+    }
+}
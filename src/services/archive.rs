@@ -0,0 +1,154 @@
+//! Conversations saved to a file: the JSON format they are written in, and
+//! handing files to the browser as downloads.
+
+use gloo_file::Blob;
+use gloo_timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, Url};
+
+use crate::services::attachments::Attachment;
+use crate::services::i18n::{t, t_args};
+
+/// Bumped whenever a change to [`Archive`] would trip up older readers.
+pub const VERSION: u32 = 1;
+/// Largest file [`parse`] is asked to read, well above any realistic export.
+pub const MAX_IMPORT_SIZE: u64 = 20 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Archive {
+    pub version: u32,
+    pub room: String,
+    /// Milliseconds since the epoch.
+    pub exported_at: f64,
+    pub messages: Vec<ArchivedMessage>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedMessage {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub from_id: Option<String>,
+    pub from: String,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub attachment: Option<Attachment>,
+    #[serde(default)]
+    pub timestamp: Option<f64>,
+    /// The other side, by user id, for direct messages; these are stored
+    /// decrypted.
+    #[serde(default)]
+    pub direct: Option<String>,
+    #[serde(default)]
+    pub bot: bool,
+}
+
+/// Why a file could not be opened as an archive.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArchiveError {
+    TooLarge,
+    Unreadable,
+    /// Not an archive's JSON, with serde's description, which includes where.
+    Malformed(String),
+    /// Written by a newer version of the app.
+    UnsupportedVersion(u32),
+    /// A message, numbered from 1, lacks a sender.
+    MissingSender(usize),
+    /// A message, numbered from 1, has a time that is not a date.
+    BadTimestamp(usize),
+}
+
+impl ArchiveError {
+    pub fn message(&self) -> String {
+        match self {
+            ArchiveError::TooLarge => t("import-too-large"),
+            ArchiveError::Unreadable => t("import-unreadable"),
+            ArchiveError::Malformed(reason) => t_args("import-malformed", &[("reason", reason)]),
+            ArchiveError::UnsupportedVersion(version) => {
+                t_args("import-unsupported-version", &[("version", &version.to_string())])
+            }
+            ArchiveError::MissingSender(n) => t_args("import-missing-sender", &[("number", &n.to_string())]),
+            ArchiveError::BadTimestamp(n) => t_args("import-bad-timestamp", &[("number", &n.to_string())]),
+        }
+    }
+}
+
+/// Reads and checks an exported archive.
+pub fn parse(json: &str) -> Result<Archive, ArchiveError> {
+    let archive: Archive = serde_json::from_str(json).map_err(|e| ArchiveError::Malformed(e.to_string()))?;
+    if archive.version == 0 || archive.version > VERSION {
+        return Err(ArchiveError::UnsupportedVersion(archive.version));
+    }
+    for (i, message) in archive.messages.iter().enumerate() {
+        if message.from.trim().is_empty() {
+            return Err(ArchiveError::MissingSender(i + 1));
+        }
+        if message.timestamp.is_some_and(|ts| !ts.is_finite() || ts < 0.0) {
+            return Err(ArchiveError::BadTimestamp(i + 1));
+        }
+    }
+    Ok(archive)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Text,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Text => "txt",
+        }
+    }
+
+    fn mime(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Text => "text/plain;charset=utf-8",
+        }
+    }
+}
+
+/// Local date of `millis` as `YYYY-MM-DD`, for file names.
+fn file_date(millis: f64) -> String {
+    let date = js_sys::Date::new(&millis.into());
+    format!("{}-{:02}-{:02}", date.get_full_year(), date.get_month() + 1, date.get_date())
+}
+
+pub fn file_name(room: &str, format: ExportFormat) -> String {
+    format!("yewchat-{}-{}.{}", room, file_date(js_sys::Date::now()), format.extension())
+}
+
+/// Saves `contents` through the browser's download flow.
+pub fn download(name: &str, format: ExportFormat, contents: &str) {
+    let document = match web_sys::window().and_then(|w| w.document()) {
+        Some(document) => document,
+        None => return,
+    };
+    let blob = Blob::new_with_options(contents, Some(format.mime()));
+    let url = match Url::create_object_url_with_blob(blob.as_ref()) {
+        Ok(url) => url,
+        Err(e) => {
+            log::error!("failed to create download url: {:?}", e);
+            return;
+        }
+    };
+    if let Ok(link) = document.create_element("a") {
+        let link = link.unchecked_into::<HtmlElement>();
+        let _ = link.set_attribute("href", &url);
+        let _ = link.set_attribute("download", name);
+        link.click();
+    }
+    // Revoking the URL right away can cancel the download in some browsers.
+    Timeout::new(10_000, move || {
+        let _ = Url::revoke_object_url(&url);
+    })
+    .forget();
+}
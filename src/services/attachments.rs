@@ -0,0 +1,176 @@
+use futures::channel::oneshot;
+use gloo_events::EventListener;
+use gloo_file::{futures::read_as_data_url, File};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{ProgressEvent, XmlHttpRequest};
+
+use crate::services::i18n::t;
+
+/// Files up to this size are sent inline as `data:` URLs instead of being
+/// uploaded.
+const INLINE_LIMIT: u64 = 256 * 1024;
+
+/// Endpoint that accepts a raw file body and answers with `{"url": ...}`.
+const UPLOAD_URL: Option<&str> = option_env!("YEWCHAT_UPLOAD_URL");
+
+const IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub name: String,
+    pub mime: String,
+    pub size: u64,
+    pub url: String,
+}
+
+#[derive(Debug)]
+pub enum AttachmentError {
+    TooLarge,
+    Failed(String),
+}
+
+impl AttachmentError {
+    pub fn message(&self) -> String {
+        match self {
+            AttachmentError::TooLarge => t("attachment-too-large"),
+            AttachmentError::Failed(_) => t("attachment-failed"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    url: String,
+}
+
+pub fn is_image(mime: &str) -> bool {
+    IMAGE_TYPES.contains(&mime)
+}
+
+/// Picks an icon for the download card from the MIME type, falling back to
+/// the file extension for files the browser could not classify.
+pub fn icon(attachment: &Attachment) -> &'static str {
+    let extension = attachment
+        .name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    let mime = attachment.mime.as_str();
+
+    if mime.starts_with("image/") {
+        "🖼️"
+    } else if mime.starts_with("audio/") {
+        "🎵"
+    } else if mime.starts_with("video/") {
+        "🎬"
+    } else if mime == "application/pdf" || extension == "pdf" {
+        "📕"
+    } else if mime.contains("zip")
+        || mime.contains("compressed")
+        || matches!(extension.as_str(), "zip" | "tar" | "gz" | "7z" | "rar")
+    {
+        "🗜️"
+    } else if mime.contains("spreadsheet")
+        || mime == "text/csv"
+        || matches!(extension.as_str(), "csv" | "xls" | "xlsx" | "ods")
+    {
+        "📊"
+    } else if mime.contains("presentation") || matches!(extension.as_str(), "ppt" | "pptx" | "odp") {
+        "📽️"
+    } else if mime.starts_with("text/") || mime.contains("document") || mime == "application/json" {
+        "📄"
+    } else {
+        "📎"
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Whether sending `size` bytes involves an upload, as opposed to embedding.
+pub fn needs_upload(size: u64) -> bool {
+    size > INLINE_LIMIT
+}
+
+/// Turns a picked file into something that can be sent over the socket:
+/// small files are embedded, larger ones are uploaded first. `on_progress`
+/// receives the uploaded fraction between 0 and 1.
+pub async fn prepare(
+    file: File,
+    on_progress: impl Fn(f64) + 'static,
+) -> Result<Attachment, AttachmentError> {
+    let mime = match file.raw_mime_type() {
+        mime if mime.is_empty() => "application/octet-stream".to_string(),
+        mime => mime,
+    };
+
+    let url = if needs_upload(file.size()) {
+        upload(&file, &mime, on_progress).await?
+    } else {
+        read_as_data_url(&file)
+            .await
+            .map_err(|e| AttachmentError::Failed(e.to_string()))?
+    };
+
+    Ok(Attachment {
+        name: file.name(),
+        mime,
+        size: file.size(),
+        url,
+    })
+}
+
+fn js_error(e: wasm_bindgen::JsValue) -> AttachmentError {
+    AttachmentError::Failed(format!("{:?}", e))
+}
+
+/// Uploads with `XMLHttpRequest` rather than `fetch`, since only the former
+/// reports upload progress.
+async fn upload(
+    file: &File,
+    mime: &str,
+    on_progress: impl Fn(f64) + 'static,
+) -> Result<String, AttachmentError> {
+    let endpoint = UPLOAD_URL.ok_or(AttachmentError::TooLarge)?;
+    let xhr = XmlHttpRequest::new().map_err(js_error)?;
+    xhr.open("POST", endpoint).map_err(js_error)?;
+    xhr.set_request_header("Content-Type", mime).map_err(js_error)?;
+
+    let upload = xhr.upload().map_err(js_error)?;
+    let _progress = EventListener::new(&upload, "progress", move |event| {
+        if let Some(event) = event.dyn_ref::<ProgressEvent>() {
+            if event.length_computable() && event.total() > 0.0 {
+                on_progress(event.loaded() / event.total());
+            }
+        }
+    });
+    let (done_tx, done_rx) = oneshot::channel();
+    let _done = EventListener::once(&xhr, "loadend", move |_| {
+        let _ = done_tx.send(());
+    });
+
+    xhr.send_with_opt_blob(Some(file.as_ref())).map_err(js_error)?;
+    let _ = done_rx.await;
+
+    let status = xhr.status().map_err(js_error)?;
+    if !(200..300).contains(&status) {
+        return Err(AttachmentError::Failed(format!("upload failed with status {}", status)));
+    }
+    let body = xhr.response_text().map_err(js_error)?.unwrap_or_default();
+    serde_json::from_str::<UploadResponse>(&body)
+        .map(|r| r.url)
+        .map_err(|e| AttachmentError::Failed(e.to_string()))
+}
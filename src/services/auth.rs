@@ -0,0 +1,264 @@
+//! Password and OAuth accounts. Registering or signing in over HTTP yields a
+//! session token, which the chat presents in its websocket `Register` message
+//! instead of a bare, unverified username.
+
+use std::ops::RangeInclusive;
+
+use gloo_storage::{LocalStorage, SessionStorage, Storage};
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+
+use crate::services::i18n::{t, t_args};
+use crate::services::session;
+
+const AUTH_URL: &str = match option_env!("YEWCHAT_AUTH_URL") {
+    Some(url) => url,
+    None => "http://127.0.0.1:8080/auth",
+};
+const SESSION_KEY: &str = "yewchat.auth";
+/// The direct message peer open when the page was left, reopened on restore.
+const CONVERSATION_KEY: &str = "yewchat.last_conversation";
+/// Provider and anti-forgery state of an OAuth redirect in flight. Kept per
+/// tab, as the redirect comes back to the same one.
+const OAUTH_STATE_KEY: &str = "yewchat.oauth_state";
+/// Where providers send the browser back to; must be registered with them.
+pub const OAUTH_CALLBACK_PATH: &str = "/oauth/callback";
+
+pub const MIN_PASSWORD_LEN: usize = 8;
+pub const USERNAME_LEN: RangeInclusive<usize> = 3..=24;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthSession {
+    pub token: String,
+    pub user_id: String,
+    pub username: String,
+    /// Picture from the OAuth provider's profile, if signed in through one.
+    #[serde(default)]
+    pub avatar: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OAuthProvider {
+    GitHub,
+    Google,
+}
+
+impl OAuthProvider {
+    pub const ALL: [OAuthProvider; 2] = [OAuthProvider::GitHub, OAuthProvider::Google];
+
+    pub fn label(&self) -> String {
+        t(match self {
+            OAuthProvider::GitHub => "oauth-github",
+            OAuthProvider::Google => "oauth-google",
+        })
+    }
+
+    fn id(&self) -> &'static str {
+        match self {
+            OAuthProvider::GitHub => "github",
+            OAuthProvider::Google => "google",
+        }
+    }
+
+    fn client_id(&self) -> Option<&'static str> {
+        match self {
+            OAuthProvider::GitHub => option_env!("YEWCHAT_GITHUB_CLIENT_ID"),
+            OAuthProvider::Google => option_env!("YEWCHAT_GOOGLE_CLIENT_ID"),
+        }
+    }
+
+    /// Providers this build has a client id for.
+    pub fn configured() -> Vec<OAuthProvider> {
+        OAuthProvider::ALL
+            .into_iter()
+            .filter(|p| p.client_id().is_some())
+            .collect()
+    }
+
+    /// The provider's consent page, remembering the provider and a fresh
+    /// state for [`complete_oauth`].
+    pub fn authorize_url(&self) -> Option<String> {
+        let client_id = self.client_id()?;
+        let state = session::random_id();
+        if let Err(e) = SessionStorage::set(OAUTH_STATE_KEY, (self, &state)) {
+            log::error!("failed to persist oauth state: {:?}", e);
+            return None;
+        }
+        let (base, scope) = match self {
+            OAuthProvider::GitHub => ("https://github.com/login/oauth/authorize", "read:user"),
+            OAuthProvider::Google => ("https://accounts.google.com/o/oauth2/v2/auth", "openid profile"),
+        };
+        let encode = |value: &str| String::from(js_sys::encode_uri_component(value));
+        Some(format!(
+            "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&state={}",
+            base,
+            encode(client_id),
+            encode(&redirect_uri()),
+            encode(scope),
+            state,
+        ))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OAuthExchange<'a> {
+    code: &'a str,
+    redirect_uri: &'a str,
+}
+
+#[derive(Serialize)]
+struct Credentials<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    PasswordTooShort,
+    UsernameLength,
+    /// Names are limited to letters, digits, `_`, `-` and `.`.
+    UsernameCharset,
+    InvalidCredentials,
+    UsernameTaken,
+    /// The provider redirected back with an error, or with a state we did not
+    /// hand out.
+    OAuthRejected,
+    Failed(String),
+}
+
+impl AuthError {
+    pub fn message(&self) -> String {
+        match self {
+            AuthError::PasswordTooShort => {
+                t_args("auth-password-too-short", &[("min", &MIN_PASSWORD_LEN.to_string())])
+            }
+            AuthError::UsernameLength => t_args(
+                "auth-username-length",
+                &[
+                    ("min", &USERNAME_LEN.start().to_string()),
+                    ("max", &USERNAME_LEN.end().to_string()),
+                ],
+            ),
+            AuthError::UsernameCharset => t("auth-username-charset"),
+            AuthError::InvalidCredentials => t("auth-invalid-credentials"),
+            AuthError::UsernameTaken => t("auth-username-taken"),
+            AuthError::OAuthRejected => t("auth-oauth-rejected"),
+            AuthError::Failed(_) => t("auth-failed"),
+        }
+    }
+}
+
+/// Checks a name for a new account. Existing accounts are not held to it,
+/// so signing in skips this.
+pub fn validate_username(username: &str) -> Result<(), AuthError> {
+    if !USERNAME_LEN.contains(&username.chars().count()) {
+        return Err(AuthError::UsernameLength);
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(AuthError::UsernameCharset);
+    }
+    Ok(())
+}
+
+pub async fn register(username: &str, password: &str) -> Result<AuthSession, AuthError> {
+    validate_username(username)?;
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(AuthError::PasswordTooShort);
+    }
+    authenticate("register", username, password).await
+}
+
+pub async fn login(username: &str, password: &str) -> Result<AuthSession, AuthError> {
+    authenticate("login", username, password).await
+}
+
+/// Finishes an OAuth redirect from the callback URL's query string: checks
+/// the state and has the server trade the code for a session, with the
+/// username and avatar taken from the provider profile.
+pub async fn complete_oauth(query: &str) -> Result<AuthSession, AuthError> {
+    let params = web_sys::UrlSearchParams::new_with_str(query)
+        .map_err(|e| AuthError::Failed(format!("{:?}", e)))?;
+    let expected = SessionStorage::get::<(OAuthProvider, String)>(OAUTH_STATE_KEY).ok();
+    SessionStorage::delete(OAUTH_STATE_KEY);
+    let (provider, code) = match (expected, params.get("state"), params.get("code")) {
+        (Some((provider, expected)), Some(state), Some(code)) if state == expected => (provider, code),
+        _ => return Err(AuthError::OAuthRejected),
+    };
+    let body = serde_json::to_string(&OAuthExchange {
+        code: &code,
+        redirect_uri: &redirect_uri(),
+    })
+    .unwrap();
+    post(&format!("oauth/{}", provider.id()), body).await
+}
+
+fn redirect_uri() -> String {
+    let origin = web_sys::window()
+        .and_then(|w| w.location().origin().ok())
+        .unwrap_or_default();
+    format!("{}{}", origin, OAUTH_CALLBACK_PATH)
+}
+
+async fn authenticate(action: &str, username: &str, password: &str) -> Result<AuthSession, AuthError> {
+    post(action, serde_json::to_string(&Credentials { username, password }).unwrap()).await
+}
+
+async fn post(action: &str, body: String) -> Result<AuthSession, AuthError> {
+    let response = Request::post(&format!("{}/{}", AUTH_URL, action))
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AuthError::Failed(e.to_string()))?;
+    match response.status() {
+        200..=299 => {
+            let session = response
+                .json::<AuthSession>()
+                .await
+                .map_err(|e| AuthError::Failed(e.to_string()))?;
+            store(&session);
+            Ok(session)
+        }
+        401 | 403 => Err(AuthError::InvalidCredentials),
+        409 => Err(AuthError::UsernameTaken),
+        status => Err(AuthError::Failed(format!("HTTP {}", status))),
+    }
+}
+
+/// The session from the last successful sign-in, if it was not cleared since.
+pub fn stored() -> Option<AuthSession> {
+    LocalStorage::get(SESSION_KEY).ok()
+}
+
+fn store(session: &AuthSession) {
+    if let Err(e) = LocalStorage::set(SESSION_KEY, session) {
+        log::error!("failed to persist auth session: {:?}", e);
+    }
+}
+
+pub fn clear() {
+    LocalStorage::delete(SESSION_KEY);
+    LocalStorage::delete(CONVERSATION_KEY);
+}
+
+/// The conversation to land on when a stored session is restored; `None` is
+/// the room.
+pub fn last_conversation() -> Option<String> {
+    LocalStorage::get(CONVERSATION_KEY).ok()
+}
+
+pub fn set_last_conversation(peer: Option<&str>) {
+    match peer {
+        Some(peer) => {
+            if let Err(e) = LocalStorage::set(CONVERSATION_KEY, peer) {
+                log::error!("failed to persist last conversation: {:?}", e);
+            }
+        }
+        None => LocalStorage::delete(CONVERSATION_KEY),
+    }
+}
@@ -0,0 +1,60 @@
+//! Generated pictures for people who have no avatar of their own.
+
+use std::cell::Cell;
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::i18n::t;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum AvatarStyle {
+    #[default]
+    Adventurer,
+    Bottts,
+    Identicon,
+    Initials,
+}
+
+impl AvatarStyle {
+    pub const ALL: [AvatarStyle; 4] = [
+        AvatarStyle::Adventurer,
+        AvatarStyle::Bottts,
+        AvatarStyle::Identicon,
+        AvatarStyle::Initials,
+    ];
+
+    pub fn label(&self) -> String {
+        t(match self {
+            AvatarStyle::Adventurer => "avatar-adventurer",
+            AvatarStyle::Bottts => "avatar-bottts",
+            AvatarStyle::Identicon => "avatar-identicon",
+            AvatarStyle::Initials => "avatar-initials",
+        })
+    }
+
+    fn path(&self) -> &'static str {
+        match self {
+            AvatarStyle::Adventurer => "adventurer-neutral",
+            AvatarStyle::Bottts => "bottts",
+            AvatarStyle::Identicon => "identicon",
+            AvatarStyle::Initials => "initials",
+        }
+    }
+
+    pub fn url(&self, seed: &str) -> String {
+        format!("https://avatars.dicebear.com/api/{}/{}.svg", self.path(), seed)
+    }
+}
+
+thread_local! {
+    static STYLE: Cell<AvatarStyle> = const { Cell::new(AvatarStyle::Adventurer) };
+}
+
+pub fn set_style(style: AvatarStyle) {
+    STYLE.with(|s| s.set(style));
+}
+
+/// The generated avatar for `seed` in the style currently set.
+pub fn url(seed: &str) -> String {
+    STYLE.with(|s| s.get()).url(seed)
+}
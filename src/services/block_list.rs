@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+const STORAGE_KEY: &str = "yewchat.block_list";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BlockList {
+    muted: HashSet<String>,
+    blocked: HashSet<String>,
+}
+
+impl BlockList {
+    pub fn load() -> Self {
+        LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist block list: {:?}", e);
+        }
+    }
+
+    pub fn is_muted(&self, user_id: &str) -> bool {
+        self.muted.contains(user_id)
+    }
+
+    pub fn is_blocked(&self, user_id: &str) -> bool {
+        self.blocked.contains(user_id)
+    }
+
+    /// Moves entries over to a new key. Only needed for peers without a
+    /// stable id, whose name doubles as their id.
+    pub fn rename(&mut self, old: &str, new: &str) {
+        let mut changed = false;
+        for set in [&mut self.muted, &mut self.blocked] {
+            if set.remove(old) {
+                set.insert(new.to_string());
+                changed = true;
+            }
+        }
+        if changed {
+            self.save();
+        }
+    }
+
+    pub fn toggle_mute(&mut self, user_id: &str) {
+        if !self.muted.remove(user_id) {
+            self.muted.insert(user_id.to_string());
+        }
+        self.save();
+    }
+
+    pub fn toggle_block(&mut self, user_id: &str) {
+        if !self.blocked.remove(user_id) {
+            self.blocked.insert(user_id.to_string());
+        }
+        self.save();
+    }
+}
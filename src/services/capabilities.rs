@@ -0,0 +1,64 @@
+//! Browser features the app cannot run without. WebAssembly itself is checked
+//! by `bootstrap.js` before any of this code loads.
+
+use wasm_bindgen::JsValue;
+
+use crate::services::i18n::t;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Capability {
+    WebSocket,
+    IndexedDb,
+    LocalStorage,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 3] = [
+        Capability::WebSocket,
+        Capability::IndexedDb,
+        Capability::LocalStorage,
+    ];
+
+    fn global(&self) -> &'static str {
+        match self {
+            Capability::WebSocket => "WebSocket",
+            Capability::IndexedDb => "indexedDB",
+            Capability::LocalStorage => "localStorage",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        t(match self {
+            Capability::WebSocket => "capability-websocket",
+            Capability::IndexedDb => "capability-indexeddb",
+            Capability::LocalStorage => "capability-local-storage",
+        })
+    }
+
+    /// What the app needs it for, and the usual reason it is missing.
+    pub fn help(&self) -> String {
+        t(match self {
+            Capability::WebSocket => "capability-websocket-help",
+            Capability::IndexedDb => "capability-indexeddb-help",
+            Capability::LocalStorage => "capability-local-storage-help",
+        })
+    }
+
+    fn available(&self) -> bool {
+        let window = match web_sys::window() {
+            Some(window) => window,
+            None => return false,
+        };
+        // Storage getters throw rather than return nothing when blocked,
+        // e.g. in sandboxed frames or with cookies disabled.
+        js_sys::Reflect::get(&window, &JsValue::from_str(self.global()))
+            .is_ok_and(|value| !value.is_undefined() && !value.is_null())
+    }
+}
+
+pub fn missing() -> Vec<Capability> {
+    Capability::ALL
+        .into_iter()
+        .filter(|capability| !capability.available())
+        .collect()
+}
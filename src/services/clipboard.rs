@@ -0,0 +1,36 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+/// Puts `text` on the clipboard. `navigator.clipboard` is still behind
+/// web-sys' unstable APIs, so it is called reflectively.
+pub fn write_text(text: &str) {
+    copy(text, || ());
+}
+
+/// Like [`write_text`], calling `on_copied` once the text really is on the
+/// clipboard.
+pub fn copy(text: &str, on_copied: impl FnOnce() + 'static) {
+    let clipboard = web_sys::window()
+        .and_then(|w| js_sys::Reflect::get(&w.navigator(), &"clipboard".into()).ok())
+        .filter(|c| !c.is_undefined());
+    let clipboard = match clipboard {
+        Some(clipboard) => clipboard,
+        None => {
+            log::error!("clipboard unavailable");
+            return;
+        }
+    };
+    let write = js_sys::Reflect::get(&clipboard, &"writeText".into())
+        .ok()
+        .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+    match write.map(|write| write.call1(&clipboard, &text.into())) {
+        Some(Ok(promise)) => spawn_local(async move {
+            match JsFuture::from(js_sys::Promise::from(promise)).await {
+                Ok(_) => on_copied(),
+                Err(e) => log::error!("failed to copy: {:?}", e),
+            }
+        }),
+        Some(Err(e)) => log::error!("failed to copy: {:?}", e),
+        None => log::error!("clipboard cannot write text"),
+    }
+}
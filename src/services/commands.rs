@@ -0,0 +1,175 @@
+//! Slash commands typed into the composer. Each command turns its arguments
+//! into an [`Outcome`] the chat carries out, so adding one only takes an entry
+//! in [`COMMANDS`].
+
+use crate::services::i18n::{t, t_args};
+use crate::services::polls;
+
+/// What the chat should do with a command.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    /// Sends text to the open conversation.
+    Send(String),
+    /// Empties the message view, without touching anyone else's.
+    Clear,
+    /// Shows text to us only, above the composer.
+    Show(String),
+    /// Posts a poll to the room: the question, then the options.
+    Poll(String, Vec<String>),
+}
+
+/// What a command gets to work with.
+pub struct Invocation<'a> {
+    /// Everything after the command name, trimmed.
+    pub args: &'a str,
+    pub username: &'a str,
+}
+
+pub struct Command {
+    pub name: &'static str,
+    /// Key of the command's line in the `/help` list.
+    help: &'static str,
+    /// `None` when the arguments do not fit the command.
+    run: fn(&Invocation) -> Option<Outcome>,
+}
+
+pub const COMMANDS: &[Command] = &[
+    Command {
+        name: "me",
+        help: "command-help-me",
+        run: |inv| (!inv.args.is_empty()).then(|| Outcome::Send(format!("*{} {}*", inv.username, inv.args))),
+    },
+    Command {
+        name: "shrug",
+        help: "command-help-shrug",
+        run: |inv| Some(Outcome::Send(format!("{} ¯\\_(ツ)_/¯", inv.args).trim_start().to_string())),
+    },
+    Command {
+        name: "poll",
+        help: "command-help-poll",
+        run: |inv| {
+            let mut args = split_args(inv.args)?.into_iter();
+            let question = args.next()?;
+            let options: Vec<String> = args.collect();
+            let distinct = options.iter().enumerate().all(|(i, option)| !options[..i].contains(option));
+            (distinct && (2..=polls::MAX_OPTIONS).contains(&options.len()))
+                .then_some(Outcome::Poll(question, options))
+        },
+    },
+    Command {
+        name: "clear",
+        help: "command-help-clear",
+        run: |_| Some(Outcome::Clear),
+    },
+    Command {
+        name: "help",
+        help: "command-help-help",
+        run: |_| Some(Outcome::Show(help())),
+    },
+];
+
+pub enum CommandError {
+    Unknown(String),
+    /// The arguments did not fit the command.
+    Usage(&'static Command),
+}
+
+impl CommandError {
+    pub fn message(&self) -> String {
+        match self {
+            CommandError::Unknown(name) => t_args("command-unknown", &[("name", name)]),
+            CommandError::Usage(command) => t_args("command-usage", &[("usage", &t(command.help))]),
+        }
+    }
+}
+
+/// Splits arguments at whitespace, keeping text in double quotes together.
+/// Blank words are dropped; `None` when a quote is left open.
+fn split_args(args: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in args.chars().chain([' ']) {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => words.push(std::mem::take(&mut word)),
+            c => word.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    Some(words.into_iter().map(|w| w.trim().to_string()).filter(|w| !w.is_empty()).collect())
+}
+
+fn help() -> String {
+    COMMANDS.iter().map(|c| t(c.help)).collect::<Vec<_>>().join("\n")
+}
+
+/// Runs `input` if it is a command. Text that merely starts with a slash,
+/// like a path, is not one, and `//` sends the rest with a single slash.
+pub fn parse(input: &str, username: &str) -> Option<Result<Outcome, CommandError>> {
+    let rest = input.trim().strip_prefix('/')?;
+    if rest.starts_with('/') {
+        return Some(Ok(Outcome::Send(rest.to_string())));
+    }
+    let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if name.is_empty() || name.contains('/') {
+        return None;
+    }
+    let name = name.to_lowercase();
+    Some(match COMMANDS.iter().find(|c| c.name == name) {
+        Some(command) => (command.run)(&Invocation {
+            args: args.trim(),
+            username,
+        })
+        .ok_or(CommandError::Usage(command)),
+        None => Err(CommandError::Unknown(name)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(input: &str) -> Option<Result<Outcome, CommandError>> {
+        parse(input, "ana")
+    }
+
+    #[test]
+    fn me_sends_an_action() {
+        assert_eq!(run("/me waves").and_then(Result::ok), Some(Outcome::Send("*ana waves*".to_string())));
+        assert_eq!(run("  /ME   waves  ").and_then(Result::ok), Some(Outcome::Send("*ana waves*".to_string())));
+        assert!(matches!(run("/me"), Some(Err(CommandError::Usage(command))) if command.name == "me"));
+        assert!(matches!(run("/me   "), Some(Err(CommandError::Usage(_)))));
+    }
+
+    #[test]
+    fn unknown_commands_are_errors() {
+        assert!(matches!(run("/nope"), Some(Err(CommandError::Unknown(name))) if name == "nope"));
+        assert!(matches!(run("/Nope at all"), Some(Err(CommandError::Unknown(name))) if name == "nope"));
+    }
+
+    #[test]
+    fn plain_text_is_not_a_command() {
+        assert!(run("hello").is_none());
+        assert!(run("/").is_none());
+        assert!(run("/ me").is_none());
+        assert!(run("/usr/bin").is_none());
+        assert_eq!(run("//me").and_then(Result::ok), Some(Outcome::Send("/me".to_string())));
+    }
+
+    #[test]
+    fn arguments_are_checked() {
+        assert_eq!(run("/shrug").and_then(Result::ok), Some(Outcome::Send("¯\\_(ツ)_/¯".to_string())));
+        assert_eq!(run("/clear everything").and_then(Result::ok), Some(Outcome::Clear));
+        assert_eq!(
+            run("/poll \"Lunch?\" pizza  tacos").and_then(Result::ok),
+            Some(Outcome::Poll("Lunch?".to_string(), vec!["pizza".to_string(), "tacos".to_string()]))
+        );
+        for poll in ["/poll", "/poll \"Lunch?\" pizza", "/poll \"Lunch? pizza tacos", "/poll q a a"] {
+            assert!(matches!(run(poll), Some(Err(CommandError::Usage(_)))), "{:?}", poll);
+        }
+    }
+}
+
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::i18n::t;
+use crate::services::sanitize;
+
+/// Where daily digests are delivered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestChannel {
+    #[default]
+    Email,
+    Webhook,
+}
+
+impl DigestChannel {
+    pub const ALL: [DigestChannel; 2] = [DigestChannel::Email, DigestChannel::Webhook];
+
+    pub fn label(&self) -> String {
+        t(match self {
+            DigestChannel::Email => "digest-email",
+            DigestChannel::Webhook => "digest-webhook",
+        })
+    }
+}
+
+/// Daily digest settings. They live on the server, which does the sending;
+/// the client only edits them and shows whether the address is confirmed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DigestPrefs {
+    pub enabled: bool,
+    pub channel: DigestChannel,
+    pub address: String,
+    /// Rooms included in the digest.
+    pub rooms: Vec<String>,
+    /// Set by the server once the address has been verified. A changed
+    /// address starts out unconfirmed again.
+    #[serde(skip_serializing)]
+    pub confirmed: bool,
+}
+
+impl DigestPrefs {
+    /// Checks the address before it is sent, returning a message to show
+    /// next to the field if it cannot work.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let address = self.address.trim();
+        match self.channel {
+            DigestChannel::Email => {
+                let valid = address
+                    .split_once('@')
+                    .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'))
+                    && !address.chars().any(char::is_whitespace);
+                valid.then_some(()).ok_or_else(|| t("digest-invalid-email"))
+            }
+            DigestChannel::Webhook => sanitize::safe_url(address)
+                .filter(|url| url.to_ascii_lowercase().starts_with("https:"))
+                .map(|_| ())
+                .ok_or_else(|| t("digest-invalid-webhook")),
+        }
+    }
+
+    pub fn follows(&self, room: &str) -> bool {
+        self.rooms.iter().any(|r| r == room)
+    }
+}
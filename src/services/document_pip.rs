@@ -0,0 +1,54 @@
+//! The Document Picture-in-Picture API: an always-on-top window holding
+//! arbitrary markup. Not yet part of web-sys, so it is looked up by name.
+
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Window;
+
+fn api() -> Option<JsValue> {
+    let window = web_sys::window()?;
+    js_sys::Reflect::get(&window, &"documentPictureInPicture".into())
+        .ok()
+        .filter(|api| !api.is_undefined() && !api.is_null())
+}
+
+pub fn is_supported() -> bool {
+    api().is_some()
+}
+
+/// Opens the window with the page's styles and theme carried over. Yew only
+/// listens for events on the main document, so handlers on elements inside
+/// it have to be attached by hand.
+pub async fn open(width: u32, height: u32) -> Result<Window, JsValue> {
+    let api = api().ok_or_else(|| JsValue::from_str("document picture-in-picture unsupported"))?;
+    let request = js_sys::Reflect::get(&api, &"requestWindow".into())?.dyn_into::<js_sys::Function>()?;
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"width".into(), &width.into())?;
+    js_sys::Reflect::set(&options, &"height".into(), &height.into())?;
+    let promise = js_sys::Promise::from(request.call1(&api, &options)?);
+    // The window is its own realm, so `instanceof Window` would not hold.
+    let window: Window = JsFuture::from(promise).await?.unchecked_into();
+    copy_styles(&window)?;
+    Ok(window)
+}
+
+fn copy_styles(target: &Window) -> Result<(), JsValue> {
+    let (source, document) = match (web_sys::window().and_then(|w| w.document()), target.document()) {
+        (Some(source), Some(document)) => (source, document),
+        _ => return Ok(()),
+    };
+    let head = match document.head() {
+        Some(head) => head,
+        None => return Ok(()),
+    };
+    let sheets = source.query_selector_all("style, link[rel=stylesheet]")?;
+    for i in 0..sheets.length() {
+        if let Some(sheet) = sheets.item(i) {
+            head.append_child(&sheet.clone_node_with_deep(true)?)?;
+        }
+    }
+    if let (Some(theme), Some(root)) = (source.document_element(), document.document_element()) {
+        root.set_class_name(&theme.class_name());
+    }
+    Ok(())
+}
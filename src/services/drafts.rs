@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use gloo_storage::{LocalStorage, Storage};
+
+const STORAGE_KEY: &str = "yewchat.drafts";
+
+/// Unsent composer text per conversation.
+#[derive(Debug, Default)]
+pub struct DraftStore {
+    drafts: HashMap<String, String>,
+}
+
+impl DraftStore {
+    pub fn load() -> Self {
+        Self {
+            drafts: LocalStorage::get(STORAGE_KEY).unwrap_or_default(),
+        }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, &self.drafts) {
+            log::error!("failed to persist drafts: {:?}", e);
+        }
+    }
+
+    pub fn get(&self, conversation: &str) -> Option<&str> {
+        self.drafts.get(conversation).map(String::as_str)
+    }
+
+    pub fn set(&mut self, conversation: &str, text: &str) {
+        if text.trim().is_empty() {
+            self.clear(conversation);
+        } else {
+            self.drafts.insert(conversation.to_string(), text.to_string());
+            self.save();
+        }
+    }
+
+    /// Forgets every draft, e.g. when signing out.
+    pub fn clear_all(&mut self) {
+        self.drafts.clear();
+        LocalStorage::delete(STORAGE_KEY);
+    }
+
+    pub fn clear(&mut self, conversation: &str) {
+        if self.drafts.remove(conversation).is_some() {
+            self.save();
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! End-to-end encryption for direct messages.
+//!
+//! Each account has an X25519 key pair per device, made on first use and kept
+//! in local storage; only the public half ever leaves the browser, except in
+//! a passphrase-sealed backup. Two people derive a shared key from their pair
+//! and the other's public key, so the server relays ciphertext it cannot
+//! read. Each message is sealed with XChaCha20-Poly1305 under a random
+//! nonce, with the sender and recipient ids as associated data so a relay
+//! cannot pass a message off as coming from, or meant for, someone else.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use gloo_storage::{LocalStorage, Storage};
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::services::i18n::t;
+
+const KEY_INFO: &[u8] = b"yewchat dm v1";
+/// Our secret keys, base64 encoded, by user id.
+const STORAGE_KEY: &str = "yewchat.identity";
+const BACKUP_VERSION: u32 = 1;
+/// Shortest passphrase a key backup may be sealed with.
+pub const MIN_PASSPHRASE_LEN: usize = 10;
+
+#[derive(Debug)]
+pub enum CryptoError {
+    /// The peer's public key is missing or malformed.
+    BadKey,
+    /// Decryption failed: the message was tampered with, or the sender's
+    /// key changed since it was sealed.
+    Unreadable,
+    /// Sealed for a key of ours this device does not have, e.g. one from
+    /// before we restored or replaced it.
+    OtherKey,
+}
+
+impl CryptoError {
+    pub fn message(&self) -> String {
+        match self {
+            CryptoError::BadKey => t("dm-no-key"),
+            CryptoError::Unreadable => t("dm-unreadable"),
+            CryptoError::OtherKey => t("dm-other-key"),
+        }
+    }
+}
+
+/// A sealed message as it travels over the socket, base64 encoded.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Sealed {
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+pub struct KeyPair {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl KeyPair {
+    pub fn generate() -> Self {
+        Self::from_secret(StaticSecret::random_from_rng(OsRng))
+    }
+
+    fn from_secret(secret: StaticSecret) -> Self {
+        let public = PublicKey::from(&secret);
+        KeyPair { secret, public }
+    }
+
+    /// This device's key pair for `user_id`, made and kept on first use.
+    pub fn load(user_id: &str) -> Self {
+        let stored: HashMap<String, String> = LocalStorage::get(STORAGE_KEY).unwrap_or_default();
+        let secret = stored.get(user_id).and_then(|s| decode_key(s));
+        match secret {
+            Some(secret) => Self::from_secret(StaticSecret::from(secret)),
+            None => {
+                let keys = Self::generate();
+                keys.save(user_id);
+                keys
+            }
+        }
+    }
+
+    /// Keeps the pair as this device's for `user_id`, in place of any other.
+    pub fn save(&self, user_id: &str) {
+        let mut stored: HashMap<String, String> = LocalStorage::get(STORAGE_KEY).unwrap_or_default();
+        stored.insert(user_id.to_string(), STANDARD.encode(self.secret.to_bytes()));
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, &stored) {
+            log::error!("failed to persist encryption key: {:?}", e);
+        }
+    }
+
+    /// Seals the secret key under `passphrase`, to restore it on another
+    /// device or after clearing this one.
+    pub fn backup(&self, user_id: &str, passphrase: &Passphrase) -> Result<KeyBackup, BackupError> {
+        let mut salt = [0u8; 16];
+        getrandom::getrandom(&mut salt).map_err(|_| BackupError::Failed)?;
+        let cipher = backup_cipher(passphrase, &salt)?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = backup_data(user_id, &self.public_key());
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: &self.secret.to_bytes(), aad: &aad })
+            .map_err(|_| BackupError::Failed)?;
+        Ok(KeyBackup {
+            version: BACKUP_VERSION,
+            user_id: user_id.to_string(),
+            public_key: self.public_key(),
+            salt: STANDARD.encode(salt),
+            sealed: Sealed {
+                nonce: STANDARD.encode(nonce),
+                ciphertext: STANDARD.encode(ciphertext),
+            },
+        })
+    }
+
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(self.public.as_bytes())
+    }
+
+    pub fn seal(&self, peer_key: &str, from: &str, to: &str, text: &str) -> Result<Sealed, CryptoError> {
+        let cipher = self.cipher(peer_key)?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = associated_data(from, to);
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: text.as_bytes(), aad: &aad })
+            .map_err(|_| CryptoError::Unreadable)?;
+        Ok(Sealed {
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+    }
+
+    pub fn open(&self, peer_key: &str, from: &str, to: &str, sealed: &Sealed) -> Result<String, CryptoError> {
+        let cipher = self.cipher(peer_key)?;
+        let nonce = STANDARD
+            .decode(&sealed.nonce)
+            .ok()
+            .filter(|n| n.len() == 24)
+            .ok_or(CryptoError::Unreadable)?;
+        let ciphertext = STANDARD
+            .decode(&sealed.ciphertext)
+            .map_err(|_| CryptoError::Unreadable)?;
+        let aad = associated_data(from, to);
+        let plain = cipher
+            .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| CryptoError::Unreadable)?;
+        String::from_utf8(plain).map_err(|_| CryptoError::Unreadable)
+    }
+
+    /// Whether `key` is our public key.
+    pub fn is_ours(&self, key: &str) -> bool {
+        parse_key(key).is_some_and(|key| key == self.public)
+    }
+
+    fn cipher(&self, peer_key: &str) -> Result<XChaCha20Poly1305, CryptoError> {
+        let peer = parse_key(peer_key).ok_or(CryptoError::BadKey)?;
+        let shared = self.secret.diffie_hellman(&peer);
+        // Both sides must use the same salt, so order the keys.
+        let mut keys = [self.public.to_bytes(), peer.to_bytes()];
+        keys.sort();
+        let hkdf = Hkdf::<Sha256>::new(Some(&keys.concat()), shared.as_bytes());
+        let mut key = [0u8; 32];
+        hkdf.expand(KEY_INFO, &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        Ok(XChaCha20Poly1305::new(&key.into()))
+    }
+}
+
+/// Whether `key` is a well-formed public key, so junk from the wire is
+/// rejected before it is stored.
+pub fn parse_key(key: &str) -> Option<PublicKey> {
+    decode_key(key).map(PublicKey::from)
+}
+
+/// Digits standing for `key`, in groups of five, to compare out of band:
+/// any change to the key changes them.
+pub fn fingerprint(user_id: &str, key: &str) -> Option<String> {
+    let key = parse_key(key)?;
+    let digest = Sha256::new()
+        .chain_update(b"yewchat fingerprint v1\0")
+        .chain_update(user_id.as_bytes())
+        .chain_update([0])
+        .chain_update(key.as_bytes())
+        .finalize();
+    // Five bytes make one group, as in Signal's safety numbers.
+    let groups: Vec<String> = digest[..30]
+        .chunks(5)
+        .map(|chunk| {
+            let value = chunk.iter().fold(0u64, |acc, &b| acc << 8 | u64::from(b));
+            format!("{:05}", value % 100_000)
+        })
+        .collect();
+    Some(groups.join(" "))
+}
+
+/// Both parties' fingerprints, ordered by user id so each side sees the same
+/// number: the safety number of a conversation.
+pub fn safety_number(ours: (&str, &str), theirs: (&str, &str)) -> Option<String> {
+    let mut parties = [ours, theirs];
+    parties.sort();
+    let [first, second] = parties;
+    Some(format!("{} {}", fingerprint(first.0, first.1)?, fingerprint(second.0, second.1)?))
+}
+
+fn decode_key(key: &str) -> Option<[u8; 32]> {
+    STANDARD.decode(key).ok()?.try_into().ok()
+}
+
+/// A key backup's passphrase, kept out of debug output such as the action
+/// log.
+#[derive(Clone, PartialEq)]
+pub struct Passphrase(pub String);
+
+impl fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Passphrase(…)")
+    }
+}
+
+#[derive(Debug)]
+pub enum BackupError {
+    /// Not a key backup, or from a newer version of the app.
+    Malformed,
+    /// Made for a different account.
+    OtherAccount,
+    WrongPassphrase,
+    TooShort,
+    Failed,
+}
+
+impl BackupError {
+    pub fn message(&self) -> String {
+        match self {
+            BackupError::Malformed => t("key-backup-malformed"),
+            BackupError::OtherAccount => t("key-backup-other-account"),
+            BackupError::WrongPassphrase => t("key-backup-wrong-passphrase"),
+            BackupError::TooShort => t("key-backup-too-short"),
+            BackupError::Failed => t("key-backup-failed"),
+        }
+    }
+}
+
+/// A secret key sealed under a passphrase, as saved to a file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBackup {
+    version: u32,
+    /// Whose key it is; it only restores into the same account.
+    user_id: String,
+    /// The public half, bound to the seal so a backup cannot be passed off
+    /// as holding some other key.
+    public_key: String,
+    salt: String,
+    #[serde(flatten)]
+    sealed: Sealed,
+}
+
+impl KeyBackup {
+    pub fn parse(text: &str) -> Result<Self, BackupError> {
+        serde_json::from_str::<KeyBackup>(text)
+            .ok()
+            .filter(|backup| backup.version == BACKUP_VERSION)
+            .ok_or(BackupError::Malformed)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a key backup serializes")
+    }
+
+    /// Opens the backup for `user_id` with `passphrase`.
+    pub fn restore(&self, user_id: &str, passphrase: &Passphrase) -> Result<KeyPair, BackupError> {
+        if self.user_id != user_id {
+            return Err(BackupError::OtherAccount);
+        }
+        let salt = STANDARD.decode(&self.salt).map_err(|_| BackupError::Malformed)?;
+        let nonce = STANDARD
+            .decode(&self.sealed.nonce)
+            .ok()
+            .filter(|n| n.len() == 24)
+            .ok_or(BackupError::Malformed)?;
+        let ciphertext = STANDARD
+            .decode(&self.sealed.ciphertext)
+            .map_err(|_| BackupError::Malformed)?;
+        let aad = backup_data(&self.user_id, &self.public_key);
+        let secret: [u8; 32] = backup_cipher(passphrase, &salt)?
+            .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| BackupError::WrongPassphrase)?
+            .try_into()
+            .map_err(|_| BackupError::Malformed)?;
+        let keys = KeyPair::from_secret(StaticSecret::from(secret));
+        if !keys.is_ours(&self.public_key) {
+            return Err(BackupError::Malformed);
+        }
+        Ok(keys)
+    }
+}
+
+/// Stretches `passphrase` with Argon2id, so guessing it from a stolen
+/// backup is slow.
+fn backup_cipher(Passphrase(passphrase): &Passphrase, salt: &[u8]) -> Result<XChaCha20Poly1305, BackupError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(BackupError::TooShort);
+    }
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|_| BackupError::Malformed)?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+fn backup_data(user_id: &str, public_key: &str) -> Vec<u8> {
+    [b"yewchat key backup v1".as_slice(), &[0], user_id.as_bytes(), &[0], public_key.as_bytes()].concat()
+}
+
+fn associated_data(from: &str, to: &str) -> Vec<u8> {
+    [from.as_bytes(), &[0], to.as_bytes()].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passphrase() -> Passphrase {
+        Passphrase("correct horse battery".to_string())
+    }
+
+    #[test]
+    fn backup_restores_the_same_key() {
+        let keys = KeyPair::generate();
+        let backup = keys.backup("alice-id", &passphrase()).unwrap();
+        let backup = KeyBackup::parse(&backup.to_json()).unwrap();
+        let restored = backup.restore("alice-id", &passphrase()).unwrap();
+        assert_eq!(restored.public_key(), keys.public_key());
+
+        let peer = KeyPair::generate();
+        let sealed = peer.seal(&keys.public_key(), "bob-id", "alice-id", "hi").unwrap();
+        assert_eq!(restored.open(&peer.public_key(), "bob-id", "alice-id", &sealed).unwrap(), "hi");
+    }
+
+    #[test]
+    fn backup_only_opens_for_its_account_and_passphrase() {
+        let backup = KeyPair::generate().backup("alice-id", &passphrase()).unwrap();
+        assert!(matches!(backup.restore("bob-id", &passphrase()), Err(BackupError::OtherAccount)));
+        let wrong = Passphrase("incorrect horse battery".to_string());
+        assert!(matches!(backup.restore("alice-id", &wrong), Err(BackupError::WrongPassphrase)));
+    }
+
+    #[test]
+    fn backup_rejects_a_swapped_public_key() {
+        let mut backup = KeyPair::generate().backup("alice-id", &passphrase()).unwrap();
+        backup.public_key = KeyPair::generate().public_key();
+        assert!(backup.restore("alice-id", &passphrase()).is_err());
+    }
+
+    #[test]
+    fn backup_needs_a_long_passphrase() {
+        let short = Passphrase("hunter2".to_string());
+        assert!(matches!(KeyPair::generate().backup("alice-id", &short), Err(BackupError::TooShort)));
+        assert!(matches!(KeyBackup::parse("{}"), Err(BackupError::Malformed)));
+    }
+
+    #[test]
+    fn safety_number_is_the_same_from_both_sides() {
+        let (alice, bob) = (KeyPair::generate().public_key(), KeyPair::generate().public_key());
+        let ours = safety_number(("alice-id", &alice), ("bob-id", &bob)).unwrap();
+        assert_eq!(safety_number(("bob-id", &bob), ("alice-id", &alice)).unwrap(), ours);
+        assert_eq!(ours.split(' ').count(), 12);
+        assert!(ours.split(' ').all(|group| group.len() == 5 && group.bytes().all(|b| b.is_ascii_digit())));
+
+        let mallory = KeyPair::generate().public_key();
+        assert_ne!(safety_number(("alice-id", &alice), ("bob-id", &mallory)).unwrap(), ours);
+        assert_eq!(fingerprint("bob-id", "not a key"), None);
+    }
+
+    #[test]
+    fn passphrase_stays_out_of_debug_output() {
+        assert!(!format!("{:?}", passphrase()).contains("horse"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Emoji shortcodes like `:smile:`. One table serves expansion, the picker
+//! and autocomplete, so a code that is offered always expands.
+
+use std::borrow::Cow;
+
+/// Shortcodes and their emoji, sorted by shortcode.
+pub const SHORTCODES: [(&str, &str); 105] = [
+    ("+1", "👍"),
+    ("-1", "👎"),
+    ("100", "💯"),
+    ("angry", "😠"),
+    ("apple", "🍎"),
+    ("balloon", "🎈"),
+    ("beer", "🍺"),
+    ("bell", "🔔"),
+    ("blush", "😊"),
+    ("book", "📖"),
+    ("boom", "💥"),
+    ("broken_heart", "💔"),
+    ("bug", "🐛"),
+    ("cake", "🍰"),
+    ("calendar", "📅"),
+    ("camera", "📷"),
+    ("cat", "🐱"),
+    ("check", "✔️"),
+    ("clap", "👏"),
+    ("clock", "🕒"),
+    ("coffee", "☕"),
+    ("cold_sweat", "😰"),
+    ("confused", "😕"),
+    ("cool", "🆒"),
+    ("cry", "😢"),
+    ("dog", "🐶"),
+    ("eyes", "👀"),
+    ("facepalm", "🤦"),
+    ("fire", "🔥"),
+    ("flushed", "😳"),
+    ("gift", "🎁"),
+    ("grimacing", "😬"),
+    ("grin", "😁"),
+    ("grinning", "😀"),
+    ("handshake", "🤝"),
+    ("heart", "❤️"),
+    ("heart_eyes", "😍"),
+    ("hourglass", "⌛"),
+    ("hugs", "🤗"),
+    ("hushed", "😯"),
+    ("innocent", "😇"),
+    ("joy", "😂"),
+    ("kiss", "😘"),
+    ("laughing", "😆"),
+    ("lock", "🔒"),
+    ("mag", "🔍"),
+    ("memo", "📝"),
+    ("moon", "🌙"),
+    ("muscle", "💪"),
+    ("neutral_face", "😐"),
+    ("no_entry", "⛔"),
+    ("ok_hand", "👌"),
+    ("open_mouth", "😮"),
+    ("party", "🥳"),
+    ("pencil", "✏️"),
+    ("pensive", "😔"),
+    ("pizza", "🍕"),
+    ("point_down", "👇"),
+    ("point_left", "👈"),
+    ("point_right", "👉"),
+    ("point_up", "👆"),
+    ("poop", "💩"),
+    ("pray", "🙏"),
+    ("question", "❓"),
+    ("rage", "😡"),
+    ("raised_hands", "🙌"),
+    ("relieved", "😌"),
+    ("rocket", "🚀"),
+    ("rofl", "🤣"),
+    ("rose", "🌹"),
+    ("scream", "😱"),
+    ("see_no_evil", "🙈"),
+    ("shrug", "🤷"),
+    ("skull", "💀"),
+    ("sleeping", "😴"),
+    ("slightly_smiling_face", "🙂"),
+    ("smile", "😄"),
+    ("smiley", "😃"),
+    ("smirk", "😏"),
+    ("sob", "😭"),
+    ("sparkles", "✨"),
+    ("star", "⭐"),
+    ("star_struck", "🤩"),
+    ("stuck_out_tongue", "😛"),
+    ("sunglasses", "😎"),
+    ("sunny", "☀️"),
+    ("sweat_smile", "😅"),
+    ("tada", "🎉"),
+    ("thinking", "🤔"),
+    ("thumbsdown", "👎"),
+    ("thumbsup", "👍"),
+    ("tired_face", "😫"),
+    ("trophy", "🏆"),
+    ("unamused", "😒"),
+    ("upside_down", "🙃"),
+    ("v", "✌️"),
+    ("warning", "⚠️"),
+    ("wave", "👋"),
+    ("weary", "😩"),
+    ("white_check_mark", "✅"),
+    ("wink", "😉"),
+    ("x", "❌"),
+    ("yum", "😋"),
+    ("zap", "⚡"),
+    ("zzz", "💤"),
+];
+
+pub fn lookup(name: &str) -> Option<&'static str> {
+    SHORTCODES
+        .binary_search_by(|(code, _)| (*code).cmp(name))
+        .ok()
+        .map(|i| SHORTCODES[i].1)
+}
+
+/// Shortcodes starting with `query`, then those containing it elsewhere, at
+/// most `limit` in all.
+pub fn search(query: &str, limit: usize) -> Vec<(&'static str, &'static str)> {
+    let query = query.to_lowercase();
+    let starting = SHORTCODES.iter().filter(|(code, _)| code.starts_with(&query));
+    let containing = SHORTCODES
+        .iter()
+        .filter(|(code, _)| !code.starts_with(&query) && code.contains(&query));
+    starting.chain(containing).copied().take(limit).collect()
+}
+
+/// Replaces known `:shortcode:`s in `text` with their emoji; unknown ones are
+/// left as typed.
+pub fn expand(text: &str) -> Cow<'_, str> {
+    if !text.contains(':') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find(':') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let emoji = after
+            .find(':')
+            .and_then(|close| Some((close, lookup(&after[..close])?)));
+        match emoji {
+            Some((close, emoji)) => {
+                out.push_str(emoji);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push(':');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Expands shortcodes in a message being sent, leaving code spans and
+/// fenced blocks alone.
+pub fn expand_message(text: &str) -> String {
+    let ticks = text.matches('`').count();
+    text.split('`')
+        .enumerate()
+        .map(|(i, segment)| {
+            // Odd segments sit between a pair of backticks.
+            if i % 2 == 1 && ticks > i {
+                Cow::Borrowed(segment)
+            } else {
+                expand(segment)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("`")
+}
@@ -0,0 +1,80 @@
+use reqwasm::http::Request;
+use serde::Deserialize;
+
+use crate::services::sanitize;
+
+const SEARCH_URL: &str = "https://tenor.googleapis.com/v2/search";
+const API_KEY: Option<&str> = option_env!("YEWCHAT_TENOR_KEY");
+const RESULT_LIMIT: usize = 24;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gif {
+    /// Full-size GIF that gets sent.
+    pub url: String,
+    /// Small rendition shown in the picker.
+    pub preview: String,
+    pub description: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    results: Vec<TenorResult>,
+}
+
+#[derive(Deserialize)]
+struct TenorResult {
+    #[serde(default)]
+    content_description: String,
+    media_formats: MediaFormats,
+}
+
+#[derive(Deserialize)]
+struct MediaFormats {
+    gif: Option<MediaFormat>,
+    tinygif: Option<MediaFormat>,
+}
+
+#[derive(Deserialize)]
+struct MediaFormat {
+    url: String,
+}
+
+pub fn is_enabled() -> bool {
+    API_KEY.is_some()
+}
+
+/// Searches Tenor, keeping only results whose URLs are safe to render and
+/// still look like GIFs to the message renderer.
+pub async fn search(query: &str) -> Result<Vec<Gif>, String> {
+    let key = API_KEY.ok_or("no GIF API key configured")?;
+    let url = format!(
+        "{}?q={}&key={}&limit={}&media_filter=gif,tinygif",
+        SEARCH_URL,
+        String::from(js_sys::encode_uri_component(query)),
+        key,
+        RESULT_LIMIT
+    );
+    let response = Request::get(&url).send().await.map_err(|e| e.to_string())?;
+    if !response.ok() {
+        return Err(response.status_text());
+    }
+    let body: SearchResponse = response.json().await.map_err(|e| e.to_string())?;
+
+    Ok(body
+        .results
+        .into_iter()
+        .filter_map(|result| {
+            let url = sanitize::safe_url(&result.media_formats.gif?.url)?;
+            let preview = result
+                .media_formats
+                .tinygif
+                .and_then(|tiny| sanitize::safe_url(&tiny.url))
+                .unwrap_or_else(|| url.clone());
+            sanitize::media_kind(&url).is_some().then_some(Gif {
+                url,
+                preview,
+                description: result.content_description,
+            })
+        })
+        .collect())
+}
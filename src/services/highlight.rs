@@ -0,0 +1,116 @@
+//! A small, language-agnostic highlighter for code blocks. It recognises
+//! comments, strings, numbers, keywords and calls, which covers most of what
+//! makes a snippet readable, and emits highlight.js class names so the
+//! sanitizer's class allowlist and the page's styles apply.
+
+use crate::services::sanitize;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "case", "catch", "class", "const", "continue", "crate", "def", "default",
+    "defer", "del", "do", "dyn", "elif", "else", "enum", "except", "export", "extends", "extern", "finally", "fn",
+    "for", "from", "func", "function", "go", "if", "impl", "implements", "import", "in", "interface", "is",
+    "lambda", "let", "loop", "match", "mod", "move", "mut", "namespace", "new", "package", "pass", "private",
+    "protected", "pub", "public", "raise", "ref", "return", "select", "self", "static", "struct", "super",
+    "switch", "this", "throw", "trait", "try", "type", "typeof", "unsafe", "use", "using", "var", "void",
+    "where", "while", "with", "yield",
+];
+const LITERALS: &[&str] = &["true", "false", "null", "nil", "None", "True", "False", "undefined"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Comments {
+    /// `//` and `/* */`, as in C and most of its descendants.
+    CStyle,
+    /// `#`, as in Python, Ruby and shells.
+    Hash,
+    /// `--`, as in SQL, Lua and Haskell.
+    Dashes,
+}
+
+fn comments_for(lang: Option<&str>) -> Comments {
+    match lang.map(str::to_lowercase).as_deref() {
+        Some("py" | "python" | "rb" | "ruby" | "sh" | "bash" | "shell" | "zsh" | "yaml" | "yml" | "toml" | "r"
+        | "perl" | "pl" | "elixir" | "ex") => Comments::Hash,
+        Some("sql" | "lua" | "haskell" | "hs") => Comments::Dashes,
+        _ => Comments::CStyle,
+    }
+}
+
+fn span(out: &mut String, class: &str, text: &str) {
+    out.push_str(&format!("<span class=\"hljs-{}\">{}</span>", class, sanitize::escape(text)));
+}
+
+/// Length in bytes of the comment starting `rest`, if one does.
+fn comment_len(rest: &str, comments: Comments) -> Option<usize> {
+    let to_line_end = || rest.find('\n').unwrap_or(rest.len());
+    match comments {
+        Comments::CStyle if rest.starts_with("//") => Some(to_line_end()),
+        Comments::CStyle if rest.starts_with("/*") => Some(rest[2..].find("*/").map_or(rest.len(), |end| end + 4)),
+        Comments::Hash if rest.starts_with('#') => Some(to_line_end()),
+        Comments::Dashes if rest.starts_with("--") => Some(to_line_end()),
+        _ => None,
+    }
+}
+
+/// Length in bytes of the string literal starting `rest`, if one does.
+/// Single and double quotes stop at the line's end; a lone `'` such as a Rust
+/// lifetime or an apostrophe is left alone.
+fn string_len(rest: &str) -> Option<usize> {
+    let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\'' | '`'))?;
+    let mut escaped = false;
+    for (i, c) in rest.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\n' if quote != '`' => return None,
+            c if c == quote => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Highlighted, escaped markup for `code`, to be wrapped in `<pre><code>`.
+pub fn highlight(code: &str, lang: Option<&str>) -> String {
+    let comments = comments_for(lang);
+    let mut out = String::with_capacity(code.len() * 2);
+    let mut rest = code;
+    // Whether the previous character could end an identifier, so a digit or
+    // name here continues it.
+    let mut in_word = false;
+    while let Some(c) = rest.chars().next() {
+        let (len, class) = if let Some(len) = comment_len(rest, comments) {
+            (len, Some("comment"))
+        } else if let Some(len) = string_len(rest) {
+            (len, Some("string"))
+        } else if !in_word && c.is_ascii_digit() {
+            (rest.find(|c: char| !(is_ident(c) || c == '.')).unwrap_or(rest.len()), Some("number"))
+        } else if !in_word && is_ident(c) {
+            let len = rest.find(|c: char| !is_ident(c)).unwrap_or(rest.len());
+            let word = &rest[..len];
+            let class = if KEYWORDS.contains(&word) {
+                Some("keyword")
+            } else if LITERALS.contains(&word) {
+                Some("literal")
+            } else if rest[len..].starts_with('(') || rest[len..].starts_with("!(") {
+                Some("title")
+            } else {
+                None
+            };
+            (len, class)
+        } else {
+            (c.len_utf8(), None)
+        };
+        let (token, tail) = rest.split_at(len);
+        match class {
+            Some(class) => span(&mut out, class, token),
+            None => out.push_str(&sanitize::escape(token)),
+        }
+        in_word = token.chars().next_back().is_some_and(is_ident);
+        rest = tail;
+    }
+    out
+}
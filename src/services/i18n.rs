@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+
+use fluent::{FluentArgs, FluentBundle, FluentResource};
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Spanish];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Locale::English => "en-US",
+            Locale::Spanish => "es",
+        }
+    }
+
+    /// The language's own name, so it is recognizable whatever the current locale.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Spanish => "Español",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::English => include_str!("../../locales/en-US/main.ftl"),
+            Locale::Spanish => include_str!("../../locales/es/main.ftl"),
+        }
+    }
+
+    /// Picks the locale matching the browser language, defaulting to English.
+    pub fn detect() -> Self {
+        let language = web_sys::window()
+            .and_then(|w| w.navigator().language())
+            .unwrap_or_default();
+        Locale::ALL
+            .iter()
+            .copied()
+            .find(|l| language.starts_with(l.id().split('-').next().unwrap_or_default()))
+            .unwrap_or_default()
+    }
+
+    fn bundle(&self) -> FluentBundle<FluentResource> {
+        let langid: LanguageIdentifier = self.id().parse().expect("valid language id");
+        let mut bundle = FluentBundle::new(vec![langid]);
+        // Unicode isolation marks around arguments show up as stray glyphs in some fonts.
+        bundle.set_use_isolating(false);
+        let resource = FluentResource::try_new(self.source().to_string())
+            .unwrap_or_else(|(resource, errors)| {
+                log::error!("errors parsing {} messages: {:?}", self.id(), errors);
+                resource
+            });
+        if let Err(errors) = bundle.add_resource(resource) {
+            log::error!("errors loading {} messages: {:?}", self.id(), errors);
+        }
+        bundle
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<(Locale, FluentBundle<FluentResource>)> =
+        RefCell::new((Locale::English, Locale::English.bundle()));
+    static FALLBACK: FluentBundle<FluentResource> = Locale::English.bundle();
+}
+
+pub fn set_locale(locale: Locale) {
+    CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        if current.0 != locale {
+            *current = (locale, locale.bundle());
+        }
+    });
+    if let Some(root) = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.document_element())
+    {
+        let _ = root.set_attribute("lang", locale.id());
+    }
+}
+
+fn format(bundle: &FluentBundle<FluentResource>, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = vec![];
+    let value = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        log::error!("errors formatting message {}: {:?}", id, errors);
+    }
+    Some(value.into_owned())
+}
+
+fn lookup(id: &str, args: Option<&FluentArgs>) -> String {
+    CURRENT
+        .with(|current| format(&current.borrow().1, id, args))
+        .or_else(|| FALLBACK.with(|fallback| format(fallback, id, args)))
+        .unwrap_or_else(|| {
+            log::error!("missing translation for {}", id);
+            id.to_string()
+        })
+}
+
+/// Looks up a UI string in the current locale, falling back to English.
+pub fn t(id: &str) -> String {
+    lookup(id, None)
+}
+
+/// Like [`t`], with named arguments substituted into the message.
+pub fn t_args(id: &str, args: &[(&str, &str)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        // Numbers must be passed as such for plural selection to work.
+        match value.parse::<f64>() {
+            Ok(number) => fluent_args.set(*name, number),
+            Err(_) => fluent_args.set(*name, value.to_string()),
+        }
+    }
+    lookup(id, Some(&fluent_args))
+}
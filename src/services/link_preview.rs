@@ -0,0 +1,53 @@
+use reqwasm::http::Request;
+use serde::Deserialize;
+
+use crate::services::sanitize;
+
+/// Unfurl endpoint, with `{url}` standing in for the encoded link. Previews
+/// are off when this is not configured.
+const PREVIEW_URL: Option<&str> = option_env!("YEWCHAT_PREVIEW_URL");
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct LinkPreview {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+impl LinkPreview {
+    /// A preview without a title or description has nothing worth showing.
+    pub fn is_empty(&self) -> bool {
+        self.title.as_deref().unwrap_or("").is_empty()
+            && self.description.as_deref().unwrap_or("").is_empty()
+    }
+}
+
+pub fn is_enabled() -> bool {
+    PREVIEW_URL.is_some_and(|template| template.contains("{url}"))
+}
+
+/// The first http(s) link in `message`, if any.
+pub fn first_url(message: &str) -> Option<String> {
+    message
+        .split_whitespace()
+        .filter(|word| word.contains("://"))
+        .find_map(sanitize::safe_url)
+}
+
+pub async fn fetch(url: &str) -> Option<LinkPreview> {
+    let template = PREVIEW_URL.filter(|_| is_enabled())?;
+    let encoded: String = js_sys::encode_uri_component(url).into();
+    let response = Request::get(&template.replace("{url}", &encoded))
+        .send()
+        .await
+        .map_err(|e| log::debug!("link preview for {} failed: {:?}", url, e))
+        .ok()?;
+    if !response.ok() {
+        return None;
+    }
+    response.json::<LinkPreview>().await.ok()
+}
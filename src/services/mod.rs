@@ -1,2 +1,43 @@
 pub mod websocket;
-pub mod event_bus;
\ No newline at end of file
+pub mod event_bus;
+pub mod block_list;
+pub mod notifications;
+pub mod sound;
+pub mod sanitize;
+pub mod settings;
+pub mod theme;
+pub mod i18n;
+pub mod session;
+pub mod moderation;
+pub mod drafts;
+pub mod attachments;
+pub mod rooms;
+pub mod link_preview;
+pub mod gif_search;
+pub mod digest;
+pub mod notification_router;
+pub mod webrtc;
+pub mod typing;
+pub mod e2ee;
+pub mod reactions;
+pub mod auth;
+pub mod capabilities;
+pub mod transport;
+pub mod document_pip;
+pub mod rate_limit;
+pub mod word_filter;
+pub mod clipboard;
+pub mod transcript;
+pub mod commands;
+pub mod action_log;
+pub mod archive;
+pub mod pwa;
+pub mod highlight;
+pub mod shortcuts;
+pub mod avatar;
+pub mod presence;
+pub mod emoji;
+pub mod polls;
+pub mod scheduler;
+pub mod translate;
+pub mod verification;
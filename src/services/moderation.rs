@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::i18n::t;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    Member,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    pub fn can_moderate(&self) -> bool {
+        matches!(self, Role::Moderator | Role::Admin)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModActionKind {
+    Delete,
+    Kick,
+    Ban,
+    Pin,
+    /// Deleting every message a user sent.
+    Purge,
+}
+
+impl ModActionKind {
+    pub const ALL: [ModActionKind; 5] = [
+        ModActionKind::Delete,
+        ModActionKind::Kick,
+        ModActionKind::Ban,
+        ModActionKind::Pin,
+        ModActionKind::Purge,
+    ];
+
+    pub fn label(&self) -> String {
+        t(match self {
+            ModActionKind::Delete => "modaction-delete",
+            ModActionKind::Kick => "modaction-kick",
+            ModActionKind::Ban => "modaction-ban",
+            ModActionKind::Pin => "modaction-pin",
+            ModActionKind::Purge => "modaction-purge",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModAction {
+    pub action: ModActionKind,
+    pub actor: String,
+    /// A message id for deletes and pins, a user id for the rest.
+    pub target: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Milliseconds since the epoch.
+    pub timestamp: f64,
+}
@@ -0,0 +1,333 @@
+//! Decides whether an incoming message should notify, in one place.
+//!
+//! Rules are checked in a fixed order and the first one that applies wins:
+//!
+//! 1. Our own messages never notify.
+//! 2. Blocked or muted senders never notify.
+//! 3. Quiet hours (do not disturb) silence everything.
+//! 4. A room set to "nothing" stays silent.
+//! 5. A direct `@name` mention notifies.
+//! 6. `@here` / `@everyone` notify unless group mentions are turned off.
+//! 7. A keyword alert notifies.
+//! 8. Otherwise the room's level decides: "all" notifies, "mentions" doesn't.
+//!
+//! Every decision carries the rule that produced it so the notification
+//! center can explain it afterwards.
+
+use crate::services::block_list::BlockList;
+use crate::services::i18n::{t, t_args};
+use crate::services::settings::{NotificationLevel, Settings};
+
+const GROUP_MENTIONS: &[&str] = &["here", "everyone"];
+
+pub struct Incoming<'a> {
+    pub room: &'a str,
+    pub sender_id: &'a str,
+    pub message: &'a str,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reason {
+    OwnMessage,
+    Blocked,
+    Muted,
+    QuietHours,
+    RoomSilenced,
+    Mentioned,
+    GroupMention(String),
+    GroupMentionsOff(String),
+    Keyword(String),
+    AllMessages,
+    NotMentioned,
+}
+
+impl Reason {
+    pub fn explain(&self) -> String {
+        match self {
+            Reason::OwnMessage => t("route-own-message"),
+            Reason::Blocked => t("route-blocked"),
+            Reason::Muted => t("route-muted"),
+            Reason::QuietHours => t("route-quiet-hours"),
+            Reason::RoomSilenced => t("route-room-silenced"),
+            Reason::Mentioned => t("route-mentioned"),
+            Reason::GroupMention(group) => t_args("route-group-mention", &[("group", group)]),
+            Reason::GroupMentionsOff(group) => {
+                t_args("route-group-mentions-off", &[("group", group)])
+            }
+            Reason::Keyword(keyword) => t_args("route-keyword", &[("keyword", keyword)]),
+            Reason::AllMessages => t("route-all-messages"),
+            Reason::NotMentioned => t("route-not-mentioned"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Route {
+    pub notify: bool,
+    pub reason: Reason,
+}
+
+impl Route {
+    fn notify(reason: Reason) -> Self {
+        Route { notify: true, reason }
+    }
+
+    fn suppress(reason: Reason) -> Self {
+        Route { notify: false, reason }
+    }
+}
+
+/// Snapshot of everything routing depends on. The clock is read by the
+/// caller so that routing itself stays a pure function.
+pub struct NotificationRouter<'a> {
+    pub settings: &'a Settings,
+    pub block_list: &'a BlockList,
+    /// What the server keys us on, our name if it predates user ids.
+    pub user_id: &'a str,
+    pub username: &'a str,
+    pub in_quiet_hours: bool,
+}
+
+impl NotificationRouter<'_> {
+    pub fn route(&self, incoming: &Incoming) -> Route {
+        let sender = incoming.sender_id;
+        if sender == self.user_id {
+            return Route::suppress(Reason::OwnMessage);
+        }
+        if self.block_list.is_blocked(sender) {
+            return Route::suppress(Reason::Blocked);
+        }
+        if self.block_list.is_muted(sender) {
+            return Route::suppress(Reason::Muted);
+        }
+        if self.in_quiet_hours {
+            return Route::suppress(Reason::QuietHours);
+        }
+        let level = self.settings.level_for(incoming.room);
+        if level == NotificationLevel::None {
+            return Route::suppress(Reason::RoomSilenced);
+        }
+        if mentions(incoming.message, self.username) {
+            return Route::notify(Reason::Mentioned);
+        }
+        if let Some(group) = GROUP_MENTIONS.iter().find(|g| mentions(incoming.message, g)) {
+            let group = format!("@{}", group);
+            return if self.settings.notify_group_mentions {
+                Route::notify(Reason::GroupMention(group))
+            } else {
+                Route::suppress(Reason::GroupMentionsOff(group))
+            };
+        }
+        if let Some(keyword) = matching_keyword(incoming.message, &self.settings.keywords) {
+            return Route::notify(Reason::Keyword(keyword.to_string()));
+        }
+        match level {
+            NotificationLevel::All => Route::notify(Reason::AllMessages),
+            _ => Route::suppress(Reason::NotMentioned),
+        }
+    }
+}
+
+pub fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Finds `needle` in `haystack` as a whole word, ignoring case.
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    let haystack = haystack.to_lowercase();
+    let needle = needle.to_lowercase();
+    if needle.is_empty() {
+        return false;
+    }
+    haystack.match_indices(&needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+/// Whether `message` contains `@name`, not counting longer names that merely
+/// start with it.
+pub fn mentions(message: &str, name: &str) -> bool {
+    !name.is_empty() && contains_word(message, &format!("@{}", name))
+}
+
+fn matching_keyword<'k>(message: &str, keywords: &'k [String]) -> Option<&'k str> {
+    keywords
+        .iter()
+        .map(|k| k.trim())
+        .find(|k| contains_word(message, k))
+}
+
+/// A routed message as remembered for the explainer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Routed {
+    pub from: String,
+    pub preview: String,
+    pub route: Route,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::services::i18n::Locale;
+    use crate::services::settings::QuietHours;
+
+    /// `Settings::default` reads the browser, so tests spell them out.
+    fn settings() -> Settings {
+        Settings {
+            notification_level: NotificationLevel::Mentions,
+            quiet_hours: None,
+            channel_overrides: HashMap::new(),
+            media_proxy: None,
+            load_original_media: false,
+            theme: Default::default(),
+            locale: Locale::English,
+            hidden_messages: HashSet::new(),
+            keywords: vec!["deploy".to_string()],
+            notify_group_mentions: true,
+            typing_signal: Default::default(),
+            pinned_dms: Vec::new(),
+            transport: Default::default(),
+            focus_mode: false,
+            filtered_words: Vec::new(),
+            sound_muted: false,
+            avatar_style: Default::default(),
+            server_url: None,
+            disappearing: HashMap::new(),
+        }
+    }
+
+    fn block_list() -> BlockList {
+        serde_json::from_str(r#"{"muted": ["muted-id"], "blocked": ["blocked-id"]}"#).unwrap()
+    }
+
+    fn route(settings: &Settings, in_quiet_hours: bool, sender_id: &str, message: &str) -> Route {
+        let block_list = block_list();
+        let router = NotificationRouter {
+            settings,
+            block_list: &block_list,
+            user_id: "me-id",
+            username: "alice",
+            in_quiet_hours,
+        };
+        router.route(&Incoming {
+            room: "general",
+            sender_id,
+            message,
+        })
+    }
+
+    #[test]
+    fn own_messages_never_notify() {
+        assert_eq!(route(&settings(), false, "me-id", "@alice deploy").reason, Reason::OwnMessage);
+        // Someone else going by our name is not us.
+        assert_eq!(route(&settings(), false, "alice", "@alice").reason, Reason::Mentioned);
+    }
+
+    #[test]
+    fn block_and_mute_beat_mentions_and_keywords() {
+        for message in ["@alice", "@everyone", "time to deploy"] {
+            assert_eq!(route(&settings(), false, "blocked-id", message), Route::suppress(Reason::Blocked));
+            assert_eq!(route(&settings(), false, "muted-id", message), Route::suppress(Reason::Muted));
+        }
+    }
+
+    #[test]
+    fn quiet_hours_silence_everything_else() {
+        for message in ["@alice", "@here", "deploy", "hi"] {
+            assert_eq!(route(&settings(), true, "bob-id", message), Route::suppress(Reason::QuietHours));
+        }
+        // Blocking is still what gets reported for a blocked sender.
+        assert_eq!(route(&settings(), true, "blocked-id", "@alice").reason, Reason::Blocked);
+    }
+
+    #[test]
+    fn quiet_hours_may_wrap_past_midnight() {
+        let night = QuietHours { start: 22 * 60, end: 7 * 60 };
+        assert!(night.contains(23 * 60));
+        assert!(night.contains(0));
+        assert!(night.contains(7 * 60 - 1));
+        assert!(!night.contains(7 * 60));
+        assert!(!night.contains(12 * 60));
+        let lunch = QuietHours { start: 12 * 60, end: 13 * 60 };
+        assert!(lunch.contains(12 * 60 + 30));
+        assert!(!lunch.contains(23 * 60));
+    }
+
+    #[test]
+    fn room_level_beats_global_level() {
+        let mut settings = settings();
+        settings.notification_level = NotificationLevel::All;
+        assert_eq!(route(&settings, false, "bob-id", "hi"), Route::notify(Reason::AllMessages));
+
+        settings.channel_overrides.insert("general".to_string(), NotificationLevel::Mentions);
+        assert_eq!(route(&settings, false, "bob-id", "hi"), Route::suppress(Reason::NotMentioned));
+
+        settings.channel_overrides.insert("general".to_string(), NotificationLevel::None);
+        assert_eq!(route(&settings, false, "bob-id", "@alice"), Route::suppress(Reason::RoomSilenced));
+        assert_eq!(route(&settings, false, "bob-id", "deploy"), Route::suppress(Reason::RoomSilenced));
+
+        settings.notification_level = NotificationLevel::None;
+        settings.channel_overrides.insert("general".to_string(), NotificationLevel::All);
+        assert_eq!(route(&settings, false, "bob-id", "hi"), Route::notify(Reason::AllMessages));
+    }
+
+    #[test]
+    fn direct_mentions_notify() {
+        assert_eq!(route(&settings(), false, "bob-id", "hey @Alice!"), Route::notify(Reason::Mentioned));
+        // A longer name that starts with ours is someone else.
+        assert_eq!(route(&settings(), false, "bob-id", "hey @alice_b"), Route::suppress(Reason::NotMentioned));
+        assert_eq!(route(&settings(), false, "bob-id", "alice@example.com"), Route::suppress(Reason::NotMentioned));
+    }
+
+    #[test]
+    fn group_mentions_follow_their_setting() {
+        assert_eq!(
+            route(&settings(), false, "bob-id", "@here standup"),
+            Route::notify(Reason::GroupMention("@here".to_string()))
+        );
+        let mut settings = settings();
+        settings.notify_group_mentions = false;
+        assert_eq!(
+            route(&settings, false, "bob-id", "@everyone deploy"),
+            Route::suppress(Reason::GroupMentionsOff("@everyone".to_string()))
+        );
+        // A direct mention still gets through alongside a group one.
+        assert_eq!(route(&settings, false, "bob-id", "@everyone @alice"), Route::notify(Reason::Mentioned));
+    }
+
+    #[test]
+    fn keywords_notify_as_whole_words() {
+        assert_eq!(
+            route(&settings(), false, "bob-id", "Deploy is done"),
+            Route::notify(Reason::Keyword("deploy".to_string()))
+        );
+        assert_eq!(route(&settings(), false, "bob-id", "redeployed"), Route::suppress(Reason::NotMentioned));
+    }
+
+    #[test]
+    fn every_reason_is_explained() {
+        let cases = [
+            (Reason::OwnMessage, "you sent it"),
+            (Reason::Blocked, "the sender is blocked"),
+            (Reason::Muted, "the sender is muted"),
+            (Reason::QuietHours, "quiet hours were on"),
+            (Reason::RoomSilenced, "this room is set to notify about nothing"),
+            (Reason::Mentioned, "you were mentioned"),
+            (Reason::GroupMention("@here".to_string()), "@here mentions everyone in the room"),
+            (
+                Reason::GroupMentionsOff("@here".to_string()),
+                "@here was used, but group mentions are turned off",
+            ),
+            (Reason::Keyword("deploy".to_string()), "it contains your keyword \"deploy\""),
+            (Reason::AllMessages, "this room notifies about all messages"),
+            (Reason::NotMentioned, "this room only notifies about mentions"),
+        ];
+        for (reason, text) in cases {
+            assert_eq!(reason.explain(), text);
+        }
+    }
+}
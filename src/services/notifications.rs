@@ -0,0 +1,95 @@
+use gloo_storage::{LocalStorage, Storage};
+use wasm_bindgen::JsCast;
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+const ENABLED_KEY: &str = "yewchat.notifications_enabled";
+const PREVIEW_LEN: usize = 100;
+
+pub fn is_supported() -> bool {
+    web_sys::window()
+        .map(|w| js_sys::Reflect::has(&w, &"Notification".into()).unwrap_or(false))
+        .unwrap_or(false)
+}
+
+pub fn is_enabled() -> bool {
+    LocalStorage::get(ENABLED_KEY).unwrap_or(true)
+}
+
+pub fn set_enabled(enabled: bool) {
+    if let Err(e) = LocalStorage::set(ENABLED_KEY, enabled) {
+        log::error!("failed to persist notification setting: {:?}", e);
+    }
+    if enabled {
+        request_permission();
+    }
+}
+
+pub fn request_permission() {
+    if is_supported() && Notification::permission() == NotificationPermission::Default {
+        if let Err(e) = Notification::request_permission() {
+            log::error!("notification permission request failed: {:?}", e);
+        }
+    }
+}
+
+fn tab_hidden() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .map(|d| d.hidden())
+        .unwrap_or(false)
+}
+
+pub fn notify_message(from: &str, message: &str) {
+    if !is_supported()
+        || !is_enabled()
+        || !tab_hidden()
+        || Notification::permission() != NotificationPermission::Granted
+    {
+        return;
+    }
+
+    let mut preview: String = message.chars().take(PREVIEW_LEN).collect();
+    if preview.len() < message.len() {
+        preview.push('…');
+    }
+
+    let mut options = NotificationOptions::new();
+    options.body(&preview).tag("yewchat-message");
+    if let Err(e) = Notification::new_with_options(from, &options) {
+        log::error!("failed to show notification: {:?}", e);
+    }
+}
+
+/// Shows how many messages notified while we were away, in the tab title and
+/// on the installed app's icon where the browser supports badges. Zero
+/// clears both.
+pub fn set_badge(count: usize) {
+    let window = match web_sys::window() {
+        Some(window) => window,
+        None => return,
+    };
+    if let Some(document) = window.document() {
+        let title = document.title();
+        let base = match title.strip_prefix('(').and_then(|rest| rest.split_once(") ")) {
+            Some((n, base)) if n.parse::<usize>().is_ok() => base.to_string(),
+            _ => title,
+        };
+        document.set_title(&if count == 0 { base.clone() } else { format!("({}) {}", count, base) });
+    }
+    // The Badging API is not in web-sys yet.
+    let navigator = window.navigator();
+    let method = if count == 0 { "clearAppBadge" } else { "setAppBadge" };
+    let call = js_sys::Reflect::get(&navigator, &method.into())
+        .ok()
+        .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+    if let Some(call) = call {
+        let result = if count == 0 {
+            call.call0(&navigator)
+        } else {
+            call.call1(&navigator, &(count as f64).into())
+        };
+        if let Err(e) = result {
+            log::debug!("unable to set app badge: {:?}", e);
+        }
+    }
+}
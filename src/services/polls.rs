@@ -0,0 +1,58 @@
+//! Polls posted to a room. The server keeps the votes and sends the whole
+//! poll again whenever someone votes or its creator closes it.
+
+use serde::{Deserialize, Serialize};
+
+/// Most options a poll may offer.
+pub const MAX_OPTIONS: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Poll {
+    pub id: String,
+    pub creator_id: String,
+    pub question: String,
+    pub options: Vec<String>,
+    /// Who picked each option, by user id, in the order of `options`.
+    #[serde(default)]
+    pub votes: Vec<Vec<String>>,
+    #[serde(default)]
+    pub closed: bool,
+}
+
+impl Poll {
+    pub fn count(&self, option: usize) -> usize {
+        self.votes.get(option).map_or(0, Vec::len)
+    }
+
+    pub fn total(&self) -> usize {
+        self.votes.iter().map(Vec::len).sum()
+    }
+
+    /// The option `user_id` voted for, if any. One vote per person.
+    pub fn choice(&self, user_id: &str) -> Option<usize> {
+        self.votes.iter().position(|voters| voters.iter().any(|v| v == user_id))
+    }
+}
+
+/// A poll for the server to post in our name.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewPoll<'a> {
+    pub question: &'a str,
+    pub options: &'a [String],
+}
+
+/// Our vote, or `None` to take it back.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollVote<'a> {
+    pub poll_id: &'a str,
+    pub option: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollClose<'a> {
+    pub poll_id: &'a str,
+}
@@ -0,0 +1,151 @@
+//! Whether we are at the keyboard. No input for a while, or the tab staying
+//! in the background, counts as away; input or the tab coming back counts as
+//! online again.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use yew::Callback;
+
+use crate::services::i18n::t;
+
+/// Milliseconds without input before we count as away.
+const IDLE_AFTER: f64 = 5.0 * 60.0 * 1000.0;
+/// Milliseconds the tab may be hidden before we count as away, so flicking
+/// between tabs does not announce anything.
+const HIDDEN_AFTER: f64 = 60.0 * 1000.0;
+const ACTIVITY_EVENTS: [&str; 5] = ["pointerdown", "pointermove", "keydown", "wheel", "touchstart"];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Presence {
+    #[default]
+    Online,
+    Away,
+}
+
+impl Presence {
+    pub fn label(&self) -> String {
+        t(match self {
+            Presence::Online => "presence-online",
+            Presence::Away => "presence-away",
+        })
+    }
+}
+
+struct State {
+    presence: Presence,
+    last_active: f64,
+    hidden_since: Option<f64>,
+    timer: Option<Timeout>,
+    on_change: Callback<Presence>,
+}
+
+/// Watches for input and tab visibility for as long as it lives, reporting
+/// each change of presence.
+pub struct IdleWatcher {
+    _state: Rc<RefCell<State>>,
+    _listeners: Vec<EventListener>,
+}
+
+impl IdleWatcher {
+    pub fn new(on_change: Callback<Presence>) -> Self {
+        let state = Rc::new(RefCell::new(State {
+            presence: Presence::Online,
+            last_active: js_sys::Date::now(),
+            hidden_since: hidden().then(js_sys::Date::now),
+            timer: None,
+            on_change,
+        }));
+        let document = web_sys::window().and_then(|w| w.document());
+        let mut listeners = Vec::new();
+        if let Some(document) = document {
+            for event in ACTIVITY_EVENTS {
+                let weak = Rc::downgrade(&state);
+                listeners.push(EventListener::new(&document, event, move |_| {
+                    if let Some(state) = weak.upgrade() {
+                        state.borrow_mut().last_active = js_sys::Date::now();
+                        set(&state, Presence::Online);
+                    }
+                }));
+            }
+            let weak = Rc::downgrade(&state);
+            listeners.push(EventListener::new(&document, "visibilitychange", move |_| {
+                if let Some(state) = weak.upgrade() {
+                    let now = js_sys::Date::now();
+                    if hidden() {
+                        state.borrow_mut().hidden_since = Some(now);
+                        schedule(&state);
+                    } else {
+                        let mut inner = state.borrow_mut();
+                        inner.hidden_since = None;
+                        inner.last_active = now;
+                        drop(inner);
+                        set(&state, Presence::Online);
+                    }
+                }
+            }));
+        }
+        schedule(&state);
+        IdleWatcher {
+            _state: state,
+            _listeners: listeners,
+        }
+    }
+}
+
+fn hidden() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .map(|d| d.hidden())
+        .unwrap_or(false)
+}
+
+fn set(state: &Rc<RefCell<State>>, presence: Presence) {
+    let mut inner = state.borrow_mut();
+    if inner.presence == presence {
+        return;
+    }
+    inner.presence = presence;
+    let on_change = inner.on_change.clone();
+    drop(inner);
+    if presence == Presence::Online {
+        schedule(state);
+    }
+    on_change.emit(presence);
+}
+
+/// Arms the timer for the moment we would go away if nothing happens.
+fn schedule(state: &Rc<RefCell<State>>) {
+    let mut inner = state.borrow_mut();
+    let deadline = match inner.hidden_since {
+        Some(since) => (since + HIDDEN_AFTER).min(inner.last_active + IDLE_AFTER),
+        None => inner.last_active + IDLE_AFTER,
+    };
+    let weak = Rc::downgrade(state);
+    let wait = (deadline - js_sys::Date::now()).max(0.0) as u32;
+    inner.timer = Some(Timeout::new(wait, move || expire(weak)));
+}
+
+/// Goes away if the deadline really passed; input since the timer was armed
+/// only moved `last_active`, so it may have to wait some more.
+fn expire(weak: Weak<RefCell<State>>) {
+    let state = match weak.upgrade() {
+        Some(state) => state,
+        None => return,
+    };
+    let inner = state.borrow();
+    let now = js_sys::Date::now();
+    let idle = now - inner.last_active >= IDLE_AFTER;
+    let hidden_long = inner.hidden_since.is_some_and(|since| now - since >= HIDDEN_AFTER);
+    drop(inner);
+    if idle || hidden_long {
+        state.borrow_mut().timer = None;
+        set(&state, Presence::Away);
+    } else {
+        schedule(&state);
+    }
+}
@@ -0,0 +1,74 @@
+//! Installability and offline start-up through the service worker in
+//! `static/sw.js`, which caches the app shell.
+
+use gloo_events::EventListener;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{ServiceWorker, ServiceWorkerRegistration, ServiceWorkerState};
+use yew::Callback;
+
+const WORKER_URL: &str = "/sw.js";
+
+fn container() -> Option<web_sys::ServiceWorkerContainer> {
+    let navigator = web_sys::window()?.navigator();
+    // Missing outside secure contexts, e.g. when served over plain http.
+    js_sys::Reflect::has(&navigator, &"serviceWorker".into())
+        .unwrap_or(false)
+        .then(|| navigator.service_worker())
+}
+
+/// Whether this page is already run by a worker, so a newly installed one is
+/// an update rather than the first install.
+fn controlled() -> bool {
+    container().is_some_and(|c| c.controller().is_some())
+}
+
+/// Registers the worker. `on_update` receives a newer version once it is
+/// installed and waiting for the open tabs to let go of the current one.
+pub fn register(on_update: Callback<ServiceWorker>) {
+    let container = match container() {
+        Some(container) => container,
+        None => return,
+    };
+    let promise = container.register(WORKER_URL);
+    spawn_local(async move {
+        let registration: ServiceWorkerRegistration = match JsFuture::from(promise).await {
+            Ok(registration) => registration.unchecked_into(),
+            Err(e) => {
+                log::warn!("service worker registration failed: {:?}", e);
+                return;
+            }
+        };
+        if let Some(waiting) = registration.waiting().filter(|_| controlled()) {
+            on_update.emit(waiting);
+        }
+        let watched = registration.clone();
+        EventListener::new(&registration, "updatefound", move |_| {
+            if let Some(worker) = watched.installing() {
+                let (installing, on_update) = (worker.clone(), on_update.clone());
+                EventListener::new(&worker, "statechange", move |_| {
+                    if installing.state() == ServiceWorkerState::Installed && controlled() {
+                        on_update.emit(installing.clone());
+                    }
+                })
+                .forget();
+            }
+        })
+        .forget();
+    });
+}
+
+/// Switches to the waiting version and reloads once it has taken over.
+pub fn apply_update(worker: &ServiceWorker) {
+    if let Some(container) = container() {
+        EventListener::once(&container, "controllerchange", |_| {
+            if let Some(window) = web_sys::window() {
+                let _ = window.location().reload();
+            }
+        })
+        .forget();
+    }
+    if let Err(e) = worker.post_message(&"skipWaiting".into()) {
+        log::error!("failed to activate the update: {:?}", e);
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+
+/// Messages allowed within [`WINDOW_MS`].
+const LIMIT: usize = 5;
+const WINDOW_MS: f64 = 10_000.0;
+
+/// Keeps one user from flooding the room: at most [`LIMIT`] messages in any
+/// [`WINDOW_MS`]. Times are in milliseconds and passed in by the caller.
+#[derive(Debug, Default)]
+pub struct SendThrottle {
+    /// When the messages still inside the window were sent, oldest first.
+    sent: VecDeque<f64>,
+}
+
+impl SendThrottle {
+    /// Records a send if one is allowed at `now`.
+    pub fn try_send(&mut self, now: f64) -> bool {
+        if self.cooldown_ms(now).is_some() {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+
+    /// How long until the next send is allowed, if it is not allowed yet.
+    pub fn cooldown_ms(&mut self, now: f64) -> Option<f64> {
+        while self.sent.front().is_some_and(|&t| now - t >= WINDOW_MS) {
+            self.sent.pop_front();
+        }
+        match self.sent.front() {
+            Some(&oldest) if self.sent.len() >= LIMIT => Some(oldest + WINDOW_MS - now),
+            _ => None,
+        }
+    }
+
+    /// [`cooldown_ms`](Self::cooldown_ms) in whole seconds, rounded up so that
+    /// a countdown never shows zero while sending is still blocked.
+    pub fn cooldown_secs(&mut self, now: f64) -> Option<u32> {
+        self.cooldown_ms(now).map(|ms| (ms / 1000.0).ceil() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_limit_sends_per_window() {
+        let mut throttle = SendThrottle::default();
+        for i in 0..LIMIT {
+            assert!(throttle.try_send(1_000.0 + i as f64), "send {}", i);
+        }
+        assert!(!throttle.try_send(2_000.0));
+        assert_eq!(throttle.cooldown_ms(2_000.0), Some(9_000.0));
+        // A refused send does not count, so the window still ends when the
+        // oldest send leaves it.
+        assert!(!throttle.try_send(10_999.0));
+        assert!(throttle.try_send(11_000.0));
+        assert!(!throttle.try_send(11_000.0));
+        assert!(throttle.try_send(11_001.0));
+    }
+
+    #[test]
+    fn cooldown_rounds_up_to_whole_seconds() {
+        let mut throttle = SendThrottle::default();
+        assert_eq!(throttle.cooldown_secs(0.0), None);
+        for _ in 0..LIMIT {
+            throttle.try_send(0.0);
+        }
+        assert_eq!(throttle.cooldown_secs(0.0), Some(10));
+        assert_eq!(throttle.cooldown_secs(0.5), Some(10));
+        assert_eq!(throttle.cooldown_secs(1_000.0), Some(9));
+        assert_eq!(throttle.cooldown_secs(9_999.0), Some(1));
+        assert_eq!(throttle.cooldown_secs(10_000.0), None);
+    }
+}
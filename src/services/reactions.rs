@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+/// Offered in the message menu; any emoji received from others is shown too.
+pub const QUICK_REACTIONS: [&str; 6] = ["👍", "❤️", "😂", "🎉", "😮", "😢"];
+
+/// Ids of the users who reacted to a message, by emoji.
+pub type Reactions = BTreeMap<String, Vec<String>>;
+
+pub fn apply(reactions: &mut Reactions, emoji: &str, user_id: &str, added: bool) {
+    let users = reactions.entry(emoji.to_string()).or_default();
+    users.retain(|u| u != user_id);
+    if added {
+        users.push(user_id.to_string());
+    } else if users.is_empty() {
+        reactions.remove(emoji);
+    }
+}
+
+pub fn reacted(reactions: &Reactions, emoji: &str, user_id: &str) -> bool {
+    reactions
+        .get(emoji)
+        .is_some_and(|users| users.iter().any(|u| u == user_id))
+}
+
+pub fn total(reactions: &Reactions) -> usize {
+    reactions.values().map(Vec::len).sum()
+}
+
+/// Reaction counts over the messages the client holds, not the whole room
+/// history.
+#[derive(Clone, Default, PartialEq)]
+pub struct RoomStats {
+    /// Emoji with how often they were used, most used first.
+    pub top_reactions: Vec<(String, usize)>,
+    /// Message indices with their reaction totals, most reacted first.
+    pub top_messages: Vec<(usize, usize)>,
+}
+
+impl RoomStats {
+    pub fn collect<'a>(messages: impl Iterator<Item = &'a Reactions>, limit: usize) -> Self {
+        let mut counts = BTreeMap::<&str, usize>::new();
+        let mut top_messages = Vec::new();
+        for (idx, reactions) in messages.enumerate() {
+            for (emoji, users) in reactions {
+                *counts.entry(emoji).or_default() += users.len();
+            }
+            match total(reactions) {
+                0 => {}
+                n => top_messages.push((idx, n)),
+            }
+        }
+        let mut top_reactions: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(emoji, n)| (emoji.to_string(), n))
+            .collect();
+        top_reactions.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+        top_reactions.truncate(limit);
+        // Newer messages win ties.
+        top_messages.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+        top_messages.truncate(limit);
+        RoomStats {
+            top_reactions,
+            top_messages,
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::{HashMap, HashSet};
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::Deserialize;
+
+const WELCOMED_KEY: &str = "yewchat.welcomed_rooms";
+const RULES_ACK_KEY: &str = "yewchat.rules_ack";
+
+/// An open invitation for the local user to join a room.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Invitation {
+    pub id: String,
+    pub room: String,
+    /// Display name of whoever sent the invitation.
+    pub from: String,
+}
+
+/// Someone asking to join a room that requires approval, as seen by the
+/// room's moderators.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinRequest {
+    pub id: String,
+    pub room: String,
+    pub user_id: String,
+    pub name: String,
+}
+
+/// Onboarding shown once to people joining a room, configured by the room's
+/// owners as part of its metadata.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct WelcomeRule {
+    /// Display name of the bot the card appears to come from.
+    #[serde(default)]
+    pub bot: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<String>,
+    /// Text of the messages newcomers should read first.
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    /// How to get roles in the room, free text.
+    #[serde(default)]
+    pub roles: Option<String>,
+}
+
+/// Rules that must be accepted before posting in a room. Bumping `version`
+/// asks everyone to accept again.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct RoomRules {
+    pub rules: Vec<String>,
+    #[serde(default)]
+    pub version: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomInfo {
+    pub room: String,
+    #[serde(default)]
+    pub welcome: Option<WelcomeRule>,
+    #[serde(default)]
+    pub required_rules: Option<RoomRules>,
+}
+
+fn accepted_rules() -> HashMap<String, u32> {
+    LocalStorage::get(RULES_ACK_KEY).unwrap_or_default()
+}
+
+pub fn rules_accepted(room: &str, rules: &RoomRules) -> bool {
+    accepted_rules().get(room) == Some(&rules.version)
+}
+
+pub fn accept_rules(room: &str, rules: &RoomRules) {
+    let mut accepted = accepted_rules();
+    accepted.insert(room.to_string(), rules.version);
+    if let Err(e) = LocalStorage::set(RULES_ACK_KEY, &accepted) {
+        log::error!("failed to persist rules acceptance: {:?}", e);
+    }
+}
+
+/// Records that the welcome card for `room` was shown, returning `false` if
+/// it already had been.
+pub fn mark_welcomed(room: &str) -> bool {
+    let mut welcomed: HashSet<String> = LocalStorage::get(WELCOMED_KEY).unwrap_or_default();
+    if !welcomed.insert(room.to_string()) {
+        return false;
+    }
+    if let Err(e) = LocalStorage::set(WELCOMED_KEY, &welcomed) {
+        log::error!("failed to persist welcomed rooms: {:?}", e);
+    }
+    true
+}
+
+#[derive(Debug, Default)]
+struct Room {
+    members: HashSet<String>,
+    /// Member ids in server order. Slots stay `None` until the page holding
+    /// them has arrived, so the list always has the room's full length.
+    listed: Vec<Option<String>>,
+}
+
+/// Who is in which room, by user id. Kept apart from the global list of known
+/// users so that rooms can be smaller than the server.
+#[derive(Debug, Default)]
+pub struct Membership {
+    rooms: HashMap<String, Room>,
+}
+
+impl Membership {
+    /// Replaces the member list of `room` with a fresh snapshot.
+    pub fn set(&mut self, room: &str, members: impl IntoIterator<Item = String>) {
+        let listed: Vec<Option<String>> = members.into_iter().map(Some).collect();
+        let members = listed.iter().flatten().cloned().collect();
+        self.rooms.insert(room.to_string(), Room { members, listed });
+    }
+
+    /// Fills in one page of a member list that is loaded lazily. `total` is
+    /// the room's current size and may differ from what earlier pages said.
+    pub fn add_page(&mut self, room: &str, total: usize, offset: usize, ids: Vec<String>) {
+        let room = self.rooms.entry(room.to_string()).or_default();
+        room.listed.resize(total.max(offset + ids.len()), None);
+        for (slot, id) in room.listed[offset..].iter_mut().zip(ids) {
+            room.members.insert(id.clone());
+            *slot = Some(id);
+        }
+    }
+
+    pub fn join(&mut self, room: &str, user_id: &str) {
+        let room = self.rooms.entry(room.to_string()).or_default();
+        if room.members.insert(user_id.to_string()) {
+            room.listed.push(Some(user_id.to_string()));
+        }
+    }
+
+    pub fn leave(&mut self, room: &str, user_id: &str) {
+        if let Some(room) = self.rooms.get_mut(room) {
+            if room.members.remove(user_id) {
+                room.listed.retain(|id| id.as_deref() != Some(user_id));
+            }
+        }
+    }
+
+    /// Until the server has sent a member list for `room`, everyone counts as
+    /// a member, which matches servers that only know a single room.
+    pub fn is_member(&self, room: &str, user_id: &str) -> bool {
+        self.rooms
+            .get(room)
+            .is_none_or(|room| room.members.contains(user_id))
+    }
+
+    /// The room's member slots in order, or `None` if nothing is known yet.
+    pub fn listed(&self, room: &str) -> Option<&[Option<String>]> {
+        self.rooms.get(room).map(|room| room.listed.as_slice())
+    }
+
+    /// First slot in `range` whose member has not been loaded yet.
+    pub fn first_missing(&self, room: &str, range: std::ops::Range<usize>) -> Option<usize> {
+        let listed = &self.rooms.get(room)?.listed;
+        let end = range.end.min(listed.len());
+        (range.start.min(end)..end).find(|&i| listed[i].is_none())
+    }
+
+    pub fn rename(&mut self, old: &str, new: &str) {
+        for room in self.rooms.values_mut() {
+            if room.members.remove(old) {
+                room.members.insert(new.to_string());
+                for id in room.listed.iter_mut().flatten().filter(|id| *id == old) {
+                    *id = new.to_string();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Every renderer that turns message content into DOM goes through this module.
+//!
+//! Plain text is safe as long as it is handed to `html!` as a text node. Anything
+//! richer (raw markup from markdown, embeds, preview cards) must either be built
+//! from [`safe_url`]-checked parts or passed through [`sanitize_html`] /
+//! [`to_html`], which keep a small allowlist of tags and attributes and drop
+//! everything else.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use ammonia::{Builder, UrlRelative};
+use yew::virtual_dom::VNode;
+use yew::Html;
+
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "del",
+    "em",
+    "i",
+    "img",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "s",
+    "span",
+    "strong",
+    "u",
+    "ul",
+];
+
+/// Attributes each tag may keep. `href`, `src` and `class` values are further
+/// checked by [`filter_attribute`].
+const ALLOWED_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href", "title"]),
+    ("img", &["src", "alt", "title"]),
+    ("code", &["class"]),
+    ("pre", &["class"]),
+    ("span", &["class"]),
+];
+
+/// Tags whose content is dropped together with the tag itself.
+const DROP_CONTENT_TAGS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "noscript", "template", "textarea", "title",
+];
+
+const URL_SCHEMES: &[&str] = &["http:", "https:"];
+/// Inline images we are willing to render. SVG is left out on purpose since it
+/// can carry script.
+const IMAGE_DATA_PREFIXES: &[&str] = &[
+    "data:image/png;base64,",
+    "data:image/jpeg;base64,",
+    "data:image/gif;base64,",
+    "data:image/webp;base64,",
+];
+const LINK_SCHEMES: &[&str] = &["http:", "https:", "mailto:"];
+/// Prefixes a `class` token needs to survive, as used by code highlighting.
+/// Anything else could pull in arbitrary Tailwind utilities, e.g. `fixed
+/// inset-0` to lay a fake dialog over the page.
+const CLASS_PREFIXES: &[&str] = &["language-", "hljs"];
+
+/// What a bare media link is embedded as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MediaKind {
+    Image,
+    Video,
+}
+
+fn safe_classes(value: &str) -> Option<String> {
+    let classes: Vec<&str> = value
+        .split_whitespace()
+        .filter(|class| {
+            CLASS_PREFIXES.iter().any(|prefix| class.starts_with(prefix))
+                && class.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .collect();
+    (!classes.is_empty()).then(|| classes.join(" "))
+}
+
+fn checked_url(url: &str, schemes: &[&str]) -> Option<String> {
+    let url = url.trim();
+    if url.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return None;
+    }
+    let lower = url.to_ascii_lowercase();
+    if schemes.iter().any(|scheme| lower.starts_with(scheme)) {
+        Some(url.to_string())
+    } else {
+        None
+    }
+}
+
+/// Returns the URL if it is an absolute http(s) URL that can be used as an
+/// image or media source.
+pub fn safe_url(url: &str) -> Option<String> {
+    checked_url(url, URL_SCHEMES)
+}
+
+fn is_base64_data_url(url: &str) -> bool {
+    let (header, payload) = match url.split_once(',') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    header.starts_with("data:")
+        && header.ends_with(";base64")
+        && !header.chars().any(|c| c.is_control() || c.is_whitespace())
+        && payload
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='))
+}
+
+/// Like [`safe_url`], but also accepts base64 `data:` URLs for raster images,
+/// as produced for small attachments.
+pub fn safe_image_src(url: &str) -> Option<String> {
+    let is_image = IMAGE_DATA_PREFIXES.iter().any(|prefix| url.starts_with(prefix));
+    if is_image && is_base64_data_url(url) {
+        Some(url.to_string())
+    } else {
+        safe_url(url)
+    }
+}
+
+/// Like [`safe_url`], but also accepts base64 `data:` URLs of any type. Only
+/// meant for links carrying the `download` attribute, which are saved rather
+/// than opened.
+pub fn safe_download_href(url: &str) -> Option<String> {
+    if is_base64_data_url(url) {
+        Some(url.to_string())
+    } else {
+        safe_url(url)
+    }
+}
+
+/// Decides whether a [`safe_url`] points at embeddable media, judging by the
+/// extension of its path. Query strings, fragments and letter case do not
+/// matter; anything unrecognized is `None` and stays text.
+pub fn media_kind(url: &str) -> Option<MediaKind> {
+    let url = web_sys::Url::new(&safe_url(url)?).ok()?;
+    let path = url.pathname();
+    let (_, extension) = path.rsplit('/').next()?.rsplit_once('.')?;
+    match extension.to_ascii_lowercase().as_str() {
+        "gif" | "png" | "jpg" | "jpeg" | "webp" => Some(MediaKind::Image),
+        "mp4" => Some(MediaKind::Video),
+        _ => None,
+    }
+}
+
+/// Like [`safe_url`], but also allows `mailto:` links.
+pub fn safe_link(url: &str) -> Option<String> {
+    checked_url(url, LINK_SCHEMES)
+}
+
+fn escape_char(c: char, out: &mut String) {
+    match c {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        '\'' => out.push_str("&#39;"),
+        _ => out.push(c),
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        escape_char(c, &mut out);
+    }
+    out
+}
+
+/// Narrows the values ammonia lets through for the attributes that carry a
+/// URL or a class. Returning `None` drops the attribute.
+fn filter_attribute<'u>(_tag: &str, attr: &str, value: &'u str) -> Option<Cow<'u, str>> {
+    let value = match attr {
+        "href" => safe_link(value)?,
+        "src" => safe_image_src(value)?,
+        "class" => safe_classes(value)?,
+        _ => return Some(Cow::Borrowed(value)),
+    };
+    Some(Cow::Owned(value))
+}
+
+/// Reduces arbitrary markup to the allowlisted subset. Disallowed tags are
+/// removed (their text content is kept, except for script-like tags),
+/// disallowed attributes are dropped, URLs are checked against an allowlist
+/// of schemes and classes against an allowlist of prefixes.
+pub fn sanitize_html(input: &str) -> String {
+    let tag_attributes: HashMap<&str, HashSet<&str>> = ALLOWED_ATTRIBUTES
+        .iter()
+        .map(|(tag, attrs)| (*tag, attrs.iter().copied().collect()))
+        .collect();
+    let url_schemes = LINK_SCHEMES
+        .iter()
+        .map(|scheme| scheme.trim_end_matches(':'))
+        .chain(["data"])
+        .collect();
+
+    Builder::empty()
+        .tags(ALLOWED_TAGS.iter().copied().collect())
+        .clean_content_tags(DROP_CONTENT_TAGS.iter().copied().collect())
+        .tag_attributes(tag_attributes)
+        .set_tag_attribute_value("a", "target", "_blank")
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .url_schemes(url_schemes)
+        .url_relative(UrlRelative::Deny)
+        .attribute_filter(filter_attribute)
+        .clean(input)
+        .to_string()
+}
+
+/// Sanitizes `input` and mounts the result as a detached element that yew can
+/// render. This is the only place raw markup may enter the DOM.
+pub fn to_html(input: &str) -> Html {
+    let clean = sanitize_html(input);
+    let container = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.create_element("span").ok());
+
+    match container {
+        Some(container) => {
+            container.set_inner_html(&clean);
+            VNode::VRef(container.into())
+        }
+        None => Html::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::message_content::format_inline;
+
+    struct Tag {
+        name: String,
+        closing: bool,
+        attrs: Vec<(String, String)>,
+        len: usize,
+    }
+
+    fn parse_tag(input: &str) -> Option<Tag> {
+        let bytes = input.as_bytes();
+        let mut i = 1;
+        let closing = bytes.get(i) == Some(&b'/');
+        if closing {
+            i += 1;
+        }
+        let name_start = i;
+        while i < bytes.len() && bytes[i].is_ascii_alphanumeric() {
+            i += 1;
+        }
+        if i == name_start {
+            return None;
+        }
+        let name = input[name_start..i].to_ascii_lowercase();
+
+        let mut attrs = Vec::new();
+        loop {
+            while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+                i += 1;
+            }
+            match bytes.get(i) {
+                None => return None,
+                Some(b'>') => {
+                    return Some(Tag {
+                        name,
+                        closing,
+                        attrs,
+                        len: i + 1,
+                    })
+                }
+                _ => {}
+            }
+
+            let attr_start = i;
+            while i < bytes.len()
+                && !bytes[i].is_ascii_whitespace()
+                && !matches!(bytes[i], b'=' | b'>' | b'/')
+            {
+                i += 1;
+            }
+            let attr = input[attr_start..i].to_ascii_lowercase();
+
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let mut value = String::new();
+            if bytes.get(i) == Some(&b'=') {
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                match bytes.get(i) {
+                    Some(&quote) if quote == b'"' || quote == b'\'' => {
+                        let value_start = i + 1;
+                        let end = input[value_start..].find(quote as char)? + value_start;
+                        value = input[value_start..end].to_string();
+                        i = end + 1;
+                    }
+                    _ => {
+                        let value_start = i;
+                        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                            i += 1;
+                        }
+                        value = input[value_start..i].to_string();
+                    }
+                }
+            }
+            if !attr.is_empty() {
+                attrs.push((attr, value));
+            }
+        }
+    }
+
+
+    /// Every tag left in `html` is allowlisted and has only allowlisted
+    /// attributes, with no URL other than http(s), mailto or a raster image.
+    fn assert_inert(html: &str) {
+        for (at, _) in html.match_indices('<') {
+            let tag = parse_tag(&html[at..]).unwrap_or_else(|| panic!("stray `<` in {:?}", html));
+            assert!(ALLOWED_TAGS.contains(&tag.name.as_str()), "<{}> in {:?}", tag.name, html);
+            for (attr, value) in &tag.attrs {
+                let allowed = ALLOWED_ATTRIBUTES.iter().any(|(name, attrs)| *name == tag.name && attrs.contains(&attr.as_str()))
+                    || (tag.name == "a" && matches!(attr.as_str(), "target" | "rel"));
+                assert!(allowed, "{}={:?} in {:?}", attr, value, html);
+                let lower = value.to_ascii_lowercase();
+                if attr == "href" || attr == "src" {
+                    assert!(
+                        ["http:", "https:", "mailto:", "data:image/png;", "data:image/jpeg;", "data:image/gif;", "data:image/webp;"]
+                            .iter()
+                            .any(|scheme| lower.starts_with(scheme)),
+                        "{}={:?} in {:?}",
+                        attr,
+                        value,
+                        html
+                    );
+                }
+            }
+        }
+        // What came out is already clean, so cleaning it again changes nothing
+        // beyond the order ammonia lists attributes in.
+        assert_eq!(normalized(&sanitize_html(html)), normalized(html));
+    }
+
+    /// `html` with the attributes of every tag sorted.
+    fn normalized(html: &str) -> String {
+        let mut out = String::new();
+        let mut rest = html;
+        while let Some(at) = rest.find('<') {
+            out.push_str(&rest[..at]);
+            let mut tag = parse_tag(&rest[at..]).unwrap_or_else(|| panic!("stray `<` in {:?}", html));
+            tag.attrs.sort();
+            out.push_str(&format!("<{}{}{:?}>", if tag.closing { "/" } else { "" }, tag.name, tag.attrs));
+            rest = &rest[at + tag.len..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    #[test]
+    fn drops_script_style_and_iframe_with_their_content() {
+        assert_eq!(sanitize_html("a<script>alert(1)</script>b"), "ab");
+        assert_eq!(sanitize_html("a<SCRIPT type=\"text/javascript\">alert(1)</ScRiPt >b"), "ab");
+        assert_eq!(sanitize_html("a<style>body { display: none }</style>b"), "ab");
+        assert_eq!(sanitize_html("a<iframe src=\"https://evil.example\">inner</iframe>b"), "ab");
+        // Without a closing tag everything after the opening one goes.
+        assert_eq!(sanitize_html("a<script>alert(1)"), "a");
+    }
+
+    #[test]
+    fn strips_event_handlers() {
+        assert_eq!(sanitize_html("<b onclick=\"alert(1)\">x</b>"), "<b>x</b>");
+        assert_eq!(sanitize_html("<img src=\"https://example.com/a.png\" OnError=alert(1)>"), "<img src=\"https://example.com/a.png\">");
+        assert_eq!(sanitize_html("<span/onmouseover='alert(1)'>x</span>"), "<span>x</span>");
+        assert_inert(&sanitize_html("<p onload=alert(1) onfocus=\"alert(2)\" autofocus>x</p>"));
+    }
+
+    #[test]
+    fn rejects_script_urls() {
+        for href in [
+            "javascript:alert(1)",
+            "JaVaScRiPt:alert(1)",
+            "  javascript:alert(1)",
+            "vbscript:msgbox(1)",
+            "&#106;avascript:alert(1)",
+            "&#x6A;avascript&#x3A;alert(1)",
+            "java&#x09;script:alert(1)",
+            "java\tscript:alert(1)",
+            "java\nscript:alert(1)",
+            "java script:alert(1)",
+            "data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==",
+        ] {
+            let html = sanitize_html(&format!("<a href=\"{}\">x</a>", href));
+            assert!(!html.contains("href"), "href {:?} gave {:?}", href, html);
+            assert_inert(&html);
+            let html = sanitize_html(&format!("<img src=\"{}\">", href));
+            assert_eq!(html, "<img>", "src {:?}", href);
+        }
+    }
+
+    #[test]
+    fn only_raster_data_urls_are_images() {
+        let png = "data:image/png;base64,iVBORw0KGgo=";
+        assert_eq!(safe_image_src(png).as_deref(), Some(png));
+        assert_eq!(safe_image_src("data:image/svg+xml;base64,PHN2Zz4="), None);
+        assert_eq!(safe_image_src("data:image/png,<svg onload=alert(1)>"), None);
+        assert_eq!(safe_image_src("DATA:image/png;base64,iVBORw0KGgo="), None);
+        assert_eq!(safe_url(png), None);
+        assert_eq!(safe_link(png), None);
+        assert_eq!(safe_link("https://example.com").as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn quoted_attributes_cannot_break_out() {
+        assert_eq!(
+            sanitize_html("<a title='x\" onclick=\"alert(1)' href=\"https://example.com\">y</a>"),
+            "<a title=\"x&quot; onclick=&quot;alert(1)\" href=\"https://example.com\" target=\"_blank\" rel=\"noopener noreferrer nofollow\">y</a>"
+        );
+        assert_eq!(
+            sanitize_html("<img alt=\"&quot; onerror=&quot;alert(1)\">"),
+            "<img alt=\"&quot; onerror=&quot;alert(1)\">"
+        );
+        assert_eq!(sanitize_html("<img alt=x\"onerror=alert(1)>"), "<img alt=\"x&quot;onerror=alert(1)\">");
+    }
+
+    #[test]
+    fn unterminated_tags_are_dropped() {
+        // A tag cut off by the end of the input never opens, as in a browser.
+        assert_eq!(sanitize_html("<img src=x onerror=alert(1)"), "");
+        assert_eq!(sanitize_html("<a href=\"javascript:alert(1)>x"), "");
+        assert_eq!(sanitize_html("1 < 2"), "1 &lt; 2");
+        assert_eq!(sanitize_html("<"), "&lt;");
+    }
+
+    #[test]
+    fn format_inline_output_stays_clean() {
+        for message in [
+            "<script>alert(1)</script>",
+            "**<img src=x onerror=alert(1)>**",
+            "`</code><script>alert(1)</script>`",
+            "*\"><svg onload=alert(1)>*",
+            "https://example.com/\"onmouseover=\"alert(1)",
+            "javascript://example.com/%0Aalert(1)",
+            "<a href=\"javascript:alert(1)\">x</a>",
+        ] {
+            assert_inert(&sanitize_html(&format_inline(message)));
+        }
+    }
+}
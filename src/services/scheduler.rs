@@ -0,0 +1,131 @@
+//! Messages held back until a chosen time. They are kept in local storage
+//! but only go out while the app is open; any whose time passed while it was
+//! closed are left for us to send or cancel.
+
+use gloo_storage::{LocalStorage, Storage};
+use gloo_timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use yew::Callback;
+
+const STORAGE_KEY: &str = "yewchat.scheduled";
+/// Longest delay `setTimeout` takes; later sends wake up early and re-arm.
+const MAX_WAIT: f64 = i32::MAX as f64;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledMessage {
+    pub id: u64,
+    /// `None` for the room, else the direct message peer's id.
+    pub peer: Option<String>,
+    pub text: String,
+    /// Milliseconds since the epoch.
+    pub send_at: f64,
+    /// Its time passed while the app was closed.
+    #[serde(skip)]
+    pub missed: bool,
+}
+
+/// The queue of scheduled messages, earliest first, with a timer for the
+/// next one due. `on_due` is called when it fires, to collect them with
+/// [`Scheduler::due`].
+pub struct Scheduler {
+    queue: Vec<ScheduledMessage>,
+    timer: Option<Timeout>,
+    on_due: Callback<()>,
+}
+
+impl Scheduler {
+    pub fn load(on_due: Callback<()>) -> Self {
+        let now = js_sys::Date::now();
+        let mut queue: Vec<ScheduledMessage> = LocalStorage::get(STORAGE_KEY).unwrap_or_default();
+        for message in &mut queue {
+            message.missed = message.send_at <= now;
+        }
+        let mut scheduler = Scheduler {
+            queue,
+            timer: None,
+            on_due,
+        };
+        scheduler.arm();
+        scheduler
+    }
+
+    pub fn pending(&self) -> &[ScheduledMessage] {
+        &self.queue
+    }
+
+    pub fn add(&mut self, peer: Option<String>, text: String, send_at: f64) {
+        let id = self.queue.iter().map(|m| m.id + 1).max().unwrap_or(0);
+        let at = self.queue.partition_point(|m| m.send_at <= send_at);
+        self.queue.insert(
+            at,
+            ScheduledMessage {
+                id,
+                peer,
+                text,
+                send_at,
+                missed: false,
+            },
+        );
+        self.changed();
+    }
+
+    pub fn cancel(&mut self, id: u64) -> Option<ScheduledMessage> {
+        let at = self.queue.iter().position(|m| m.id == id)?;
+        let message = self.queue.remove(at);
+        self.changed();
+        Some(message)
+    }
+
+    /// The messages whose time has come, leaving missed ones alone. They stay
+    /// queued until [`Scheduler::cancel`]led once sent, or set aside with
+    /// [`Scheduler::mark_missed`] if they could not be.
+    pub fn due(&mut self, now: f64) -> Vec<ScheduledMessage> {
+        // The timer may have woken early for a send too far off to wait for
+        // in one go.
+        self.arm();
+        self.queue
+            .iter()
+            .filter(|m| !m.missed && m.send_at <= now)
+            .cloned()
+            .collect()
+    }
+
+    /// Leaves a message that could not go out for us to send or cancel.
+    pub fn mark_missed(&mut self, id: u64) {
+        if let Some(message) = self.queue.iter_mut().find(|m| m.id == id) {
+            message.missed = true;
+        }
+        self.arm();
+    }
+
+    /// Checks for due messages again after `wait` milliseconds rather than
+    /// right away, e.g. while sending is rate limited.
+    pub fn retry_in(&mut self, wait: f64) {
+        let on_due = self.on_due.clone();
+        self.timer = Some(Timeout::new(wait.clamp(0.0, MAX_WAIT) as u32, move || on_due.emit(())));
+    }
+
+    /// Forgets every scheduled message, e.g. when signing out.
+    pub fn clear_all(&mut self) {
+        self.queue.clear();
+        self.timer = None;
+        LocalStorage::delete(STORAGE_KEY);
+    }
+
+    fn changed(&mut self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, &self.queue) {
+            log::error!("failed to persist scheduled messages: {:?}", e);
+        }
+        self.arm();
+    }
+
+    fn arm(&mut self) {
+        let next = self.queue.iter().filter(|m| !m.missed).map(|m| m.send_at).reduce(f64::min);
+        self.timer = next.map(|at| {
+            let wait = (at - js_sys::Date::now()).clamp(0.0, MAX_WAIT) as u32;
+            let on_due = self.on_due.clone();
+            Timeout::new(wait, move || on_due.emit(()))
+        });
+    }
+}
@@ -0,0 +1,77 @@
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+const SESSION_ID_KEY: &str = "yewchat.session_id";
+const USER_ID_KEY: &str = "yewchat.user_id";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEntry {
+    pub id: String,
+    pub device: String,
+    /// Milliseconds since the epoch.
+    pub last_active: f64,
+    /// Coarse location hint supplied by the server, e.g. a truncated IP.
+    #[serde(default)]
+    pub address_hint: Option<String>,
+}
+
+pub fn random_id() -> String {
+    (0..4)
+        .map(|_| format!("{:08x}", (js_sys::Math::random() * u32::MAX as f64) as u32))
+        .collect()
+}
+
+/// A random (version 4) UUID, as a client-side message id.
+pub fn uuid() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("no randomness source");
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+fn stored_id(key: &str) -> String {
+    LocalStorage::get(key).unwrap_or_else(|_| {
+        let id = random_id();
+        if let Err(e) = LocalStorage::set(key, &id) {
+            log::error!("failed to persist {}: {:?}", key, e);
+        }
+        id
+    })
+}
+
+/// Identifier for this browser's session, kept across reloads.
+pub fn session_id() -> String {
+    stored_id(SESSION_ID_KEY)
+}
+
+/// Stable identifier for the local user, independent of the display name.
+pub fn user_id() -> String {
+    stored_id(USER_ID_KEY)
+}
+
+pub fn clear_session_id() {
+    LocalStorage::delete(SESSION_ID_KEY);
+}
+
+/// A short human-readable description of the current browser.
+pub fn device_name() -> String {
+    let agent = web_sys::window()
+        .and_then(|w| w.navigator().user_agent().ok())
+        .unwrap_or_default();
+
+    let browser = ["Edg", "Firefox", "Chrome", "Safari"]
+        .iter()
+        .find(|b| agent.contains(*b))
+        .map(|b| if *b == "Edg" { "Edge" } else { b })
+        .unwrap_or("Browser");
+    let platform = ["Android", "iPhone", "iPad", "Windows", "Mac OS", "Linux"]
+        .iter()
+        .find(|p| agent.contains(*p))
+        .map(|p| if *p == "Mac OS" { "macOS" } else { p })
+        .unwrap_or("Unknown OS");
+
+    format!("{} on {}", browser, platform)
+}
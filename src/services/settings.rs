@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::services::avatar::AvatarStyle;
+use crate::services::i18n::{t, Locale};
+use crate::services::theme::Theme;
+use crate::services::transport::TransportPreset;
+use crate::services::typing::TypingSignal;
+use crate::services::websocket;
+
+const STORAGE_KEY: &str = "yewchat.settings";
+/// Where the sound switch was kept before it moved into the settings.
+const LEGACY_MUTED_KEY: &str = "yewchat.sound_muted";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum NotificationLevel {
+    #[default]
+    All,
+    Mentions,
+    None,
+}
+
+impl NotificationLevel {
+    pub const ALL: [NotificationLevel; 3] = [
+        NotificationLevel::All,
+        NotificationLevel::Mentions,
+        NotificationLevel::None,
+    ];
+
+    pub fn label(&self) -> String {
+        t(match self {
+            NotificationLevel::All => "level-all",
+            NotificationLevel::Mentions => "level-mentions",
+            NotificationLevel::None => "level-none",
+        })
+    }
+}
+
+/// Minutes since local midnight. `start` may be after `end`, in which case the
+/// range wraps past midnight.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl QuietHours {
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start <= self.end {
+            minute >= self.start && minute < self.end
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        QuietHours {
+            start: 22 * 60,
+            end: 7 * 60,
+        }
+    }
+}
+
+pub fn format_minutes(minutes: u32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// The disappearing message timers on offer, in seconds.
+pub const DISAPPEARING_AFTER: [u32; 4] = [5 * 60, 60 * 60, 24 * 60 * 60, 7 * 24 * 60 * 60];
+
+pub fn parse_minutes(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then(|| hours * 60 + minutes)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub notification_level: NotificationLevel,
+    pub quiet_hours: Option<QuietHours>,
+    pub channel_overrides: HashMap<String, NotificationLevel>,
+    /// URL template for fetching external media, with `{url}` standing in for
+    /// the encoded original URL.
+    pub media_proxy: Option<String>,
+    pub load_original_media: bool,
+    pub theme: Theme,
+    pub locale: Locale,
+    /// Ids of messages removed for this user only.
+    pub hidden_messages: HashSet<String>,
+    /// Words that notify like a mention, matched as whole words.
+    pub keywords: Vec<String>,
+    /// Whether `@here` and `@everyone` count as mentions.
+    pub notify_group_mentions: bool,
+    pub typing_signal: TypingSignal,
+    /// User ids of direct message conversations pinned to the top of the
+    /// sidebar, in display order.
+    pub pinned_dms: Vec<String>,
+    pub transport: TransportPreset,
+    /// Hides the sidebar and header around a narrower message column.
+    pub focus_mode: bool,
+    /// Words masked in others' messages until revealed; empty turns the
+    /// filter off.
+    pub filtered_words: Vec<String>,
+    pub sound_muted: bool,
+    pub avatar_style: AvatarStyle,
+    /// Websocket address of the chat server; `None` is the one built in.
+    pub server_url: Option<String>,
+    /// Seconds after which our messages disappear, by room name or direct
+    /// message peer id. Conversations not listed keep them.
+    pub disappearing: HashMap<String, u32>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            notification_level: NotificationLevel::default(),
+            quiet_hours: None,
+            channel_overrides: HashMap::new(),
+            media_proxy: option_env!("YEWCHAT_MEDIA_PROXY").map(String::from),
+            load_original_media: false,
+            theme: Theme::default(),
+            locale: Locale::detect(),
+            hidden_messages: HashSet::new(),
+            keywords: Vec::new(),
+            notify_group_mentions: true,
+            typing_signal: TypingSignal::default(),
+            pinned_dms: Vec::new(),
+            transport: TransportPreset::default(),
+            focus_mode: false,
+            filtered_words: Vec::new(),
+            sound_muted: LocalStorage::get(LEGACY_MUTED_KEY).unwrap_or(false),
+            avatar_style: AvatarStyle::default(),
+            server_url: None,
+            disappearing: HashMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist settings: {:?}", e);
+        }
+        LocalStorage::delete(LEGACY_MUTED_KEY);
+    }
+
+    pub fn media_proxy(&self) -> Option<&str> {
+        if self.load_original_media {
+            None
+        } else {
+            self.media_proxy.as_deref().filter(|t| t.contains("{url}"))
+        }
+    }
+
+    pub fn server_url(&self) -> &str {
+        self.server_url.as_deref().unwrap_or(websocket::DEFAULT_URL)
+    }
+
+    pub fn level_for(&self, channel: &str) -> NotificationLevel {
+        self.channel_overrides
+            .get(channel)
+            .copied()
+            .unwrap_or(self.notification_level)
+    }
+
+    /// Pins the conversation with `user_id` at the bottom of the pins, or
+    /// unpins it.
+    pub fn toggle_pinned_dm(&mut self, user_id: &str) {
+        match self.pinned_dms.iter().position(|id| id == user_id) {
+            Some(index) => {
+                self.pinned_dms.remove(index);
+            }
+            None => self.pinned_dms.push(user_id.to_string()),
+        }
+    }
+
+    pub fn move_pinned_dm(&mut self, from: usize, to: usize) {
+        if from < self.pinned_dms.len() && to < self.pinned_dms.len() {
+            let id = self.pinned_dms.remove(from);
+            self.pinned_dms.insert(to, id);
+        }
+    }
+
+    pub fn in_quiet_hours(&self) -> bool {
+        let now = js_sys::Date::new_0();
+        let minute = now.get_hours() * 60 + now.get_minutes();
+        self.quiet_hours.is_some_and(|q| q.contains(minute))
+    }
+}
@@ -0,0 +1,95 @@
+//! Keyboard shortcuts that work anywhere in the chat. Key presses are matched
+//! here so the bindings and the help overlay listing them cannot drift apart.
+
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+
+use crate::services::action_log;
+use crate::services::i18n::t;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    QuickSwitcher,
+    /// Closes whatever panel, dialog or edit is open.
+    ClosePanels,
+    /// Handled by the composer, where it only applies while the input is
+    /// empty; listed here for the overlay.
+    EditLast,
+    PreviousConversation,
+    NextConversation,
+    ToggleFocusMode,
+    ToggleActionLog,
+    ShowHelp,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::QuickSwitcher,
+        Action::ClosePanels,
+        Action::EditLast,
+        Action::PreviousConversation,
+        Action::NextConversation,
+        Action::ToggleFocusMode,
+        Action::ToggleActionLog,
+        Action::ShowHelp,
+    ];
+
+    /// The keys as shown in the overlay.
+    pub fn keys(&self) -> &'static str {
+        match self {
+            Action::QuickSwitcher => "Ctrl+K",
+            Action::ClosePanels => "Esc",
+            Action::EditLast => "↑",
+            Action::PreviousConversation => "Alt+↑",
+            Action::NextConversation => "Alt+↓",
+            Action::ToggleFocusMode => "Ctrl+Shift+F",
+            Action::ToggleActionLog => "Ctrl+Shift+D",
+            Action::ShowHelp => "?",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        t(match self {
+            Action::QuickSwitcher => "shortcut-quick-switcher",
+            Action::ClosePanels => "shortcut-close",
+            Action::EditLast => "shortcut-edit-last",
+            Action::PreviousConversation => "shortcut-previous-conversation",
+            Action::NextConversation => "shortcut-next-conversation",
+            Action::ToggleFocusMode => "shortcut-focus-mode",
+            Action::ToggleActionLog => "shortcut-action-log",
+            Action::ShowHelp => "shortcut-help",
+        })
+    }
+
+    /// Whether this build has the shortcut at all.
+    pub fn available(&self) -> bool {
+        *self != Action::ToggleActionLog || action_log::ENABLED
+    }
+}
+
+/// The shortcut a key press on the document triggers, if any. Cmd stands in
+/// for Ctrl on macOS.
+pub fn action(e: &KeyboardEvent) -> Option<Action> {
+    let command = e.ctrl_key() || e.meta_key();
+    let key = e.key();
+    let action = match key.as_str() {
+        "Escape" => Action::ClosePanels,
+        "ArrowUp" if e.alt_key() => Action::PreviousConversation,
+        "ArrowDown" if e.alt_key() => Action::NextConversation,
+        // Plain `?` is text in an input.
+        "?" if !command && !e.alt_key() && !in_text_field(e) => Action::ShowHelp,
+        key if command && !e.shift_key() && key.eq_ignore_ascii_case("k") => Action::QuickSwitcher,
+        key if command && e.shift_key() && key.eq_ignore_ascii_case("f") => Action::ToggleFocusMode,
+        key if command && e.shift_key() && key.eq_ignore_ascii_case("d") => Action::ToggleActionLog,
+        _ => return None,
+    };
+    action.available().then_some(action)
+}
+
+fn in_text_field(e: &KeyboardEvent) -> bool {
+    e.target()
+        .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        .is_some_and(|element| {
+            matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT") || element.is_content_editable()
+        })
+}
@@ -0,0 +1,41 @@
+use std::cell::RefCell;
+
+use web_sys::{AudioContext, OscillatorType};
+
+thread_local! {
+    // Browsers cap the number of live audio contexts, so one is shared for all chimes.
+    static AUDIO_CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// Synthesizes a short two-tone chime, so no audio asset has to be shipped.
+pub fn play_chime() {
+    if let Err(e) = try_play_chime() {
+        log::debug!("unable to play chime: {:?}", e);
+    }
+}
+
+fn try_play_chime() -> Result<(), wasm_bindgen::JsValue> {
+    let ctx = AUDIO_CONTEXT.with(|cell| -> Result<AudioContext, wasm_bindgen::JsValue> {
+        let mut cell = cell.borrow_mut();
+        if cell.is_none() {
+            *cell = Some(AudioContext::new()?);
+        }
+        Ok(cell.as_ref().unwrap().clone())
+    })?;
+    let now = ctx.current_time();
+
+    let gain = ctx.create_gain()?;
+    gain.gain().set_value_at_time(0.15, now)?;
+    gain.gain().exponential_ramp_to_value_at_time(0.001, now + 0.3)?;
+    gain.connect_with_audio_node(&ctx.destination())?;
+
+    let osc = ctx.create_oscillator()?;
+    osc.set_type(OscillatorType::Sine);
+    osc.frequency().set_value_at_time(880.0, now)?;
+    osc.frequency().set_value_at_time(1320.0, now + 0.1)?;
+    osc.connect_with_audio_node(&gain)?;
+    osc.start()?;
+    osc.stop_with_when(now + 0.3)?;
+
+    Ok(())
+}
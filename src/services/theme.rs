@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+
+use gloo_events::EventListener;
+use serde::{Deserialize, Serialize};
+use web_sys::MediaQueryList;
+
+use crate::services::i18n::t;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    pub fn next(self) -> Self {
+        match self {
+            Theme::System => Theme::Light,
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::System,
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Theme::System => "🖥",
+            Theme::Light => "☀",
+            Theme::Dark => "🌙",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        t(match self {
+            Theme::System => "theme-system",
+            Theme::Light => "theme-light",
+            Theme::Dark => "theme-dark",
+        })
+    }
+}
+
+thread_local! {
+    static SYSTEM_LISTENER: RefCell<Option<EventListener>> = const { RefCell::new(None) };
+}
+
+fn system_query() -> Option<MediaQueryList> {
+    web_sys::window()?
+        .match_media("(prefers-color-scheme: dark)")
+        .ok()
+        .flatten()
+}
+
+fn set_dark(dark: bool) {
+    let root = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.document_element());
+    if let Some(root) = root {
+        let result = if dark {
+            root.class_list().add_1("dark")
+        } else {
+            root.class_list().remove_1("dark")
+        };
+        if let Err(e) = result {
+            log::error!("failed to switch theme: {:?}", e);
+        }
+    }
+}
+
+/// Applies the theme to the root element. In `System` mode the choice keeps
+/// following `prefers-color-scheme` until another theme is applied.
+pub fn apply(theme: Theme) {
+    let query = system_query();
+    let dark = match theme {
+        Theme::Light => false,
+        Theme::Dark => true,
+        Theme::System => query.as_ref().map(|q| q.matches()).unwrap_or(false),
+    };
+    set_dark(dark);
+
+    let listener = match (theme, query) {
+        (Theme::System, Some(query)) => {
+            let target = query.clone();
+            Some(EventListener::new(&query, "change", move |_| {
+                set_dark(target.matches())
+            }))
+        }
+        _ => None,
+    };
+    SYSTEM_LISTENER.with(|l| *l.borrow_mut() = listener);
+}
@@ -0,0 +1,51 @@
+//! Recognising pasted chat transcripts, so they can be sent as a quote rather
+//! than as lines that read like our own words.
+
+/// Longest name accepted before the `:` of a transcript line.
+const MAX_NAME_LEN: usize = 32;
+
+/// Splits a `name: message` line, optionally led by a `[time]` stamp, into
+/// the stamp, the name and the message.
+fn split(line: &str) -> Option<(&str, &str, &str)> {
+    let (stamp, rest) = match line.strip_prefix('[') {
+        Some(rest) => {
+            let end = rest.find(']')? + 2;
+            (&line[..end], line[end..].trim_start())
+        }
+        None => ("", line),
+    };
+    let (name, message) = rest.split_once(": ")?;
+    let name = name.trim();
+    let plausible = !name.is_empty()
+        && name.chars().count() <= MAX_NAME_LEN
+        && !name.contains("://")
+        && !message.trim().is_empty();
+    plausible.then_some((stamp, name, message.trim()))
+}
+
+/// Whether `text` reads as a transcript: several lines, each non-blank one
+/// starting with a speaker. Continuation lines are allowed, as long as
+/// speaker lines are the majority.
+pub fn looks_like_transcript(text: &str) -> bool {
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    if lines.len() < 2 || split(lines[0]).is_none() {
+        return false;
+    }
+    let spoken = lines.iter().filter(|l| split(l).is_some()).count();
+    spoken >= 2 && spoken * 2 > lines.len()
+}
+
+/// Turns a transcript into a quote block, with each speaker in bold.
+pub fn to_quote(text: &str) -> String {
+    text.trim()
+        .lines()
+        .map(str::trim)
+        .map(|line| match split(line) {
+            Some(("", name, message)) => format!("> **{}:** {}", name, message),
+            Some((stamp, name, message)) => format!("> {} **{}:** {}", stamp, name, message),
+            None if line.is_empty() => ">".to_string(),
+            None => format!("> {}", line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,55 @@
+use reqwasm::http::Request;
+use serde::{Deserialize, Serialize};
+
+use crate::services::i18n::Locale;
+
+/// A LibreTranslate-compatible `/translate` endpoint. Translation is off
+/// when this is not configured.
+const TRANSLATE_URL: Option<&str> = option_env!("YEWCHAT_TRANSLATE_URL");
+const API_KEY: Option<&str> = option_env!("YEWCHAT_TRANSLATE_KEY");
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateResponse {
+    translated_text: String,
+}
+
+pub fn is_enabled() -> bool {
+    TRANSLATE_URL.is_some()
+}
+
+/// Translates `text` from whatever language it is in into `locale`'s.
+pub async fn translate(text: &str, locale: Locale) -> Result<String, String> {
+    let url = TRANSLATE_URL.ok_or("no translation API configured")?;
+    // The API wants bare language codes, e.g. `en` rather than `en-US`.
+    let target = locale.id().split('-').next().unwrap_or_default();
+    let body = serde_json::to_string(&TranslateRequest {
+        q: text,
+        source: "auto",
+        target,
+        format: "text",
+        api_key: API_KEY,
+    })
+    .map_err(|e| e.to_string())?;
+    let response = Request::post(url)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.ok() {
+        return Err(response.status_text());
+    }
+    let body: TranslateResponse = response.json().await.map_err(|e| e.to_string())?;
+    Ok(body.translated_text)
+}
@@ -0,0 +1,86 @@
+//! Named bundles of websocket tuning, so nobody has to reason about backoff
+//! caps and heartbeats individually.
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::i18n::t;
+
+/// How frames are put on the wire. The server accepts both.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Codec {
+    Text,
+    Binary,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransportPolicy {
+    /// Delay before the first reconnect attempt, doubled after every failure.
+    pub initial_backoff_ms: u32,
+    pub max_backoff_ms: u32,
+    pub heartbeat_ms: u32,
+    /// Outgoing messages kept while disconnected; the oldest are dropped
+    /// beyond this.
+    pub queue_size: usize,
+    pub codec: Codec,
+}
+
+impl TransportPolicy {
+    pub fn backoff_ms(&self, attempt: u32) -> u32 {
+        self.initial_backoff_ms
+            .saturating_mul(1 << attempt.min(16))
+            .min(self.max_backoff_ms)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TransportPreset {
+    #[default]
+    Default,
+    /// Gentle on battery and flaky networks: slow heartbeats, patient
+    /// reconnects and a deep queue for messages written while offline.
+    MobileFriendly,
+    /// Notices and repairs dropped connections quickly.
+    LowLatency,
+}
+
+impl TransportPreset {
+    pub const ALL: [TransportPreset; 3] = [
+        TransportPreset::Default,
+        TransportPreset::MobileFriendly,
+        TransportPreset::LowLatency,
+    ];
+
+    pub fn label(&self) -> String {
+        t(match self {
+            TransportPreset::Default => "transport-default",
+            TransportPreset::MobileFriendly => "transport-mobile-friendly",
+            TransportPreset::LowLatency => "transport-low-latency",
+        })
+    }
+
+    pub fn policy(&self) -> TransportPolicy {
+        match self {
+            TransportPreset::Default => TransportPolicy {
+                initial_backoff_ms: 1_000,
+                max_backoff_ms: 30_000,
+                heartbeat_ms: 30_000,
+                queue_size: 100,
+                codec: Codec::Text,
+            },
+            TransportPreset::MobileFriendly => TransportPolicy {
+                initial_backoff_ms: 2_000,
+                max_backoff_ms: 120_000,
+                heartbeat_ms: 60_000,
+                queue_size: 500,
+                codec: Codec::Binary,
+            },
+            TransportPreset::LowLatency => TransportPolicy {
+                initial_backoff_ms: 250,
+                max_backoff_ms: 5_000,
+                heartbeat_ms: 10_000,
+                queue_size: 50,
+                codec: Codec::Text,
+            },
+        }
+    }
+}
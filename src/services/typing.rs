@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::i18n::{t, t_args};
+
+/// Typing that stops for longer than this starts a new burst.
+const PAUSE_MS: f64 = 1_500.0;
+/// How long a burst must last before a delayed signal goes out.
+const DELAY_MS: f64 = 2_000.0;
+/// While typing continues the signal is repeated this often, so that
+/// receivers can expire it on their own when it stops.
+const REFRESH_MS: f64 = 3_000.0;
+
+/// Names in the typing line are cut short past this many characters.
+const NAME_CHARS: usize = 20;
+
+/// How much of our typing others get to see.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum TypingSignal {
+    #[default]
+    Immediate,
+    /// Only after a couple of seconds of sustained typing, so quick replies
+    /// and abandoned starts go unnoticed.
+    Delayed,
+    Never,
+}
+
+impl TypingSignal {
+    pub const ALL: [TypingSignal; 3] = [
+        TypingSignal::Immediate,
+        TypingSignal::Delayed,
+        TypingSignal::Never,
+    ];
+
+    pub fn label(&self) -> String {
+        t(match self {
+            TypingSignal::Immediate => "typing-signal-immediate",
+            TypingSignal::Delayed => "typing-signal-delayed",
+            TypingSignal::Never => "typing-signal-never",
+        })
+    }
+
+    fn delay(&self) -> Option<f64> {
+        match self {
+            TypingSignal::Immediate => Some(0.0),
+            TypingSignal::Delayed => Some(DELAY_MS),
+            TypingSignal::Never => None,
+        }
+    }
+}
+
+/// Decides when to tell the room we are typing. Times are in milliseconds
+/// and passed in by the caller.
+#[derive(Debug, Default)]
+pub struct TypingBroadcaster {
+    burst_start: f64,
+    last_input: f64,
+    /// When the last "typing" signal went out, if it is still in effect.
+    announced: Option<f64>,
+}
+
+impl TypingBroadcaster {
+    /// Feeds the composer's current text. Returns the state to broadcast, if
+    /// it needs broadcasting.
+    pub fn input(&mut self, text: &str, now: f64, mode: TypingSignal) -> Option<bool> {
+        // Slash commands never reach the room, so neither does typing them.
+        let trimmed = text.trim_start();
+        let delay = match mode.delay() {
+            Some(delay) if !trimmed.is_empty() && !trimmed.starts_with('/') => delay,
+            _ => return self.stop(),
+        };
+        if now - self.last_input > PAUSE_MS {
+            self.burst_start = now;
+        }
+        self.last_input = now;
+
+        match self.announced {
+            Some(at) if now - at < REFRESH_MS => None,
+            Some(_) => {
+                self.announced = Some(now);
+                Some(true)
+            }
+            None if now - self.burst_start >= delay => {
+                self.announced = Some(now);
+                Some(true)
+            }
+            None => None,
+        }
+    }
+
+    /// Call when the message is sent or dropped.
+    pub fn stop(&mut self) -> Option<bool> {
+        self.announced.take().map(|_| false)
+    }
+}
+
+/// The line under the messages for the people typing, naming the first two
+/// and counting the rest past three.
+pub fn summary(names: &[&str]) -> Option<String> {
+    let short = |name: &str| match name.char_indices().nth(NAME_CHARS) {
+        Some((end, _)) => format!("{}…", &name[..end]),
+        None => name.to_string(),
+    };
+    let names: Vec<String> = names.iter().map(|name| short(name)).collect();
+    Some(match names.as_slice() {
+        [] => return None,
+        [name] => t_args("typing-one", &[("name", name)]),
+        [first, second] => t_args("typing-two", &[("first", first), ("second", second)]),
+        [first, second, third] => t_args(
+            "typing-three",
+            &[("first", first), ("second", second), ("third", third)],
+        ),
+        [first, second, rest @ ..] => t_args(
+            "typing-many",
+            &[("first", first), ("second", second), ("count", &rest.len().to_string())],
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn immediate_signal_is_debounced() {
+        let mut typing = TypingBroadcaster::default();
+        let mode = TypingSignal::Immediate;
+        assert_eq!(typing.input("h", 10_000.0, mode), Some(true));
+        assert_eq!(typing.input("he", 10_500.0, mode), None);
+        assert_eq!(typing.input("hel", 12_999.0, mode), None);
+        // Repeated once the last signal is old enough to expire.
+        assert_eq!(typing.input("hell", 13_000.0, mode), Some(true));
+        assert_eq!(typing.input("hello", 13_100.0, mode), None);
+        assert_eq!(typing.input("", 13_200.0, mode), Some(false));
+        assert_eq!(typing.input("", 13_300.0, mode), None);
+    }
+
+    #[test]
+    fn delayed_signal_waits_for_sustained_typing() {
+        let mut typing = TypingBroadcaster::default();
+        let mode = TypingSignal::Delayed;
+        assert_eq!(typing.input("h", 10_000.0, mode), None);
+        assert_eq!(typing.input("he", 11_000.0, mode), None);
+        assert_eq!(typing.input("hel", 12_000.0, mode), Some(true));
+        assert_eq!(typing.stop(), Some(false));
+
+        // A long enough pause starts the wait over.
+        assert_eq!(typing.input("a", 20_000.0, mode), None);
+        assert_eq!(typing.input("ab", 21_501.0, mode), None);
+        assert_eq!(typing.input("abc", 23_000.0, mode), None);
+        assert_eq!(typing.input("abcd", 23_501.0, mode), Some(true));
+    }
+
+    #[test]
+    fn commands_and_never_send_nothing() {
+        let mut typing = TypingBroadcaster::default();
+        assert_eq!(typing.input("/me waves", 10_000.0, TypingSignal::Immediate), None);
+        assert_eq!(typing.input("hi", 10_100.0, TypingSignal::Never), None);
+        assert_eq!(typing.input("hi", 10_200.0, TypingSignal::Immediate), Some(true));
+        assert_eq!(typing.input("  /shrug", 10_300.0, TypingSignal::Immediate), Some(false));
+    }
+}
+
@@ -0,0 +1,98 @@
+//! Peers' encryption keys as we have seen and verified them, so a key that
+//! changes behind our back is noticed instead of quietly trusted.
+
+use std::collections::HashMap;
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+const STORAGE_KEY: &str = "yewchat.known_keys";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KnownKey {
+    key: String,
+    /// We compared safety numbers with them for this key.
+    #[serde(default)]
+    verified: bool,
+    /// It replaced another key, and we have not acknowledged that yet.
+    #[serde(default)]
+    changed: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum KeySeen {
+    New,
+    Same,
+    /// It differs from the one we had; `was_verified` says whether we had
+    /// verified that one, which makes the change more suspicious.
+    Changed { was_verified: bool },
+}
+
+/// By user id.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct KnownKeys(HashMap<String, KnownKey>);
+
+impl KnownKeys {
+    pub fn load() -> Self {
+        LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            log::error!("failed to persist known keys: {:?}", e);
+        }
+    }
+
+    /// Records `key` as `peer`'s current one. A different key takes the
+    /// old one's place unverified, flagged as changed.
+    pub fn see(&mut self, peer: &str, key: &str) -> KeySeen {
+        let seen = match self.0.get(peer) {
+            None => KeySeen::New,
+            Some(known) if known.key == key => return KeySeen::Same,
+            Some(known) => KeySeen::Changed { was_verified: known.verified },
+        };
+        self.0.insert(
+            peer.to_string(),
+            KnownKey {
+                key: key.to_string(),
+                verified: false,
+                changed: seen != KeySeen::New,
+            },
+        );
+        self.save();
+        seen
+    }
+
+    pub fn is_verified(&self, peer: &str) -> bool {
+        self.0.get(peer).is_some_and(|known| known.verified)
+    }
+
+    /// Whether `peer`'s key changed and we have not acknowledged it.
+    pub fn has_changed(&self, peer: &str) -> bool {
+        self.0.get(peer).is_some_and(|known| known.changed)
+    }
+
+    /// Marks `key` verified, unless it is no longer `peer`'s key, e.g. it
+    /// changed while the safety number was on screen.
+    pub fn verify(&mut self, peer: &str, key: &str) {
+        if let Some(known) = self.0.get_mut(peer).filter(|known| known.key == key) {
+            known.verified = true;
+            known.changed = false;
+            self.save();
+        }
+    }
+
+    pub fn unverify(&mut self, peer: &str) {
+        if let Some(known) = self.0.get_mut(peer).filter(|known| known.verified) {
+            known.verified = false;
+            self.save();
+        }
+    }
+
+    pub fn acknowledge_change(&mut self, peer: &str) {
+        if let Some(known) = self.0.get_mut(peer).filter(|known| known.changed) {
+            known.changed = false;
+            self.save();
+        }
+    }
+}
@@ -0,0 +1,791 @@
+//! One-to-one voice and video calls. Media flows directly between the two
+//! browsers; the chat server only relays the signaling messages defined here.
+//!
+//! Every signal carries a `peer`: the recipient's user id when we send it,
+//! rewritten by the server to the sender's id when it is delivered.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gloo_events::EventListener;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    HtmlAudioElement, HtmlVideoElement, MediaDeviceInfo, MediaDeviceKind, MediaDevices,
+    MediaStream, MediaStreamConstraints, MediaStreamTrack, RtcConfiguration, RtcIceCandidateInit,
+    RtcIceConnectionState, RtcIceServer, RtcPeerConnection, RtcPeerConnectionIceEvent,
+    RtcRtpSender, RtcSdpType, RtcSessionDescription, RtcSessionDescriptionInit,
+    RtcSignalingState, RtcTrackEvent,
+};
+use yew::Callback;
+
+use crate::services::session;
+
+/// STUN server used to discover our public address. Calls between networks
+/// that need relaying would also need a TURN server here.
+const STUN_URL: &str = match option_env!("YEWCHAT_STUN_URL") {
+    Some(url) => url,
+    None => "stun:stun.l.google.com:19302",
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallOffer {
+    pub call_id: String,
+    pub peer: String,
+    /// Display name of the caller, for the ringing screen.
+    #[serde(default)]
+    pub name: String,
+    pub sdp: String,
+    /// Whether the caller is sending video. The callee may still answer
+    /// with audio only.
+    #[serde(default)]
+    pub video: bool,
+    /// Id of the stream carrying a screen share, if one is running. Offers
+    /// for an existing call renegotiate it when sharing starts or stops.
+    #[serde(default)]
+    pub screen: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAnswer {
+    pub call_id: String,
+    pub peer: String,
+    pub sdp: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallCandidate {
+    pub call_id: String,
+    pub peer: String,
+    pub candidate: String,
+    #[serde(default)]
+    pub sdp_mid: Option<String>,
+    #[serde(default)]
+    pub sdp_m_line_index: Option<u16>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndReason {
+    #[default]
+    HangUp,
+    Declined,
+    Busy,
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallEnd {
+    pub call_id: String,
+    pub peer: String,
+    #[serde(default)]
+    pub reason: EndReason,
+}
+
+/// Something to send to the peer through the chat server.
+#[derive(Clone, Debug)]
+pub enum Signal {
+    Offer(CallOffer),
+    Answer(CallAnswer),
+    Candidate(CallCandidate),
+    End(CallEnd),
+}
+
+#[derive(Clone, Debug)]
+pub enum CallEvent {
+    Signal(Signal),
+    Connected,
+    /// A local or remote stream was added or replaced.
+    Media,
+    Failed(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeviceKind {
+    Microphone,
+    Camera,
+}
+
+impl DeviceKind {
+    fn track_kind(&self) -> &'static str {
+        match self {
+            DeviceKind::Microphone => "audio",
+            DeviceKind::Camera => "video",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Device {
+    pub id: String,
+    pub label: String,
+    pub kind: DeviceKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CallState {
+    /// We are ringing the peer.
+    Outgoing,
+    /// The peer is ringing us.
+    Incoming,
+    Connecting,
+    Active,
+}
+
+pub fn is_supported() -> bool {
+    web_sys::window()
+        .map(|w| js_sys::Reflect::has(&w, &"RTCPeerConnection".into()).unwrap_or(false))
+        .unwrap_or(false)
+}
+
+fn js_error(e: JsValue) -> String {
+    format!("{:?}", e)
+}
+
+fn media_devices() -> Result<MediaDevices, String> {
+    web_sys::window()
+        .ok_or("no window")?
+        .navigator()
+        .media_devices()
+        .map_err(js_error)
+}
+
+/// Microphones and cameras the user can pick from. Labels are only filled
+/// in once the page has been granted media access.
+pub async fn devices() -> Vec<Device> {
+    let list = async {
+        let promise = media_devices()?.enumerate_devices().map_err(js_error)?;
+        JsFuture::from(promise).await.map_err(js_error)
+    };
+    let list = match list.await {
+        Ok(list) => js_sys::Array::from(&list),
+        Err(e) => {
+            log::error!("failed to list media devices: {}", e);
+            return Vec::new();
+        }
+    };
+    list.iter()
+        .filter_map(|info| {
+            let info: MediaDeviceInfo = info.unchecked_into();
+            let kind = match info.kind() {
+                MediaDeviceKind::Audioinput => DeviceKind::Microphone,
+                MediaDeviceKind::Videoinput => DeviceKind::Camera,
+                _ => return None,
+            };
+            Some(Device {
+                id: info.device_id(),
+                label: info.label(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+pub fn screen_share_supported() -> bool {
+    media_devices()
+        .map(|devices| js_sys::Reflect::has(&devices, &"getDisplayMedia".into()).unwrap_or(false))
+        .unwrap_or(false)
+}
+
+/// Whether the browser can float a video above other windows.
+pub fn picture_in_picture_supported() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| js_sys::Reflect::get(&d, &"pictureInPictureEnabled".into()).ok())
+        .and_then(|enabled| enabled.as_bool())
+        .unwrap_or(false)
+}
+
+/// Moves `video` into the browser's picture-in-picture window. Not yet part
+/// of web-sys, so the method is looked up by name.
+pub fn request_picture_in_picture(video: &HtmlVideoElement) {
+    let request = js_sys::Reflect::get(video, &"requestPictureInPicture".into())
+        .ok()
+        .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+    if let Some(request) = request {
+        match request.call0(video) {
+            Ok(promise) => spawn_local(async move {
+                if let Err(e) = JsFuture::from(js_sys::Promise::from(promise)).await {
+                    log::error!("picture-in-picture failed: {:?}", e);
+                }
+            }),
+            Err(e) => log::error!("picture-in-picture failed: {:?}", e),
+        }
+    }
+}
+
+/// State shared with the async setup steps and event listeners.
+#[derive(Default)]
+struct Shared {
+    local: RefCell<Option<MediaStream>>,
+    remote: RefCell<Option<MediaStream>>,
+    /// Our screen share and the listener for it being stopped from the
+    /// browser's own controls.
+    screen: RefCell<Option<(MediaStream, EventListener)>>,
+    remote_screen: RefCell<Option<MediaStream>>,
+    /// Stream id the peer announced for its screen share.
+    remote_screen_id: RefCell<Option<String>>,
+    /// Candidates that arrived before the remote description was applied.
+    queued: RefCell<Vec<CallCandidate>>,
+    remote_ready: Cell<bool>,
+}
+
+pub struct Call {
+    pub id: String,
+    /// User id of the other side.
+    pub peer: String,
+    pub peer_name: String,
+    pub state: CallState,
+    pub muted: bool,
+    /// Whether we send video. Fixed when the call is set up.
+    pub video: bool,
+    /// Whether the peer offered video, for the ringing screen.
+    pub offered_video: bool,
+    pub camera_off: bool,
+    pc: RtcPeerConnection,
+    shared: Rc<Shared>,
+    remote_audio: HtmlAudioElement,
+    /// The caller's offer, kept while we are still ringing.
+    offer: Option<String>,
+    on_event: Callback<CallEvent>,
+    _listeners: Vec<EventListener>,
+}
+
+impl Call {
+    /// Rings `peer`, asking for the microphone (and camera) first.
+    pub fn start(
+        peer: &str,
+        peer_name: &str,
+        my_name: &str,
+        video: bool,
+        on_event: Callback<CallEvent>,
+    ) -> Result<Call, String> {
+        let mut call = Call::new(session::random_id(), peer, peer_name, CallState::Outgoing, on_event)?;
+        call.video = video;
+        let (pc, shared, on_event) = (call.pc.clone(), call.shared.clone(), call.on_event.clone());
+        let offer = CallOffer {
+            call_id: call.id.clone(),
+            peer: peer.to_string(),
+            name: my_name.to_string(),
+            sdp: String::new(),
+            video,
+            screen: None,
+        };
+        spawn_local(async move {
+            let result = async {
+                add_media(&pc, &shared, video, &on_event).await?;
+                let description = JsFuture::from(pc.create_offer()).await.map_err(js_error)?;
+                let sdp = set_local(&pc, RtcSdpType::Offer, description).await?;
+                Ok::<_, String>(CallOffer { sdp, ..offer })
+            };
+            match result.await {
+                Ok(offer) => on_event.emit(CallEvent::Signal(Signal::Offer(offer))),
+                Err(e) => on_event.emit(CallEvent::Failed(e)),
+            }
+        });
+        Ok(call)
+    }
+
+    /// Sets up a call that is ringing until [`Call::accept`] is called. The
+    /// offer's `video` flag is only a hint; [`Call::accept`] decides.
+    pub fn incoming(offer: CallOffer, on_event: Callback<CallEvent>) -> Result<Call, String> {
+        let mut call = Call::new(
+            offer.call_id,
+            &offer.peer,
+            &offer.name,
+            CallState::Incoming,
+            on_event,
+        )?;
+        call.offer = Some(offer.sdp);
+        call.offered_video = offer.video;
+        Ok(call)
+    }
+
+    fn new(
+        id: String,
+        peer: &str,
+        peer_name: &str,
+        state: CallState,
+        on_event: Callback<CallEvent>,
+    ) -> Result<Call, String> {
+        let mut server = RtcIceServer::new();
+        server.urls(&JsValue::from_str(STUN_URL));
+        let mut config = RtcConfiguration::new();
+        config.ice_servers(&js_sys::Array::of1(&server));
+        let pc = RtcPeerConnection::new_with_configuration(&config).map_err(js_error)?;
+
+        let remote_audio = HtmlAudioElement::new().map_err(js_error)?;
+        remote_audio.set_autoplay(true);
+        let shared = Rc::new(Shared::default());
+
+        let listeners = vec![
+            {
+                let (on_event, id, peer) = (on_event.clone(), id.clone(), peer.to_string());
+                EventListener::new(&pc, "icecandidate", move |event| {
+                    let candidate = event
+                        .dyn_ref::<RtcPeerConnectionIceEvent>()
+                        .and_then(|e| e.candidate());
+                    if let Some(candidate) = candidate {
+                        on_event.emit(CallEvent::Signal(Signal::Candidate(CallCandidate {
+                            call_id: id.clone(),
+                            peer: peer.clone(),
+                            candidate: candidate.candidate(),
+                            sdp_mid: candidate.sdp_mid(),
+                            sdp_m_line_index: candidate.sdp_m_line_index(),
+                        })));
+                    }
+                })
+            },
+            {
+                // Sound always plays through this element, so that remote
+                // video elements can come and go (or stay muted) freely.
+                let (remote_audio, shared, on_event) =
+                    (remote_audio.clone(), shared.clone(), on_event.clone());
+                EventListener::new(&pc, "track", move |event| {
+                    let stream = event
+                        .dyn_ref::<RtcTrackEvent>()
+                        .and_then(|e| e.streams().get(0).dyn_into::<MediaStream>().ok());
+                    let stream = match stream {
+                        Some(stream) => stream,
+                        None => return,
+                    };
+                    if shared.remote_screen_id.borrow().as_deref() == Some(stream.id().as_str()) {
+                        *shared.remote_screen.borrow_mut() = Some(stream);
+                    } else {
+                        remote_audio.set_src_object(Some(&stream));
+                        *shared.remote.borrow_mut() = Some(stream);
+                    }
+                    on_event.emit(CallEvent::Media);
+                })
+            },
+            {
+                let (on_event, pc) = (on_event.clone(), pc.clone());
+                EventListener::new(&pc.clone(), "iceconnectionstatechange", move |_| {
+                    match pc.ice_connection_state() {
+                        RtcIceConnectionState::Connected | RtcIceConnectionState::Completed => {
+                            on_event.emit(CallEvent::Connected)
+                        }
+                        RtcIceConnectionState::Failed => {
+                            on_event.emit(CallEvent::Failed("ICE negotiation failed".into()))
+                        }
+                        _ => {}
+                    }
+                })
+            },
+        ];
+
+        Ok(Call {
+            id,
+            peer: peer.to_string(),
+            peer_name: peer_name.to_string(),
+            state,
+            muted: false,
+            video: false,
+            offered_video: false,
+            camera_off: false,
+            pc,
+            shared,
+            remote_audio,
+            offer: None,
+            on_event,
+            _listeners: listeners,
+        })
+    }
+
+    /// Picks up a ringing call, with or without sending our camera.
+    pub fn accept(&mut self, video: bool) {
+        let sdp = match self.offer.take() {
+            Some(sdp) => sdp,
+            None => return,
+        };
+        self.state = CallState::Connecting;
+        self.video = video;
+        let (pc, shared, on_event) = (self.pc.clone(), self.shared.clone(), self.on_event.clone());
+        let (call_id, peer) = (self.id.clone(), self.peer.clone());
+        spawn_local(async move {
+            let result = async {
+                add_media(&pc, &shared, video, &on_event).await?;
+                set_remote(&pc, &shared, RtcSdpType::Offer, &sdp).await?;
+                let description = JsFuture::from(pc.create_answer()).await.map_err(js_error)?;
+                set_local(&pc, RtcSdpType::Answer, description).await
+            };
+            match result.await {
+                Ok(sdp) => on_event.emit(CallEvent::Signal(Signal::Answer(CallAnswer {
+                    call_id,
+                    peer,
+                    sdp,
+                }))),
+                Err(e) => on_event.emit(CallEvent::Failed(e)),
+            }
+        });
+    }
+
+    /// Applies the peer's answer to our offer, be it the first one or a
+    /// renegotiation.
+    pub fn answered(&mut self, answer: CallAnswer) {
+        if self.pc.signaling_state() != RtcSignalingState::HaveLocalOffer {
+            return;
+        }
+        if self.state == CallState::Outgoing {
+            self.state = CallState::Connecting;
+        }
+        let (pc, shared, on_event) = (self.pc.clone(), self.shared.clone(), self.on_event.clone());
+        spawn_local(async move {
+            if let Err(e) = set_remote(&pc, &shared, RtcSdpType::Answer, &answer.sdp).await {
+                on_event.emit(CallEvent::Failed(e));
+            }
+        });
+    }
+
+    /// Handles a new offer for this call from the peer, sent when their
+    /// screen share starts or stops.
+    pub fn renegotiated(&self, offer: CallOffer) {
+        if offer.screen.is_none() {
+            self.shared.remote_screen.borrow_mut().take();
+        }
+        *self.shared.remote_screen_id.borrow_mut() = offer.screen;
+        let (pc, shared, on_event) = (self.pc.clone(), self.shared.clone(), self.on_event.clone());
+        let (call_id, peer) = (self.id.clone(), self.peer.clone());
+        spawn_local(async move {
+            let result = async {
+                set_remote(&pc, &shared, RtcSdpType::Offer, &offer.sdp).await?;
+                let description = JsFuture::from(pc.create_answer()).await.map_err(js_error)?;
+                set_local(&pc, RtcSdpType::Answer, description).await
+            };
+            match result.await {
+                Ok(sdp) => {
+                    on_event.emit(CallEvent::Signal(Signal::Answer(CallAnswer { call_id, peer, sdp })));
+                    on_event.emit(CallEvent::Media);
+                }
+                Err(e) => on_event.emit(CallEvent::Failed(e)),
+            }
+        });
+    }
+
+    pub fn is_sharing_screen(&self) -> bool {
+        self.shared.screen.borrow().is_some()
+    }
+
+    pub fn screen_stream(&self) -> Option<MediaStream> {
+        self.shared.screen.borrow().as_ref().map(|(stream, _)| stream.clone())
+    }
+
+    pub fn remote_screen_stream(&self) -> Option<MediaStream> {
+        self.shared.remote_screen.borrow().clone()
+    }
+
+    /// Starts sharing a screen or window the user picks, as an extra video
+    /// track next to the camera.
+    pub fn share_screen(&self) {
+        if self.is_sharing_screen() {
+            return;
+        }
+        let renegotiation = self.renegotiation();
+        spawn_local(async move {
+            let result = async {
+                let promise = media_devices()?.get_display_media().map_err(js_error)?;
+                let stream: MediaStream = JsFuture::from(promise).await.map_err(js_error)?.unchecked_into();
+                let track: MediaStreamTrack = stream.get_video_tracks().get(0).dyn_into().map_err(js_error)?;
+                renegotiation.pc.add_track_0(&track, &stream);
+                let ended = {
+                    let renegotiation = renegotiation.clone();
+                    // Deferred, since stopping drops this very listener.
+                    EventListener::once(&track, "ended", move |_| {
+                        let renegotiation = renegotiation.clone();
+                        spawn_local(async move { renegotiation.stop_screen() });
+                    })
+                };
+                let id = stream.id();
+                *renegotiation.shared.screen.borrow_mut() = Some((stream, ended));
+                renegotiation.on_event.emit(CallEvent::Media);
+                Ok::<_, String>(id)
+            };
+            match result.await {
+                Ok(id) => renegotiation.offer(Some(id)),
+                // Most often the user closed the picker.
+                Err(e) => log::debug!("screen share not started: {}", e),
+            }
+        });
+    }
+
+    pub fn stop_screen_share(&self) {
+        self.renegotiation().stop_screen();
+    }
+
+    fn renegotiation(&self) -> Renegotiation {
+        Renegotiation {
+            pc: self.pc.clone(),
+            shared: self.shared.clone(),
+            call_id: self.id.clone(),
+            peer: self.peer.clone(),
+            on_event: self.on_event.clone(),
+        }
+    }
+
+    pub fn add_candidate(&self, candidate: CallCandidate) {
+        if self.shared.remote_ready.get() {
+            add_candidate(&self.pc, &candidate);
+        } else {
+            self.shared.queued.borrow_mut().push(candidate);
+        }
+    }
+
+    pub fn local_stream(&self) -> Option<MediaStream> {
+        self.shared.local.borrow().clone()
+    }
+
+    pub fn remote_stream(&self) -> Option<MediaStream> {
+        self.shared.remote.borrow().clone()
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+        self.set_tracks_enabled(DeviceKind::Microphone, !self.muted);
+    }
+
+    pub fn toggle_camera(&mut self) {
+        self.camera_off = !self.camera_off;
+        self.set_tracks_enabled(DeviceKind::Camera, !self.camera_off);
+    }
+
+    fn set_tracks_enabled(&self, kind: DeviceKind, enabled: bool) {
+        if let Some(stream) = &*self.shared.local.borrow() {
+            for track in stream.get_tracks().iter() {
+                let track: MediaStreamTrack = track.unchecked_into();
+                if track.kind() == kind.track_kind() {
+                    track.set_enabled(enabled);
+                }
+            }
+        }
+    }
+
+    /// Swaps the microphone or camera mid-call without renegotiating.
+    pub fn switch_device(&self, kind: DeviceKind, device_id: String) {
+        let (pc, shared, on_event) = (self.pc.clone(), self.shared.clone(), self.on_event.clone());
+        let enabled = match kind {
+            DeviceKind::Microphone => !self.muted,
+            DeviceKind::Camera => !self.camera_off,
+        };
+        spawn_local(async move {
+            if let Err(e) = replace_device(&pc, &shared, kind, &device_id, enabled).await {
+                log::error!("failed to switch {:?}: {}", kind, e);
+                return;
+            }
+            on_event.emit(CallEvent::Media);
+        });
+    }
+
+    /// Tells the peer the call is over. Dropping the call releases the
+    /// microphone and connection.
+    pub fn end(&self, reason: EndReason) {
+        self.on_event.emit(CallEvent::Signal(Signal::End(CallEnd {
+            call_id: self.id.clone(),
+            peer: self.peer.clone(),
+            reason,
+        })));
+    }
+}
+
+/// What changing the set of tracks mid-call needs, detached from [`Call`] so
+/// that browser callbacks can hold on to it.
+#[derive(Clone)]
+struct Renegotiation {
+    pc: RtcPeerConnection,
+    shared: Rc<Shared>,
+    call_id: String,
+    peer: String,
+    on_event: Callback<CallEvent>,
+}
+
+impl Renegotiation {
+    fn offer(&self, screen: Option<String>) {
+        let this = self.clone();
+        spawn_local(async move {
+            let result = async {
+                let description = JsFuture::from(this.pc.create_offer()).await.map_err(js_error)?;
+                set_local(&this.pc, RtcSdpType::Offer, description).await
+            };
+            match result.await {
+                Ok(sdp) => this.on_event.emit(CallEvent::Signal(Signal::Offer(CallOffer {
+                    call_id: this.call_id.clone(),
+                    peer: this.peer.clone(),
+                    name: String::new(),
+                    sdp,
+                    video: true,
+                    screen,
+                }))),
+                Err(e) => log::error!("failed to renegotiate call: {}", e),
+            }
+        });
+    }
+
+    fn stop_screen(&self) {
+        let (stream, _ended) = match self.shared.screen.borrow_mut().take() {
+            Some(screen) => screen,
+            None => return,
+        };
+        for sender in self.pc.get_senders().iter() {
+            let sender: RtcRtpSender = sender.unchecked_into();
+            if sender.track().is_some_and(|t| stream.get_track_by_id(&t.id()).is_some()) {
+                self.pc.remove_track(&sender);
+            }
+        }
+        stop_tracks(&stream);
+        self.on_event.emit(CallEvent::Media);
+        self.offer(None);
+    }
+}
+
+fn stop_tracks(stream: &MediaStream) {
+    for track in stream.get_tracks().iter() {
+        track.unchecked_into::<MediaStreamTrack>().stop();
+    }
+}
+
+impl Drop for Call {
+    fn drop(&mut self) {
+        self.pc.close();
+        self.remote_audio.set_src_object(None);
+        if let Some((stream, _)) = self.shared.screen.borrow_mut().take() {
+            stop_tracks(&stream);
+        }
+        if let Some(stream) = self.shared.local.borrow_mut().take() {
+            stop_tracks(&stream);
+        }
+    }
+}
+
+/// Builds `{"deviceId": {"exact": id}}`.
+fn device_constraint(id: &str) -> JsValue {
+    let exact = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&exact, &"exact".into(), &id.into());
+    let constraint = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&constraint, &"deviceId".into(), &exact);
+    constraint.into()
+}
+
+async fn get_user_media(constraints: &MediaStreamConstraints) -> Result<MediaStream, String> {
+    let promise = media_devices()?
+        .get_user_media_with_constraints(constraints)
+        .map_err(js_error)?;
+    Ok(JsFuture::from(promise).await.map_err(js_error)?.unchecked_into())
+}
+
+async fn add_media(
+    pc: &RtcPeerConnection,
+    shared: &Shared,
+    video: bool,
+    on_event: &Callback<CallEvent>,
+) -> Result<(), String> {
+    let mut constraints = MediaStreamConstraints::new();
+    constraints.audio(&JsValue::TRUE).video(&JsValue::from_bool(video));
+    let stream = get_user_media(&constraints).await?;
+    for track in stream.get_tracks().iter() {
+        pc.add_track_0(&track.unchecked_into(), &stream);
+    }
+    *shared.local.borrow_mut() = Some(stream);
+    on_event.emit(CallEvent::Media);
+    Ok(())
+}
+
+async fn replace_device(
+    pc: &RtcPeerConnection,
+    shared: &Shared,
+    kind: DeviceKind,
+    device_id: &str,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut constraints = MediaStreamConstraints::new();
+    match kind {
+        DeviceKind::Microphone => constraints.audio(&device_constraint(device_id)),
+        DeviceKind::Camera => constraints.video(&device_constraint(device_id)),
+    };
+    let track: MediaStreamTrack = get_user_media(&constraints)
+        .await?
+        .get_tracks()
+        .get(0)
+        .dyn_into()
+        .map_err(js_error)?;
+    track.set_enabled(enabled);
+
+    let screen = shared.screen.borrow().as_ref().map(|(stream, _)| stream.clone());
+    let is_screen = |track: &MediaStreamTrack| {
+        screen
+            .as_ref()
+            .is_some_and(|s| s.get_track_by_id(&track.id()).is_some())
+    };
+    let sender = pc
+        .get_senders()
+        .iter()
+        .map(|s| s.unchecked_into::<RtcRtpSender>())
+        .find(|s| {
+            s.track()
+                .is_some_and(|t| t.kind() == kind.track_kind() && !is_screen(&t))
+        })
+        .ok_or("no sender for this kind of track")?;
+    JsFuture::from(sender.replace_track(Some(&track)))
+        .await
+        .map_err(js_error)?;
+
+    if let Some(stream) = &*shared.local.borrow() {
+        for old in stream.get_tracks().iter() {
+            let old: MediaStreamTrack = old.unchecked_into();
+            if old.kind() == kind.track_kind() {
+                old.stop();
+                stream.remove_track(&old);
+            }
+        }
+        stream.add_track(&track);
+    }
+    Ok(())
+}
+
+/// Applies a freshly created description and returns its SDP for sending.
+async fn set_local(
+    pc: &RtcPeerConnection,
+    kind: RtcSdpType,
+    description: JsValue,
+) -> Result<String, String> {
+    let sdp = description.unchecked_into::<RtcSessionDescription>().sdp();
+    let mut init = RtcSessionDescriptionInit::new(kind);
+    init.sdp(&sdp);
+    JsFuture::from(pc.set_local_description(&init))
+        .await
+        .map_err(js_error)?;
+    Ok(sdp)
+}
+
+async fn set_remote(
+    pc: &RtcPeerConnection,
+    shared: &Shared,
+    kind: RtcSdpType,
+    sdp: &str,
+) -> Result<(), String> {
+    let mut init = RtcSessionDescriptionInit::new(kind);
+    init.sdp(sdp);
+    JsFuture::from(pc.set_remote_description(&init))
+        .await
+        .map_err(js_error)?;
+    shared.remote_ready.set(true);
+    for candidate in shared.queued.borrow_mut().drain(..) {
+        add_candidate(pc, &candidate);
+    }
+    Ok(())
+}
+
+fn add_candidate(pc: &RtcPeerConnection, candidate: &CallCandidate) {
+    let mut init = RtcIceCandidateInit::new(&candidate.candidate);
+    init.sdp_mid(candidate.sdp_mid.as_deref())
+        .sdp_m_line_index(candidate.sdp_m_line_index);
+    let promise = pc.add_ice_candidate_with_opt_rtc_ice_candidate_init(Some(&init));
+    spawn_local(async move {
+        if let Err(e) = JsFuture::from(promise).await {
+            log::error!("failed to add ICE candidate: {:?}", e);
+        }
+    });
+}
@@ -1,53 +1,195 @@
-use futures::{channel::mpsc::Sender, SinkExt, StreamExt};
-use reqwasm::websocket::{futures::WebSocket, Message};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use futures::channel::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
+use futures::{FutureExt, SinkExt, StreamExt};
+use gloo_timers::future::TimeoutFuture;
+use reqwasm::websocket::{futures::WebSocket, Message, State};
 
 use wasm_bindgen_futures::spawn_local;
 use yew_agent::Dispatched;
 
 use crate::services::event_bus::{EventBus, Request};
+use crate::services::transport::{Codec, TransportPolicy};
+
+pub const DEFAULT_URL: &str = "ws://127.0.0.1:8080";
+/// Sent when the connection has been quiet for a heartbeat interval, so
+/// proxies and NATs keep it open.
+const HEARTBEAT: &str = r#"{"messageType":"heartbeat","dataArray":null,"data":null}"#;
+/// How often to look whether a new socket finished connecting.
+const CONNECT_POLL_MS: u32 = 50;
+
+struct Shared {
+    url: RefCell<String>,
+    policy: Cell<TransportPolicy>,
+    /// Sent first on every connection, e.g. to register again after a drop.
+    handshake: RefCell<Vec<String>>,
+}
 
 pub struct WebsocketService {
     pub tx: Sender<String>,
+    shared: Rc<Shared>,
+    reconnect: UnboundedSender<()>,
 }
 
 impl WebsocketService {
-    pub fn new() -> Self {
-        let ws = WebSocket::open("ws://127.0.0.1:8080").unwrap();
+    pub fn new(url: &str, policy: TransportPolicy) -> Self {
+        let (in_tx, in_rx) = futures::channel::mpsc::channel::<String>(1000);
+        let (reconnect, reconnect_rx) = futures::channel::mpsc::unbounded();
+        let shared = Rc::new(Shared {
+            url: RefCell::new(url.to_string()),
+            policy: Cell::new(policy),
+            handshake: RefCell::new(Vec::new()),
+        });
+        spawn_local(run(Rc::clone(&shared), in_rx, reconnect_rx));
+        Self {
+            tx: in_tx,
+            shared,
+            reconnect,
+        }
+    }
 
-        let (mut write, mut read) = ws.split();
+    /// Drops the current connection and connects to `url` instead, keeping
+    /// whatever is queued.
+    pub fn set_url(&self, url: &str) {
+        if *self.shared.url.borrow() != url {
+            *self.shared.url.borrow_mut() = url.to_string();
+            let _ = self.reconnect.unbounded_send(());
+        }
+    }
 
-        let (in_tx, mut in_rx) = futures::channel::mpsc::channel::<String>(1000);
-        let mut event_bus = EventBus::dispatcher();
+    /// Takes effect from the next heartbeat, frame or reconnect on, without
+    /// dropping the current connection.
+    pub fn set_policy(&self, policy: TransportPolicy) {
+        self.shared.policy.set(policy);
+    }
 
-        spawn_local(async move {
-            while let Some(s) = in_rx.next().await {
-                log::debug!("got event from channel! {}", s);
-                write.send(Message::Text(s)).await.unwrap();
+    pub fn set_handshake(&self, messages: Vec<String>) {
+        *self.shared.handshake.borrow_mut() = messages;
+    }
+
+    /// Sends what is already queued, then closes the socket for good instead
+    /// of reconnecting.
+    pub fn close(&mut self) {
+        self.tx.close_channel();
+    }
+}
+
+/// Whether `url` is something a websocket can be opened to.
+pub fn valid_url(url: &str) -> bool {
+    web_sys::Url::new(url).is_ok_and(|url| matches!(url.protocol().as_str(), "ws:" | "wss:"))
+}
+
+fn encode(text: String, codec: Codec) -> Message {
+    match codec {
+        Codec::Text => Message::Text(text),
+        Codec::Binary => Message::Bytes(text.into_bytes()),
+    }
+}
+
+/// Keeps a connection up for as long as the service lives, reconnecting with
+/// backoff and holding on to what is sent in between.
+async fn run(shared: Rc<Shared>, mut in_rx: Receiver<String>, mut reconnect_rx: UnboundedReceiver<()>) {
+    let mut event_bus = EventBus::dispatcher();
+    let mut queued = VecDeque::<String>::new();
+    let mut attempt = 0;
+
+    loop {
+        let url = shared.url.borrow().clone();
+        let mut switching = false;
+        let connected = match WebSocket::open(&url) {
+            Ok(ws) => {
+                while matches!(ws.state(), State::Connecting) {
+                    TimeoutFuture::new(CONNECT_POLL_MS).await;
+                }
+                matches!(ws.state(), State::Open).then_some(ws)
             }
-        });
+            Err(e) => {
+                log::error!("ws: {:?}", e);
+                None
+            }
+        };
+
+        if let Some(ws) = connected {
+            attempt = 0;
+            let (mut write, mut read) = ws.split();
+            let handshake = shared.handshake.borrow().clone();
+            let mut backlog: VecDeque<String> = handshake.into_iter().chain(queued.drain(..)).collect();
 
-        spawn_local(async move {
-            while let Some(msg) = read.next().await {
-                match msg {
-                    Ok(Message::Text(data)) => {
-                        log::debug!("from websocket: {}", data);
-                        event_bus.send(Request::EventBusMsg(data));
+            'connection: loop {
+                while let Some(text) = backlog.pop_front() {
+                    if write.send(encode(text.clone(), shared.policy.get().codec)).await.is_err() {
+                        queued.push_back(text);
+                        break 'connection;
                     }
-                    Ok(Message::Bytes(b)) => {
-                        let decoded = std::str::from_utf8(&b);
-                        if let Ok(val) = decoded {
-                            log::debug!("from websocket: {}", val);
-                            event_bus.send(Request::EventBusMsg(val.into()));
+                }
+                let mut heartbeat = TimeoutFuture::new(shared.policy.get().heartbeat_ms).fuse();
+                futures::select! {
+                    incoming = read.next().fuse() => match incoming {
+                        Some(Ok(Message::Text(data))) => {
+                            log::debug!("from websocket: {}", data);
+                            event_bus.send(Request::EventBusMsg(data));
                         }
-                    }
-                    Err(e) => {
-                        log::error!("ws: {:?}", e)
+                        Some(Ok(Message::Bytes(b))) => {
+                            if let Ok(val) = std::str::from_utf8(&b) {
+                                log::debug!("from websocket: {}", val);
+                                event_bus.send(Request::EventBusMsg(val.into()));
+                            }
+                        }
+                        Some(Err(e)) => {
+                            log::error!("ws: {:?}", e);
+                            break 'connection;
+                        }
+                        None => break 'connection,
+                    },
+                    outgoing = in_rx.next() => match outgoing {
+                        Some(text) => {
+                            log::debug!("got event from channel! {}", text);
+                            backlog.push_back(text);
+                        }
+                        // The service was closed or dropped.
+                        None => {
+                            let _ = write.close().await;
+                            return;
+                        }
+                    },
+                    _ = heartbeat => backlog.push_back(HEARTBEAT.to_string()),
+                    _ = reconnect_rx.next() => {
+                        let _ = write.close().await;
+                        switching = true;
+                        break 'connection;
                     }
                 }
             }
             log::debug!("WebSocket Closed");
-        });
+        }
 
-        Self { tx: in_tx }
+        let policy = shared.policy.get();
+        // A new address is tried straight away rather than after a backoff.
+        if switching {
+            attempt = 0;
+        } else {
+            let mut backoff = TimeoutFuture::new(policy.backoff_ms(attempt)).fuse();
+            futures::select! {
+                _ = backoff => attempt += 1,
+                _ = reconnect_rx.next() => attempt = 0,
+            }
+        }
+        // Whatever was sent while we waited goes out after reconnecting, up
+        // to the queue limit.
+        loop {
+            match in_rx.try_next() {
+                Ok(Some(text)) => queued.push_back(text),
+                // Closed while offline; nothing is left to deliver to.
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+        let overflow = queued.len().saturating_sub(policy.queue_size);
+        if overflow > 0 {
+            log::warn!("dropping {} queued messages while offline", overflow);
+            queued.drain(..overflow);
+        }
     }
-}
\ No newline at end of file
+}
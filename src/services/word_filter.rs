@@ -0,0 +1,34 @@
+use crate::services::notification_router::is_word_char;
+
+const MASK: char = '•';
+
+/// Hides filtered words in `text`, matching whole words and ignoring case.
+/// Returns `None` when none of `words` occur.
+pub fn mask(text: &str, words: &[String]) -> Option<String> {
+    let words: Vec<String> = words
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    let mut masked = String::with_capacity(text.len());
+    let mut matched = false;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let in_word = rest.starts_with(is_word_char);
+        let end = rest
+            .find(|c: char| is_word_char(c) != in_word)
+            .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(end);
+        if in_word && words.contains(&run.to_lowercase()) {
+            matched = true;
+            masked.extend(run.chars().map(|_| MASK));
+        } else {
+            masked.push_str(run);
+        }
+        rest = tail;
+    }
+    matched.then_some(masked)
+}
@@ -0,0 +1,65 @@
+// Keeps the app shell available offline. Bump CACHE whenever a release
+// changes any file in SHELL: the new worker then installs alongside the old
+// one, and the app offers to reload into it.
+const CACHE = 'yewchat-shell-v3';
+const SHELL = [
+    '/',
+    '/index.html',
+    '/yewchat.js',
+    // The wasm bindings, split out of yewchat.js by their dynamic import.
+    '/yewchat-app.js',
+    '/yewchat_bg.wasm',
+    '/manifest.webmanifest',
+    '/icon.svg',
+];
+// Styles come from the CDN. Cached on a best-effort basis, so an unreachable
+// CDN does not fail the install.
+const STYLES = 'https://cdn.tailwindcss.com/';
+
+self.addEventListener('install', (event) => {
+    event.waitUntil(caches.open(CACHE).then((cache) => Promise.all([
+        cache.addAll(SHELL),
+        cache.add(new Request(STYLES, { mode: 'no-cors' })).catch(() => {}),
+    ])));
+});
+
+self.addEventListener('activate', (event) => {
+    event.waitUntil(
+        caches.keys()
+            .then((keys) => Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))))
+            .then(() => self.clients.claim()),
+    );
+});
+
+// Sent by the app once the user accepts the update.
+self.addEventListener('message', (event) => {
+    if (event.data === 'skipWaiting') {
+        self.skipWaiting();
+    }
+});
+
+self.addEventListener('fetch', (event) => {
+    const request = event.request;
+    const external = new URL(request.url).origin !== self.location.origin;
+    if (request.method !== 'GET' || (external && request.url !== STYLES)) {
+        return;
+    }
+    // Every route is the same single page; offline, serve the cached one.
+    if (request.mode === 'navigate') {
+        event.respondWith(fetch(request).catch(() => caches.match('/index.html')));
+        return;
+    }
+    // Scripts and wasm of ours are kept as they are fetched, in case a build
+    // splits out a chunk SHELL does not list.
+    const code = !external && /\.(js|wasm)$/.test(new URL(request.url).pathname);
+    event.respondWith(caches.match(request).then((cached) => cached || fetch(request).then((response) => {
+        if (response.ok && code) {
+            const copy = response.clone();
+            caches.open(CACHE).then((cache) => cache.put(request, copy));
+        }
+        return response;
+    })));
+});
+
+// Push notifications would be handled here, with a 'push' listener calling
+// self.registration.showNotification.
@@ -8,11 +8,16 @@ module.exports = {
     mode: 'production',
     devServer: {
         port: 8000,
+        // Routes such as /chat/general are the app's, not files.
+        historyApiFallback: true,
     },
     entry: './bootstrap.js',
     output: {
         path: distPath,
         filename: 'yewchat.js',
+        // A fixed name for the chunk bootstrap.js imports, so the service
+        // worker can precache it.
+        chunkFilename: 'yewchat-[name].js',
         webassemblyModuleFilename: 'yewchat_bg.wasm',
     },
     plugins: [